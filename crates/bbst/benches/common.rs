@@ -1,7 +1,7 @@
 use std::hint::black_box;
 use std::time::{Duration, Instant};
 
-use bench::apply_small_runtime_config;
+use bench::{apply_small_runtime_config, bench_seed};
 use criterion::measurement::Measurement;
 use criterion::{BenchmarkGroup, BenchmarkId, Criterion};
 use rand::rngs::StdRng;
@@ -295,7 +295,7 @@ where
 }
 
 fn seed_base(feature: FeatureSet, size: u64) -> u64 {
-    let seed = 0x5EED_2026 ^ (size.wrapping_mul(SEED_MIX));
+    let seed = bench_seed() ^ 0x5EED_2026 ^ (size.wrapping_mul(SEED_MIX));
     let seed = seed ^ (feature_id(feature).wrapping_mul(SEED_MIX.rotate_left(31)));
     mix_seed(seed)
 }
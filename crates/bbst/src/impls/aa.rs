@@ -1,7 +1,11 @@
 use std::ops::{Bound, RangeBounds};
 
 use crate::policy::LazyMapMonoid;
-use crate::traits::{SequenceAgg, SequenceBase, SequenceLazy, SequenceReverse, SequenceSplitMerge};
+use crate::traits::{
+    SequenceAgg, SequenceBase, SequenceLazy, SequenceReverse, SequenceSearch, SequenceSplitMerge,
+};
+
+use super::descent::{self, DescentLink};
 
 pub struct ImplicitAaTree<P: LazyMapMonoid> {
     root: Link<P>,
@@ -86,21 +90,25 @@ impl<P: LazyMapMonoid> Node<P> {
     fn recalc(&mut self) {
         let left_agg = Self::agg(&self.left);
         let right_agg = Self::agg(&self.right);
-        let left_rev = Self::agg_rev(&self.left);
-        let right_rev = Self::agg_rev(&self.right);
         let left_size = Self::size(&self.left);
         let right_size = Self::size(&self.right);
 
         self.size = 1 + left_size + right_size;
         self.agg = P::agg_merge(&left_agg, &self.key, &right_agg);
-        self.agg_rev = P::agg_merge(&right_rev, &self.key, &left_rev);
+        if !P::REVERSAL_INVARIANT {
+            let left_rev = Self::agg_rev(&self.left);
+            let right_rev = Self::agg_rev(&self.right);
+            self.agg_rev = P::agg_merge(&right_rev, &self.key, &left_rev);
+        }
     }
 
     fn apply_action(&mut self, act: &P::Act) {
         self.key = P::act_apply_key(&self.key, act);
         let size = self.size as usize;
         self.agg = P::act_apply_agg(&self.agg, act, size);
-        self.agg_rev = P::act_apply_agg(&self.agg_rev, act, size);
+        if !P::REVERSAL_INVARIANT {
+            self.agg_rev = P::act_apply_agg(&self.agg_rev, act, size);
+        }
         self.lazy = P::act_compose(act, &self.lazy);
         self.lazy_pending = true;
     }
@@ -108,7 +116,21 @@ impl<P: LazyMapMonoid> Node<P> {
     fn apply_reverse(&mut self) {
         self.rev = !self.rev;
         std::mem::swap(&mut self.left, &mut self.right);
-        std::mem::swap(&mut self.agg, &mut self.agg_rev);
+        if !P::REVERSAL_INVARIANT {
+            std::mem::swap(&mut self.agg, &mut self.agg_rev);
+        }
+    }
+
+    /// Pushes every pending tag in the subtree down to the leaves, so the keys
+    /// can be read without further `push` calls.
+    fn push_all(root: &mut Link<P>) {
+        let mut stack: Vec<&mut Node<P>> = root.as_deref_mut().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            node.push();
+            let Node { left, right, .. } = node;
+            stack.extend(left.as_deref_mut());
+            stack.extend(right.as_deref_mut());
+        }
     }
 
     fn push(&mut self) {
@@ -150,6 +172,37 @@ impl<P: LazyMapMonoid> ImplicitAaTree<P> {
         Self { root: None, len: 0 }
     }
 
+    /// Builds a tree holding `values` in order in O(n).
+    ///
+    /// The split leans right, so the left subtree is never the larger one;
+    /// then `level = 1 + level(left)` keeps every left child exactly one level
+    /// down and every right child at most a single horizontal link away.
+    pub fn from_slice(values: &[P::Key]) -> Self
+    where
+        P::Key: Clone,
+    {
+        let mut tree = Self::new();
+        tree.root = Self::build(values);
+        tree.len = values.len();
+        tree
+    }
+
+    fn build(values: &[P::Key]) -> Link<P>
+    where
+        P::Key: Clone,
+    {
+        if values.is_empty() {
+            return None;
+        }
+        let mid = (values.len() - 1) / 2;
+        let mut node = Box::new(Node::new(values[mid].clone()));
+        node.left = Self::build(&values[..mid]);
+        node.right = Self::build(&values[mid + 1..]);
+        node.level = Node::level(&node.left) + 1;
+        node.recalc();
+        Some(node)
+    }
+
     fn normalize_range<R: RangeBounds<usize>>(range: R, len: usize) -> Option<(usize, usize)> {
         let start = match range.start_bound() {
             Bound::Included(&start) => start,
@@ -524,6 +577,28 @@ impl<P: LazyMapMonoid> SequenceBase for ImplicitAaTree<P> {
         self.len -= 1;
         removed
     }
+
+    fn set(&mut self, index: usize, key: Self::Key) {
+        if index >= self.len {
+            return;
+        }
+        descent::set_at(&mut self.root, index, key);
+    }
+
+    fn iter(&mut self) -> impl Iterator<Item = &Self::Key> {
+        Node::push_all(&mut self.root);
+        let mut stack: Vec<&Node<P>> = Vec::new();
+        let mut cur = self.root.as_deref();
+        std::iter::from_fn(move || {
+            while let Some(node) = cur {
+                stack.push(node);
+                cur = node.left.as_deref();
+            }
+            let node = stack.pop()?;
+            cur = node.right.as_deref();
+            Some(&node.key)
+        })
+    }
 }
 
 impl<P: LazyMapMonoid> SequenceSplitMerge for ImplicitAaTree<P> {
@@ -549,6 +624,29 @@ impl<P: LazyMapMonoid> SequenceSplitMerge for ImplicitAaTree<P> {
     }
 }
 
+impl<P: LazyMapMonoid> DescentLink<P> for Box<Node<P>> {
+    fn push(&mut self) {
+        Node::push(self);
+    }
+
+    fn agg(&self) -> &P::Agg {
+        &self.agg
+    }
+
+    fn size(&self) -> usize {
+        self.size as usize
+    }
+
+    fn recalc(&mut self) {
+        Node::recalc(self);
+    }
+
+    fn parts(&mut self) -> (&mut P::Key, &mut Link<P>, &mut Link<P>) {
+        let node: &mut Node<P> = self;
+        (&mut node.key, &mut node.left, &mut node.right)
+    }
+}
+
 impl<P: LazyMapMonoid> SequenceAgg for ImplicitAaTree<P> {
     type Agg = P::Agg;
 
@@ -562,6 +660,23 @@ impl<P: LazyMapMonoid> SequenceAgg for ImplicitAaTree<P> {
 
         Self::fold_range(&mut self.root, start, end)
     }
+
+    fn max_right<F: Fn(&Self::Agg) -> bool>(&mut self, l: usize, pred: F) -> usize {
+        let len = self.len;
+        assert!(
+            l <= len,
+            "max_right start {l} out of range for length {len}"
+        );
+        debug_assert!(pred(&P::agg_unit()), "pred must accept the empty fold");
+        descent::max_right_from(&mut self.root, l, &pred)
+    }
+
+    fn min_left<F: Fn(&Self::Agg) -> bool>(&mut self, r: usize, pred: F) -> usize {
+        let len = self.len;
+        assert!(r <= len, "min_left end {r} out of range for length {len}");
+        debug_assert!(pred(&P::agg_unit()), "pred must accept the empty fold");
+        descent::min_left_from(&mut self.root, r, &pred)
+    }
 }
 
 impl<P: LazyMapMonoid> SequenceLazy for ImplicitAaTree<P> {
@@ -597,11 +712,29 @@ impl<P: LazyMapMonoid> SequenceReverse for ImplicitAaTree<P> {
     }
 }
 
+impl<P: LazyMapMonoid> SequenceSearch for ImplicitAaTree<P>
+where
+    P::Key: Ord,
+{
+    fn lower_bound_sorted(&mut self, key: &Self::Key) -> usize {
+        descent::partition_point(&mut self.root, |k| k < key)
+    }
+
+    fn upper_bound_sorted(&mut self, key: &Self::Key) -> usize {
+        descent::partition_point(&mut self.root, |k| k <= key)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::ImplicitAaTree;
-    use crate::policy::RangeSumRangeAdd;
-    use crate::traits::{SequenceAgg, SequenceBase, SequenceLazy, SequenceReverse};
+    use std::collections::VecDeque;
+
+    use super::{ImplicitAaTree, Link, Node};
+    use crate::policy::{LazyMapMonoid, RangeSumRangeAdd};
+    use crate::traits::{
+        SequenceAgg, SequenceBase, SequenceLazy, SequenceReverse, SequenceSearch,
+        SequenceSplitMerge,
+    };
     use rand::rngs::StdRng;
     use rand::{Rng, SeedableRng};
 
@@ -682,5 +815,205 @@ mod tests {
                 }
             }
         }
+        assert_eq!(tree.to_vec(), vec);
+        assert!(tree.iter().eq(vec.iter()));
+    }
+
+    #[test]
+    fn set_matches_vec() {
+        let mut rng = StdRng::seed_from_u64(0x5E70_2026);
+        let values: Vec<i64> = (0..200).map(|_| rng.random_range(-1000..=1000)).collect();
+        let mut tree = ImplicitAaTree::<RangeSumRangeAdd>::from_slice(&values);
+        let mut vec = values;
+
+        for _ in 0..2000 {
+            let l = rng.random_range(0..vec.len());
+            let r = rng.random_range((l + 1)..=vec.len());
+            match rng.random_range(0..4) {
+                0 => {
+                    let value = rng.random_range(-1000..=1000);
+                    tree.set(l, value);
+                    vec[l] = value;
+                }
+                1 => {
+                    let add = rng.random_range(-10..=10);
+                    tree.update(l..r, add);
+                    vec[l..r].iter_mut().for_each(|value| *value += add);
+                }
+                2 => {
+                    tree.reverse(l..r);
+                    vec[l..r].reverse();
+                }
+                _ => assert_eq!(tree.fold(l..r), vec[l..r].iter().sum::<i64>()),
+            }
+        }
+        tree.set(vec.len(), 0);
+        assert_eq!(tree.to_vec(), vec);
+    }
+
+    #[test]
+    fn drain_matches_vec_drain() {
+        let mut rng = StdRng::seed_from_u64(0xD4A1_2026);
+        let values: Vec<i64> = (0..300).map(|_| rng.random_range(-1000..=1000)).collect();
+        let mut tree = ImplicitAaTree::<RangeSumRangeAdd>::from_slice(&values);
+        let mut vec = values;
+
+        for _ in 0..50 {
+            let l = rng.random_range(0..=vec.len());
+            let r = rng.random_range(l..=vec.len());
+            let mut drained = tree.drain(l..r);
+            let expected: Vec<i64> = vec.drain(l..r).collect();
+            assert_eq!(drained.to_vec(), expected);
+            assert_eq!(drained.len(), expected.len());
+            assert_eq!(tree.len(), vec.len());
+            assert_eq!(tree.fold(..), vec.iter().sum::<i64>());
+
+            let index = rng.random_range(0..=vec.len());
+            let mut right = tree.split_at(index);
+            tree.merge(drained);
+            right.update(.., 1);
+            tree.merge(right);
+            let tail = vec.split_off(index);
+            vec.extend(expected);
+            vec.extend(tail.iter().map(|value| value + 1));
+            assert_eq!(tree.to_vec(), vec);
+        }
+    }
+
+    #[test]
+    fn concat_matches_flattened_parts() {
+        let mut rng = StdRng::seed_from_u64(0xC0CA_2026);
+        for count in [0, 1, 2, 7, 64] {
+            let contents: Vec<Vec<i64>> = (0..count)
+                .map(|_| {
+                    let len = rng.random_range(0..20);
+                    (0..len).map(|_| rng.random_range(-1000..=1000)).collect()
+                })
+                .collect();
+            let parts = contents
+                .iter()
+                .map(|values| ImplicitAaTree::<RangeSumRangeAdd>::from_slice(values))
+                .collect();
+            let mut tree = ImplicitAaTree::concat(parts);
+            let expected = contents.concat();
+            assert_eq!(tree.len(), expected.len());
+            assert_eq!(tree.fold(..), expected.iter().sum::<i64>());
+            assert_eq!(tree.to_vec(), expected);
+        }
+    }
+
+    #[test]
+    fn sorted_search_matches_binary_search() {
+        let mut rng = StdRng::seed_from_u64(0x5027_2026);
+        let mut tree = ImplicitAaTree::<RangeSumRangeAdd>::new();
+        let mut vec = Vec::<i64>::new();
+
+        for _ in 0..1000 {
+            let key = rng.random_range(-200..=200);
+            let lower = tree.lower_bound_sorted(&key);
+            let upper = tree.upper_bound_sorted(&key);
+            assert_eq!(lower, vec.partition_point(|&x| x < key));
+            assert_eq!(upper, vec.partition_point(|&x| x <= key));
+            match vec.binary_search(&key) {
+                Ok(index) => assert!(lower <= index && index < upper),
+                Err(index) => assert!(lower == index && upper == index),
+            }
+
+            if rng.random_bool(0.25) && lower < upper {
+                assert_eq!(tree.remove(lower), Some(vec.remove(lower)));
+            } else {
+                tree.insert(upper, key);
+                vec.insert(upper, key);
+            }
+        }
+        assert_eq!(tree.to_vec(), vec);
+    }
+
+    #[test]
+    fn deque_operations_match_vecdeque() {
+        let mut rng = StdRng::seed_from_u64(0xDE0E_2026);
+        let mut tree = ImplicitAaTree::<RangeSumRangeAdd>::new();
+        let mut deque = VecDeque::<i64>::new();
+
+        for _ in 0..3000 {
+            match rng.random_range(0..6) {
+                0 => {
+                    let value = rng.random_range(-1000..=1000);
+                    tree.push_front(value);
+                    deque.push_front(value);
+                }
+                1 => {
+                    let value = rng.random_range(-1000..=1000);
+                    tree.push_back(value);
+                    deque.push_back(value);
+                }
+                2 => assert_eq!(tree.pop_front(), deque.pop_front()),
+                3 => assert_eq!(tree.pop_back(), deque.pop_back()),
+                4 => {
+                    if deque.is_empty() {
+                        continue;
+                    }
+                    let l = rng.random_range(0..deque.len());
+                    let r = rng.random_range((l + 1)..=deque.len());
+                    tree.reverse(l..r);
+                    deque.make_contiguous()[l..r].reverse();
+                }
+                _ => {
+                    let expected: i64 = deque.iter().sum();
+                    assert_eq!(tree.fold(..), expected);
+                }
+            }
+            assert_eq!(tree.len(), deque.len());
+        }
+        while let Some(value) = deque.pop_back() {
+            assert_eq!(tree.pop_back(), Some(value));
+        }
+        assert_eq!(tree.pop_front(), None);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn from_slice_matches_values() {
+        let mut rng = StdRng::seed_from_u64(0xF50A_2026);
+        for n in [0, 1, 2, 3, 4, 5, 7, 8, 26, 27, 100, 1000] {
+            let values: Vec<i64> = (0..n).map(|_| rng.random_range(-1000..=1000)).collect();
+            let mut tree = ImplicitAaTree::<RangeSumRangeAdd>::from_slice(&values);
+            check_levels(&tree.root);
+            assert_eq!(tree.len(), n);
+            assert_eq!(tree.fold(0..n), values.iter().sum::<i64>());
+            for (i, value) in values.iter().enumerate() {
+                assert_eq!(tree.get(i), Some(value));
+            }
+
+            let mut vec = values;
+            for _ in 0..100 {
+                let index = rng.random_range(0..=vec.len());
+                tree.insert(index, index as i64);
+                vec.insert(index, index as i64);
+                let index = rng.random_range(0..vec.len());
+                assert_eq!(tree.remove(index), Some(vec.remove(index)));
+            }
+            assert_eq!(tree.to_vec(), vec);
+        }
+    }
+
+    fn check_levels<P: LazyMapMonoid>(node: &Link<P>) {
+        let Some(node) = node else {
+            return;
+        };
+        assert_eq!(Node::level(&node.left) + 1, node.level, "left level");
+        let right = Node::level(&node.right);
+        assert!(
+            right + 1 == node.level || right == node.level,
+            "right level"
+        );
+        if let Some(right) = node.right.as_ref() {
+            assert!(
+                Node::level(&right.right) < node.level,
+                "double horizontal link"
+            );
+        }
+        check_levels(&node.left);
+        check_levels(&node.right);
     }
 }
@@ -0,0 +1,236 @@
+//! Root-to-leaf walks shared by every tree: `max_right`/`min_left`,
+//! `partition_point` and `set`. A tree exposes its child links through
+//! [`DescentLink`]; the walks push pending tags and read or recompute sizes
+//! and aggregates, but never restructure.
+
+use std::cmp::Ordering;
+
+use crate::policy::LazyMapMonoid;
+
+/// Access to the node behind an owning child link (`Box`, or the persistent
+/// red-black tree's copy-on-write pointer).
+pub trait DescentLink<P: LazyMapMonoid>: Sized {
+    /// Pushes pending tags so both children's sizes and aggregates are current.
+    fn push(&mut self);
+    fn agg(&self) -> &P::Agg;
+    fn size(&self) -> usize;
+    /// Recomputes the size and aggregates from the key and both children.
+    fn recalc(&mut self);
+    /// The key and both children, borrowed together.
+    fn parts(&mut self) -> (&mut P::Key, &mut Option<Self>, &mut Option<Self>);
+}
+
+/// Number of leading keys satisfying `pred`, which must hold on a prefix of
+/// the sequence and fail on the rest.
+pub(crate) fn partition_point<P, L, F>(root: &mut Option<L>, pred: F) -> usize
+where
+    P: LazyMapMonoid,
+    L: DescentLink<P>,
+    F: Fn(&P::Key) -> bool,
+{
+    let mut cur = root;
+    let mut index = 0;
+    while let Some(node) = cur.as_mut() {
+        node.push();
+        let (key, left, right) = node.parts();
+        if pred(key) {
+            index += left.as_ref().map_or(0, L::size) + 1;
+            cur = right;
+        } else {
+            cur = left;
+        }
+    }
+    index
+}
+
+/// Overwrites the key at `index`, pushing tags on the way down and
+/// recalculating aggregates on the way back up.
+pub(crate) fn set_at<P, L>(link: &mut Option<L>, index: usize, key: P::Key)
+where
+    P: LazyMapMonoid,
+    L: DescentLink<P>,
+{
+    let Some(node) = link.as_mut() else {
+        return;
+    };
+    node.push();
+    let (node_key, left, right) = node.parts();
+    let left_size = left.as_ref().map_or(0, L::size);
+    match index.cmp(&left_size) {
+        Ordering::Less => set_at(left, index, key),
+        Ordering::Equal => *node_key = key,
+        Ordering::Greater => set_at(right, index - left_size - 1, key),
+    }
+    node.recalc();
+}
+
+/// Largest `end` with `pred(fold(start..end))`. The suffix from `start` is
+/// the (key, right subtree) pairs of the nodes where the walk to `start`
+/// turns left, deepest first; the scan stops in the first pair that fails.
+pub(crate) fn max_right_from<P, L, F>(root: &mut Option<L>, start: usize, pred: &F) -> usize
+where
+    P: LazyMapMonoid,
+    L: DescentLink<P>,
+    F: Fn(&P::Agg) -> bool,
+{
+    let mut pieces = Vec::new();
+    let mut cur = root;
+    let mut offset = start;
+    while let Some(node) = cur.as_mut() {
+        node.push();
+        let (key, left, right) = node.parts();
+        let left_size = left.as_ref().map_or(0, L::size);
+        if offset <= left_size {
+            pieces.push((&*key, right));
+            cur = left;
+        } else {
+            offset -= left_size + 1;
+            cur = right;
+        }
+    }
+
+    let unit = P::agg_unit();
+    let mut acc = P::agg_unit();
+    let mut end = start;
+    while let Some((key, right)) = pieces.pop() {
+        if !pred(&P::agg_merge(&acc, key, &unit)) {
+            return end;
+        }
+        let Some(right_node) = right.as_ref() else {
+            acc = P::agg_merge(&acc, key, &unit);
+            end += 1;
+            continue;
+        };
+        let whole = P::agg_merge(&acc, key, right_node.agg());
+        if pred(&whole) {
+            acc = whole;
+            end += 1 + right_node.size();
+            continue;
+        }
+        return end + 1 + max_prefix(right, acc, key, pred);
+    }
+    end
+}
+
+/// Length of the longest prefix of `cur` that still satisfies `pred` when
+/// folded after `acc` and `key`.
+fn max_prefix<'a, P, L, F>(
+    mut cur: &'a mut Option<L>,
+    mut acc: P::Agg,
+    mut key: &'a P::Key,
+    pred: &F,
+) -> usize
+where
+    P: LazyMapMonoid,
+    L: DescentLink<P>,
+    F: Fn(&P::Agg) -> bool,
+{
+    let unit = P::agg_unit();
+    let mut len = 0;
+    while let Some(node) = cur.as_mut() {
+        node.push();
+        let (node_key, left, right) = node.parts();
+        let with_left = match left.as_ref() {
+            Some(left) => P::agg_merge(&acc, key, left.agg()),
+            None => P::agg_merge(&acc, key, &unit),
+        };
+        if !pred(&with_left) {
+            cur = left;
+            continue;
+        }
+        len += left.as_ref().map_or(0, L::size);
+        if !pred(&P::agg_merge(&with_left, node_key, &unit)) {
+            return len;
+        }
+        len += 1;
+        acc = with_left;
+        key = node_key;
+        cur = right;
+    }
+    len
+}
+
+/// Mirror of [`max_right_from`]: smallest `start` with
+/// `pred(fold(start..end))`.
+pub(crate) fn min_left_from<P, L, F>(root: &mut Option<L>, end: usize, pred: &F) -> usize
+where
+    P: LazyMapMonoid,
+    L: DescentLink<P>,
+    F: Fn(&P::Agg) -> bool,
+{
+    let mut pieces = Vec::new();
+    let mut cur = root;
+    let mut offset = end;
+    while let Some(node) = cur.as_mut() {
+        node.push();
+        let (key, left, right) = node.parts();
+        let left_size = left.as_ref().map_or(0, L::size);
+        if offset > left_size {
+            offset -= left_size + 1;
+            pieces.push((left, &*key));
+            cur = right;
+        } else {
+            cur = left;
+        }
+    }
+
+    let unit = P::agg_unit();
+    let mut acc = P::agg_unit();
+    let mut start = end;
+    while let Some((left, key)) = pieces.pop() {
+        if !pred(&P::agg_merge(&unit, key, &acc)) {
+            return start;
+        }
+        let Some(left_node) = left.as_ref() else {
+            acc = P::agg_merge(&unit, key, &acc);
+            start -= 1;
+            continue;
+        };
+        let whole = P::agg_merge(left_node.agg(), key, &acc);
+        if pred(&whole) {
+            acc = whole;
+            start -= 1 + left_node.size();
+            continue;
+        }
+        return start - 1 - max_suffix(left, key, acc, pred);
+    }
+    start
+}
+
+/// Length of the longest suffix of `cur` that still satisfies `pred` when
+/// folded before `key` and `acc`.
+fn max_suffix<'a, P, L, F>(
+    mut cur: &'a mut Option<L>,
+    mut key: &'a P::Key,
+    mut acc: P::Agg,
+    pred: &F,
+) -> usize
+where
+    P: LazyMapMonoid,
+    L: DescentLink<P>,
+    F: Fn(&P::Agg) -> bool,
+{
+    let unit = P::agg_unit();
+    let mut len = 0;
+    while let Some(node) = cur.as_mut() {
+        node.push();
+        let (node_key, left, right) = node.parts();
+        let with_right = match right.as_ref() {
+            Some(right) => P::agg_merge(right.agg(), key, &acc),
+            None => P::agg_merge(&unit, key, &acc),
+        };
+        if !pred(&with_right) {
+            cur = right;
+            continue;
+        }
+        len += right.as_ref().map_or(0, L::size);
+        if !pred(&P::agg_merge(&unit, node_key, &with_right)) {
+            return len;
+        }
+        len += 1;
+        acc = with_right;
+        key = node_key;
+        cur = left;
+    }
+    len
+}
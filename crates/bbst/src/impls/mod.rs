@@ -1,5 +1,6 @@
 pub mod aa;
 pub mod avl;
+mod descent;
 pub mod llrb;
 pub mod rb;
 pub mod rbst;
@@ -7,3 +8,351 @@ pub mod splay;
 pub mod treap;
 pub mod wbt;
 pub mod zip;
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        aa::ImplicitAaTree,
+        avl::ImplicitAvl,
+        llrb::ImplicitLlrbTree,
+        rb::{ImplicitRbTree, PersistentSequence},
+        rbst::ImplicitRbst,
+        splay::ImplicitSplay,
+        treap::ImplicitTreap,
+        wbt::ImplicitWbt,
+        zip::ImplicitZipTree,
+    };
+    use crate::policy::{LazyMapMonoid, RangeSumRangeAdd};
+    use crate::traits::{SequenceLazy, SequenceReverse, SequenceSplitMerge};
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// `RangeSumRangeAdd` with the reversal shortcut switched by `INVARIANT`.
+    struct SumAdd<const INVARIANT: bool>;
+
+    impl<const INVARIANT: bool> LazyMapMonoid for SumAdd<INVARIANT> {
+        type Key = i64;
+        type Agg = i64;
+        type Act = i64;
+
+        const REVERSAL_INVARIANT: bool = INVARIANT;
+
+        fn agg_unit() -> i64 {
+            RangeSumRangeAdd::agg_unit()
+        }
+
+        fn agg_from_key(key: &i64) -> i64 {
+            RangeSumRangeAdd::agg_from_key(key)
+        }
+
+        fn agg_merge(left: &i64, key: &i64, right: &i64) -> i64 {
+            RangeSumRangeAdd::agg_merge(left, key, right)
+        }
+
+        fn act_unit() -> i64 {
+            RangeSumRangeAdd::act_unit()
+        }
+
+        fn act_compose(new: &i64, old: &i64) -> i64 {
+            RangeSumRangeAdd::act_compose(new, old)
+        }
+
+        fn act_apply_key(key: &i64, act: &i64) -> i64 {
+            RangeSumRangeAdd::act_apply_key(key, act)
+        }
+
+        fn act_apply_agg(agg: &i64, act: &i64, len: usize) -> i64 {
+            RangeSumRangeAdd::act_apply_agg(agg, act, len)
+        }
+    }
+
+    trait Tree: SequenceLazy<Key = i64, Agg = i64, Act = i64> + SequenceReverse + Default {}
+
+    impl<T: SequenceLazy<Key = i64, Agg = i64, Act = i64> + SequenceReverse + Default> Tree for T {}
+
+    /// Replays the same random workload on both trees and a `Vec`, comparing
+    /// every fold and the final contents.
+    fn check_same_results<Fast: Tree, Full: Tree>(seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut fast = Fast::default();
+        let mut full = Full::default();
+        let mut vec = Vec::<i64>::new();
+
+        for _ in 0..1500 {
+            let len = vec.len();
+            match rng.random_range(0..6) {
+                0 | 1 => {
+                    let index = rng.random_range(0..=len);
+                    let value = rng.random_range(-1000..=1000);
+                    fast.insert(index, value);
+                    full.insert(index, value);
+                    vec.insert(index, value);
+                }
+                2 if len > 0 => {
+                    let index = rng.random_range(0..len);
+                    assert_eq!(fast.remove(index), Some(vec.remove(index)));
+                    full.remove(index);
+                }
+                3 => {
+                    let l = rng.random_range(0..=len);
+                    let r = rng.random_range(l..=len);
+                    fast.reverse(l..r);
+                    full.reverse(l..r);
+                    vec[l..r].reverse();
+                }
+                4 => {
+                    let l = rng.random_range(0..=len);
+                    let r = rng.random_range(l..=len);
+                    let act = rng.random_range(-50..=50);
+                    fast.update(l..r, act);
+                    full.update(l..r, act);
+                    vec[l..r].iter_mut().for_each(|x| *x += act);
+                }
+                _ => {
+                    let l = rng.random_range(0..=len);
+                    let r = rng.random_range(l..=len);
+                    let expected: i64 = vec[l..r].iter().sum();
+                    assert_eq!(fast.fold(l..r), expected);
+                    assert_eq!(full.fold(l..r), expected);
+                }
+            }
+        }
+        assert_eq!(fast.to_vec(), vec);
+        assert_eq!(full.to_vec(), vec);
+    }
+
+    #[test]
+    fn reversal_invariant_policy_matches_full_maintenance() {
+        check_same_results::<ImplicitTreap<SumAdd<true>>, ImplicitTreap<SumAdd<false>>>(1);
+        check_same_results::<ImplicitSplay<SumAdd<true>>, ImplicitSplay<SumAdd<false>>>(2);
+        check_same_results::<ImplicitWbt<SumAdd<true>>, ImplicitWbt<SumAdd<false>>>(3);
+        check_same_results::<ImplicitZipTree<SumAdd<true>>, ImplicitZipTree<SumAdd<false>>>(4);
+        check_same_results::<ImplicitRbst<SumAdd<true>>, ImplicitRbst<SumAdd<false>>>(5);
+        check_same_results::<ImplicitAaTree<SumAdd<true>>, ImplicitAaTree<SumAdd<false>>>(6);
+        check_same_results::<ImplicitAvl<SumAdd<true>>, ImplicitAvl<SumAdd<false>>>(7);
+        check_same_results::<ImplicitRbTree<SumAdd<true>>, ImplicitRbTree<SumAdd<false>>>(8);
+        check_same_results::<ImplicitLlrbTree<SumAdd<true>>, ImplicitLlrbTree<SumAdd<false>>>(9);
+    }
+
+    const MOD: i64 = 998_244_353;
+
+    /// `x -> a * x + b` modulo a prime, as `(a, b)`.
+    type Affine = (i64, i64);
+
+    /// Applies `first`, then `then`.
+    fn affine_then(first: Affine, then: Affine) -> Affine {
+        (first.0 * then.0 % MOD, (first.1 * then.0 + then.1) % MOD)
+    }
+
+    /// Composes the keys' maps left to right, with range assignment. The
+    /// fold depends on order, so `reverse` is only right if `agg_rev` is kept.
+    struct AffineAssign;
+
+    impl LazyMapMonoid for AffineAssign {
+        type Key = Affine;
+        type Agg = Affine;
+        type Act = Option<Affine>;
+
+        fn agg_unit() -> Affine {
+            (1, 0)
+        }
+
+        fn agg_from_key(key: &Affine) -> Affine {
+            *key
+        }
+
+        fn agg_merge(left: &Affine, key: &Affine, right: &Affine) -> Affine {
+            affine_then(affine_then(*left, *key), *right)
+        }
+
+        fn act_unit() -> Option<Affine> {
+            None
+        }
+
+        fn act_compose(new: &Option<Affine>, old: &Option<Affine>) -> Option<Affine> {
+            new.or(*old)
+        }
+
+        fn act_apply_key(key: &Affine, act: &Option<Affine>) -> Affine {
+            act.unwrap_or(*key)
+        }
+
+        fn act_apply_agg(agg: &Affine, act: &Option<Affine>, len: usize) -> Affine {
+            let Some(mut base) = *act else {
+                return *agg;
+            };
+            let (mut pow, mut len) = (Self::agg_unit(), len);
+            while len > 0 {
+                if len & 1 == 1 {
+                    pow = affine_then(pow, base);
+                }
+                base = affine_then(base, base);
+                len >>= 1;
+            }
+            pow
+        }
+    }
+
+    /// Reverses, assigns and folds random ranges of `AffineAssign` keys,
+    /// checking every fold against composing a `Vec` in order.
+    fn check_ordered_fold<T>(seed: u64)
+    where
+        T: SequenceLazy<Key = Affine, Agg = Affine, Act = Option<Affine>>
+            + SequenceReverse
+            + Default,
+    {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut tree = T::default();
+        let mut vec = Vec::<Affine>::new();
+        let random_map = |rng: &mut StdRng| (rng.random_range(1..MOD), rng.random_range(0..MOD));
+
+        for _ in 0..1500 {
+            let len = vec.len();
+            match rng.random_range(0..6) {
+                0 | 1 => {
+                    let index = rng.random_range(0..=len);
+                    let map = random_map(&mut rng);
+                    tree.insert(index, map);
+                    vec.insert(index, map);
+                }
+                2 if len > 0 => {
+                    let index = rng.random_range(0..len);
+                    assert_eq!(tree.remove(index), Some(vec.remove(index)));
+                }
+                3 => {
+                    let l = rng.random_range(0..=len);
+                    let r = rng.random_range(l..=len);
+                    tree.reverse(l..r);
+                    vec[l..r].reverse();
+                }
+                4 => {
+                    let l = rng.random_range(0..=len);
+                    let r = rng.random_range(l..=len);
+                    let map = random_map(&mut rng);
+                    tree.update(l..r, Some(map));
+                    vec[l..r].fill(map);
+                }
+                _ => {
+                    let l = rng.random_range(0..=len);
+                    let r = rng.random_range(l..=len);
+                    let expected = vec[l..r].iter().fold((1, 0), |acc, &m| affine_then(acc, m));
+                    assert_eq!(tree.fold(l..r), expected, "fold {l}..{r}");
+                }
+            }
+        }
+        assert_eq!(tree.to_vec(), vec);
+    }
+
+    #[test]
+    fn reverse_keeps_order_sensitive_folds() {
+        check_ordered_fold::<ImplicitTreap<AffineAssign>>(31);
+        check_ordered_fold::<ImplicitSplay<AffineAssign>>(32);
+        check_ordered_fold::<ImplicitWbt<AffineAssign>>(33);
+        check_ordered_fold::<ImplicitZipTree<AffineAssign>>(34);
+        check_ordered_fold::<ImplicitRbst<AffineAssign>>(35);
+        check_ordered_fold::<ImplicitAaTree<AffineAssign>>(36);
+        check_ordered_fold::<ImplicitAvl<AffineAssign>>(37);
+        check_ordered_fold::<ImplicitRbTree<AffineAssign>>(38);
+        check_ordered_fold::<ImplicitLlrbTree<AffineAssign>>(39);
+        check_ordered_fold::<PersistentSequence<AffineAssign>>(40);
+    }
+
+    /// `max_right`/`min_left` on prefix sums against a linear scan, with
+    /// lazy adds and reversals pending along the descents.
+    fn check_max_right_min_left<T: Tree>(seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut tree = T::default();
+        let mut vec = Vec::<i64>::new();
+
+        for _ in 0..400 {
+            let index = rng.random_range(0..=vec.len());
+            let value = rng.random_range(0..=20);
+            tree.insert(index, value);
+            vec.insert(index, value);
+
+            let l = rng.random_range(0..vec.len());
+            let r = rng.random_range(l..=vec.len());
+            let add = rng.random_range(0..=3);
+            tree.update(l..r, add);
+            vec[l..r].iter_mut().for_each(|value| *value += add);
+            tree.reverse(l..r);
+            vec[l..r].reverse();
+
+            let limit = rng.random_range(0..=300);
+            let start = rng.random_range(0..=vec.len());
+            let (mut expected, mut sum) = (start, 0);
+            while expected < vec.len() && sum + vec[expected] <= limit {
+                sum += vec[expected];
+                expected += 1;
+            }
+            assert_eq!(tree.max_right(start, |&sum| sum <= limit), expected);
+
+            let end = rng.random_range(0..=vec.len());
+            let (mut expected, mut sum) = (end, 0);
+            while expected > 0 && sum + vec[expected - 1] <= limit {
+                expected -= 1;
+                sum += vec[expected];
+            }
+            assert_eq!(tree.min_left(end, |&sum| sum <= limit), expected);
+        }
+        assert_eq!(tree.to_vec(), vec);
+    }
+
+    #[test]
+    fn max_right_and_min_left_match_scan() {
+        check_max_right_min_left::<ImplicitTreap<RangeSumRangeAdd>>(21);
+        check_max_right_min_left::<ImplicitSplay<RangeSumRangeAdd>>(22);
+        check_max_right_min_left::<ImplicitWbt<RangeSumRangeAdd>>(23);
+        check_max_right_min_left::<ImplicitZipTree<RangeSumRangeAdd>>(24);
+        check_max_right_min_left::<ImplicitRbst<RangeSumRangeAdd>>(25);
+        check_max_right_min_left::<ImplicitAaTree<RangeSumRangeAdd>>(26);
+        check_max_right_min_left::<ImplicitAvl<RangeSumRangeAdd>>(27);
+        check_max_right_min_left::<ImplicitRbTree<RangeSumRangeAdd>>(28);
+        check_max_right_min_left::<ImplicitLlrbTree<RangeSumRangeAdd>>(29);
+        check_max_right_min_left::<PersistentSequence<RangeSumRangeAdd>>(30);
+    }
+
+    fn check_rotate_range<T: SequenceSplitMerge<Key = i64> + Default>(seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut tree = T::default();
+        let mut vec: Vec<i64> = (0..200).collect();
+        tree.extend(vec.iter().copied());
+
+        for _ in 0..300 {
+            let l = rng.random_range(0..=vec.len());
+            let r = rng.random_range(l..=vec.len());
+            let k = rng.random_range(0..3 * (r - l + 1));
+            tree.rotate_range(l..r, k);
+            if r > l {
+                vec[l..r].rotate_left(k % (r - l));
+            }
+            assert_eq!(tree.len(), vec.len());
+        }
+        tree.rotate_range(.., 0);
+        tree.rotate_range(..=9, 10);
+        tree.rotate_range(5..5, 3);
+        tree.rotate_range(190.., 3);
+        vec[190..].rotate_left(3);
+        assert_eq!(tree.to_vec(), vec);
+    }
+
+    #[test]
+    fn rotate_range_matches_slice_rotate_left() {
+        check_rotate_range::<ImplicitTreap<RangeSumRangeAdd>>(11);
+        check_rotate_range::<ImplicitSplay<RangeSumRangeAdd>>(12);
+        check_rotate_range::<ImplicitWbt<RangeSumRangeAdd>>(13);
+        check_rotate_range::<ImplicitZipTree<RangeSumRangeAdd>>(14);
+        check_rotate_range::<ImplicitRbst<RangeSumRangeAdd>>(15);
+        check_rotate_range::<ImplicitAaTree<RangeSumRangeAdd>>(16);
+        check_rotate_range::<ImplicitAvl<RangeSumRangeAdd>>(17);
+        check_rotate_range::<ImplicitRbTree<RangeSumRangeAdd>>(18);
+        check_rotate_range::<ImplicitLlrbTree<RangeSumRangeAdd>>(19);
+    }
+
+    #[test]
+    #[should_panic(expected = "rotate range 3..9 out of bounds for length 5")]
+    fn rotate_range_rejects_out_of_bounds() {
+        let mut tree = ImplicitAvl::<RangeSumRangeAdd>::from_slice(&[1, 2, 3, 4, 5]);
+        tree.rotate_range(3..9, 1);
+    }
+}
@@ -1,56 +1,192 @@
 use std::ops::{Bound, RangeBounds};
 
 use crate::policy::LazyMapMonoid;
-use crate::traits::{SequenceAgg, SequenceBase, SequenceLazy, SequenceReverse, SequenceSplitMerge};
+use crate::traits::{
+    SequenceAgg, SequenceBase, SequenceLazy, SequenceReverse, SequenceSearch, SequenceSplitMerge,
+};
 
-pub struct ImplicitRbTree<P: LazyMapMonoid> {
-    root: Link<P>,
+use super::descent::{self, DescentLink};
+
+use node::{CowNode, Node, NodePtr, Sharing};
+
+/// Red-black tree over an implicit sequence. `S` picks how nodes hold their
+/// children: [`Unique`] boxes them, [`Shared`] shares them copy-on-write (see
+/// [`PersistentSequence`]).
+pub struct ImplicitRbTree<P: LazyMapMonoid, S: Sharing<P> = Unique> {
+    root: Link<P, S>,
     len: u32,
-    split_left_stack: Vec<Box<Node<P>>>,
-    split_right_stack: Vec<Box<Node<P>>>,
+    split_left_stack: Vec<S::Ptr>,
+    split_right_stack: Vec<S::Ptr>,
 }
 
-struct Node<P: LazyMapMonoid> {
-    key: P::Key,
-    agg: P::Agg,
-    agg_rev: P::Agg,
-    lazy: P::Act,
-    lazy_pending: bool,
-    rev: bool,
-    size: u32,
-    red: bool,
-    black_height: u8,
-    left: Link<P>,
-    right: Link<P>,
-}
+/// Children in a `Box`, owned by one tree.
+pub struct Unique;
 
-type Link<P> = Option<Box<Node<P>>>;
+/// Children behind reference counts, shared between versions and copied on
+/// the first write.
+pub struct Shared;
 
-impl<P> Clone for Node<P>
-where
-    P: LazyMapMonoid,
-    P::Key: Clone,
-    P::Agg: Clone,
-    P::Act: Clone,
-{
-    fn clone(&self) -> Self {
-        Self {
-            key: self.key.clone(),
-            agg: self.agg.clone(),
-            agg_rev: self.agg_rev.clone(),
-            lazy: self.lazy.clone(),
-            lazy_pending: self.lazy_pending,
-            rev: self.rev,
-            size: self.size,
-            red: self.red,
-            black_height: self.black_height,
-            left: self.left.clone(),
-            right: self.right.clone(),
+/// [`ImplicitRbTree`] over [`Shared`] nodes, so
+/// [`PersistentSequence::clone_persistent`] is O(1) and every later update
+/// copies only the O(log n) nodes on its path.
+pub type PersistentSequence<P> = ImplicitRbTree<P, Shared>;
+
+type Link<P, S> = Option<<S as Sharing<P>>::Ptr>;
+
+/// Node layout and child pointers. Public only so they can appear in the
+/// sealed [`Sharing`] bound; the module keeps them out of the crate's API.
+mod node {
+    use std::ops::{Deref, DerefMut};
+    use std::rc::Rc;
+
+    use super::{Link, Shared, Unique};
+    use crate::impls::descent::DescentLink;
+    use crate::policy::LazyMapMonoid;
+
+    pub trait Sharing<P: LazyMapMonoid>: Sized {
+        type Ptr: NodePtr<P, Self>;
+    }
+
+    /// Owning child pointer.
+    pub trait NodePtr<P: LazyMapMonoid, S: Sharing<P>>:
+        DerefMut<Target = Node<P, S>> + DescentLink<P>
+    {
+        fn new(node: Node<P, S>) -> Self;
+        fn into_inner(self) -> Node<P, S>;
+        /// A pointer to the same contents: a deep copy for a `Box`, another
+        /// reference for a shared node.
+        fn duplicate(&self) -> Self
+        where
+            P::Key: Clone;
+    }
+
+    pub struct Node<P: LazyMapMonoid, S: Sharing<P>> {
+        pub(super) key: P::Key,
+        pub(super) agg: P::Agg,
+        pub(super) agg_rev: P::Agg,
+        pub(super) lazy: P::Act,
+        pub(super) lazy_pending: bool,
+        pub(super) rev: bool,
+        pub(super) size: u32,
+        pub(super) red: bool,
+        pub(super) black_height: u8,
+        pub(super) left: Link<P, S>,
+        pub(super) right: Link<P, S>,
+    }
+
+    impl<P: LazyMapMonoid, S: Sharing<P>> Node<P, S> {
+        fn duplicate(&self) -> Self
+        where
+            P::Key: Clone,
+        {
+            Self {
+                key: self.key.clone(),
+                agg: self.agg.clone(),
+                agg_rev: self.agg_rev.clone(),
+                lazy: self.lazy.clone(),
+                lazy_pending: self.lazy_pending,
+                rev: self.rev,
+                size: self.size,
+                red: self.red,
+                black_height: self.black_height,
+                left: self.left.as_ref().map(NodePtr::duplicate),
+                right: self.right.as_ref().map(NodePtr::duplicate),
+            }
+        }
+    }
+
+    impl<P: LazyMapMonoid> Sharing<P> for Unique {
+        type Ptr = Box<Node<P, Unique>>;
+    }
+
+    impl<P: LazyMapMonoid> NodePtr<P, Unique> for Box<Node<P, Unique>> {
+        fn new(node: Node<P, Unique>) -> Self {
+            Box::new(node)
+        }
+
+        fn into_inner(self) -> Node<P, Unique> {
+            *self
+        }
+
+        fn duplicate(&self) -> Self
+        where
+            P::Key: Clone,
+        {
+            Box::new(Node::duplicate(self))
+        }
+    }
+
+    impl<P: LazyMapMonoid> Sharing<P> for Shared
+    where
+        P::Key: Clone,
+    {
+        type Ptr = CowNode<P>;
+    }
+
+    /// Copy-on-write child pointer. Duplicating one only bumps a reference
+    /// count; the first mutable access to a shared node copies that node
+    /// alone (its children stay shared), so a write never shows through in
+    /// another version.
+    pub struct CowNode<P: LazyMapMonoid>(pub(super) Rc<Node<P, Shared>>)
+    where
+        P::Key: Clone;
+
+    impl<P: LazyMapMonoid> NodePtr<P, Shared> for CowNode<P>
+    where
+        P::Key: Clone,
+    {
+        fn new(node: Node<P, Shared>) -> Self {
+            Self(Rc::new(node))
+        }
+
+        fn into_inner(self) -> Node<P, Shared> {
+            Rc::try_unwrap(self.0).unwrap_or_else(|shared| shared.duplicate())
+        }
+
+        fn duplicate(&self) -> Self {
+            Self(Rc::clone(&self.0))
+        }
+    }
+
+    impl<P: LazyMapMonoid> Deref for CowNode<P>
+    where
+        P::Key: Clone,
+    {
+        type Target = Node<P, Shared>;
+
+        fn deref(&self) -> &Node<P, Shared> {
+            &self.0
+        }
+    }
+
+    impl<P: LazyMapMonoid> DerefMut for CowNode<P>
+    where
+        P::Key: Clone,
+    {
+        // `Rc::make_mut` with the copy moved out of line: nearly every access
+        // hits a unique node, and inlining that check keeps the hot paths
+        // close to the `Box` version.
+        #[inline(always)]
+        fn deref_mut(&mut self) -> &mut Node<P, Shared> {
+            if Rc::get_mut(&mut self.0).is_none() {
+                self.unshare();
+            }
+            Rc::get_mut(&mut self.0).expect("node is unique after unshare")
+        }
+    }
+
+    impl<P: LazyMapMonoid> CowNode<P>
+    where
+        P::Key: Clone,
+    {
+        #[cold]
+        fn unshare(&mut self) {
+            self.0 = Rc::new(self.0.duplicate());
         }
     }
 }
 
-impl<P: LazyMapMonoid> Node<P> {
+impl<P: LazyMapMonoid, S: Sharing<P>> Node<P, S> {
     fn new(key: P::Key, red: bool) -> Self {
         let agg = P::agg_from_key(&key);
         Self {
@@ -68,7 +204,7 @@ impl<P: LazyMapMonoid> Node<P> {
         }
     }
 
-    fn size(node: &Link<P>) -> u32 {
+    fn size(node: &Link<P, S>) -> u32 {
         node.as_ref().map(|n| n.size).unwrap_or(0)
     }
 
@@ -86,7 +222,9 @@ impl<P: LazyMapMonoid> Node<P> {
 
         self.size = 1 + left_size + right_size;
         self.agg = P::agg_merge(left_agg, &self.key, right_agg);
-        self.agg_rev = P::agg_merge(right_rev, &self.key, left_rev);
+        if !P::REVERSAL_INVARIANT {
+            self.agg_rev = P::agg_merge(right_rev, &self.key, left_rev);
+        }
         self.recalc_black_height();
     }
 
@@ -100,7 +238,9 @@ impl<P: LazyMapMonoid> Node<P> {
         self.key = P::act_apply_key(&self.key, act);
         let size = self.size as usize;
         self.agg = P::act_apply_agg(&self.agg, act, size);
-        self.agg_rev = P::act_apply_agg(&self.agg_rev, act, size);
+        if !P::REVERSAL_INVARIANT {
+            self.agg_rev = P::act_apply_agg(&self.agg_rev, act, size);
+        }
         self.lazy = P::act_compose(act, &self.lazy);
         self.lazy_pending = true;
     }
@@ -108,7 +248,21 @@ impl<P: LazyMapMonoid> Node<P> {
     fn apply_reverse(&mut self) {
         self.rev = !self.rev;
         std::mem::swap(&mut self.left, &mut self.right);
-        std::mem::swap(&mut self.agg, &mut self.agg_rev);
+        if !P::REVERSAL_INVARIANT {
+            std::mem::swap(&mut self.agg, &mut self.agg_rev);
+        }
+    }
+
+    /// Pushes every pending tag in the subtree down to the leaves, so the keys
+    /// can be read without further `push` calls.
+    fn push_all(root: &mut Link<P, S>) {
+        let mut stack: Vec<&mut Node<P, S>> = root.as_deref_mut().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            node.push();
+            let Node { left, right, .. } = node;
+            stack.extend(left.as_deref_mut());
+            stack.extend(right.as_deref_mut());
+        }
     }
 
     fn push(&mut self) {
@@ -136,7 +290,7 @@ impl<P: LazyMapMonoid> Node<P> {
     }
 }
 
-impl<P: LazyMapMonoid> ImplicitRbTree<P> {
+impl<P: LazyMapMonoid, S: Sharing<P>> ImplicitRbTree<P, S> {
     pub fn new() -> Self {
         Self::with_seed(0)
     }
@@ -150,6 +304,60 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         }
     }
 
+    /// Builds a tree holding `values` in order in O(n).
+    ///
+    /// The tree is laid out as a 2-3 tree with all leaves at depth
+    /// `log2(n + 1)`: a 2-node is a black node, and a 3-node is a black node
+    /// with a red left child, so the result is also left-leaning.
+    pub fn from_slice(values: &[P::Key]) -> Self
+    where
+        P::Key: Clone,
+    {
+        let mut tree = Self::new();
+        let black_height = (values.len() + 1).ilog2();
+        tree.root = Self::build(values, black_height);
+        tree.len = values.len() as u32;
+        tree
+    }
+
+    /// Needs `2^black_height - 1 <= values.len() <= 3^black_height - 1`.
+    fn build(values: &[P::Key], black_height: u32) -> Link<P, S>
+    where
+        P::Key: Clone,
+    {
+        if black_height == 0 {
+            debug_assert!(values.is_empty());
+            return None;
+        }
+        let n = values.len();
+        let max_child = 3_usize.saturating_pow(black_height - 1) - 1;
+
+        if n - 1 <= max_child.saturating_mul(2) {
+            let mid = (n - 1) / 2;
+            let mut node = S::Ptr::new(Node::new(values[mid].clone(), false));
+            node.left = Self::build(&values[..mid], black_height - 1);
+            node.right = Self::build(&values[mid + 1..], black_height - 1);
+            node.recalc();
+            return Some(node);
+        }
+
+        let third = (n - 2) / 3;
+        let rem = (n - 2) % 3;
+        let a = third + usize::from(rem >= 1);
+        let b = a + 1 + third + usize::from(rem >= 2);
+
+        let mut red = S::Ptr::new(Node::new(values[a].clone(), true));
+        red.left = Self::build(&values[..a], black_height - 1);
+        red.right = Self::build(&values[a + 1..b], black_height - 1);
+        red.recalc();
+
+        let mut node = S::Ptr::new(Node::new(values[b].clone(), false));
+        node.left = Some(red);
+        node.right = Self::build(&values[b + 1..], black_height - 1);
+        node.recalc();
+        Some(node)
+    }
+
     fn normalize_range<R: RangeBounds<usize>>(range: R, len: usize) -> Option<(usize, usize)> {
         let start = match range.start_bound() {
             Bound::Included(&start) => start,
@@ -169,7 +377,7 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         Some((start, end))
     }
 
-    fn fold_range(node: &mut Link<P>, start: usize, end: usize) -> P::Agg {
+    fn fold_range(node: &mut Link<P, S>, start: usize, end: usize) -> P::Agg {
         if start >= end {
             return P::agg_unit();
         }
@@ -182,7 +390,7 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         }
 
         node_ref.push();
-        let left_size = Node::size(&node_ref.left) as usize;
+        let left_size = Node::<P, S>::size(&node_ref.left) as usize;
         if end <= left_size {
             return Self::fold_range(&mut node_ref.left, start, end);
         }
@@ -208,7 +416,7 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         P::agg_merge(&left_agg, &node_ref.key, &right_agg)
     }
 
-    fn update_range(node: &mut Link<P>, start: usize, end: usize, act: &P::Act) {
+    fn update_range(node: &mut Link<P, S>, start: usize, end: usize, act: &P::Act) {
         if start >= end {
             return;
         }
@@ -222,7 +430,7 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         }
 
         node_ref.push();
-        let left_size = Node::size(&node_ref.left) as usize;
+        let left_size = Node::<P, S>::size(&node_ref.left) as usize;
         if start < left_size {
             let left_end = left_size.min(end);
             Self::update_range(&mut node_ref.left, start, left_end, act);
@@ -243,11 +451,11 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         node_ref.recalc();
     }
 
-    fn is_red(node: &Link<P>) -> bool {
+    fn is_red(node: &Link<P, S>) -> bool {
         node.as_ref().map(|n| n.red).unwrap_or(false)
     }
 
-    fn make_black(mut root: Link<P>) -> Link<P> {
+    fn make_black(mut root: Link<P, S>) -> Link<P, S> {
         if let Some(node) = root.as_deref_mut()
             && node.red
         {
@@ -257,7 +465,7 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         root
     }
 
-    fn rotate_left(mut root: Box<Node<P>>) -> Box<Node<P>> {
+    fn rotate_left(mut root: S::Ptr) -> S::Ptr {
         root.push();
         let mut right = root.right.take().expect("rotate_left needs right");
         right.push();
@@ -268,7 +476,7 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         right
     }
 
-    fn rotate_right(mut root: Box<Node<P>>) -> Box<Node<P>> {
+    fn rotate_right(mut root: S::Ptr) -> S::Ptr {
         root.push();
         let mut left = root.left.take().expect("rotate_right needs left");
         left.push();
@@ -279,12 +487,12 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         left
     }
 
-    fn insert_at(node: Link<P>, index: usize, key: P::Key) -> Link<P> {
+    fn insert_at(node: Link<P, S>, index: usize, key: P::Key) -> Link<P, S> {
         match node {
-            None => Some(Box::new(Node::new(key, true))),
+            None => Some(S::Ptr::new(Node::new(key, true))),
             Some(mut node) => {
                 node.push();
-                let left_size = Node::size(&node.left) as usize;
+                let left_size = Node::<P, S>::size(&node.left) as usize;
                 if index <= left_size {
                     let left = node.left.take();
                     node.left = Self::insert_at(left, index, key);
@@ -297,7 +505,26 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         }
     }
 
-    fn fix_double_black_left(mut node: Box<Node<P>>) -> (Box<Node<P>>, bool) {
+    /// Inserts `key` as the first (`back == false`) or last element, walking
+    /// only the spine instead of comparing subtree sizes.
+    fn insert_end(node: Link<P, S>, key: P::Key, back: bool) -> Link<P, S> {
+        match node {
+            None => Some(S::Ptr::new(Node::new(key, true))),
+            Some(mut node) => {
+                node.push();
+                if back {
+                    let right = node.right.take();
+                    node.right = Self::insert_end(right, key, back);
+                } else {
+                    let left = node.left.take();
+                    node.left = Self::insert_end(left, key, back);
+                }
+                Some(Self::fix_up(node))
+            }
+        }
+    }
+
+    fn fix_double_black_left(mut node: S::Ptr) -> (S::Ptr, bool) {
         if Self::is_red(&node.right) {
             let mut new_root = Self::rotate_left(node);
             if let Some(left) = new_root.left.as_deref_mut() {
@@ -362,7 +589,7 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         (new_root, false)
     }
 
-    fn fix_double_black_right(mut node: Box<Node<P>>) -> (Box<Node<P>>, bool) {
+    fn fix_double_black_right(mut node: S::Ptr) -> (S::Ptr, bool) {
         if Self::is_red(&node.left) {
             let mut new_root = Self::rotate_right(node);
             if let Some(right) = new_root.right.as_deref_mut() {
@@ -424,12 +651,12 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         (new_root, false)
     }
 
-    fn delete_min(mut node: Box<Node<P>>) -> (Link<P>, P::Key, bool) {
+    fn delete_min(mut node: S::Ptr) -> (Link<P, S>, P::Key, bool) {
         node.push();
         if node.left.is_none() {
             let right = node.right.take();
-            let key = node.key;
-            if node.red {
+            let Node { key, red, .. } = node.into_inner();
+            if red {
                 return (right, key, false);
             }
             if let Some(mut right) = right {
@@ -453,12 +680,41 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         (Some(node), key, false)
     }
 
-    fn delete_at(node: Link<P>, index: usize) -> (Link<P>, Option<P::Key>, bool) {
+    fn delete_max(mut node: S::Ptr) -> (Link<P, S>, P::Key, bool) {
+        node.push();
+        if node.right.is_none() {
+            let left = node.left.take();
+            let Node { key, red, .. } = node.into_inner();
+            if red {
+                return (left, key, false);
+            }
+            if let Some(mut left) = left {
+                if left.red {
+                    left.red = false;
+                    left.recalc_black_height();
+                    return (Some(left), key, false);
+                }
+                return (Some(left), key, true);
+            }
+            return (None, key, true);
+        }
+        let right = node.right.take().expect("delete_max expects right");
+        let (new_right, key, needs_fix) = Self::delete_max(right);
+        node.right = new_right;
+        if needs_fix {
+            let (node, needs_fix) = Self::fix_double_black_right(node);
+            return (Some(node), key, needs_fix);
+        }
+        node.recalc();
+        (Some(node), key, false)
+    }
+
+    fn delete_at(node: Link<P, S>, index: usize) -> (Link<P, S>, Option<P::Key>, bool) {
         let Some(mut node) = node else {
             return (None, None, false);
         };
         node.push();
-        let left_size = Node::size(&node.left) as usize;
+        let left_size = Node::<P, S>::size(&node.left) as usize;
         if index < left_size {
             let left = node.left.take();
             let (new_left, removed, needs_fix) = Self::delete_at(left, index);
@@ -484,15 +740,19 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
 
         match (node.left.take(), node.right.take()) {
             (None, None) => {
-                let old_key = node.key;
-                if node.red {
+                let Node {
+                    key: old_key, red, ..
+                } = node.into_inner();
+                if red {
                     return (None, Some(old_key), false);
                 }
                 (None, Some(old_key), true)
             }
             (Some(mut child), None) | (None, Some(mut child)) => {
-                let old_key = node.key;
-                if node.red {
+                let Node {
+                    key: old_key, red, ..
+                } = node.into_inner();
+                if red {
                     return (Some(child), Some(old_key), false);
                 }
                 if child.red {
@@ -517,7 +777,7 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         }
     }
 
-    fn fix_up(mut node: Box<Node<P>>) -> Box<Node<P>> {
+    fn fix_up(mut node: S::Ptr) -> S::Ptr {
         if node.red {
             node.recalc();
             return node;
@@ -596,11 +856,11 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
     }
 
     fn join_left_node(
-        mut left: Box<Node<P>>,
-        mut pivot: Box<Node<P>>,
-        right: Link<P>,
+        mut left: S::Ptr,
+        mut pivot: S::Ptr,
+        right: Link<P, S>,
         target_bh: u8,
-    ) -> Box<Node<P>> {
+    ) -> S::Ptr {
         left.push();
         let right_bh = left.right.as_ref().map(|n| n.black_height).unwrap_or(0);
         if right_bh == target_bh {
@@ -629,11 +889,11 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
     }
 
     fn join_right_node(
-        mut right: Box<Node<P>>,
-        mut pivot: Box<Node<P>>,
-        left: Link<P>,
+        mut right: S::Ptr,
+        mut pivot: S::Ptr,
+        left: Link<P, S>,
         target_bh: u8,
-    ) -> Box<Node<P>> {
+    ) -> S::Ptr {
         right.push();
         let left_bh = right.left.as_ref().map(|n| n.black_height).unwrap_or(0);
         if left_bh == target_bh {
@@ -661,7 +921,7 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         Self::fix_up(right)
     }
 
-    fn join_with_node(left: Link<P>, mut pivot: Box<Node<P>>, right: Link<P>) -> Link<P> {
+    fn join_with_node(left: Link<P, S>, mut pivot: S::Ptr, right: Link<P, S>) -> Link<P, S> {
         pivot.push();
         match (left, right) {
             (None, None) => {
@@ -699,7 +959,7 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         }
     }
 
-    fn pop_last(&mut self, root: Link<P>) -> (Link<P>, Option<Box<Node<P>>>) {
+    fn pop_last(&mut self, root: Link<P, S>) -> (Link<P, S>, Option<S::Ptr>) {
         let len = root.as_ref().map(|node| node.size).unwrap_or(0) as usize;
         if len == 0 {
             return (None, None);
@@ -708,7 +968,7 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         (left, pivot)
     }
 
-    fn split_nodes(&mut self, root: Link<P>, left_count: usize) -> (Link<P>, Link<P>) {
+    fn split_nodes(&mut self, root: Link<P, S>, left_count: usize) -> (Link<P, S>, Link<P, S>) {
         self.split_left_stack.clear();
         self.split_right_stack.clear();
 
@@ -724,7 +984,7 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         let mut left_count = left_count;
         while let Some(mut current) = node {
             current.push();
-            let left_size = Node::size(&current.left) as usize;
+            let left_size = Node::<P, S>::size(&current.left) as usize;
             if left_count <= left_size {
                 let next = current.left.take();
                 self.split_right_stack.push(current);
@@ -737,24 +997,25 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
             }
         }
 
+        // Rejoin bottom-up: each node on the path becomes the pivot between
+        // its kept subtree and the side built so far. `join_with_node`
+        // descends to matching black heights, which a bare `fix_up` would not.
         let mut left = None;
         while let Some(mut current) = self.split_left_stack.pop() {
-            current.right = left;
-            let current = Self::fix_up(current);
-            left = Some(current);
+            let kept = current.left.take();
+            left = Self::join_with_node(kept, current, left);
         }
 
         let mut right = None;
         while let Some(mut current) = self.split_right_stack.pop() {
-            current.left = right;
-            let current = Self::fix_up(current);
-            right = Some(current);
+            let kept = current.right.take();
+            right = Self::join_with_node(right, current, kept);
         }
 
         (left, right)
     }
 
-    fn merge_nodes(&mut self, left: Link<P>, right: Link<P>) -> Link<P> {
+    fn merge_nodes(&mut self, left: Link<P, S>, right: Link<P, S>) -> Link<P, S> {
         match (left, right) {
             (None, right) => right,
             (left, None) => left,
@@ -768,12 +1029,16 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
         }
     }
 
-    fn get_node(node: &mut Link<P>, index: usize) -> Option<&P::Key> {
+    fn get_node<'a>(node: &'a mut Link<P, S>, index: usize) -> Option<&'a P::Key>
+    where
+        P: 'a,
+        S: 'a,
+    {
         let mut current = node.as_deref_mut()?;
         let mut index = index;
         loop {
             current.push();
-            let left_size = Node::size(&current.left) as usize;
+            let left_size = Node::<P, S>::size(&current.left) as usize;
             if index < left_size {
                 current = current.left.as_deref_mut()?;
             } else if index == left_size {
@@ -786,30 +1051,43 @@ impl<P: LazyMapMonoid> ImplicitRbTree<P> {
     }
 }
 
-impl<P> Clone for ImplicitRbTree<P>
+impl<P: LazyMapMonoid> PersistentSequence<P>
+where
+    P::Key: Clone,
+{
+    /// O(1) snapshot sharing every node with `self`. Writes to either version
+    /// copy the nodes they touch instead of mutating shared ones; so do reads
+    /// that push pending lazy tags, which keeps both versions correct at the
+    /// price of some sharing.
+    pub fn clone_persistent(&self) -> Self {
+        self.clone()
+    }
+}
+
+impl<P, S> Clone for ImplicitRbTree<P, S>
 where
     P: LazyMapMonoid,
     P::Key: Clone,
-    P::Agg: Clone,
-    P::Act: Clone,
+    S: Sharing<P>,
 {
+    /// A deep copy over [`Unique`] nodes; O(1) over [`Shared`] ones.
     fn clone(&self) -> Self {
         Self {
-            root: self.root.clone(),
+            root: self.root.as_ref().map(NodePtr::duplicate),
             len: self.len,
-            split_left_stack: Vec::new(),
-            split_right_stack: Vec::new(),
+            split_left_stack: Vec::with_capacity(64),
+            split_right_stack: Vec::with_capacity(64),
         }
     }
 }
 
-impl<P: LazyMapMonoid> Default for ImplicitRbTree<P> {
+impl<P: LazyMapMonoid, S: Sharing<P>> Default for ImplicitRbTree<P, S> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<P: LazyMapMonoid> SequenceBase for ImplicitRbTree<P> {
+impl<P: LazyMapMonoid, S: Sharing<P>> SequenceBase for ImplicitRbTree<P, S> {
     type Key = P::Key;
 
     fn len(&self) -> usize {
@@ -842,9 +1120,59 @@ impl<P: LazyMapMonoid> SequenceBase for ImplicitRbTree<P> {
         self.len -= 1;
         removed
     }
+
+    fn set(&mut self, index: usize, key: Self::Key) {
+        if index >= self.len as usize {
+            return;
+        }
+        descent::set_at(&mut self.root, index, key);
+    }
+
+    fn push_front(&mut self, key: Self::Key) {
+        self.root = Self::insert_end(self.root.take(), key, false);
+        self.root = Self::make_black(self.root.take());
+        self.len += 1;
+    }
+
+    fn push_back(&mut self, key: Self::Key) {
+        self.root = Self::insert_end(self.root.take(), key, true);
+        self.root = Self::make_black(self.root.take());
+        self.len += 1;
+    }
+
+    fn pop_front(&mut self) -> Option<Self::Key> {
+        let root = self.root.take()?;
+        let (root, removed, _needs_fix) = Self::delete_min(root);
+        self.root = Self::make_black(root);
+        self.len -= 1;
+        Some(removed)
+    }
+
+    fn pop_back(&mut self) -> Option<Self::Key> {
+        let root = self.root.take()?;
+        let (root, removed, _needs_fix) = Self::delete_max(root);
+        self.root = Self::make_black(root);
+        self.len -= 1;
+        Some(removed)
+    }
+
+    fn iter(&mut self) -> impl Iterator<Item = &Self::Key> {
+        Node::<P, S>::push_all(&mut self.root);
+        let mut stack: Vec<&Node<P, S>> = Vec::new();
+        let mut cur = self.root.as_deref();
+        std::iter::from_fn(move || {
+            while let Some(node) = cur {
+                stack.push(node);
+                cur = node.left.as_deref();
+            }
+            let node = stack.pop()?;
+            cur = node.right.as_deref();
+            Some(&node.key)
+        })
+    }
 }
 
-impl<P: LazyMapMonoid> SequenceSplitMerge for ImplicitRbTree<P> {
+impl<P: LazyMapMonoid, S: Sharing<P>> SequenceSplitMerge for ImplicitRbTree<P, S> {
     fn split_at(&mut self, index: usize) -> Self {
         let root = self.root.take();
         let (left, right) = self.split_nodes(root, index.min(self.len as usize));
@@ -867,7 +1195,56 @@ impl<P: LazyMapMonoid> SequenceSplitMerge for ImplicitRbTree<P> {
     }
 }
 
-impl<P: LazyMapMonoid> SequenceAgg for ImplicitRbTree<P> {
+impl<P: LazyMapMonoid> DescentLink<P> for Box<Node<P, Unique>> {
+    fn push(&mut self) {
+        Node::push(self);
+    }
+
+    fn agg(&self) -> &P::Agg {
+        &self.agg
+    }
+
+    fn size(&self) -> usize {
+        self.size as usize
+    }
+
+    fn recalc(&mut self) {
+        Node::recalc(self);
+    }
+
+    fn parts(&mut self) -> (&mut P::Key, &mut Option<Self>, &mut Option<Self>) {
+        let node: &mut Node<P, Unique> = self;
+        (&mut node.key, &mut node.left, &mut node.right)
+    }
+}
+
+impl<P: LazyMapMonoid> DescentLink<P> for CowNode<P>
+where
+    P::Key: Clone,
+{
+    fn push(&mut self) {
+        Node::push(self);
+    }
+
+    fn agg(&self) -> &P::Agg {
+        &self.agg
+    }
+
+    fn size(&self) -> usize {
+        self.size as usize
+    }
+
+    fn recalc(&mut self) {
+        Node::recalc(self);
+    }
+
+    fn parts(&mut self) -> (&mut P::Key, &mut Option<Self>, &mut Option<Self>) {
+        let node: &mut Node<P, Shared> = self;
+        (&mut node.key, &mut node.left, &mut node.right)
+    }
+}
+
+impl<P: LazyMapMonoid, S: Sharing<P>> SequenceAgg for ImplicitRbTree<P, S> {
     type Agg = P::Agg;
 
     fn fold<R: RangeBounds<usize>>(&mut self, range: R) -> Self::Agg {
@@ -880,9 +1257,26 @@ impl<P: LazyMapMonoid> SequenceAgg for ImplicitRbTree<P> {
 
         Self::fold_range(&mut self.root, start, end)
     }
+
+    fn max_right<F: Fn(&Self::Agg) -> bool>(&mut self, l: usize, pred: F) -> usize {
+        let len = self.len as usize;
+        assert!(
+            l <= len,
+            "max_right start {l} out of range for length {len}"
+        );
+        debug_assert!(pred(&P::agg_unit()), "pred must accept the empty fold");
+        descent::max_right_from(&mut self.root, l, &pred)
+    }
+
+    fn min_left<F: Fn(&Self::Agg) -> bool>(&mut self, r: usize, pred: F) -> usize {
+        let len = self.len as usize;
+        assert!(r <= len, "min_left end {r} out of range for length {len}");
+        debug_assert!(pred(&P::agg_unit()), "pred must accept the empty fold");
+        descent::min_left_from(&mut self.root, r, &pred)
+    }
 }
 
-impl<P: LazyMapMonoid> SequenceLazy for ImplicitRbTree<P> {
+impl<P: LazyMapMonoid, S: Sharing<P>> SequenceLazy for ImplicitRbTree<P, S> {
     type Act = P::Act;
 
     fn update<R: RangeBounds<usize>>(&mut self, range: R, act: Self::Act) {
@@ -897,7 +1291,7 @@ impl<P: LazyMapMonoid> SequenceLazy for ImplicitRbTree<P> {
     }
 }
 
-impl<P: LazyMapMonoid> SequenceReverse for ImplicitRbTree<P> {
+impl<P: LazyMapMonoid, S: Sharing<P>> SequenceReverse for ImplicitRbTree<P, S> {
     fn reverse<R: RangeBounds<usize>>(&mut self, range: R) {
         let Some((start, end)) = Self::normalize_range(range, self.len as usize) else {
             return;
@@ -917,11 +1311,31 @@ impl<P: LazyMapMonoid> SequenceReverse for ImplicitRbTree<P> {
     }
 }
 
+impl<P: LazyMapMonoid, S: Sharing<P>> SequenceSearch for ImplicitRbTree<P, S>
+where
+    P::Key: Ord,
+{
+    fn lower_bound_sorted(&mut self, key: &Self::Key) -> usize {
+        descent::partition_point(&mut self.root, |k| k < key)
+    }
+
+    fn upper_bound_sorted(&mut self, key: &Self::Key) -> usize {
+        descent::partition_point(&mut self.root, |k| k <= key)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::ImplicitRbTree;
-    use crate::policy::RangeSumRangeAdd;
-    use crate::traits::{SequenceAgg, SequenceBase, SequenceLazy, SequenceReverse};
+    use std::collections::VecDeque;
+
+    use std::collections::HashSet;
+
+    use super::{ImplicitRbTree, Link, Node, PersistentSequence, Shared, Sharing};
+    use crate::policy::{LazyMapMonoid, RangeSumRangeAdd, RangeSumRangeAssign};
+    use crate::traits::{
+        SequenceAgg, SequenceBase, SequenceLazy, SequenceReverse, SequenceSearch,
+        SequenceSplitMerge,
+    };
     use rand::rngs::StdRng;
     use rand::{Rng, SeedableRng};
 
@@ -990,6 +1404,7 @@ mod tests {
                     let r = rng.random_range((l + 1)..=vec.len());
                     tree.reverse(l..r);
                     vec[l..r].reverse();
+                    check_shape(&tree);
                 }
                 _ => {
                     if vec.is_empty() {
@@ -1002,5 +1417,382 @@ mod tests {
                 }
             }
         }
+        assert_eq!(tree.to_vec(), vec);
+        assert!(tree.iter().eq(vec.iter()));
+    }
+
+    #[test]
+    fn set_matches_vec() {
+        let mut rng = StdRng::seed_from_u64(0x5E70_2026);
+        let values: Vec<i64> = (0..200).map(|_| rng.random_range(-1000..=1000)).collect();
+        let mut tree = ImplicitRbTree::<RangeSumRangeAdd>::from_slice(&values);
+        let mut vec = values;
+
+        for _ in 0..2000 {
+            let l = rng.random_range(0..vec.len());
+            let r = rng.random_range((l + 1)..=vec.len());
+            match rng.random_range(0..4) {
+                0 => {
+                    let value = rng.random_range(-1000..=1000);
+                    tree.set(l, value);
+                    vec[l] = value;
+                }
+                1 => {
+                    let add = rng.random_range(-10..=10);
+                    tree.update(l..r, add);
+                    vec[l..r].iter_mut().for_each(|value| *value += add);
+                }
+                2 => {
+                    tree.reverse(l..r);
+                    vec[l..r].reverse();
+                }
+                _ => assert_eq!(tree.fold(l..r), vec[l..r].iter().sum::<i64>()),
+            }
+        }
+        tree.set(vec.len(), 0);
+        assert_eq!(tree.to_vec(), vec);
+    }
+
+    #[test]
+    fn drain_matches_vec_drain() {
+        let mut rng = StdRng::seed_from_u64(0xD4A1_2026);
+        let values: Vec<i64> = (0..300).map(|_| rng.random_range(-1000..=1000)).collect();
+        let mut tree = ImplicitRbTree::<RangeSumRangeAdd>::from_slice(&values);
+        let mut vec = values;
+
+        for _ in 0..50 {
+            let l = rng.random_range(0..=vec.len());
+            let r = rng.random_range(l..=vec.len());
+            let mut drained = tree.drain(l..r);
+            let expected: Vec<i64> = vec.drain(l..r).collect();
+            assert_eq!(drained.to_vec(), expected);
+            assert_eq!(drained.len(), expected.len());
+            assert_eq!(tree.len(), vec.len());
+            check_shape(&tree);
+            check_shape(&drained);
+            assert_eq!(tree.fold(..), vec.iter().sum::<i64>());
+
+            let index = rng.random_range(0..=vec.len());
+            let mut right = tree.split_at(index);
+            tree.merge(drained);
+            right.update(.., 1);
+            tree.merge(right);
+            let tail = vec.split_off(index);
+            vec.extend(expected);
+            vec.extend(tail.iter().map(|value| value + 1));
+            assert_eq!(tree.to_vec(), vec);
+            check_shape(&tree);
+        }
+    }
+
+    #[test]
+    fn rotate_range_keeps_shape() {
+        let mut rng = StdRng::seed_from_u64(0x7074_2026);
+        let mut vec: Vec<i64> = (0..300).collect();
+        let mut tree = ImplicitRbTree::<RangeSumRangeAdd>::from_slice(&vec);
+
+        for _ in 0..300 {
+            let l = rng.random_range(0..=vec.len());
+            let r = rng.random_range(l..=vec.len());
+            let k = rng.random_range(0..=r - l);
+            tree.rotate_range(l..r, k);
+            if r > l {
+                vec[l..r].rotate_left(k % (r - l));
+            }
+            check_shape(&tree);
+        }
+        assert_eq!(tree.to_vec(), vec);
+    }
+
+    #[test]
+    fn concat_matches_flattened_parts() {
+        let mut rng = StdRng::seed_from_u64(0xC0CA_2026);
+        for count in [0, 1, 2, 7, 64] {
+            let contents: Vec<Vec<i64>> = (0..count)
+                .map(|_| {
+                    let len = rng.random_range(0..20);
+                    (0..len).map(|_| rng.random_range(-1000..=1000)).collect()
+                })
+                .collect();
+            let parts = contents
+                .iter()
+                .map(|values| ImplicitRbTree::<RangeSumRangeAdd>::from_slice(values))
+                .collect();
+            let mut tree = ImplicitRbTree::concat(parts);
+            let expected = contents.concat();
+            assert_eq!(tree.len(), expected.len());
+            assert_eq!(tree.fold(..), expected.iter().sum::<i64>());
+            assert_eq!(tree.to_vec(), expected);
+            check_shape(&tree);
+        }
+    }
+
+    #[test]
+    fn sorted_search_matches_binary_search() {
+        let mut rng = StdRng::seed_from_u64(0x5027_2026);
+        let mut tree = ImplicitRbTree::<RangeSumRangeAdd>::new();
+        let mut vec = Vec::<i64>::new();
+
+        for _ in 0..1000 {
+            let key = rng.random_range(-200..=200);
+            let lower = tree.lower_bound_sorted(&key);
+            let upper = tree.upper_bound_sorted(&key);
+            assert_eq!(lower, vec.partition_point(|&x| x < key));
+            assert_eq!(upper, vec.partition_point(|&x| x <= key));
+            match vec.binary_search(&key) {
+                Ok(index) => assert!(lower <= index && index < upper),
+                Err(index) => assert!(lower == index && upper == index),
+            }
+
+            if rng.random_bool(0.25) && lower < upper {
+                assert_eq!(tree.remove(lower), Some(vec.remove(lower)));
+            } else {
+                tree.insert(upper, key);
+                vec.insert(upper, key);
+            }
+        }
+        assert_eq!(tree.to_vec(), vec);
+    }
+
+    #[test]
+    fn range_assign_matches_vec() {
+        let mut rng = StdRng::seed_from_u64(0xA551_2026);
+        let mut tree = ImplicitRbTree::<RangeSumRangeAssign>::new();
+        let mut vec = Vec::<i64>::new();
+
+        for _ in 0..2000 {
+            match rng.random_range(0..4) {
+                0 => {
+                    let index = rng.random_range(0..=vec.len());
+                    let value = rng.random_range(-1000..=1000);
+                    tree.insert(index, value);
+                    vec.insert(index, value);
+                }
+                1 => {
+                    if vec.is_empty() {
+                        continue;
+                    }
+                    let l = rng.random_range(0..vec.len());
+                    let r = rng.random_range((l + 1)..=vec.len());
+                    let value = rng.random_range(-1000..=1000);
+                    tree.update(l..r, Some(value));
+                    vec[l..r].fill(value);
+                }
+                2 => {
+                    if vec.is_empty() {
+                        continue;
+                    }
+                    let l = rng.random_range(0..vec.len());
+                    let r = rng.random_range((l + 1)..=vec.len());
+                    tree.reverse(l..r);
+                    vec[l..r].reverse();
+                    check_shape(&tree);
+                }
+                _ => {
+                    if vec.is_empty() {
+                        continue;
+                    }
+                    let l = rng.random_range(0..vec.len());
+                    let r = rng.random_range((l + 1)..=vec.len());
+                    let expected: i64 = vec[l..r].iter().sum();
+                    assert_eq!(tree.fold(l..r), expected);
+                }
+            }
+        }
+        assert_eq!(tree.to_vec(), vec);
+    }
+
+    #[test]
+    fn deque_operations_match_vecdeque() {
+        let mut rng = StdRng::seed_from_u64(0xDE0E_2026);
+        let mut tree = ImplicitRbTree::<RangeSumRangeAdd>::new();
+        let mut deque = VecDeque::<i64>::new();
+
+        for _ in 0..3000 {
+            match rng.random_range(0..6) {
+                0 => {
+                    let value = rng.random_range(-1000..=1000);
+                    tree.push_front(value);
+                    deque.push_front(value);
+                }
+                1 => {
+                    let value = rng.random_range(-1000..=1000);
+                    tree.push_back(value);
+                    deque.push_back(value);
+                }
+                2 => assert_eq!(tree.pop_front(), deque.pop_front()),
+                3 => assert_eq!(tree.pop_back(), deque.pop_back()),
+                4 => {
+                    if deque.is_empty() {
+                        continue;
+                    }
+                    let l = rng.random_range(0..deque.len());
+                    let r = rng.random_range((l + 1)..=deque.len());
+                    tree.reverse(l..r);
+                    deque.make_contiguous()[l..r].reverse();
+                }
+                _ => {
+                    let expected: i64 = deque.iter().sum();
+                    assert_eq!(tree.fold(..), expected);
+                }
+            }
+            assert_eq!(tree.len(), deque.len());
+        }
+        while let Some(value) = deque.pop_back() {
+            assert_eq!(tree.pop_back(), Some(value));
+        }
+        assert_eq!(tree.pop_front(), None);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn from_slice_matches_values() {
+        let mut rng = StdRng::seed_from_u64(0xF50A_2026);
+        for n in [0, 1, 2, 3, 4, 5, 7, 8, 26, 27, 100, 1000] {
+            let values: Vec<i64> = (0..n).map(|_| rng.random_range(-1000..=1000)).collect();
+            let mut tree = ImplicitRbTree::<RangeSumRangeAdd>::from_slice(&values);
+            check_shape(&tree);
+            assert_eq!(tree.len(), n);
+            assert_eq!(tree.fold(0..n), values.iter().sum::<i64>());
+            for (i, value) in values.iter().enumerate() {
+                assert_eq!(tree.get(i), Some(value));
+            }
+
+            let mut vec = values;
+            for _ in 0..100 {
+                let index = rng.random_range(0..=vec.len());
+                tree.insert(index, index as i64);
+                vec.insert(index, index as i64);
+                let index = rng.random_range(0..vec.len());
+                assert_eq!(tree.remove(index), Some(vec.remove(index)));
+            }
+            assert_eq!(tree.to_vec(), vec);
+        }
+    }
+
+    #[test]
+    fn boxed_tree_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ImplicitRbTree<RangeSumRangeAdd>>();
+    }
+
+    #[test]
+    fn persistent_versions_are_isolated() {
+        let values: Vec<i64> = (0..1_000).collect();
+        let mut original = PersistentSequence::<RangeSumRangeAdd>::from_slice(&values);
+        let mut version = original.clone_persistent();
+        version.set(0, -1);
+        version.insert(10, 7);
+        version.update(0..20, 100);
+
+        // Only the paths to the touched positions were copied.
+        let mut original_nodes = HashSet::new();
+        node_ptrs(&original.root, &mut |ptr| {
+            original_nodes.insert(ptr);
+        });
+        let (mut shared, mut copied) = (0, 0);
+        node_ptrs(&version.root, &mut |ptr| {
+            if original_nodes.contains(&ptr) {
+                shared += 1;
+            } else {
+                copied += 1;
+            }
+        });
+        assert_eq!(shared + copied, 1_001);
+        assert!(copied <= 60, "copied {copied} nodes");
+
+        let mut expected = values.clone();
+        expected[0] = -1;
+        expected.insert(10, 7);
+        for value in &mut expected[..20] {
+            *value += 100;
+        }
+        assert_eq!(version.to_vec(), expected);
+        assert_eq!(original.to_vec(), values);
+        assert_eq!(original.fold(..), values.iter().sum::<i64>());
+    }
+
+    #[test]
+    fn persistent_random_versions_match_vecs() {
+        let mut rng = StdRng::seed_from_u64(0x9E25_157E);
+        let mut versions = vec![(PersistentSequence::<RangeSumRangeAdd>::new(), Vec::new())];
+        for _ in 0..2_000 {
+            let pick = rng.random_range(0..versions.len());
+            let (tree, model) = &versions[pick];
+            let (mut tree, mut model) = (tree.clone_persistent(), model.clone());
+            let len = model.len();
+            match rng.random_range(0..5) {
+                0 | 1 => {
+                    let index = rng.random_range(0..=len);
+                    let value = rng.random_range(-100..100);
+                    tree.insert(index, value);
+                    model.insert(index, value);
+                }
+                2 if len > 0 => {
+                    let index = rng.random_range(0..len);
+                    assert_eq!(tree.remove(index), Some(model.remove(index)));
+                }
+                3 if len > 0 => {
+                    let l = rng.random_range(0..len);
+                    let r = rng.random_range(l..=len);
+                    let delta = rng.random_range(-10..10);
+                    tree.update(l..r, delta);
+                    for value in &mut model[l..r] {
+                        *value += delta;
+                    }
+                }
+                _ if len > 0 => {
+                    let l = rng.random_range(0..len);
+                    let r = rng.random_range(l..=len);
+                    tree.reverse(l..r);
+                    model[l..r].reverse();
+                }
+                _ => {}
+            }
+            versions.push((tree, model));
+        }
+        for (tree, model) in &mut versions {
+            check_shape(tree);
+            assert_eq!(tree.len(), model.len());
+            assert_eq!(tree.fold(..), model.iter().sum::<i64>());
+            assert_eq!(&tree.to_vec(), model);
+        }
+    }
+
+    fn node_ptrs<P: LazyMapMonoid>(
+        link: &Link<P, Shared>,
+        visit: &mut impl FnMut(*const Node<P, Shared>),
+    ) where
+        P::Key: Clone,
+    {
+        if let Some(node) = link {
+            visit(std::rc::Rc::as_ptr(&node.0));
+            node_ptrs(&node.left, visit);
+            node_ptrs(&node.right, visit);
+        }
+    }
+
+    /// Black height of `tree`, asserting equal black counts on every path and
+    /// no red node with a red child.
+    fn check_shape<P: LazyMapMonoid, S: Sharing<P>>(tree: &ImplicitRbTree<P, S>) -> usize {
+        subtree_shape::<P, S>(&tree.root)
+    }
+
+    fn subtree_shape<P: LazyMapMonoid, S: Sharing<P>>(node: &Link<P, S>) -> usize {
+        let Some(node) = node else {
+            return 0;
+        };
+        for child in [&node.left, &node.right].into_iter().flatten() {
+            assert!(!(node.red && child.red), "red node with a red child");
+        }
+        let left = subtree_shape::<P, S>(&node.left);
+        assert_eq!(
+            left,
+            subtree_shape::<P, S>(&node.right),
+            "unequal black heights"
+        );
+        let height = left + usize::from(!node.red);
+        assert_eq!(usize::from(node.black_height), height, "stale black height");
+        height
     }
 }
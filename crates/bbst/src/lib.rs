@@ -4,9 +4,19 @@ mod traits;
 pub mod impls;
 
 pub use impls::{
-    aa::ImplicitAaTree, avl::ImplicitAvl, llrb::ImplicitLlrbTree, rb::ImplicitRbTree,
-    rbst::ImplicitRbst, splay::ImplicitSplay, treap::ImplicitTreap, wbt::ImplicitWbt,
+    aa::ImplicitAaTree,
+    avl::ImplicitAvl,
+    llrb::ImplicitLlrbTree,
+    rb::{ImplicitRbTree, PersistentSequence},
+    rbst::ImplicitRbst,
+    splay::ImplicitSplay,
+    treap::ImplicitTreap,
+    wbt::ImplicitWbt,
     zip::ImplicitZipTree,
 };
-pub use policy::{CorePolicy, LazyMapMonoid, RangeSum, RangeSumRangeAdd};
-pub use traits::{SequenceAgg, SequenceBase, SequenceLazy, SequenceReverse, SequenceSplitMerge};
+pub use policy::{
+    CorePolicy, LazyMapMonoid, RangeMinRangeAdd, RangeSum, RangeSumRangeAdd, RangeSumRangeAssign,
+};
+pub use traits::{
+    SequenceAgg, SequenceBase, SequenceLazy, SequenceReverse, SequenceSearch, SequenceSplitMerge,
+};
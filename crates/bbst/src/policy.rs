@@ -3,6 +3,12 @@ pub trait LazyMapMonoid {
     type Agg: Clone;
     type Act: Clone;
 
+    /// Whether `agg` of a reversed sequence equals `agg` of the original, as
+    /// for sums and minima. When `true` the trees skip computing `agg_rev`
+    /// and swapping it on reversal; the field is kept but never read.
+    /// Defaults to `false`, which is correct for any monoid.
+    const REVERSAL_INVARIANT: bool = false;
+
     fn agg_unit() -> Self::Agg;
     fn agg_from_key(key: &Self::Key) -> Self::Agg;
     fn agg_merge(left: &Self::Agg, key: &Self::Key, right: &Self::Agg) -> Self::Agg;
@@ -23,6 +29,8 @@ impl LazyMapMonoid for CorePolicy {
     type Agg = ();
     type Act = ();
 
+    const REVERSAL_INVARIANT: bool = true;
+
     fn agg_unit() -> Self::Agg {}
 
     fn agg_from_key(_key: &Self::Key) -> Self::Agg {}
@@ -47,6 +55,8 @@ impl LazyMapMonoid for RangeSum {
     type Agg = i64;
     type Act = ();
 
+    const REVERSAL_INVARIANT: bool = true;
+
     fn agg_unit() -> Self::Agg {
         0
     }
@@ -79,6 +89,8 @@ impl LazyMapMonoid for RangeSumRangeAdd {
     type Agg = i64;
     type Act = i64;
 
+    const REVERSAL_INVARIANT: bool = true;
+
     fn agg_unit() -> Self::Agg {
         0
     }
@@ -107,3 +119,86 @@ impl LazyMapMonoid for RangeSumRangeAdd {
         *agg + act * len as i64
     }
 }
+
+/// Range sum with range assignment. `Some(c)` sets every element to `c`;
+/// `None` is the identity, and a later assignment overrides an earlier one.
+pub struct RangeSumRangeAssign;
+
+impl LazyMapMonoid for RangeSumRangeAssign {
+    type Key = i64;
+    type Agg = i64;
+    type Act = Option<i64>;
+
+    const REVERSAL_INVARIANT: bool = true;
+
+    fn agg_unit() -> Self::Agg {
+        0
+    }
+
+    fn agg_from_key(key: &Self::Key) -> Self::Agg {
+        *key
+    }
+
+    fn agg_merge(left: &Self::Agg, key: &Self::Key, right: &Self::Agg) -> Self::Agg {
+        left + key + right
+    }
+
+    fn act_unit() -> Self::Act {
+        None
+    }
+
+    fn act_compose(new: &Self::Act, old: &Self::Act) -> Self::Act {
+        new.or(*old)
+    }
+
+    fn act_apply_key(key: &Self::Key, act: &Self::Act) -> Self::Key {
+        act.unwrap_or(*key)
+    }
+
+    fn act_apply_agg(agg: &Self::Agg, act: &Self::Act, len: usize) -> Self::Agg {
+        match act {
+            Some(value) => value * len as i64,
+            None => *agg,
+        }
+    }
+}
+
+/// Range minimum with range add. The minimum of a reversed range is the same,
+/// so `agg_rev` needs no maintenance.
+pub struct RangeMinRangeAdd;
+
+impl LazyMapMonoid for RangeMinRangeAdd {
+    type Key = i64;
+    type Agg = i64;
+    type Act = i64;
+
+    const REVERSAL_INVARIANT: bool = true;
+
+    fn agg_unit() -> Self::Agg {
+        i64::MAX
+    }
+
+    fn agg_from_key(key: &Self::Key) -> Self::Agg {
+        *key
+    }
+
+    fn agg_merge(left: &Self::Agg, key: &Self::Key, right: &Self::Agg) -> Self::Agg {
+        *left.min(key).min(right)
+    }
+
+    fn act_unit() -> Self::Act {
+        0
+    }
+
+    fn act_compose(new: &Self::Act, old: &Self::Act) -> Self::Act {
+        new + old
+    }
+
+    fn act_apply_key(key: &Self::Key, act: &Self::Act) -> Self::Key {
+        key + act
+    }
+
+    fn act_apply_agg(agg: &Self::Agg, act: &Self::Act, _len: usize) -> Self::Agg {
+        agg + act
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops::RangeBounds;
+use std::ops::{Bound, RangeBounds};
 
 pub trait SequenceBase {
     type Key;
@@ -13,10 +13,42 @@ pub trait SequenceBase {
     fn insert(&mut self, index: usize, key: Self::Key);
     fn remove(&mut self, index: usize) -> Option<Self::Key>;
 
+    /// Overwrites the key at `index` in place; does nothing if `index` is out
+    /// of range, like `insert` past the end.
+    fn set(&mut self, index: usize, key: Self::Key);
+
+    fn push_front(&mut self, key: Self::Key) {
+        self.insert(0, key);
+    }
+
+    fn push_back(&mut self, key: Self::Key) {
+        let index = self.len();
+        self.insert(index, key);
+    }
+
+    fn pop_front(&mut self) -> Option<Self::Key> {
+        self.remove(0)
+    }
+
+    fn pop_back(&mut self) -> Option<Self::Key> {
+        let index = self.len().checked_sub(1)?;
+        self.remove(index)
+    }
+
+    /// Keys in sequence order. Pending lazy tags are pushed down first, which
+    /// is why this needs `&mut self`.
+    fn iter(&mut self) -> impl Iterator<Item = &Self::Key>;
+
+    fn to_vec(&mut self) -> Vec<Self::Key>
+    where
+        Self::Key: Clone,
+    {
+        self.iter().cloned().collect()
+    }
+
     fn extend<I: IntoIterator<Item = Self::Key>>(&mut self, iter: I) {
         for value in iter {
-            let index = self.len();
-            self.insert(index, value);
+            self.push_back(value);
         }
     }
 }
@@ -24,11 +56,91 @@ pub trait SequenceBase {
 pub trait SequenceSplitMerge: SequenceBase + Sized {
     fn split_at(&mut self, index: usize) -> Self;
     fn merge(&mut self, right: Self);
+
+    /// Detaches `range` with two splits and returns it as its own sequence;
+    /// `self` keeps the elements on both sides. Panics if the range is out
+    /// of bounds, like `Vec::drain`.
+    fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Self {
+        let (start, end) = bounds(&range, self.len(), "drain");
+        let right = self.split_at(end);
+        let middle = self.split_at(start);
+        self.merge(right);
+        middle
+    }
+
+    /// Rotates `range` left by `k`, like `slice::rotate_left` on that
+    /// subslice but with `k` taken modulo the range length: splits off the
+    /// range and its first `k` elements, then merges the pieces back swapped.
+    /// Panics if the range is out of bounds.
+    fn rotate_range<R: RangeBounds<usize>>(&mut self, range: R, k: usize) {
+        let (start, end) = bounds(&range, self.len(), "rotate");
+        let len = end - start;
+        if len == 0 || k.is_multiple_of(len) {
+            return;
+        }
+        let right = self.split_at(end);
+        let mut head = self.split_at(start);
+        let tail = head.split_at(k % len);
+        self.merge(tail);
+        self.merge(head);
+        self.merge(right);
+    }
+
+    /// Concatenates `parts` in order, merging neighbours pairwise round by
+    /// round so each merge joins trees of similar size.
+    fn concat(mut parts: Vec<Self>) -> Self
+    where
+        Self: Default,
+    {
+        while parts.len() > 1 {
+            let mut next = Vec::with_capacity(parts.len().div_ceil(2));
+            let mut iter = parts.into_iter();
+            while let Some(mut left) = iter.next() {
+                if let Some(right) = iter.next() {
+                    left.merge(right);
+                }
+                next.push(left);
+            }
+            parts = next;
+        }
+        parts.pop().unwrap_or_default()
+    }
+}
+
+/// Resolves `range` against a sequence of length `len`; panics with `op` in
+/// the message if it is out of bounds.
+fn bounds<R: RangeBounds<usize>>(range: &R, len: usize, op: &str) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end + 1,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    assert!(
+        start <= end && end <= len,
+        "{op} range {start}..{end} out of bounds for length {len}"
+    );
+    (start, end)
 }
 
 pub trait SequenceAgg: SequenceBase {
     type Agg;
     fn fold<R: RangeBounds<usize>>(&mut self, range: R) -> Self::Agg;
+
+    /// Largest `r` in `l..=len` with `pred(&fold(l..r))`, found in one descent.
+    ///
+    /// `pred` must accept the empty fold and be monotone: once it rejects
+    /// `fold(l..r)` it must reject every longer range from `l`. Panics if
+    /// `l > len`.
+    fn max_right<F: Fn(&Self::Agg) -> bool>(&mut self, l: usize, pred: F) -> usize;
+
+    /// Smallest `l` in `0..=r` with `pred(&fold(l..r))`; the mirror of
+    /// [`Self::max_right`] with the same requirements on `pred`.
+    fn min_left<F: Fn(&Self::Agg) -> bool>(&mut self, r: usize, pred: F) -> usize;
 }
 
 pub trait SequenceLazy: SequenceAgg {
@@ -39,3 +151,14 @@ pub trait SequenceLazy: SequenceAgg {
 pub trait SequenceReverse: SequenceBase {
     fn reverse<R: RangeBounds<usize>>(&mut self, range: R);
 }
+
+/// Binary search over a sequence whose keys the caller keeps sorted, which
+/// turns an implicit tree into an order-statistics sorted multiset.
+pub trait SequenceSearch: SequenceBase {
+    /// Number of keys less than `key`: the first index where `key` can be
+    /// inserted without breaking the order.
+    fn lower_bound_sorted(&mut self, key: &Self::Key) -> usize;
+
+    /// Number of keys not greater than `key`: the last such insertion index.
+    fn upper_bound_sorted(&mut self, key: &Self::Key) -> usize;
+}
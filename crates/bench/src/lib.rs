@@ -1,3 +1,9 @@
+#[cfg(feature = "peak-alloc")]
+mod peak_alloc;
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
 use std::time::Duration;
 
 use criterion::BenchmarkGroup;
@@ -5,6 +11,9 @@ use criterion::measurement::Measurement;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
+#[cfg(feature = "peak-alloc")]
+pub use peak_alloc::PeakAlloc;
+
 const SMALL_RUNTIME_SAMPLE_SIZE: usize = 15;
 const SMALL_RUNTIME_WARM_UP_MS: u64 = 100;
 const SMALL_RUNTIME_MEASURE_MS: u64 = 200;
@@ -35,9 +44,29 @@ pub fn apply_large_runtime_config<M: Measurement>(group: &mut BenchmarkGroup<'_,
 }
 
 pub fn default_rng() -> StdRng {
-    StdRng::seed_from_u64(RNG_SEED)
+    rng_with_seed(RNG_SEED)
+}
+
+pub fn rng_with_seed(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
 }
 
+/// Seed for bench inputs: `BENCH_SEED` (decimal or `0x` hex) when set, so a
+/// reported regression can be replayed or swept, otherwise the fixed default.
+pub fn bench_seed() -> u64 {
+    let Ok(value) = std::env::var("BENCH_SEED") else {
+        return RNG_SEED;
+    };
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    parsed.unwrap_or_else(|_| panic!("BENCH_SEED must be a u64, got {value:?}"))
+}
+
+/// A value with exactly `bits` significant bits: bit `bits - 1` is always
+/// set, so every draw sits in the top half of the range. Use
+/// [`random_below_bits`] for a plain uniform over `[0, 2^bits)`.
 pub fn random_with_bits<R: Rng + ?Sized>(rng: &mut R, bits: u32) -> u64 {
     if bits == 0 {
         return 0;
@@ -52,3 +81,58 @@ pub fn random_with_bits<R: Rng + ?Sized>(rng: &mut R, bits: u32) -> u64 {
     };
     rng.random_range(min..=max)
 }
+
+/// Uniform over `[0, 2^bits)`, small values included: the "bounded" inputs
+/// that radix-sort benchmarks want. `bits >= 64` covers all of `u64`.
+pub fn random_below_bits<R: Rng + ?Sized>(rng: &mut R, bits: u32) -> u64 {
+    if bits >= 64 {
+        return rng.random();
+    }
+    rng.random_range(0..1_u64 << bits)
+}
+
+/// Median of `runs` timings returned by `run`; the caller times only the part
+/// it wants measured.
+pub fn median_time<F: FnMut() -> Duration>(runs: usize, mut run: F) -> Duration {
+    assert!(runs > 0, "runs must be positive");
+    let mut samples: Vec<Duration> = (0..runs).map(|_| run()).collect();
+    samples.sort_unstable();
+    samples[runs / 2]
+}
+
+/// Writes `name,median_ns` rows for plotting outside Criterion.
+pub fn write_results_csv(path: &Path, rows: &[(String, Duration)]) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    writeln!(out, "name,median_ns")?;
+    for (name, time) in rows {
+        writeln!(out, "{name},{}", time.as_nanos())?;
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{random_below_bits, random_with_bits, rng_with_seed};
+    use rand::Rng;
+
+    #[test]
+    fn rng_with_seed_is_reproducible() {
+        let draw = |seed| {
+            let mut rng = rng_with_seed(seed);
+            (0..16).map(|_| rng.random::<u64>()).collect::<Vec<_>>()
+        };
+        assert_eq!(draw(1), draw(1));
+        assert_ne!(draw(1), draw(2));
+    }
+
+    #[test]
+    fn random_below_bits_reaches_small_values() {
+        let mut rng = rng_with_seed(7);
+        let draws: Vec<u64> = (0..4096).map(|_| random_below_bits(&mut rng, 4)).collect();
+        assert!(draws.iter().all(|&x| x < 16));
+        assert!(draws.contains(&0));
+        assert!(draws.iter().any(|&x| x < 8));
+        assert!((0..256).all(|_| random_with_bits(&mut rng, 4) >= 8));
+        assert_eq!(random_below_bits(&mut rng, 0), 0);
+    }
+}
@@ -0,0 +1,127 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Global allocator wrapper over [`System`] that tracks the peak number of
+/// live heap bytes since the last [`PeakAlloc::reset`].
+///
+/// Register it in a bench binary and bracket the code under measurement:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOC: bench::PeakAlloc = bench::PeakAlloc::new();
+///
+/// ALLOC.reset();
+/// let map = build(n);
+/// let bytes = ALLOC.peak();
+/// ```
+///
+/// Counters are relaxed atomics, so the wrapper is thread-safe and adds two
+/// atomic updates per allocation.
+pub struct PeakAlloc {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+    baseline: AtomicUsize,
+}
+
+impl PeakAlloc {
+    pub const fn new() -> Self {
+        Self {
+            current: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            baseline: AtomicUsize::new(0),
+        }
+    }
+
+    /// Starts a new measurement from the bytes currently live.
+    pub fn reset(&self) {
+        let current = self.current.load(Ordering::Relaxed);
+        self.baseline.store(current, Ordering::Relaxed);
+        self.peak.store(current, Ordering::Relaxed);
+    }
+
+    /// Highest number of live bytes above the baseline since [`Self::reset`].
+    pub fn peak(&self) -> usize {
+        let peak = self.peak.load(Ordering::Relaxed);
+        peak.saturating_sub(self.baseline.load(Ordering::Relaxed))
+    }
+
+    /// Live heap bytes right now.
+    pub fn current(&self) -> usize {
+        self.current.load(Ordering::Relaxed)
+    }
+
+    fn grow(&self, bytes: usize) {
+        let current = self.current.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        self.peak.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn shrink(&self, bytes: usize) {
+        self.current.fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
+
+impl Default for PeakAlloc {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            self.grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        self.shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            if new_size >= layout.size() {
+                self.grow(new_size - layout.size());
+            } else {
+                self.shrink(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::alloc::{GlobalAlloc, Layout};
+
+    use super::PeakAlloc;
+
+    #[test]
+    fn large_allocation_raises_peak() {
+        let alloc = PeakAlloc::new();
+        let layout = Layout::from_size_align(1 << 20, 8).unwrap();
+
+        alloc.reset();
+        assert_eq!(alloc.peak(), 0);
+        let ptr = unsafe { alloc.alloc(layout) };
+        assert!(!ptr.is_null());
+        assert_eq!(alloc.peak(), 1 << 20);
+        unsafe { alloc.dealloc(ptr, layout) };
+        assert_eq!(alloc.current(), 0);
+        assert_eq!(alloc.peak(), 1 << 20);
+
+        alloc.reset();
+        assert_eq!(alloc.peak(), 0);
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::VecDeque;
 
+use bench::bench_seed;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 
@@ -17,7 +18,8 @@ fn mix_seed(mut z: u64) -> u64 {
 }
 
 fn rng_for(kind: u64, size: usize) -> StdRng {
-    let seed = 0x5EED_2026
+    let seed = bench_seed()
+        ^ 0x5EED_2026
         ^ (kind.wrapping_mul(SEED_MIX))
         ^ (size as u64).wrapping_mul(SEED_MIX.rotate_left(17));
     StdRng::seed_from_u64(mix_seed(seed))
@@ -318,6 +318,19 @@ impl<P: LazyMapMonoid> EulerTourTree<P> {
         id
     }
 
+    fn arc(&self, u: usize, v: usize) -> Option<Id> {
+        let v = v as u32;
+        self.arcs[u]
+            .iter()
+            .find_map(|&(to, node)| if to == v { Some(node) } else { None })
+    }
+
+    /// Number of vertex nodes before `x` in its tour.
+    fn vertices_before(&mut self, x: Id) -> usize {
+        self.splay(x);
+        self.v_cnt(self.node(x).ch[0]) as usize
+    }
+
     fn remove_arc_opt(&mut self, u: usize, v: usize) -> Option<Id> {
         let list = &mut self.arcs[u];
         let v = v as u32;
@@ -423,6 +436,53 @@ impl<P: LazyMapMonoid> EulerTourTree<P> {
         self.node(r).v_cnt as usize
     }
 
+    /// Size of `child`'s side of the edge `(child, parent)`, or `None` if there
+    /// is no such edge.
+    ///
+    /// Reads tour positions instead of cutting: the tour enters `child`'s side
+    /// through the arc `parent -> child` and leaves it through `child -> parent`,
+    /// so the vertices between the two are the subtree, or the rest of the
+    /// component if the tour happens to start inside it.
+    pub fn subtree_size(&mut self, child: usize, parent: usize) -> Option<usize> {
+        debug_assert!(child < self.len() && parent < self.len());
+        let down = self.arc(parent, child)?;
+        let up = self.arc(child, parent)?;
+        let enter = self.vertices_before(down);
+        let leave = self.vertices_before(up);
+        if enter < leave {
+            return Some(leave - enter);
+        }
+        Some(self.component_size(child) - (enter - leave))
+    }
+
+    /// Size of `v`'s subtree with the tree rooted at `root`, or `None` if the
+    /// two are disconnected.
+    ///
+    /// Reroots the tour at `root`; `v`'s parent is then the neighbor whose arc
+    /// from `v` comes last, since the arcs to `v`'s children all lie inside its
+    /// subtree.
+    pub fn rooted_subtree_size(&mut self, root: usize, v: usize) -> Option<usize> {
+        debug_assert!(root < self.len() && v < self.len());
+        if !self.connected(root, v) {
+            return None;
+        }
+        if root == v {
+            return Some(self.component_size(v));
+        }
+        self.reroot(self.vertex_node[root]);
+        let mut parent = None;
+        let mut last = 0;
+        for i in 0..self.arcs[v].len() {
+            let (to, arc) = self.arcs[v][i];
+            let pos = self.vertices_before(arc);
+            if parent.is_none() || pos > last {
+                parent = Some(to as usize);
+                last = pos;
+            }
+        }
+        self.subtree_size(v, parent?)
+    }
+
     pub fn subtree_fold(&mut self, child: usize, parent: usize) -> P::Agg {
         debug_assert!(child < self.len() && parent < self.len());
         let ok = self.cut(child, parent);
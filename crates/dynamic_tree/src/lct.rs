@@ -473,6 +473,80 @@ impl<P: LazyMapMonoid> LinkCutTree<P> {
             x = self.node(x).ch[1];
         }
     }
+
+    /// Exposes the `u`-`v` path once and returns a view answering length,
+    /// k-th vertex, fold, and apply on it without re-exposing. `None` when
+    /// `u` and `v` are in different trees.
+    pub fn path_slice(&mut self, u: usize, v: usize) -> Option<LinkCutPathView<'_, P>> {
+        debug_assert!(u < self.len() && v < self.len());
+        let u_id = id(u);
+        let v_id = id(v);
+        self.makeroot(u);
+        self.access(v_id);
+        if u != v && self.node(u_id).p.is_nil() {
+            return None;
+        }
+        let len = self.node(v_id).sz as usize;
+        Some(LinkCutPathView {
+            tree: self,
+            root: v_id,
+            len,
+        })
+    }
+}
+
+/// A `u`-`v` path of a [`LinkCutTree`] exposed as one splay tree, from
+/// [`LinkCutTree::path_slice`]. Vertices are indexed from `u`.
+pub struct LinkCutPathView<'a, P: LazyMapMonoid> {
+    tree: &'a mut LinkCutTree<P>,
+    root: Id,
+    len: usize,
+}
+
+impl<P: LazyMapMonoid> LinkCutPathView<'_, P> {
+    /// Number of vertices on the path, endpoints included.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Always `false`: a path holds at least its endpoint.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The `k`-th vertex from `u`, or `None` if `k >= len()`.
+    pub fn kth(&mut self, mut k: usize) -> Option<usize> {
+        if k >= self.len {
+            return None;
+        }
+        let tree = &mut *self.tree;
+        let mut x = self.root;
+        loop {
+            tree.push(x);
+            let l = tree.node(x).ch[0];
+            let lsz = tree.sz(l) as usize;
+            if k < lsz {
+                x = l;
+                continue;
+            }
+            if k == lsz {
+                break;
+            }
+            k -= lsz + 1;
+            x = tree.node(x).ch[1];
+        }
+        tree.splay(x);
+        self.root = x;
+        Some(x.idx())
+    }
+
+    pub fn fold(&self) -> P::Agg {
+        self.tree.node(self.root).agg
+    }
+
+    pub fn apply(&mut self, act: P::Act) {
+        self.tree.apply_act(self.root, act);
+    }
 }
 
 impl LinkCutTree<VertexSumAdd> {
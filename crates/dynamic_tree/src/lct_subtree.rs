@@ -543,6 +543,21 @@ impl<P: LazyMapMonoid<Key = i64, Agg = i64, Act = i64>> LinkCutTreeSubtree<P> {
         self.node(x).key
     }
 
+    /// Reads `v`'s value without restructuring, or `None` if the stored key
+    /// may be stale.
+    ///
+    /// Lazies are applied to a node's own `key` eagerly but only reach it
+    /// through its splay ancestors and its virtual parent, so the stored key
+    /// is exact exactly when `v` has no parent pointer at all. That holds
+    /// right after `vertex_get/vertex_set/makeroot(v)` (each ends with an
+    /// `access(v)`) until another operation touches `v`'s tree, and for
+    /// isolated vertices. Fall back to [`Self::vertex_get`] on `None`.
+    pub fn peek_value(&self, v: usize) -> Option<P::Key> {
+        debug_assert!(v < self.len());
+        let nx = self.node(id(v));
+        nx.p.is_nil().then_some(nx.key)
+    }
+
     pub fn vertex_set(&mut self, v: usize, key: P::Key) {
         debug_assert!(v < self.len());
         let x = id(v);
@@ -670,6 +685,46 @@ impl<P: LazyMapMonoid<Key = i64, Agg = i64, Act = i64>> LinkCutTreeSubtree<P> {
         self.node(x).all_sz as usize
     }
 
+    /// Every vertex in `v`'s component, in increasing order.
+    ///
+    /// Virtual children are not linked from their parents, so this cannot walk
+    /// down from `v`. Instead, once `access(v)` has put `v` on top of its whole
+    /// component, every vertex whose splay/virtual parent chain ends at `v` is
+    /// in it. Each chain stops at the first vertex already classified, so the
+    /// scan is O(n) overall.
+    pub fn component_vertices(&mut self, v: usize) -> Vec<usize> {
+        #[derive(Clone, Copy, PartialEq, Eq)]
+        enum Mark {
+            Unknown,
+            Inside,
+            Outside,
+        }
+
+        debug_assert!(v < self.len());
+        self.access(id(v));
+        let mut marks = vec![Mark::Unknown; self.len()];
+        marks[v] = Mark::Inside;
+        let mut chain = Vec::new();
+        for u in 0..self.len() {
+            let mut y = id(u);
+            while !y.is_nil() && marks[y.idx()] == Mark::Unknown {
+                chain.push(y);
+                y = self.node(y).p;
+            }
+            let mark = if y.is_nil() {
+                Mark::Outside
+            } else {
+                marks[y.idx()]
+            };
+            for x in chain.drain(..) {
+                marks[x.idx()] = mark;
+            }
+        }
+        (0..self.len())
+            .filter(|&u| marks[u] == Mark::Inside)
+            .collect()
+    }
+
     #[inline(always)]
     fn apply_exposed_subtree_add(&mut self, x: Id, delta: P::Act) {
         if x.is_nil() || delta == 0 {
@@ -7,9 +7,9 @@ mod lct_subtree;
 mod top_tree;
 
 pub use ett::EulerTourTree;
-pub use lct::LinkCutTree;
+pub use lct::{LinkCutPathView, LinkCutTree};
 pub use lct_subtree::LinkCutTreeSubtree;
-pub use top_tree::TopTree;
+pub use top_tree::{TopTree, TopTreePathView};
 
 pub use traits::{ComponentOps, DynamicForest, PathOps, SubtreeOps, VertexOps};
 
@@ -114,6 +114,32 @@ mod tests {
         verts
     }
 
+    /// Subtree size of every vertex in `root`'s component with the tree rooted
+    /// at `root`, plus each vertex's parent; `usize::MAX` parents mark the root
+    /// and vertices outside the component.
+    fn bfs_subtree_sizes(g: &[Vec<usize>], root: usize) -> (Vec<usize>, Vec<usize>) {
+        let order = bfs_component_vertices(g, root);
+        let mut par = vec![usize::MAX; g.len()];
+        let mut seen = vec![false; g.len()];
+        seen[root] = true;
+        for &v in &order {
+            for &to in &g[v] {
+                if !seen[to] {
+                    seen[to] = true;
+                    par[to] = v;
+                }
+            }
+        }
+        let mut size = vec![0; g.len()];
+        for &v in order.iter().rev() {
+            size[v] += 1;
+            if par[v] != usize::MAX {
+                size[par[v]] += size[v];
+            }
+        }
+        (size, par)
+    }
+
     fn bfs_component_sum(g: &[Vec<usize>], values: &[i64], s: usize) -> i64 {
         bfs_component_vertices(g, s)
             .into_iter()
@@ -590,6 +616,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn ett_subtree_size_matches_bfs() {
+        let mut rng = StdRng::seed_from_u64(0x5B_7EE5_u64);
+        let n = 40_usize;
+        let mut ett = EulerTourTree::<VertexSumAdd>::new(&vec![0; n]);
+        let mut g = vec![Vec::<usize>::new(); n];
+        let mut edges = Vec::<(usize, usize)>::new();
+
+        for it in 0..4_000 {
+            let u = rng.random_range(0..n);
+            let v = rng.random_range(0..n);
+            match rng.random_range(0..3) {
+                0 | 1 => {
+                    if u != v && !bfs_connected(&g, u, v) {
+                        assert!(ett.link(u, v));
+                        add_undirected_edge(&mut g, u, v);
+                        edges.push(edge_key(u, v));
+                    }
+                }
+                _ => {
+                    if !edges.is_empty() {
+                        let (a, b) = edges.swap_remove(rng.random_range(0..edges.len()));
+                        assert!(ett.cut(a, b));
+                        remove_undirected_edge(&mut g, a, b);
+                    }
+                }
+            }
+
+            let (size, _) = bfs_subtree_sizes(&g, u);
+            let expected = bfs_connected(&g, u, v).then_some(size[v]);
+            assert_eq!(
+                ett.rooted_subtree_size(u, v),
+                expected,
+                "it={it} root={u} v={v}"
+            );
+
+            if let Some(&(a, b)) = edges.get(rng.random_range(0..edges.len().max(1))) {
+                let (size, par) = bfs_subtree_sizes(&g, a);
+                assert_eq!(par[b], a);
+                assert_eq!(
+                    ett.subtree_size(b, a),
+                    Some(size[b]),
+                    "it={it} edge=({a},{b})"
+                );
+                assert_eq!(ett.subtree_size(a, b), Some(size[a] - size[b]));
+            }
+            if u != v && !g[u].contains(&v) {
+                assert_eq!(ett.subtree_size(u, v), None);
+            }
+        }
+    }
+
     #[test]
     fn top_tree_random_against_bfs_with_ops() {
         let mut rng = StdRng::seed_from_u64(0x7A7A_2026_u64);
@@ -750,6 +828,60 @@ mod tests {
         assert_eq!(got_tt, expected);
     }
 
+    #[test]
+    fn top_tree_path_apply_batch_matches_one_by_one() {
+        let mut rng = StdRng::seed_from_u64(0xBA7C_u64);
+        let n = 25_usize;
+        let values = (0..n)
+            .map(|_| rng.random_range(-50_i64..=50))
+            .collect::<Vec<_>>();
+        let mut batched = TopTree::<VertexAffineSum>::new(&values);
+        let mut single = TopTree::<VertexAffineSum>::new(&values);
+        let mut g = vec![Vec::<usize>::new(); n];
+        for _ in 0..n {
+            let u = rng.random_range(0..n);
+            let v = rng.random_range(0..n);
+            if u != v && !bfs_connected(&g, u, v) {
+                assert!(batched.link(u, v));
+                assert!(single.link(u, v));
+                add_undirected_edge(&mut g, u, v);
+            }
+        }
+
+        for _ in 0..300 {
+            let mut ops = Vec::new();
+            for _ in 0..rng.random_range(0..12) {
+                let act = Affine {
+                    a: rng.random_range(-1_i64..=1),
+                    b: rng.random_range(-5_i64..=5),
+                };
+                // Repeat the previous path (sometimes reversed) to exercise run merging.
+                let (u, v) = match ops.last() {
+                    Some(&(u, v, _)) if rng.random_bool(0.5) => {
+                        if rng.random_bool(0.5) {
+                            (u, v)
+                        } else {
+                            (v, u)
+                        }
+                    }
+                    _ => (rng.random_range(0..n), rng.random_range(0..n)),
+                };
+                ops.push((u, v, act));
+            }
+
+            let expected = ops
+                .iter()
+                .filter(|&&(u, v, act)| single.path_apply(u, v, act))
+                .count();
+            assert_eq!(batched.path_apply_batch(&ops), expected);
+            for x in 0..n {
+                assert_eq!(batched.vertex_get(x), single.vertex_get(x), "vertex {x}");
+            }
+            let (u, v) = (rng.random_range(0..n), rng.random_range(0..n));
+            assert_eq!(batched.path_fold(u, v), single.path_fold(u, v));
+        }
+    }
+
     #[test]
     fn top_tree_edge_ops_affect_path_and_component_fold() {
         let values = vec![5_i64, 7];
@@ -767,6 +899,47 @@ mod tests {
         assert_eq!(tt.component_sum(0), 20);
     }
 
+    #[test]
+    fn top_tree_subtree_diameter_matches_reference() {
+        let mut rng = StdRng::seed_from_u64(0xD1A3_u64);
+        let n = 40_usize;
+        let mut tt = TopTree::<VertexSumAdd>::new(&vec![0_i64; n]);
+        let mut g = vec![Vec::<(usize, u64)>::new(); n];
+        let mut edges = Vec::<(usize, usize)>::new();
+        for v in 1..n {
+            let u = rng.random_range(0..v);
+            let w = rng.random_range(0..=1_000_u64);
+            assert!(tt.link_with_edge(u, v, w as i64));
+            g[u].push((v, w));
+            g[v].push((u, w));
+            edges.push((u, v));
+        }
+
+        for &(u, v) in &edges {
+            for (child, parent) in [(u, v), (v, u)] {
+                let mut index = vec![usize::MAX; n];
+                let mut sub_edges = Vec::new();
+                let mut stack = vec![child];
+                index[child] = 0;
+                let mut count = 1;
+                while let Some(x) = stack.pop() {
+                    for &(y, w) in &g[x] {
+                        if (x == child && y == parent) || index[y] != usize::MAX {
+                            continue;
+                        }
+                        index[y] = count;
+                        count += 1;
+                        sub_edges.push((index[x], index[y], w));
+                        stack.push(y);
+                    }
+                }
+                let expected = xor_linked_tree::diameter_vec(count, &sub_edges);
+                assert_eq!(tt.subtree_diameter(child, parent), expected);
+            }
+        }
+        assert_eq!(tt.component_size(0), n);
+    }
+
     #[test]
     fn lct_path_sum_disconnected_returns_none() {
         let values = vec![1_i64, 2, 3];
@@ -776,4 +949,181 @@ mod tests {
         assert_eq!(lct.path_sum(1, 2), None);
         assert_eq!(lct.path_sum(2, 2), Some(3));
     }
+
+    #[test]
+    fn lct_subtree_peek_value_is_exact_when_some() {
+        let mut rng = StdRng::seed_from_u64(0x9EE4_u64);
+        let n = 20_usize;
+        let mut values = (0..n)
+            .map(|_| rng.random_range(-100_i64..=100))
+            .collect::<Vec<_>>();
+        let mut lct = LinkCutTreeSubtree::<VertexSumAdd>::new(&values);
+        let mut g = vec![Vec::<usize>::new(); n];
+        let mut edges = Vec::<(usize, usize)>::new();
+
+        for it in 0..5_000 {
+            let u = rng.random_range(0..n);
+            let v = rng.random_range(0..n);
+            let delta = rng.random_range(-10_i64..=10);
+            match rng.random_range(0..5) {
+                0 => {
+                    if u != v && !bfs_connected(&g, u, v) {
+                        assert!(lct.link(u, v));
+                        add_undirected_edge(&mut g, u, v);
+                        edges.push(edge_key(u, v));
+                    }
+                }
+                1 => {
+                    if !edges.is_empty() {
+                        let (a, b) = edges.swap_remove(rng.random_range(0..edges.len()));
+                        assert!(lct.cut(a, b));
+                        remove_undirected_edge(&mut g, a, b);
+                    }
+                }
+                2 => {
+                    if let Some(path) = bfs_path(&g, u, v) {
+                        assert!(lct.path_apply(u, v, delta));
+                        for x in path {
+                            values[x] += delta;
+                        }
+                    }
+                }
+                3 => {
+                    lct.component_apply(u, delta);
+                    for x in bfs_component_vertices(&g, u) {
+                        values[x] += delta;
+                    }
+                }
+                _ => {
+                    values[u] += delta;
+                    lct.vertex_set(u, values[u]);
+                    assert_eq!(lct.peek_value(u), Some(values[u]), "it={it}");
+                }
+            }
+
+            for (x, &value) in values.iter().enumerate() {
+                if let Some(key) = lct.peek_value(x) {
+                    assert_eq!(key, value, "it={it} peek_value({x})");
+                }
+            }
+            let x = rng.random_range(0..n);
+            assert_eq!(lct.vertex_get(x), values[x]);
+            assert_eq!(
+                lct.peek_value(x),
+                Some(values[x]),
+                "it={it} after vertex_get({x})"
+            );
+        }
+    }
+
+    #[test]
+    fn lct_subtree_component_vertices_match_bfs() {
+        let mut rng = StdRng::seed_from_u64(0xC0_3B0E_u64);
+        let n = 40_usize;
+        let mut lct = LinkCutTreeSubtree::<VertexSumAdd>::new(&vec![0; n]);
+        let mut g = vec![Vec::<usize>::new(); n];
+        let mut edges = Vec::<(usize, usize)>::new();
+
+        for it in 0..5_000 {
+            let u = rng.random_range(0..n);
+            let v = rng.random_range(0..n);
+            match rng.random_range(0..4) {
+                0 | 1 => {
+                    if u != v && !bfs_connected(&g, u, v) {
+                        assert!(lct.link(u, v));
+                        add_undirected_edge(&mut g, u, v);
+                        edges.push(edge_key(u, v));
+                    }
+                }
+                2 => {
+                    if !edges.is_empty() {
+                        let (a, b) = edges.swap_remove(rng.random_range(0..edges.len()));
+                        assert!(lct.cut(a, b));
+                        remove_undirected_edge(&mut g, a, b);
+                    }
+                }
+                _ => {
+                    // Restructure so the query does not always start from a fresh access.
+                    if let Some(path) = bfs_path(&g, u, v) {
+                        assert_eq!(lct.path_len(u, v), Some(path.len()));
+                    }
+                }
+            }
+
+            let mut expected = bfs_component_vertices(&g, u);
+            expected.sort_unstable();
+            assert_eq!(lct.component_vertices(u), expected, "it={it} v={u}");
+        }
+    }
+
+    #[test]
+    fn path_slice_views_match_path_ops() {
+        let mut rng = StdRng::seed_from_u64(0x5_11CE_u64);
+        let n = 24_usize;
+        let values = (0..n)
+            .map(|_| rng.random_range(-500_i64..=500))
+            .collect::<Vec<_>>();
+        let mut lct = LinkCutTree::<VertexSumAdd>::new(&values);
+        let mut tt = TopTree::<VertexSumAdd>::new(&values);
+        let mut g = vec![Vec::<usize>::new(); n];
+        let mut edges = Vec::<(usize, usize)>::new();
+
+        for it in 0..5_000 {
+            let u = rng.random_range(0..n);
+            let v = rng.random_range(0..n);
+            match rng.random_range(0..4) {
+                0 => {
+                    if u == v || bfs_connected(&g, u, v) {
+                        continue;
+                    }
+                    assert!(lct.link(u, v));
+                    assert!(tt.link(u, v));
+                    add_undirected_edge(&mut g, u, v);
+                    edges.push(edge_key(u, v));
+                }
+                1 => {
+                    if edges.is_empty() {
+                        continue;
+                    }
+                    let (a, b) = edges.swap_remove(rng.random_range(0..edges.len()));
+                    assert!(lct.cut(a, b));
+                    assert!(tt.cut(a, b));
+                    remove_undirected_edge(&mut g, a, b);
+                }
+                _ => {
+                    let Some(path) = bfs_path(&g, u, v) else {
+                        assert!(lct.path_slice(u, v).is_none(), "it={it}");
+                        assert!(tt.path_slice(u, v).is_none(), "it={it}");
+                        continue;
+                    };
+                    let delta = rng.random_range(-10_i64..=10);
+                    let expected_len = lct.path_len(u, v);
+                    let expected_fold = lct.path_fold(u, v);
+
+                    let mut view = lct.path_slice(u, v).unwrap();
+                    assert_eq!(Some(view.len()), expected_len, "it={it}");
+                    assert_eq!(Some(view.fold()), expected_fold, "it={it}");
+                    for (k, &x) in path.iter().enumerate() {
+                        assert_eq!(view.kth(k), Some(x), "it={it} kth({k})");
+                    }
+                    assert_eq!(view.kth(path.len()), None);
+                    view.apply(delta);
+                    let lct_fold = view.fold();
+
+                    let mut view = tt.path_slice(u, v).unwrap();
+                    assert_eq!(view.len(), path.len(), "it={it}");
+                    assert_eq!(Some(view.fold()), expected_fold, "it={it}");
+                    for (k, &x) in path.iter().enumerate() {
+                        assert_eq!(view.kth(k), Some(x), "it={it} kth({k})");
+                    }
+                    assert_eq!(view.kth(path.len()), None);
+                    view.apply(delta);
+                    assert_eq!(view.fold(), lct_fold, "it={it}");
+
+                    assert_eq!(lct.path_fold(u, v), Some(lct_fold), "it={it}");
+                    assert_eq!(tt.path_fold(u, v), Some(lct_fold), "it={it}");
+                }
+            }
+        }
+    }
 }
@@ -1342,6 +1342,29 @@ impl<P: LazyMapMonoid> TopTree<P> {
         true
     }
 
+    /// Applies every `(u, v, act)` as [`Self::path_apply`] would, in order,
+    /// and returns how many of them hit connected pairs. A run of consecutive
+    /// ops on the same path (in either direction) is composed into a single
+    /// action first, so that path is exposed and fixed upwards once per run.
+    pub fn path_apply_batch(&mut self, ops: &[(usize, usize, P::Act)]) -> usize {
+        let mut applied = 0;
+        let mut rest = ops;
+        while let Some(&(u, v, first)) = rest.first() {
+            let run = rest
+                .iter()
+                .take_while(|&&(a, b, _)| (a, b) == (u, v) || (a, b) == (v, u))
+                .count();
+            let act = rest[1..run]
+                .iter()
+                .fold(first, |acc, (_, _, act)| P::act_compose(act, &acc));
+            if self.path_apply(u, v, act) {
+                applied += run;
+            }
+            rest = &rest[run..];
+        }
+        applied
+    }
+
     pub fn path_len(&mut self, u: usize, v: usize) -> Option<usize> {
         debug_assert!(u < self.real_n && v < self.real_n);
         let uid = v_id(u);
@@ -1393,6 +1416,37 @@ impl<P: LazyMapMonoid> TopTree<P> {
         Some(vv.idx())
     }
 
+    /// Exposes the `u`-`v` path once and returns a view answering length,
+    /// k-th vertex, fold, and apply on it without re-exposing. `None` when
+    /// `u` and `v` are in different trees.
+    pub fn path_slice(&mut self, u: usize, v: usize) -> Option<TopTreePathView<'_, P>> {
+        debug_assert!(u < self.real_n && v < self.real_n);
+        let uid = v_id(u);
+        let vid = v_id(v);
+        if !self.connected_internal(uid, vid) {
+            return None;
+        }
+        if u == v {
+            return Some(TopTreePathView {
+                tree: self,
+                u: uid,
+                v: vid,
+                node: NodeId::NIL,
+                len: 1,
+            });
+        }
+        let node = self.path_query_node(uid, vid);
+        self.push(node);
+        let len = self.node(node).fold.path_v_cnt as usize + 2;
+        Some(TopTreePathView {
+            tree: self,
+            u: uid,
+            v: vid,
+            node,
+            len,
+        })
+    }
+
     pub fn subtree_fold(&mut self, child: usize, parent: usize) -> P::Agg {
         debug_assert!(child < self.real_n && parent < self.real_n);
         let w = self
@@ -1415,6 +1469,118 @@ impl<P: LazyMapMonoid> TopTree<P> {
     }
 }
 
+impl<P: LazyMapMonoid<Key = i64>> TopTree<P> {
+    /// Weighted diameter of the subtree on the `child` side of the edge
+    /// `(child, parent)`, using the edge values as non-negative lengths.
+    ///
+    /// Cuts the edge like [`Self::subtree_fold`], runs two farthest-vertex
+    /// sweeps over the edge lists of `child`'s component, then links back.
+    /// O(subtree size + log n).
+    pub fn subtree_diameter(&mut self, child: usize, parent: usize) -> u64 {
+        debug_assert!(child < self.real_n && parent < self.real_n);
+        let w = self
+            .cut_with_edge_key(child, parent)
+            .expect("subtree_diameter requires an existing edge");
+        let (far, _) = self.farthest_in_component(child);
+        let (_, diameter) = self.farthest_in_component(far);
+        let ok = self.link_with_edge(child, parent, w);
+        debug_assert!(ok);
+        diameter
+    }
+
+    /// Farthest vertex from `start` within its component, with its distance.
+    fn farthest_in_component(&self, start: usize) -> (usize, u64) {
+        let mut best = (start, 0);
+        let mut stack = vec![(start, usize::MAX, 0_u64)];
+        while let Some((u, from, dist)) = stack.pop() {
+            if dist > best.1 {
+                best = (u, dist);
+            }
+            for &(to, e) in &self.edges[u] {
+                let to = to as usize;
+                if to == from {
+                    continue;
+                }
+                let w = self.node(e).edge_key;
+                assert!(w >= 0, "subtree_diameter needs non-negative edge values");
+                stack.push((to, u, dist + w as u64));
+            }
+        }
+        best
+    }
+}
+
+/// A `u`-`v` path of a [`TopTree`] exposed as one cluster, from
+/// [`TopTree::path_slice`]. Vertices are indexed from `u`.
+pub struct TopTreePathView<'a, P: LazyMapMonoid> {
+    tree: &'a mut TopTree<P>,
+    u: VertexId,
+    v: VertexId,
+    node: NodeId, // path cluster between the endpoints; NIL when u == v
+    len: usize,
+}
+
+impl<P: LazyMapMonoid> TopTreePathView<'_, P> {
+    /// Number of vertices on the path, endpoints included.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Always `false`: a path holds at least its endpoint.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The `k`-th vertex from `u`, or `None` if `k >= len()`.
+    pub fn kth(&mut self, k: usize) -> Option<usize> {
+        if k >= self.len {
+            return None;
+        }
+        if k == 0 {
+            return Some(self.u.idx());
+        }
+        if k + 1 == self.len {
+            return Some(self.v.idx());
+        }
+        let tree = &mut *self.tree;
+        tree.push(self.node);
+        let internal_cnt = tree.node(self.node).fold.path_v_cnt;
+        let mut ik = (k - 1) as u32;
+        if tree.node(self.node).endpoint[0] != self.u {
+            ik = internal_cnt - 1 - ik;
+        }
+        Some(tree.kth_on_path_internal(self.node, ik).idx())
+    }
+
+    pub fn fold(&mut self) -> P::Agg {
+        let tree = &mut *self.tree;
+        if self.node.is_nil() {
+            return P::agg_from_key(&tree.value(self.u));
+        }
+        tree.push(self.node);
+        let internal = if tree.node(self.node).endpoint[0] == self.u {
+            tree.node(self.node).fold.path_fwd
+        } else {
+            tree.node(self.node).fold.path_rev
+        };
+        let agg = P::agg_merge(&P::agg_unit(), &tree.value(self.u), &internal);
+        P::agg_merge(&agg, &tree.value(self.v), &P::agg_unit())
+    }
+
+    pub fn apply(&mut self, act: P::Act) {
+        let tree = &mut *self.tree;
+        if self.node.is_nil() {
+            tree.vertex_apply(self.u.idx(), act);
+            return;
+        }
+        tree.push(self.node);
+        tree.apply_path(self.node, act);
+        tree.apply_act_to_vertex_if_real(self.u, act);
+        tree.apply_act_to_vertex_if_real(self.v, act);
+        tree.fix_upwards(self.node);
+    }
+}
+
 impl TopTree<VertexSumAdd> {
     pub fn vertex_add(&mut self, v: usize, delta: i64) {
         self.vertex_apply(v, delta);
@@ -1,17 +1,22 @@
 use std::hint::black_box;
 
-use bench::{apply_small_runtime_config, default_rng, random_with_bits};
+use bench::{apply_small_runtime_config, bench_seed, random_with_bits, rng_with_seed};
 use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
-use gcd::{gcd_binary, gcd_euclid};
+use gcd::{gcd_binary, gcd_binary_fast, gcd_euclid};
+
+type GcdFn = fn(u64, u64) -> u64;
+
+const IMPLS: [(&str, GcdFn); 3] = [
+    ("euclid", gcd_euclid),
+    ("binary", gcd_binary),
+    ("binary_fast", gcd_binary_fast),
+];
 
 fn bench_gcd(c: &mut Criterion) {
     const DATASET_SIZE: usize = 1024;
     const BIT_LENGTHS: [u32; 8] = [8, 16, 24, 32, 40, 48, 56, 64];
 
-    type GcdFn = fn(u64, u64) -> u64;
-    let impls: [(&str, GcdFn); 2] = [("euclid", gcd_euclid), ("binary", gcd_binary)];
-
-    let mut rng = default_rng();
+    let mut rng = rng_with_seed(bench_seed());
 
     let mut group = c.benchmark_group("gcd_bitlen");
     apply_small_runtime_config(&mut group);
@@ -26,7 +31,7 @@ fn bench_gcd(c: &mut Criterion) {
             })
             .collect::<Vec<_>>();
 
-        for &(name, func) in &impls {
+        for &(name, func) in &IMPLS {
             group.bench_function(BenchmarkId::new(name, bits), |bencher| {
                 bencher.iter(|| {
                     for &(a, b) in &pairs {
@@ -39,5 +44,38 @@ fn bench_gcd(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_gcd);
+/// Consecutive Fibonacci numbers, Euclid's worst case, scaled by small odd
+/// factors and powers of two so the binary variants also see shared twos.
+fn bench_gcd_fibonacci(c: &mut Criterion) {
+    let mut fib = vec![1_u64, 2];
+    while let Some(next) = fib[fib.len() - 2].checked_add(fib[fib.len() - 1]) {
+        fib.push(next);
+    }
+    let pairs = fib
+        .windows(2)
+        .enumerate()
+        .flat_map(|(i, w)| {
+            let (k, s) = (2 * (i as u64 % 4) + 1, i % 4);
+            [
+                (w[1], w[0]),
+                (w[1].wrapping_mul(k) << s, w[0].wrapping_mul(k) << s),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("gcd_fibonacci");
+    apply_small_runtime_config(&mut group);
+    for &(name, func) in &IMPLS {
+        group.bench_function(name, |bencher| {
+            bencher.iter(|| {
+                for &(a, b) in &pairs {
+                    black_box(func(black_box(a), black_box(b)));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_gcd, bench_gcd_fibonacci);
 criterion_main!(benches);
@@ -30,6 +30,157 @@ pub fn gcd_binary(mut a: u64, mut b: u64) -> u64 {
     }
 }
 
+/// Gcd of two signed values, `gcd_i64(0, 0) == 0`.
+///
+/// Works on absolute values and returns a `u64`, so it never panics: the gcd
+/// is `2^63` when both inputs are `0` or `i64::MIN` and at least one is
+/// `i64::MIN`, which `i64` (and `ext_gcd`) cannot represent.
+pub fn gcd_i64(a: i64, b: i64) -> u64 {
+    gcd(a.unsigned_abs(), b.unsigned_abs())
+}
+
+/// Gcd for any primitive integer type; the result is never negative and
+/// panics on a signed type only when it equals `2^(BITS - 1)`. [`gcd_i64`]
+/// returns that case as a `u64` instead.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    T::gcd_of(a, b)
+}
+
+/// Primitive integer types accepted by [`gcd`]. Sealed: implemented for
+/// `u8..=u128`, `usize`, `i8..=i128` and `isize` only.
+pub trait Integer: Copy + sealed::Sealed {
+    #[doc(hidden)]
+    fn gcd_of(a: Self, b: Self) -> Self;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for $t {}
+
+        impl Integer for $t {
+            fn gcd_of(mut a: Self, mut b: Self) -> Self {
+                while b != 0 {
+                    (a, b) = (b, a % b);
+                }
+                a
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {$(
+        impl sealed::Sealed for $t {}
+
+        impl Integer for $t {
+            fn gcd_of(a: Self, b: Self) -> Self {
+                let g = Integer::gcd_of(a.unsigned_abs(), b.unsigned_abs());
+                <$t>::try_from(g).expect("gcd 2^(BITS - 1) does not fit in a signed type")
+            }
+        }
+    )*};
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_signed!(i8, i16, i32, i64, i128, isize);
+
+/// Binary gcd without the swap branch: each step computes `|a - b|` and
+/// `min(a, b)` (both lower to conditional moves) and takes the trailing zero
+/// count of the wrapping difference, which is the same for `a - b` and
+/// `b - a`, before the subtraction result is even needed.
+pub fn gcd_binary_fast(a: u64, b: u64) -> u64 {
+    if a == 0 {
+        return b;
+    }
+    if b == 0 {
+        return a;
+    }
+
+    let shift = (a | b).trailing_zeros();
+    let mut a = a >> a.trailing_zeros();
+    let mut b = b;
+    let mut zeros = b.trailing_zeros();
+
+    while b != 0 {
+        b >>= zeros;
+        zeros = b.wrapping_sub(a).trailing_zeros();
+        (a, b) = (a.min(b), a.abs_diff(b));
+    }
+    a << shift
+}
+
+/// Least common multiple, with `lcm(0, x) == 0`. Divides before multiplying,
+/// so it only overflows when the result itself does not fit.
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    a / gcd_euclid(a, b) * b
+}
+
+/// Gcd of all values; `0` for an empty slice, the identity of gcd.
+pub fn gcd_slice(xs: &[u64]) -> u64 {
+    xs.iter().fold(0, |g, &x| gcd_euclid(g, x))
+}
+
+/// Lcm of all values, or `None` if it exceeds `u64::MAX`; `Some(1)` for an
+/// empty slice and `Some(0)` as soon as any value is zero.
+pub fn lcm_slice(xs: &[u64]) -> Option<u64> {
+    if xs.contains(&0) {
+        return Some(0);
+    }
+    xs.iter()
+        .try_fold(1_u64, |l, &x| (l / gcd_euclid(l, x)).checked_mul(x))
+}
+
+/// Extended Euclid: `(g, x, y)` with `a * x + b * y == g` and
+/// `g == gcd(|a|, |b|)`, so `g >= 0`; `ext_gcd(0, 0) == (0, 0, 0)`.
+///
+/// The coefficients satisfy `|x| <= |b| / g` and `|y| <= |a| / g` whenever
+/// neither input is zero. Panics if the gcd is `2^63`, which only happens when
+/// both inputs are `0` or `i64::MIN` and at least one is `i64::MIN`.
+pub fn ext_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    if a == 0 && b == 0 {
+        return (0, 0, 0);
+    }
+    // i128 keeps the intermediate negations and products of `i64::MIN` in range.
+    let (g, x, y) = ext_gcd_wide(i128::from(a), i128::from(b));
+    let g = i64::try_from(g).expect("gcd 2^63 does not fit in i64");
+    (g, x as i64, y as i64)
+}
+
+/// Inverse of `a` modulo `m` in `[0, m)`, or `None` if `gcd(a, m) != 1` or
+/// `m == 0`. Modulo `1` every value is `0` and `0 * 0 == 1`, so `m == 1`
+/// gives `Some(0)`.
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    // Same recurrence as `ext_gcd`, widened so that any `u64` modulus fits.
+    let (g, x, _) = ext_gcd_wide(i128::from(a % m), i128::from(m));
+    (g == 1 || m == 1).then(|| x.rem_euclid(i128::from(m)) as u64)
+}
+
+fn ext_gcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1_i128, 0_i128);
+    let (mut old_y, mut y) = (0_i128, 1_i128);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (old_r, old_x, old_y) = (-old_r, -old_x, -old_y);
+    }
+    (old_r, old_x, old_y)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,6 +200,7 @@ mod tests {
         for (a, b, expected) in cases {
             assert_eq!(gcd_euclid(a, b), expected);
             assert_eq!(gcd_binary(a, b), expected);
+            assert_eq!(gcd_binary_fast(a, b), expected);
         }
     }
 
@@ -66,6 +218,219 @@ mod tests {
         for (a, b) in pairs {
             let euclid = gcd_euclid(a, b);
             assert_eq!(gcd_binary(a, b), euclid);
+            assert_eq!(gcd_binary_fast(a, b), euclid);
+        }
+
+        // Consecutive Fibonacci numbers are Euclid's worst case; multiples of
+        // them and mixed powers of two exercise the shift handling.
+        let (mut f0, mut f1) = (1_u64, 2_u64);
+        while let Some(f2) = f0.checked_add(f1) {
+            for (a, b) in [(f0, f1), (f1 << 3, f2 << 5), (f2, f0.wrapping_mul(6))] {
+                assert_eq!(gcd_binary(a, b), gcd_euclid(a, b), "{a} {b}");
+                assert_eq!(gcd_binary_fast(a, b), gcd_euclid(a, b), "{a} {b}");
+            }
+            (f0, f1) = (f1, f2);
+        }
+
+        let mut x = 0x9E37_79B9_7F4A_7C15_u64;
+        for _ in 0..10_000 {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            let (a, b) = (x >> (x % 64), x.rotate_left(29) >> (x % 61));
+            assert_eq!(gcd_binary_fast(a, b), gcd_euclid(a, b), "{a} {b}");
+        }
+        for (a, b) in [
+            (u64::MAX, u64::MAX),
+            (1 << 63, 1 << 63),
+            (u64::MAX, 1 << 63),
+        ] {
+            assert_eq!(gcd_binary_fast(a, b), gcd_euclid(a, b), "{a} {b}");
+        }
+    }
+
+    #[test]
+    fn gcd_signed_known_cases() {
+        let cases = [
+            (0_i64, 0_i64, 0_u64),
+            (12, -18, 6),
+            (-12, 18, 6),
+            (-12, -18, 6),
+            (-7, 0, 7),
+            (0, -7, 7),
+            (i64::MIN, 3, 1),
+            (i64::MIN, 6, 2),
+            (-6, i64::MIN, 2),
+            (i64::MIN, i64::MIN + 1, 1),
+            (i64::MIN, 1 << 62, 1 << 62),
+            (i64::MIN, -(1 << 62), 1 << 62),
+            (i64::MAX, i64::MIN, 1),
+        ];
+        for (a, b, expected) in cases {
+            assert_eq!(gcd_i64(a, b), expected, "{a} {b}");
+            assert_eq!(gcd_i64(b, a), expected, "{b} {a}");
+            assert_eq!(gcd(i128::from(a), i128::from(b)), i128::from(expected));
+        }
+    }
+
+    #[test]
+    fn gcd_i64_returns_two_to_the_63() {
+        assert_eq!(gcd_i64(i64::MIN, 0), 1 << 63);
+        assert_eq!(gcd_i64(0, i64::MIN), 1 << 63);
+        assert_eq!(gcd_i64(i64::MIN, i64::MIN), 1 << 63);
+        assert_eq!(gcd_i64(i64::MIN, i64::MAX), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn gcd_generic_i64_min_and_zero_overflows() {
+        gcd(i64::MIN, 0);
+    }
+
+    #[test]
+    fn gcd_generic_matches_u64() {
+        let values = [0_u64, 1, 2, 3, 6, 12, 18, 35, 97, 127, 128, 255];
+        for &a in &values {
+            for &b in &values {
+                let expected = gcd_euclid(a, b);
+                assert_eq!(gcd(a, b), expected);
+                assert_eq!(u64::from(gcd(a as u8, b as u8)), expected);
+                assert_eq!(gcd(a as usize, b as usize) as u64, expected);
+                assert_eq!(gcd(a as u128, b as u128) as u64, expected);
+                if a < 128 && b < 128 {
+                    let (sa, sb) = (a as i8, b as i8);
+                    assert_eq!(gcd(-sa, sb) as u64, expected);
+                    assert_eq!(gcd(sa, -sb) as u64, expected);
+                    assert_eq!(gcd(-(a as isize), -(b as isize)) as u64, expected);
+                }
+            }
+        }
+        assert_eq!(gcd(i8::MIN, 6), 2);
+        assert_eq!(gcd(i32::MIN, i32::MIN + 2), 2);
+        assert_eq!(gcd(u128::MAX, 5), 5);
+    }
+
+    #[test]
+    fn ext_gcd_satisfies_bezout() {
+        assert_eq!(ext_gcd(0, 0), (0, 0, 0));
+        assert_eq!(ext_gcd(0, -7), (7, 0, -1));
+        assert_eq!(ext_gcd(12, 0), (12, 1, 0));
+        assert_eq!(ext_gcd(240, 46).0, 2);
+
+        let values = [
+            0_i64,
+            1,
+            -1,
+            2,
+            -3,
+            12,
+            -18,
+            35,
+            97,
+            -1_024,
+            9_699,
+            -3_231,
+            1 << 40,
+            i64::MAX,
+            i64::MIN + 1,
+            i64::MIN,
+        ];
+        for &a in &values {
+            for &b in &values {
+                if a.unsigned_abs() | b.unsigned_abs() == 1 << 63 {
+                    continue;
+                }
+                let (g, x, y) = ext_gcd(a, b);
+                assert_eq!(
+                    g as u64,
+                    gcd_euclid(a.unsigned_abs(), b.unsigned_abs()),
+                    "{a} {b}"
+                );
+                assert_eq!(
+                    i128::from(a) * i128::from(x) + i128::from(b) * i128::from(y),
+                    i128::from(g),
+                    "{a} {b}"
+                );
+                if a != 0 && b != 0 {
+                    assert!(x.unsigned_abs() <= b.unsigned_abs() / g as u64, "{a} {b}");
+                    assert!(y.unsigned_abs() <= a.unsigned_abs() / g as u64, "{a} {b}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lcm_known_cases() {
+        assert_eq!(lcm(0, 0), 0);
+        assert_eq!(lcm(0, 7), 0);
+        assert_eq!(lcm(7, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(17, 13), 221);
+        // a * b alone would overflow; the result does not.
+        assert_eq!(lcm(1 << 62, 1 << 63), 1 << 63);
+        assert_eq!(lcm(u64::MAX, u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn slice_helpers() {
+        assert_eq!(gcd_slice(&[]), 0);
+        assert_eq!(gcd_slice(&[42]), 42);
+        assert_eq!(gcd_slice(&[0, 0]), 0);
+        assert_eq!(gcd_slice(&[0, 12, 18]), 6);
+        assert_eq!(gcd_slice(&[48, 180, 54]), 6);
+
+        assert_eq!(lcm_slice(&[]), Some(1));
+        assert_eq!(lcm_slice(&[42]), Some(42));
+        assert_eq!(lcm_slice(&[4, 6, 10]), Some(60));
+        assert_eq!(lcm_slice(&[3, 0, 5]), Some(0));
+        assert_eq!(lcm_slice(&[1 << 40, 1 << 63, 1 << 20]), Some(1 << 63));
+
+        // The first 16 primes multiply to about 3.3e19 > u64::MAX; 15 of them fit.
+        let primes = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+        assert_eq!(
+            lcm_slice(&primes[..15]),
+            Some(primes[..15].iter().product())
+        );
+        assert_eq!(lcm_slice(&primes), None);
+        assert_eq!(lcm_slice(&[u64::MAX, u64::MAX - 1]), None);
+    }
+
+    #[test]
+    fn mod_inverse_known_cases() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(14, 11), Some(4));
+        assert_eq!(mod_inverse(10, 17), Some(12));
+        assert_eq!(mod_inverse(1, 2), Some(1));
+        assert_eq!(mod_inverse(5, 1), Some(0));
+        assert_eq!(mod_inverse(0, 1), Some(0));
+
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(0, 7), None);
+        assert_eq!(mod_inverse(7, 0), None);
+
+        let moduli = [
+            2_u64,
+            7,
+            12,
+            1_000_000_007,
+            998_244_353,
+            u64::MAX,
+            u64::MAX - 58,
+        ];
+        for &m in &moduli {
+            for a in [1_u64, 2, 3, 10, 12_345, m - 1, m / 2 + 1] {
+                match mod_inverse(a, m) {
+                    Some(inv) => {
+                        assert!(inv < m);
+                        assert_eq!(
+                            u128::from(a) * u128::from(inv) % u128::from(m),
+                            1,
+                            "{a} {m}"
+                        );
+                    }
+                    None => assert_ne!(gcd_euclid(a, m), 1, "{a} {m}"),
+                }
+            }
         }
     }
 }
@@ -1,7 +1,7 @@
 use std::hint::black_box;
 use std::time::{Duration, Instant};
 
-use bench::apply_small_runtime_config;
+use bench::{PeakAlloc, apply_small_runtime_config, bench_seed};
 use criterion::measurement::Measurement;
 use criterion::{BenchmarkGroup, BenchmarkId};
 use rand::rngs::StdRng;
@@ -9,8 +9,8 @@ use rand::{Rng, SeedableRng};
 
 use ordered_map::{
     AaTreeMap, AvlTreeMap, BTreeMapCustom, FusionTreeMap, LlrbTreeMap, OrderedMap, RbTreeMap,
-    ScapegoatTreeMap, SkipListMap, SplayTreeMap, StdBTreeMap, TreapMap, VebMap, WbtTreeMap,
-    XFastTrieMap, YFastTrieMap, ZipTreeMap,
+    ScapegoatTreeMap, SkipListMap, SortedVecMap, SplayTreeMap, StdBTreeMap, TreapMap, VebMap,
+    WbtTreeMap, XFastTrieMap, YFastTrieMap, ZipTreeMap,
 };
 
 const SIZES: [usize; 5] = [1_000, 4_000, 16_000, 64_000, 256_000];
@@ -72,6 +72,31 @@ where
     }
 }
 
+/// Prints the heap bytes a map holds after inserting the read workload's keys
+/// at each size, plus the peak reached while building it. `alloc` must be the
+/// bench binary's registered global allocator.
+pub fn report_memory<M>(alloc: &PeakAlloc, label: &str)
+where
+    M: OrderedMap<Key = u64, Value = u64>,
+{
+    for &size in &SIZES {
+        let keys = generate_initial_keys(size, seed_base(1, size as u64));
+        let before = alloc.current();
+        alloc.reset();
+        let mut map = M::new();
+        for (i, &k) in keys.iter().enumerate() {
+            black_box(map.insert(k, i as u64));
+        }
+        let live = alloc.current().saturating_sub(before);
+        let peak = alloc.peak();
+        println!(
+            "ordered_map/memory/{label}/{size}: {live} bytes live ({:.1} per key), {peak} bytes peak",
+            live as f64 / size as f64
+        );
+        drop(black_box(map));
+    }
+}
+
 pub fn bench_update<M, T>(group: &mut BenchmarkGroup<'_, T>, label: &str)
 where
     T: Measurement<Value = Duration>,
@@ -106,6 +131,48 @@ where
     }
 }
 
+/// Ascending runs of `OPS_PER_ITER` fresh keys, each landing right after the
+/// previous one, into a map prefilled with the even keys `0..2 * size`. The
+/// run is removed again outside the timed region.
+pub fn bench_sequential_insert<M, T>(
+    group: &mut BenchmarkGroup<'_, T>,
+    label: &str,
+    make: fn() -> M,
+) where
+    T: Measurement<Value = Duration>,
+    M: OrderedMap<Key = u64, Value = u64>,
+{
+    for &size in &SIZES {
+        apply_small_runtime_config(group);
+        let base_seed = seed_base(4, size as u64);
+        let mut map = make();
+        for i in 0..size as u64 {
+            black_box(map.insert(2 * i, i));
+        }
+
+        group.bench_function(BenchmarkId::new(label, size), |bencher| {
+            bencher.iter_custom(|iters| {
+                let mut total = Duration::ZERO;
+                for iter in 0..iters {
+                    let mut rng = StdRng::seed_from_u64(seed_for_iter(base_seed, iter));
+                    let start_key = 2 * rng.random_range(0..(size - OPS_PER_ITER) as u64) + 1;
+                    let run = (0..OPS_PER_ITER as u64).map(|j| start_key + 2 * j);
+                    let start = Instant::now();
+                    for key in run.clone() {
+                        black_box(map.insert(key, key));
+                    }
+                    black_box(map.len());
+                    total += start.elapsed();
+                    for key in run {
+                        black_box(map.remove(&key));
+                    }
+                }
+                total
+            })
+        });
+    }
+}
+
 pub fn bench_mixed<M, T>(group: &mut BenchmarkGroup<'_, T>, label: &str)
 where
     T: Measurement<Value = Duration>,
@@ -140,6 +207,47 @@ where
     }
 }
 
+/// `OPS_PER_ITER` calls to `pop_last` right after an ascending run of inserts
+/// into the middle of a map prefilled with the even keys `0..2 * size`, so a
+/// gap buffer sits mid-map with the popped keys behind it. The popped entries
+/// and the run are restored outside the timed region.
+pub fn bench_pop_last<M, T>(group: &mut BenchmarkGroup<'_, T>, label: &str, make: fn() -> M)
+where
+    T: Measurement<Value = Duration>,
+    M: OrderedMap<Key = u64, Value = u64>,
+{
+    for &size in &SIZES {
+        apply_small_runtime_config(group);
+        let mut map = make();
+        for i in 0..size as u64 {
+            black_box(map.insert(2 * i, i));
+        }
+
+        group.bench_function(BenchmarkId::new(label, size), |bencher| {
+            bencher.iter_custom(|iters| {
+                let mut total = Duration::ZERO;
+                for _ in 0..iters {
+                    let run = (0..OPS_PER_ITER as u64).map(|j| size as u64 + 2 * j + 1);
+                    for key in run.clone() {
+                        black_box(map.insert(key, key));
+                    }
+                    let start = Instant::now();
+                    let popped: Vec<_> = (0..OPS_PER_ITER).filter_map(|_| map.pop_last()).collect();
+                    black_box(map.len());
+                    total += start.elapsed();
+                    for (key, value) in popped {
+                        black_box(map.insert(key, value));
+                    }
+                    for key in run {
+                        black_box(map.remove(&key));
+                    }
+                }
+                total
+            })
+        });
+    }
+}
+
 fn generate_initial_keys(size: usize, base_seed: u64) -> Vec<u64> {
     (0..size)
         .map(|i| mix_seed(base_seed ^ (i as u64)))
@@ -330,7 +438,7 @@ where
 }
 
 fn seed_base(workload_id: u64, size: u64) -> u64 {
-    mix_seed(0x0DDB_A11A_2026_0000_u64 ^ (workload_id << 48) ^ size)
+    mix_seed(bench_seed() ^ 0x0DDB_A11A_2026_0000_u64 ^ (workload_id << 48) ^ size)
 }
 
 fn seed_for_iter(base: u64, iter: u64) -> u64 {
@@ -410,3 +518,48 @@ where
     bench_update::<YFastTrieMap<u64>, _>(group, "yfast");
     bench_update::<FusionTreeMap<u64>, _>(group, "fusion");
 }
+
+pub fn report_all_memory(alloc: &PeakAlloc) {
+    report_memory::<StdBTreeMap<u64, u64>>(alloc, "std_btree");
+    report_memory::<AvlTreeMap<u64, u64>>(alloc, "avl");
+    report_memory::<WbtTreeMap<u64, u64>>(alloc, "wbt");
+    report_memory::<AaTreeMap<u64, u64>>(alloc, "aa");
+    report_memory::<LlrbTreeMap<u64, u64>>(alloc, "llrb");
+    report_memory::<RbTreeMap<u64, u64>>(alloc, "rb");
+    report_memory::<TreapMap<u64, u64>>(alloc, "treap");
+    report_memory::<ZipTreeMap<u64, u64>>(alloc, "zip");
+    report_memory::<SplayTreeMap<u64, u64>>(alloc, "splay");
+    report_memory::<ScapegoatTreeMap<u64, u64>>(alloc, "scapegoat");
+    report_memory::<SkipListMap<u64, u64>>(alloc, "skiplist");
+    report_memory::<BTreeMapCustom<u64, u64>>(alloc, "btree");
+    report_memory::<VebMap<u64>>(alloc, "veb");
+    report_memory::<XFastTrieMap<u64>>(alloc, "xfast");
+    report_memory::<YFastTrieMap<u64>>(alloc, "yfast");
+    report_memory::<FusionTreeMap<u64>>(alloc, "fusion");
+}
+
+pub fn bench_all_sequential_insert<T>(group: &mut BenchmarkGroup<'_, T>)
+where
+    T: Measurement<Value = Duration>,
+{
+    bench_sequential_insert(group, "sorted_vec", SortedVecMap::<u64, u64>::new);
+    bench_sequential_insert(
+        group,
+        "sorted_vec_gap",
+        SortedVecMap::<u64, u64>::with_gap_buffer,
+    );
+    bench_sequential_insert(group, "std_btree", StdBTreeMap::<u64, u64>::new);
+}
+
+pub fn bench_all_pop_last<T>(group: &mut BenchmarkGroup<'_, T>)
+where
+    T: Measurement<Value = Duration>,
+{
+    bench_pop_last(group, "sorted_vec", SortedVecMap::<u64, u64>::new);
+    bench_pop_last(
+        group,
+        "sorted_vec_gap",
+        SortedVecMap::<u64, u64>::with_gap_buffer,
+    );
+    bench_pop_last(group, "std_btree", StdBTreeMap::<u64, u64>::new);
+}
@@ -2,7 +2,12 @@ use criterion::{Criterion, criterion_group, criterion_main};
 
 mod common;
 
+#[global_allocator]
+static ALLOC: bench::PeakAlloc = bench::PeakAlloc::new();
+
 fn bench(c: &mut Criterion) {
+    common::report_all_memory(&ALLOC);
+
     let mut read = c.benchmark_group("ordered_map/read");
     common::bench_all_read(&mut read);
     read.finish();
@@ -14,6 +19,14 @@ fn bench(c: &mut Criterion) {
     let mut update = c.benchmark_group("ordered_map/update");
     common::bench_all_update(&mut update);
     update.finish();
+
+    let mut sequential = c.benchmark_group("ordered_map/sequential_insert");
+    common::bench_all_sequential_insert(&mut sequential);
+    sequential.finish();
+
+    let mut pop_last = c.benchmark_group("ordered_map/pop_last");
+    common::bench_all_pop_last(&mut pop_last);
+    pop_last.finish();
 }
 
 criterion_group!(benches, bench);
@@ -0,0 +1,75 @@
+use crate::{OrderedMap, OrderedMapEntry};
+
+/// A view into a single key of an [`OrderedMap`], found with one traversal.
+pub enum Entry<'a, M: OrderedMapEntry + ?Sized + 'a> {
+    Occupied(OccupiedEntry<'a, M>),
+    Vacant(VacantEntry<'a, M>),
+}
+
+pub struct OccupiedEntry<'a, M: OrderedMap + ?Sized + 'a> {
+    value: &'a mut M::Value,
+}
+
+/// The key is absent. The map's [`OrderedMapEntry::Vacant`] holds whatever it
+/// learned during the lookup (an index, a rank, ...), so filling the slot
+/// does not compare keys again.
+pub struct VacantEntry<'a, M: OrderedMapEntry + ?Sized + 'a> {
+    slot: M::Vacant<'a>,
+}
+
+/// Per-map state of a lookup that missed, consumed by inserting the value
+/// where the key belongs.
+pub trait VacantSlot<'a, V> {
+    fn insert(self, value: V) -> &'a mut V;
+}
+
+impl<'a, M: OrderedMapEntry + ?Sized + 'a> Entry<'a, M> {
+    pub fn or_insert(self, default: M::Value) -> &'a mut M::Value {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> M::Value>(self, default: F) -> &'a mut M::Value {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn and_modify<F: FnOnce(&mut M::Value)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, M: OrderedMap + ?Sized + 'a> OccupiedEntry<'a, M> {
+    pub(crate) fn new(value: &'a mut M::Value) -> Self {
+        Self { value }
+    }
+
+    pub fn get(&self) -> &M::Value {
+        self.value
+    }
+
+    pub fn get_mut(&mut self) -> &mut M::Value {
+        self.value
+    }
+
+    pub fn into_mut(self) -> &'a mut M::Value {
+        self.value
+    }
+}
+
+impl<'a, M: OrderedMapEntry + ?Sized + 'a> VacantEntry<'a, M> {
+    pub(crate) fn new(slot: M::Vacant<'a>) -> Self {
+        Self { slot }
+    }
+
+    pub fn insert(self, value: M::Value) -> &'a mut M::Value {
+        self.slot.insert(value)
+    }
+}
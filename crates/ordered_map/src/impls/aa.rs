@@ -1,5 +1,9 @@
+use std::ops::{Bound, RangeBounds};
+
 use crate::OrderedMap;
 
+use super::{after_start, before_end, strictly_ascending};
+
 pub struct AaTreeMap<K: Ord, V> {
     root: Link<K, V>,
     len: usize,
@@ -11,6 +15,7 @@ struct Node<K, V> {
     key: K,
     value: V,
     level: u8,
+    size: usize,
     left: Link<K, V>,
     right: Link<K, V>,
 }
@@ -21,6 +26,7 @@ impl<K, V> Node<K, V> {
             key,
             value,
             level: 1,
+            size: 1,
             left: None,
             right: None,
         }
@@ -29,6 +35,14 @@ impl<K, V> Node<K, V> {
     fn level(node: &Link<K, V>) -> u8 {
         node.as_ref().map(|n| n.level).unwrap_or(0)
     }
+
+    fn size(node: &Link<K, V>) -> usize {
+        node.as_ref().map(|n| n.size).unwrap_or(0)
+    }
+
+    fn recalc(&mut self) {
+        self.size = 1 + Self::size(&self.left) + Self::size(&self.right);
+    }
 }
 
 impl<K: Ord, V> AaTreeMap<K, V> {
@@ -37,7 +51,9 @@ impl<K: Ord, V> AaTreeMap<K, V> {
         if left_level != 0 && left_level == node.level {
             let mut left = node.left.take().unwrap();
             node.left = left.right.take();
+            node.recalc();
             left.right = Some(node);
+            left.recalc();
             return left;
         }
         node
@@ -53,7 +69,9 @@ impl<K: Ord, V> AaTreeMap<K, V> {
         if right_right_level != 0 && right_right_level == node.level {
             let mut right = node.right.take().unwrap();
             node.right = right.left.take();
+            node.recalc();
             right.left = Some(node);
+            right.recalc();
             right.level = right.level.saturating_add(1);
             return right;
         }
@@ -74,6 +92,7 @@ impl<K: Ord, V> AaTreeMap<K, V> {
     }
 
     fn rebalance_after_delete(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        node.recalc();
         node = Self::decrease_level(node);
         node = Self::skew(node);
 
@@ -146,6 +165,7 @@ impl<K: Ord, V> AaTreeMap<K, V> {
             }
         };
 
+        node.recalc();
         let node = Self::split(Self::skew(node));
         (Some(node), old, inserted)
     }
@@ -192,6 +212,108 @@ impl<K: Ord, V> AaTreeMap<K, V> {
         let node = Self::rebalance_after_delete(node);
         (Some(node), removed, did_remove)
     }
+
+    /// Builds an AA tree from the next `n` entries of a sorted stream. Giving
+    /// the right side the larger half and every subtree of size `s` the level
+    /// `floor(log2(s + 1))` keeps left children exactly one level down and
+    /// right children at most level with their parent.
+    fn build_sorted<I: Iterator<Item = (K, V)>>(entries: &mut I, n: usize) -> Link<K, V> {
+        if n == 0 {
+            return None;
+        }
+        let left = Self::build_sorted(entries, (n - 1) / 2);
+        let (key, value) = entries.next().expect("stream holds n entries");
+        let mut node = Box::new(Node::new(key, value));
+        node.level = (n + 1).ilog2() as u8;
+        node.left = left;
+        node.right = Self::build_sorted(entries, n / 2);
+        node.recalc();
+        Some(node)
+    }
+
+    /// Dismantles a tree into its entries in ascending key order.
+    fn into_sorted(root: Link<K, V>, len: usize) -> Vec<(K, V)> {
+        let mut entries = Vec::with_capacity(len);
+        let mut stack = Vec::new();
+        let mut cur = root;
+        loop {
+            while let Some(mut node) = cur {
+                cur = node.left.take();
+                stack.push(node);
+            }
+            let Some(node) = stack.pop() else {
+                return entries;
+            };
+            let Node {
+                key, value, right, ..
+            } = *node;
+            entries.push((key, value));
+            cur = right;
+        }
+    }
+
+    fn iter_from<'a>(
+        &'a self,
+        start: Bound<&K>,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + use<'a, K, V> {
+        // Seed the stack with the ancestors at or after `start`; whatever
+        // hangs to their left is skipped.
+        let mut stack = Vec::new();
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            if after_start(start, &node.key) {
+                stack.push(node);
+                cur = node.left.as_deref();
+            } else {
+                cur = node.right.as_deref();
+            }
+        }
+        let mut cur = None;
+        std::iter::from_fn(move || {
+            while let Some(node) = cur {
+                stack.push(node);
+                cur = node.left.as_deref();
+            }
+            let node = stack.pop()?;
+            cur = node.right.as_deref();
+            Some((&node.key, &node.value))
+        })
+    }
+
+    /// Asserts the AA level rules and cached sizes; returns the root level.
+    #[cfg(test)]
+    pub(crate) fn check_balanced(&self) -> u8 {
+        fn walk<K, V>(link: &Link<K, V>) -> usize {
+            let Some(node) = link else {
+                return 0;
+            };
+            let level = node.level;
+            assert_eq!(
+                Node::level(&node.left) + 1,
+                level,
+                "left child not one level down"
+            );
+            let right = Node::level(&node.right);
+            assert!(
+                right == level || right + 1 == level,
+                "right child level off"
+            );
+            if let Some(r) = node.right.as_deref() {
+                assert!(
+                    Node::level(&r.right) < level,
+                    "two horizontal links in a row"
+                );
+            }
+            if level > 1 {
+                assert!(node.left.is_some() && node.right.is_some());
+            }
+            let size = walk(&node.left) + walk(&node.right) + 1;
+            assert_eq!(node.size, size);
+            size
+        }
+        walk(&self.root);
+        Node::level(&self.root)
+    }
 }
 
 impl<K: Ord, V> OrderedMap for AaTreeMap<K, V> {
@@ -202,11 +324,27 @@ impl<K: Ord, V> OrderedMap for AaTreeMap<K, V> {
         Self { root: None, len: 0 }
     }
 
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self {
+        debug_assert!(
+            strictly_ascending(&entries),
+            "from_sorted needs strictly ascending keys"
+        );
+        let len = entries.len();
+        let mut map = Self::new();
+        map.root = Self::build_sorted(&mut entries.into_iter(), len);
+        map.len = len;
+        map
+    }
+
     fn len(&self) -> usize {
         self.len
     }
 
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
         let mut cur = self.root.as_deref();
         while let Some(node) = cur {
             match key.cmp(&node.key) {
@@ -237,6 +375,10 @@ impl<K: Ord, V> OrderedMap for AaTreeMap<K, V> {
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
         let mut cur = self.root.as_deref();
         let mut candidate = None;
         while let Some(node) = cur {
@@ -250,4 +392,131 @@ impl<K: Ord, V> OrderedMap for AaTreeMap<K, V> {
         }
         candidate.map(|n| (&n.key, &n.value))
     }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Less => {
+                    candidate = Some(node);
+                    cur = node.left.as_deref();
+                }
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    cur = node.right.as_deref()
+                }
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less | std::cmp::Ordering::Equal => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(Bound::Unbounded)
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(range.start_bound())
+            .take_while(move |(k, _)| before_end(range.end_bound(), k))
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        let mut cur = self.root.as_deref();
+        let mut rank = 0;
+        while let Some(node) = cur {
+            if node.key < *key {
+                rank += Node::size(&node.left) + 1;
+                cur = node.right.as_deref();
+            } else {
+                cur = node.left.as_deref();
+            }
+        }
+        rank
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut k = k;
+        while let Some(node) = cur {
+            let left = Node::size(&node.left);
+            match k.cmp(&left) {
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+                std::cmp::Ordering::Equal => return Some((&node.key, &node.value)),
+                std::cmp::Ordering::Greater => {
+                    k -= left + 1;
+                    cur = node.right.as_deref();
+                }
+            }
+        }
+        None
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let (root, min_node) = Self::remove_min(self.root.take()?);
+        self.root = root;
+        self.len -= 1;
+        Some((min_node.key, min_node.value))
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let (root, max_node) = Self::remove_max(self.root.take()?);
+        self.root = root;
+        self.len -= 1;
+        Some((max_node.key, max_node.value))
+    }
+
+    fn retain<F: FnMut(&Self::Key, &mut Self::Value) -> bool>(&mut self, mut f: F) {
+        let mut entries = Self::into_sorted(self.root.take(), self.len);
+        entries.retain_mut(|(key, value)| f(key, value));
+        self.len = entries.len();
+        self.root = Self::build_sorted(&mut entries.into_iter(), self.len);
+    }
 }
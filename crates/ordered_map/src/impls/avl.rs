@@ -1,4 +1,8 @@
-use crate::OrderedMap;
+use std::ops::{Bound, RangeBounds};
+
+use crate::{Entry, OccupiedEntry, OrderedMap, OrderedMapEntry, VacantEntry, VacantSlot};
+
+use super::{after_start, before_end, strictly_ascending};
 
 pub struct AvlTreeMap<K: Ord, V> {
     root: Link<K, V>,
@@ -11,6 +15,7 @@ struct Node<K, V> {
     key: K,
     value: V,
     height: i16,
+    size: usize,
     left: Link<K, V>,
     right: Link<K, V>,
 }
@@ -21,6 +26,7 @@ impl<K, V> Node<K, V> {
             key,
             value,
             height: 1,
+            size: 1,
             left: None,
             right: None,
         }
@@ -30,10 +36,15 @@ impl<K, V> Node<K, V> {
         node.as_ref().map(|n| n.height).unwrap_or(0)
     }
 
+    fn size(node: &Link<K, V>) -> usize {
+        node.as_ref().map(|n| n.size).unwrap_or(0)
+    }
+
     fn recalc(&mut self) {
         let hl = Self::height(&self.left);
         let hr = Self::height(&self.right);
         self.height = 1 + hl.max(hr);
+        self.size = 1 + Self::size(&self.left) + Self::size(&self.right);
     }
 
     fn balance_factor(&self) -> i16 {
@@ -99,6 +110,17 @@ impl<K: Ord, V> AvlTreeMap<K, V> {
         (Some(node), min_node)
     }
 
+    fn pop_max(mut node: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+        if node.right.is_none() {
+            let left = node.left.take();
+            return (left, node);
+        }
+        let (new_right, max_node) = Self::pop_max(node.right.take().unwrap());
+        node.right = new_right;
+        let node = Self::rebalance(node);
+        (Some(node), max_node)
+    }
+
     fn insert_node(root: Link<K, V>, key: K, value: V) -> (Link<K, V>, Option<V>, bool) {
         let Some(mut node) = root else {
             return (Some(Box::new(Node::new(key, value))), None, true);
@@ -160,6 +182,114 @@ impl<K: Ord, V> AvlTreeMap<K, V> {
             }
         }
     }
+
+    /// Builds a perfectly balanced tree from the next `n` entries of a sorted stream.
+    /// Hangs `leaf` after the first `index` nodes and rebalances on the way up.
+    fn insert_at(root: Link<K, V>, index: usize, leaf: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        let Some(mut node) = root else {
+            return leaf;
+        };
+        let left = Node::size(&node.left);
+        if index <= left {
+            node.left = Some(Self::insert_at(node.left.take(), index, leaf));
+        } else {
+            node.right = Some(Self::insert_at(node.right.take(), index - left - 1, leaf));
+        }
+        Self::rebalance(node)
+    }
+
+    fn value_at_mut(mut node: &mut Node<K, V>, mut index: usize) -> &mut V {
+        loop {
+            let left = Node::size(&node.left);
+            match index.cmp(&left) {
+                std::cmp::Ordering::Less => node = node.left.as_deref_mut().expect("index < size"),
+                std::cmp::Ordering::Equal => return &mut node.value,
+                std::cmp::Ordering::Greater => {
+                    index -= left + 1;
+                    node = node.right.as_deref_mut().expect("index < size");
+                }
+            }
+        }
+    }
+
+    fn build_sorted<I: Iterator<Item = (K, V)>>(entries: &mut I, n: usize) -> Link<K, V> {
+        if n == 0 {
+            return None;
+        }
+        let left = Self::build_sorted(entries, n / 2);
+        let (key, value) = entries.next().expect("stream holds n entries");
+        let mut node = Box::new(Node::new(key, value));
+        node.left = left;
+        node.right = Self::build_sorted(entries, n - n / 2 - 1);
+        node.recalc();
+        Some(node)
+    }
+
+    /// Dismantles a tree into its entries in ascending key order.
+    fn into_sorted(root: Link<K, V>, len: usize) -> Vec<(K, V)> {
+        let mut entries = Vec::with_capacity(len);
+        let mut stack = Vec::new();
+        let mut cur = root;
+        loop {
+            while let Some(mut node) = cur {
+                cur = node.left.take();
+                stack.push(node);
+            }
+            let Some(node) = stack.pop() else {
+                return entries;
+            };
+            let Node {
+                key, value, right, ..
+            } = *node;
+            entries.push((key, value));
+            cur = right;
+        }
+    }
+
+    fn iter_from<'a>(
+        &'a self,
+        start: Bound<&K>,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + use<'a, K, V> {
+        // Seed the stack with the ancestors at or after `start`; whatever
+        // hangs to their left is skipped.
+        let mut stack = Vec::new();
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            if after_start(start, &node.key) {
+                stack.push(node);
+                cur = node.left.as_deref();
+            } else {
+                cur = node.right.as_deref();
+            }
+        }
+        let mut cur = None;
+        std::iter::from_fn(move || {
+            while let Some(node) = cur {
+                stack.push(node);
+                cur = node.left.as_deref();
+            }
+            let node = stack.pop()?;
+            cur = node.right.as_deref();
+            Some((&node.key, &node.value))
+        })
+    }
+
+    /// Asserts the AVL invariants and cached fields; returns the height.
+    #[cfg(test)]
+    pub(crate) fn check_balanced(&self) -> i16 {
+        fn walk<K, V>(link: &Link<K, V>) -> (i16, usize) {
+            let Some(node) = link else {
+                return (0, 0);
+            };
+            let (lh, ls) = walk(&node.left);
+            let (rh, rs) = walk(&node.right);
+            assert!((lh - rh).abs() <= 1, "AVL balance violated");
+            assert_eq!(node.height, lh.max(rh) + 1);
+            assert_eq!(node.size, ls + rs + 1);
+            (node.height, node.size)
+        }
+        walk(&self.root).0
+    }
 }
 
 impl<K: Ord, V> OrderedMap for AvlTreeMap<K, V> {
@@ -170,11 +300,27 @@ impl<K: Ord, V> OrderedMap for AvlTreeMap<K, V> {
         Self { root: None, len: 0 }
     }
 
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self {
+        debug_assert!(
+            strictly_ascending(&entries),
+            "from_sorted needs strictly ascending keys"
+        );
+        let len = entries.len();
+        let mut map = Self::new();
+        map.root = Self::build_sorted(&mut entries.into_iter(), len);
+        map.len = len;
+        map
+    }
+
     fn len(&self) -> usize {
         self.len
     }
 
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
         let mut cur = self.root.as_deref();
         while let Some(node) = cur {
             match key.cmp(&node.key) {
@@ -205,6 +351,10 @@ impl<K: Ord, V> OrderedMap for AvlTreeMap<K, V> {
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
         let mut cur = self.root.as_deref();
         let mut candidate = None;
         while let Some(node) = cur {
@@ -218,4 +368,183 @@ impl<K: Ord, V> OrderedMap for AvlTreeMap<K, V> {
         }
         candidate.map(|n| (&n.key, &n.value))
     }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Less => {
+                    candidate = Some(node);
+                    cur = node.left.as_deref();
+                }
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    cur = node.right.as_deref()
+                }
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less | std::cmp::Ordering::Equal => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(Bound::Unbounded)
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(range.start_bound())
+            .take_while(move |(k, _)| before_end(range.end_bound(), k))
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        let mut cur = self.root.as_deref();
+        let mut rank = 0;
+        while let Some(node) = cur {
+            if node.key < *key {
+                rank += Node::size(&node.left) + 1;
+                cur = node.right.as_deref();
+            } else {
+                cur = node.left.as_deref();
+            }
+        }
+        rank
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut k = k;
+        while let Some(node) = cur {
+            let left = Node::size(&node.left);
+            match k.cmp(&left) {
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+                std::cmp::Ordering::Equal => return Some((&node.key, &node.value)),
+                std::cmp::Ordering::Greater => {
+                    k -= left + 1;
+                    cur = node.right.as_deref();
+                }
+            }
+        }
+        None
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let (root, min_node) = Self::pop_min(self.root.take()?);
+        self.root = root;
+        self.len -= 1;
+        Some((min_node.key, min_node.value))
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let (root, max_node) = Self::pop_max(self.root.take()?);
+        self.root = root;
+        self.len -= 1;
+        Some((max_node.key, max_node.value))
+    }
+
+    fn retain<F: FnMut(&Self::Key, &mut Self::Value) -> bool>(&mut self, mut f: F) {
+        let mut entries = Self::into_sorted(self.root.take(), self.len);
+        entries.retain_mut(|(key, value)| f(key, value));
+        self.len = entries.len();
+        self.root = Self::build_sorted(&mut entries.into_iter(), self.len);
+    }
+}
+
+/// Vacant [`AvlTreeMap`] lookup: the rank the key will take, which finds the
+/// new node again after the rebalancing rotations have moved it.
+pub struct AvlVacantEntry<'a, K: Ord, V> {
+    key: K,
+    rank: usize,
+    root: &'a mut Link<K, V>,
+    len: &'a mut usize,
+}
+
+impl<'a, K: Ord, V> VacantSlot<'a, V> for AvlVacantEntry<'a, K, V> {
+    fn insert(self, value: V) -> &'a mut V {
+        let leaf = Box::new(Node::new(self.key, value));
+        let root = AvlTreeMap::insert_at(self.root.take(), self.rank, leaf);
+        *self.len += 1;
+        AvlTreeMap::value_at_mut(self.root.insert(root), self.rank)
+    }
+}
+
+impl<K: Ord, V> OrderedMapEntry for AvlTreeMap<K, V> {
+    type Vacant<'a>
+        = AvlVacantEntry<'a, K, V>
+    where
+        Self: 'a;
+
+    fn entry(&mut self, key: K) -> Entry<'_, Self> {
+        // Only the rank is kept from the search: both the occupied value and
+        // the vacant slot are reached again by size, without comparing keys.
+        let mut cur = self.root.as_deref();
+        let mut rank = 0;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+                std::cmp::Ordering::Greater => {
+                    rank += Node::size(&node.left) + 1;
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Equal => {
+                    rank += Node::size(&node.left);
+                    let root = self.root.as_deref_mut().expect("key was found");
+                    return Entry::Occupied(OccupiedEntry::new(Self::value_at_mut(root, rank)));
+                }
+            }
+        }
+        Entry::Vacant(VacantEntry::new(AvlVacantEntry {
+            key,
+            rank,
+            root: &mut self.root,
+            len: &mut self.len,
+        }))
+    }
 }
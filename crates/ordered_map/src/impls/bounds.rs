@@ -0,0 +1,24 @@
+use std::ops::Bound;
+
+/// Whether `key` is not cut off by the range start `start`.
+pub(crate) fn after_start<K: Ord>(start: Bound<&K>, key: &K) -> bool {
+    match start {
+        Bound::Included(lo) => key >= lo,
+        Bound::Excluded(lo) => key > lo,
+        Bound::Unbounded => true,
+    }
+}
+
+/// Whether `key` is not cut off by the range end `end`.
+pub(crate) fn before_end<K: Ord>(end: Bound<&K>, key: &K) -> bool {
+    match end {
+        Bound::Included(hi) => key <= hi,
+        Bound::Excluded(hi) => key < hi,
+        Bound::Unbounded => true,
+    }
+}
+
+/// Whether `entries` is sorted by strictly increasing key, as `from_sorted` requires.
+pub(crate) fn strictly_ascending<K: Ord, V>(entries: &[(K, V)]) -> bool {
+    entries.windows(2).all(|w| w[0].0 < w[1].0)
+}
@@ -1,7 +1,11 @@
 #![allow(clippy::vec_box)]
 
+use std::ops::{Bound, RangeBounds};
+
 use crate::OrderedMap;
 
+use super::{after_start, before_end};
+
 const MIN_DEGREE_CUSTOM: usize = 32;
 
 pub struct BTreeMapCustom<K: Ord, V> {
@@ -23,7 +27,11 @@ impl<K: Ord, V> OrderedMap for BTreeMapCustom<K, V> {
     }
 
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
-        self.inner.get(key)
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
+        self.inner.get_ref(key)
     }
 
     fn insert(&mut self, key: Self::Key, value: Self::Value) -> Option<Self::Value> {
@@ -35,7 +43,58 @@ impl<K: Ord, V> OrderedMap for BTreeMapCustom<K, V> {
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
-        self.inner.lower_bound(key)
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.lower_bound_ref(key)
+    }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.upper_bound(key)
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.floor(key)
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.predecessor(key)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.inner.iter()
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.inner.range(range)
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        self.inner.rank(key)
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.select(k)
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.first()
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.last()
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        self.inner.pop_first()
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        self.inner.pop_last()
     }
 }
 
@@ -53,14 +112,152 @@ impl<K: Ord, V, const T: usize> BTreeMapBase<K, V, T> {
         self.len
     }
 
-    pub(crate) fn get(&mut self, key: &K) -> Option<&V> {
+    pub(crate) fn get_ref(&self, key: &K) -> Option<&V> {
         self.root.as_deref().and_then(|r| r.get(key))
     }
 
-    pub(crate) fn lower_bound(&mut self, key: &K) -> Option<(&K, &V)> {
+    pub(crate) fn lower_bound_ref(&self, key: &K) -> Option<(&K, &V)> {
         self.root.as_deref().and_then(|r| r.lower_bound(key))
     }
 
+    pub(crate) fn upper_bound(&mut self, key: &K) -> Option<(&K, &V)> {
+        self.root.as_deref().and_then(|r| r.upper_bound(key))
+    }
+
+    /// Lower bounds of `queries`, sorted by key, written to `out[index]`.
+    pub(crate) fn lower_bound_many<'a>(
+        &'a self,
+        queries: &[(&K, usize)],
+        out: &mut [Option<&'a K>],
+    ) {
+        if let Some(root) = self.root.as_deref() {
+            root.lower_bound_batch(queries, None, out);
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.iter_from(Bound::Unbounded)
+    }
+
+    pub(crate) fn range(&self, range: impl RangeBounds<K>) -> impl Iterator<Item = (&K, &V)> {
+        self.iter_from(range.start_bound())
+            .take_while(move |(k, _)| before_end(range.end_bound(), k))
+    }
+
+    fn iter_from<'a>(
+        &'a self,
+        start: Bound<&K>,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + use<'a, K, V, T> {
+        // Each frame is a node plus the index of its next key to yield; the
+        // seek starts every frame at its first key not before `start`.
+        let mut stack = Vec::new();
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            let i = node.keys.partition_point(|k| !after_start(start, k));
+            stack.push((node, i));
+            cur = node.children.get(i).map(|c| &**c);
+        }
+        std::iter::from_fn(move || {
+            loop {
+                let (node, next) = stack.last_mut()?;
+                let node: &Node<K, V, T> = node;
+                if *next == node.keys.len() {
+                    stack.pop();
+                    continue;
+                }
+                let i = *next;
+                *next += 1;
+                Node::push_left_spine(&mut stack, node.children.get(i + 1).map(|c| &**c));
+                return Some((&node.keys[i], &node.values[i]));
+            }
+        })
+    }
+
+    pub(crate) fn floor(&mut self, key: &K) -> Option<(&K, &V)> {
+        self.root.as_deref().and_then(|r| r.floor(key))
+    }
+
+    pub(crate) fn predecessor(&mut self, key: &K) -> Option<(&K, &V)> {
+        self.root.as_deref().and_then(|r| r.predecessor(key))
+    }
+
+    pub(crate) fn rank(&mut self, key: &K) -> usize {
+        let mut cur = self.root.as_deref();
+        let mut rank = 0;
+        while let Some(node) = cur {
+            let i = node.keys.partition_point(|k| k < key);
+            rank += i;
+            if node.is_leaf() {
+                break;
+            }
+            rank += node.children[..i].iter().map(|c| c.size).sum::<usize>();
+            cur = Some(&node.children[i]);
+        }
+        rank
+    }
+
+    pub(crate) fn select(&mut self, mut k: usize) -> Option<(&K, &V)> {
+        if k >= self.len {
+            return None;
+        }
+        let mut node = self.root.as_deref()?;
+        'descend: loop {
+            if node.is_leaf() {
+                return Some((&node.keys[k], &node.values[k]));
+            }
+            for (i, child) in node.children.iter().enumerate() {
+                if k < child.size {
+                    node = child;
+                    continue 'descend;
+                }
+                k -= child.size;
+                if k == 0 {
+                    return Some((&node.keys[i], &node.values[i]));
+                }
+                k -= 1;
+            }
+            unreachable!("subtree sizes out of sync");
+        }
+    }
+
+    pub(crate) fn first(&mut self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(child) = node.children.first() {
+            node = child;
+        }
+        Some((node.keys.first()?, node.values.first()?))
+    }
+
+    pub(crate) fn last(&mut self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(child) = node.children.last() {
+            node = child;
+        }
+        Some((node.keys.last()?, node.values.last()?))
+    }
+
+    pub(crate) fn pop_first(&mut self) -> Option<(K, V)> {
+        let root = self.root.as_deref_mut()?;
+        let entry = root.pop_min();
+        self.after_pop();
+        Some(entry)
+    }
+
+    pub(crate) fn pop_last(&mut self) -> Option<(K, V)> {
+        let root = self.root.as_deref_mut()?;
+        let entry = root.pop_max();
+        self.after_pop();
+        Some(entry)
+    }
+
+    fn after_pop(&mut self) {
+        self.len -= 1;
+        if self.root.as_ref().is_some_and(|r| r.keys.is_empty()) {
+            // Shrink height.
+            self.root = self.root.take().unwrap().children.pop();
+        }
+    }
+
     pub(crate) fn insert(&mut self, key: K, value: V) -> Option<V> {
         debug_assert!(T >= 2, "B-tree degree must be >= 2");
         if self.root.is_none() {
@@ -112,11 +309,14 @@ struct Node<K: Ord, V, const T: usize> {
     keys: Vec<K>,
     values: Vec<V>,
     children: Vec<Box<Node<K, V, T>>>,
+    /// Number of entries in this subtree.
+    size: usize,
 }
 
 impl<K: Ord, V, const T: usize> Node<K, V, T> {
     fn new_leaf(keys: Vec<K>, values: Vec<V>) -> Self {
         Self {
+            size: keys.len(),
             keys,
             values,
             children: Vec::new(),
@@ -124,11 +324,18 @@ impl<K: Ord, V, const T: usize> Node<K, V, T> {
     }
 
     fn new_internal(keys: Vec<K>, values: Vec<V>, children: Vec<Box<Node<K, V, T>>>) -> Self {
-        Self {
+        let mut node = Self {
             keys,
             values,
             children,
-        }
+            size: 0,
+        };
+        node.recalc_size();
+        node
+    }
+
+    fn recalc_size(&mut self) {
+        self.size = self.keys.len() + self.children.iter().map(|c| c.size).sum::<usize>();
     }
 
     fn is_leaf(&self) -> bool {
@@ -180,6 +387,81 @@ impl<K: Ord, V, const T: usize> Node<K, V, T> {
         }
     }
 
+    // One merged sweep of the sorted queries over `keys`; each run of queries
+    // that falls strictly between two separators descends into that child
+    // together. `above` is the smallest key past this subtree, if any.
+    fn lower_bound_batch<'a>(
+        &'a self,
+        queries: &[(&K, usize)],
+        above: Option<&'a K>,
+        out: &mut [Option<&'a K>],
+    ) {
+        let mut i = 0;
+        let mut q = 0;
+        while q < queries.len() {
+            let key = queries[q].0;
+            while i < self.keys.len() && self.keys[i] < *key {
+                i += 1;
+            }
+            let sep = self.keys.get(i);
+            if self.is_leaf() || sep == Some(key) {
+                out[queries[q].1] = sep.or(above);
+                q += 1;
+                continue;
+            }
+            let run = queries[q..].partition_point(|&(k, _)| sep.is_none_or(|s| k < s));
+            self.children[i].lower_bound_batch(&queries[q..q + run], sep.or(above), out);
+            q += run;
+        }
+    }
+
+    fn upper_bound(&self, key: &K) -> Option<(&K, &V)> {
+        let i = self.keys.partition_point(|k| k <= key);
+        if !self.is_leaf()
+            && let Some(ans) = self.children[i].upper_bound(key)
+        {
+            return Some(ans);
+        }
+        if i < self.keys.len() {
+            Some((&self.keys[i], &self.values[i]))
+        } else {
+            None
+        }
+    }
+
+    fn floor(&self, key: &K) -> Option<(&K, &V)> {
+        let i = self.keys.partition_point(|k| k <= key);
+        self.last_before(i, |child| child.floor(key))
+    }
+
+    fn predecessor(&self, key: &K) -> Option<(&K, &V)> {
+        let i = self.keys.partition_point(|k| k < key);
+        self.last_before(i, |child| child.predecessor(key))
+    }
+
+    // Everything in `children[i]` lies above `keys[i - 1]`, so a hit there
+    // beats the separator.
+    fn last_before<'a>(
+        &'a self,
+        i: usize,
+        descend: impl FnOnce(&'a Self) -> Option<(&'a K, &'a V)>,
+    ) -> Option<(&'a K, &'a V)> {
+        if !self.is_leaf()
+            && let Some(ans) = descend(&self.children[i])
+        {
+            return Some(ans);
+        }
+        let i = i.checked_sub(1)?;
+        Some((&self.keys[i], &self.values[i]))
+    }
+
+    fn push_left_spine<'a>(stack: &mut Vec<(&'a Self, usize)>, mut cur: Option<&'a Self>) {
+        while let Some(node) = cur {
+            stack.push((node, 0));
+            cur = node.children.first().map(|c| &**c);
+        }
+    }
+
     fn split_child(&mut self, i: usize) {
         debug_assert!(self.children[i].is_full());
         let mut y = self.children.remove(i);
@@ -197,6 +479,7 @@ impl<K: Ord, V, const T: usize> Node<K, V, T> {
         };
 
         let z = Box::new(Node::new_internal(z_keys, z_vals, z_children));
+        y.recalc_size();
 
         self.keys.insert(i, median_key);
         self.values.insert(i, median_val);
@@ -214,6 +497,7 @@ impl<K: Ord, V, const T: usize> Node<K, V, T> {
                 if self.is_leaf() {
                     self.keys.insert(i, key);
                     self.values.insert(i, value);
+                    self.size += 1;
                     return (None, true);
                 }
 
@@ -229,7 +513,11 @@ impl<K: Ord, V, const T: usize> Node<K, V, T> {
                     }
                 }
 
-                self.children[i].insert_non_full(key, value)
+                let (old, inserted) = self.children[i].insert_non_full(key, value);
+                if inserted {
+                    self.size += 1;
+                }
+                (old, inserted)
             }
         }
     }
@@ -249,6 +537,8 @@ impl<K: Ord, V, const T: usize> Node<K, V, T> {
             let moved = left_sib.children.pop().unwrap();
             child.children.insert(0, moved);
         }
+        left_sib.recalc_size();
+        child.recalc_size();
     }
 
     fn borrow_from_next(&mut self, i: usize) {
@@ -266,6 +556,8 @@ impl<K: Ord, V, const T: usize> Node<K, V, T> {
             let moved = right_sib.children.remove(0);
             child.children.push(moved);
         }
+        right_sib.recalc_size();
+        child.recalc_size();
     }
 
     fn merge_children(&mut self, i: usize) {
@@ -284,6 +576,7 @@ impl<K: Ord, V, const T: usize> Node<K, V, T> {
         if !right.is_leaf() {
             left.children.append(&mut right.children);
         }
+        left.recalc_size();
     }
 
     fn ensure_child_has_t(&mut self, i: usize) -> usize {
@@ -312,6 +605,7 @@ impl<K: Ord, V, const T: usize> Node<K, V, T> {
     }
 
     fn pop_min(&mut self) -> (K, V) {
+        self.size -= 1;
         if self.is_leaf() {
             let k = self.keys.remove(0);
             let v = self.values.remove(0);
@@ -328,6 +622,7 @@ impl<K: Ord, V, const T: usize> Node<K, V, T> {
     }
 
     fn pop_max(&mut self) -> (K, V) {
+        self.size -= 1;
         if self.is_leaf() {
             let k = self.keys.pop().unwrap();
             let v = self.values.pop().unwrap();
@@ -345,6 +640,14 @@ impl<K: Ord, V, const T: usize> Node<K, V, T> {
     }
 
     fn remove_from(&mut self, key: &K) -> Option<V> {
+        let removed = self.remove_from_subtree(key);
+        if removed.is_some() {
+            self.size -= 1;
+        }
+        removed
+    }
+
+    fn remove_from_subtree(&mut self, key: &K) -> Option<V> {
         match self.find_index(key) {
             Ok(i) => {
                 if self.is_leaf() {
@@ -1,3 +1,5 @@
+use std::ops::RangeBounds;
+
 use crate::OrderedMap;
 
 use super::btree::BTreeMapBase;
@@ -12,6 +14,23 @@ pub struct FusionTreeMap<V> {
     inner: BTreeMapBase<u64, V, MIN_DEGREE_FUSION>,
 }
 
+impl<V> FusionTreeMap<V> {
+    /// `lower_bound` of every key in `keys`, in the same order, into `out`.
+    ///
+    /// The queries are sorted once and pushed down the tree together: each
+    /// visited node is searched by one merged sweep over its keys for all
+    /// queries that reach it, instead of a binary search per query. This is
+    /// the batch entry point node sketches will plug into.
+    pub fn lower_bound_many(&self, keys: &[u64], out: &mut Vec<Option<u64>>) {
+        let mut queries: Vec<(&u64, usize)> = keys.iter().zip(0..).collect();
+        queries.sort_unstable();
+        let mut found = vec![None; keys.len()];
+        self.inner.lower_bound_many(&queries, &mut found);
+        out.clear();
+        out.extend(found.into_iter().map(|k| k.copied()));
+    }
+}
+
 impl<V> OrderedMap for FusionTreeMap<V> {
     type Key = u64;
     type Value = V;
@@ -27,7 +46,11 @@ impl<V> OrderedMap for FusionTreeMap<V> {
     }
 
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
-        self.inner.get(key)
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
+        self.inner.get_ref(key)
     }
 
     fn insert(&mut self, key: Self::Key, value: Self::Value) -> Option<Self::Value> {
@@ -39,6 +62,57 @@ impl<V> OrderedMap for FusionTreeMap<V> {
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
-        self.inner.lower_bound(key)
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.lower_bound_ref(key)
+    }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.upper_bound(key)
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.floor(key)
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.predecessor(key)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.inner.iter()
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.inner.range(range)
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        self.inner.rank(key)
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.select(k)
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.first()
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.last()
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        self.inner.pop_first()
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        self.inner.pop_last()
     }
 }
@@ -1,5 +1,9 @@
+use std::ops::{Bound, RangeBounds};
+
 use crate::OrderedMap;
 
+use super::{after_start, before_end, strictly_ascending};
+
 pub struct LlrbTreeMap<K: Ord, V> {
     root: Link<K, V>,
     len: usize,
@@ -11,6 +15,7 @@ struct Node<K, V> {
     key: K,
     value: V,
     red: bool,
+    size: usize,
     left: Link<K, V>,
     right: Link<K, V>,
 }
@@ -21,10 +26,19 @@ impl<K, V> Node<K, V> {
             key,
             value,
             red,
+            size: 1,
             left: None,
             right: None,
         }
     }
+
+    fn size(node: &Link<K, V>) -> usize {
+        node.as_ref().map(|n| n.size).unwrap_or(0)
+    }
+
+    fn recalc(&mut self) {
+        self.size = 1 + Self::size(&self.left) + Self::size(&self.right);
+    }
 }
 
 impl<K: Ord, V> LlrbTreeMap<K, V> {
@@ -35,7 +49,9 @@ impl<K: Ord, V> LlrbTreeMap<K, V> {
     fn rotate_left(mut h: Box<Node<K, V>>) -> Box<Node<K, V>> {
         let mut x = h.right.take().expect("rotate_left requires right");
         h.right = x.left.take();
+        h.recalc();
         x.left = Some(h);
+        x.recalc();
         x.red = x.left.as_ref().unwrap().red;
         x.left.as_mut().unwrap().red = true;
         x
@@ -44,7 +60,9 @@ impl<K: Ord, V> LlrbTreeMap<K, V> {
     fn rotate_right(mut h: Box<Node<K, V>>) -> Box<Node<K, V>> {
         let mut x = h.left.take().expect("rotate_right requires left");
         h.left = x.right.take();
+        h.recalc();
         x.right = Some(h);
+        x.recalc();
         x.red = x.right.as_ref().unwrap().red;
         x.right.as_mut().unwrap().red = true;
         x
@@ -61,6 +79,7 @@ impl<K: Ord, V> LlrbTreeMap<K, V> {
     }
 
     fn fix_up(mut h: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        h.recalc();
         if Self::is_red(&h.right) {
             h = Self::rotate_left(h);
         }
@@ -116,6 +135,7 @@ impl<K: Ord, V> LlrbTreeMap<K, V> {
         };
 
         let mut h = h;
+        h.recalc();
         if Self::is_red(&h.right) && !Self::is_red(&h.left) {
             h = Self::rotate_left(h);
         }
@@ -143,6 +163,43 @@ impl<K: Ord, V> LlrbTreeMap<K, V> {
         (Some(h), min_node)
     }
 
+    fn delete_max_with_node(mut h: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+        if Self::is_red(&h.left) {
+            h = Self::rotate_right(h);
+        }
+        if h.right.is_none() {
+            let left = h.left.take();
+            return (left, h);
+        }
+        if !Self::is_red(&h.right) && !h.right.as_ref().is_some_and(|r| Self::is_red(&r.left)) {
+            h = Self::move_red_right(h);
+        }
+        let (new_right, max_node) = Self::delete_max_with_node(h.right.take().unwrap());
+        h.right = new_right;
+        let h = Self::fix_up(h);
+        (Some(h), max_node)
+    }
+
+    /// Detaches the min (or max) node from the root with the usual root
+    /// recoloring around the delete descent.
+    fn pop_extreme(&mut self, max: bool) -> Option<Box<Node<K, V>>> {
+        let mut root = self.root.take()?;
+        if !Self::is_red(&root.left) && !Self::is_red(&root.right) {
+            root.red = true;
+        }
+        let (root, node) = if max {
+            Self::delete_max_with_node(root)
+        } else {
+            Self::delete_min_with_node(root)
+        };
+        self.root = root;
+        if let Some(r) = self.root.as_deref_mut() {
+            r.red = false;
+        }
+        self.len -= 1;
+        Some(node)
+    }
+
     fn remove_node(h: Link<K, V>, key: &K) -> (Link<K, V>, Option<V>) {
         let Some(mut h) = h else {
             return (None, None);
@@ -189,6 +246,225 @@ impl<K: Ord, V> LlrbTreeMap<K, V> {
         let h = Self::fix_up(h);
         (Some(h), removed)
     }
+
+    /// Builds an LLRB tree from the next `n` entries of a sorted stream. This
+    /// mirrors the AA construction: the left side takes the larger half, a
+    /// subtree of size `s` has black height `floor(log2(s + 1))`, and a node is
+    /// red exactly when it shares its parent's black height, which only a left
+    /// child can.
+    fn build_sorted<I: Iterator<Item = (K, V)>>(
+        entries: &mut I,
+        n: usize,
+        parent_height: u32,
+    ) -> Link<K, V> {
+        if n == 0 {
+            return None;
+        }
+        let height = (n + 1).ilog2();
+        let left = Self::build_sorted(entries, n / 2, height);
+        let (key, value) = entries.next().expect("stream holds n entries");
+        let mut node = Box::new(Node::new(key, value, height == parent_height));
+        node.left = left;
+        node.right = Self::build_sorted(entries, (n - 1) / 2, height);
+        node.recalc();
+        Some(node)
+    }
+
+    /// Dismantles a tree into its entries in ascending key order.
+    fn into_sorted(root: Link<K, V>, len: usize) -> Vec<(K, V)> {
+        let mut entries = Vec::with_capacity(len);
+        let mut stack = Vec::new();
+        let mut cur = root;
+        loop {
+            while let Some(mut node) = cur {
+                cur = node.left.take();
+                stack.push(node);
+            }
+            let Some(node) = stack.pop() else {
+                return entries;
+            };
+            let Node {
+                key, value, right, ..
+            } = *node;
+            entries.push((key, value));
+            cur = right;
+        }
+    }
+
+    fn blacken(mut link: Link<K, V>) -> Link<K, V> {
+        if let Some(node) = link.as_deref_mut() {
+            node.red = false;
+        }
+        link
+    }
+
+    fn black_height(mut link: &Link<K, V>) -> usize {
+        let mut height = 0;
+        while let Some(node) = link {
+            height += usize::from(!node.red);
+            link = &node.left;
+        }
+        height
+    }
+
+    /// Joins `left < mid < right` into one tree with a black root. Both
+    /// sides must have black roots.
+    fn join(left: Link<K, V>, mid: Box<Node<K, V>>, right: Link<K, V>) -> Box<Node<K, V>> {
+        let left_height = Self::black_height(&left);
+        let right_height = Self::black_height(&right);
+        let mut root = if left_height >= right_height {
+            Self::join_right(left, left_height, mid, right, right_height)
+        } else {
+            Self::join_left(left, left_height, mid, right, right_height)
+        };
+        root.red = false;
+        root
+    }
+
+    // Walks down the right spine of the taller `left` to the first black node
+    // as tall as `right` and hangs `mid` there as a red link; the fix-ups on
+    // the way back are exactly those of an insertion.
+    fn join_right(
+        left: Link<K, V>,
+        left_height: usize,
+        mut mid: Box<Node<K, V>>,
+        right: Link<K, V>,
+        right_height: usize,
+    ) -> Box<Node<K, V>> {
+        match left {
+            Some(mut h) if left_height > right_height || h.red => {
+                let child_height = left_height - usize::from(!h.red);
+                let child = h.right.take();
+                h.right = Some(Self::join_right(
+                    child,
+                    child_height,
+                    mid,
+                    right,
+                    right_height,
+                ));
+                Self::fix_up(h)
+            }
+            left => {
+                mid.red = true;
+                mid.left = left;
+                mid.right = right;
+                mid.recalc();
+                mid
+            }
+        }
+    }
+
+    fn join_left(
+        left: Link<K, V>,
+        left_height: usize,
+        mut mid: Box<Node<K, V>>,
+        right: Link<K, V>,
+        right_height: usize,
+    ) -> Box<Node<K, V>> {
+        match right {
+            Some(mut h) if right_height > left_height || h.red => {
+                let child_height = right_height - usize::from(!h.red);
+                let child = h.left.take();
+                h.left = Some(Self::join_left(left, left_height, mid, child, child_height));
+                Self::fix_up(h)
+            }
+            right => {
+                mid.red = true;
+                mid.left = left;
+                mid.right = right;
+                mid.recalc();
+                mid
+            }
+        }
+    }
+
+    /// Splits `h` into the keys `< key`, the node holding `key` (detached and
+    /// with stale fields), and the keys `> key`. Both trees have black roots.
+    fn split(h: Link<K, V>, key: &K) -> (Link<K, V>, Link<K, V>, Link<K, V>) {
+        let Some(mut node) = h else {
+            return (None, None, None);
+        };
+        let left = Self::blacken(node.left.take());
+        let right = Self::blacken(node.right.take());
+        match key.cmp(&node.key) {
+            std::cmp::Ordering::Less => {
+                let (lt, eq, gt) = Self::split(left, key);
+                (lt, eq, Some(Self::join(gt, node, right)))
+            }
+            std::cmp::Ordering::Greater => {
+                let (lt, eq, gt) = Self::split(right, key);
+                (Some(Self::join(left, node, lt)), eq, gt)
+            }
+            std::cmp::Ordering::Equal => (left, Some(node), right),
+        }
+    }
+
+    /// Union of two trees with black roots; on equal keys `b`'s entry wins.
+    fn union(a: Link<K, V>, b: Link<K, V>) -> Link<K, V> {
+        let Some(mut mid) = b else {
+            return a;
+        };
+        if a.is_none() {
+            return Some(mid);
+        }
+        let left = Self::blacken(mid.left.take());
+        let right = Self::blacken(mid.right.take());
+        let (lt, _, gt) = Self::split(a, &mid.key);
+        let left = Self::union(lt, left);
+        let right = Self::union(gt, right);
+        Some(Self::join(left, mid, right))
+    }
+
+    fn iter_from<'a>(
+        &'a self,
+        start: Bound<&K>,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + use<'a, K, V> {
+        // Seed the stack with the ancestors at or after `start`; whatever
+        // hangs to their left is skipped.
+        let mut stack = Vec::new();
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            if after_start(start, &node.key) {
+                stack.push(node);
+                cur = node.left.as_deref();
+            } else {
+                cur = node.right.as_deref();
+            }
+        }
+        let mut cur = None;
+        std::iter::from_fn(move || {
+            while let Some(node) = cur {
+                stack.push(node);
+                cur = node.left.as_deref();
+            }
+            let node = stack.pop()?;
+            cur = node.right.as_deref();
+            Some((&node.key, &node.value))
+        })
+    }
+
+    /// Asserts the left-leaning red-black rules and cached sizes; returns the
+    /// black height.
+    #[cfg(test)]
+    pub(crate) fn check_balanced(&self) -> usize {
+        fn walk<K: Ord, V>(link: &Link<K, V>, parent_red: bool) -> (usize, usize) {
+            let Some(node) = link else {
+                return (0, 0);
+            };
+            assert!(!(parent_red && node.red), "red node with a red parent");
+            assert!(
+                !LlrbTreeMap::<K, V>::is_red(&node.right),
+                "right-leaning red link"
+            );
+            let (lb, ls) = walk(&node.left, node.red);
+            let (rb, rs) = walk(&node.right, node.red);
+            assert_eq!(lb, rb, "black heights differ");
+            assert_eq!(node.size, ls + rs + 1);
+            (lb + usize::from(!node.red), node.size)
+        }
+        assert!(!Self::is_red(&self.root), "red root");
+        walk(&self.root, false).0
+    }
 }
 
 impl<K: Ord, V> OrderedMap for LlrbTreeMap<K, V> {
@@ -199,11 +475,27 @@ impl<K: Ord, V> OrderedMap for LlrbTreeMap<K, V> {
         Self { root: None, len: 0 }
     }
 
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self {
+        debug_assert!(
+            strictly_ascending(&entries),
+            "from_sorted needs strictly ascending keys"
+        );
+        let len = entries.len();
+        let mut map = Self::new();
+        map.root = Self::build_sorted(&mut entries.into_iter(), len, u32::MAX);
+        map.len = len;
+        map
+    }
+
     fn len(&self) -> usize {
         self.len
     }
 
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
         let mut cur = self.root.as_deref();
         while let Some(node) = cur {
             match key.cmp(&node.key) {
@@ -247,6 +539,10 @@ impl<K: Ord, V> OrderedMap for LlrbTreeMap<K, V> {
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
         let mut cur = self.root.as_deref();
         let mut candidate = None;
         while let Some(node) = cur {
@@ -260,4 +556,147 @@ impl<K: Ord, V> OrderedMap for LlrbTreeMap<K, V> {
         }
         candidate.map(|n| (&n.key, &n.value))
     }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Less => {
+                    candidate = Some(node);
+                    cur = node.left.as_deref();
+                }
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    cur = node.right.as_deref()
+                }
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less | std::cmp::Ordering::Equal => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(Bound::Unbounded)
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(range.start_bound())
+            .take_while(move |(k, _)| before_end(range.end_bound(), k))
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        let mut cur = self.root.as_deref();
+        let mut rank = 0;
+        while let Some(node) = cur {
+            if node.key < *key {
+                rank += Node::size(&node.left) + 1;
+                cur = node.right.as_deref();
+            } else {
+                cur = node.left.as_deref();
+            }
+        }
+        rank
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut k = k;
+        while let Some(node) = cur {
+            let left = Node::size(&node.left);
+            match k.cmp(&left) {
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+                std::cmp::Ordering::Equal => return Some((&node.key, &node.value)),
+                std::cmp::Ordering::Greater => {
+                    k -= left + 1;
+                    cur = node.right.as_deref();
+                }
+            }
+        }
+        None
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let node = self.pop_extreme(false)?;
+        Some((node.key, node.value))
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let node = self.pop_extreme(true)?;
+        Some((node.key, node.value))
+    }
+
+    fn split_off(&mut self, key: &Self::Key) -> Self {
+        let (lt, eq, gt) = Self::split(self.root.take(), key);
+        let right = match eq {
+            Some(node) => Some(Self::join(None, node, gt)),
+            None => gt,
+        };
+        let right_len = Node::size(&right);
+        self.root = lt;
+        self.len -= right_len;
+        Self {
+            root: right,
+            len: right_len,
+        }
+    }
+
+    fn append(&mut self, other: Self) {
+        self.root = Self::union(self.root.take(), other.root);
+        self.len = Node::size(&self.root);
+    }
+
+    fn retain<F: FnMut(&Self::Key, &mut Self::Value) -> bool>(&mut self, mut f: F) {
+        let mut entries = Self::into_sorted(self.root.take(), self.len);
+        entries.retain_mut(|(key, value)| f(key, value));
+        self.len = entries.len();
+        self.root = Self::build_sorted(&mut entries.into_iter(), self.len, u32::MAX);
+    }
 }
@@ -1,11 +1,14 @@
 mod aa;
 mod avl;
+mod bounds;
 mod btree;
 mod fast_hash;
 mod fusion;
 mod llrb;
 mod rb;
 mod scapegoat;
+#[cfg(feature = "serde")]
+mod serde_impls;
 mod skip_list;
 mod sorted_vec;
 mod splay;
@@ -17,20 +20,21 @@ mod xfast;
 mod yfast;
 mod zip;
 
+pub(crate) use bounds::{after_start, before_end, strictly_ascending};
 pub(crate) use fast_hash::FastHashMap;
 
 pub use aa::AaTreeMap;
-pub use avl::AvlTreeMap;
+pub use avl::{AvlTreeMap, AvlVacantEntry};
 pub use btree::BTreeMapCustom;
 pub use fusion::FusionTreeMap;
 pub use llrb::LlrbTreeMap;
 pub use rb::RbTreeMap;
 pub use scapegoat::ScapegoatTreeMap;
 pub use skip_list::SkipListMap;
-pub use sorted_vec::SortedVecMap;
+pub use sorted_vec::{SortedVecMap, SortedVecVacantEntry};
 pub use splay::SplayTreeMap;
 pub use std_btree::StdBTreeMap;
-pub use treap::TreapMap;
+pub use treap::{TreapMap, TreapVacantEntry};
 pub use veb::VebMap;
 pub use wbt::WbtTreeMap;
 pub use xfast::XFastTrieMap;
@@ -1,3 +1,5 @@
+use std::ops::RangeBounds;
+
 use crate::OrderedMap;
 
 use super::LlrbTreeMap;
@@ -9,6 +11,14 @@ pub struct RbTreeMap<K: Ord, V> {
     inner: LlrbTreeMap<K, V>,
 }
 
+impl<K: Ord, V> RbTreeMap<K, V> {
+    /// Black height, after checking the red-black invariants.
+    #[cfg(test)]
+    pub(crate) fn check_balanced(&self) -> usize {
+        self.inner.check_balanced()
+    }
+}
+
 impl<K: Ord, V> OrderedMap for RbTreeMap<K, V> {
     type Key = K;
     type Value = V;
@@ -19,12 +29,22 @@ impl<K: Ord, V> OrderedMap for RbTreeMap<K, V> {
         }
     }
 
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self {
+        Self {
+            inner: LlrbTreeMap::from_sorted(entries),
+        }
+    }
+
     fn len(&self) -> usize {
         self.inner.len()
     }
 
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
-        self.inner.get(key)
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
+        self.inner.get_ref(key)
     }
 
     fn insert(&mut self, key: Self::Key, value: Self::Value) -> Option<Self::Value> {
@@ -36,6 +56,71 @@ impl<K: Ord, V> OrderedMap for RbTreeMap<K, V> {
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
-        self.inner.lower_bound(key)
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.lower_bound_ref(key)
+    }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.upper_bound(key)
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.floor(key)
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.predecessor(key)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.inner.iter()
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.inner.range(range)
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        self.inner.rank(key)
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.select(k)
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.first()
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.last()
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        self.inner.pop_first()
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        self.inner.pop_last()
+    }
+
+    fn split_off(&mut self, key: &Self::Key) -> Self {
+        Self {
+            inner: self.inner.split_off(key),
+        }
+    }
+
+    fn append(&mut self, other: Self) {
+        self.inner.append(other.inner);
+    }
+
+    fn retain<F: FnMut(&Self::Key, &mut Self::Value) -> bool>(&mut self, f: F) {
+        self.inner.retain(f);
     }
 }
@@ -1,5 +1,9 @@
+use std::ops::{Bound, RangeBounds};
+
 use crate::OrderedMap;
 
+use super::{after_start, before_end, strictly_ascending};
+
 const ALPHA_NUM: usize = 2;
 const ALPHA_DEN: usize = 3;
 
@@ -100,6 +104,25 @@ impl<K: Ord, V> ScapegoatTreeMap<K, V> {
         (Some(node), min_node)
     }
 
+    fn pop_max(mut node: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+        if node.right.is_none() {
+            let left = node.left.take();
+            return (left, node);
+        }
+        let (new_right, max_node) = Self::pop_max(node.right.take().unwrap());
+        node.right = new_right;
+        node.recalc();
+        (Some(node), max_node)
+    }
+
+    fn note_removed(&mut self) {
+        self.n -= 1;
+        if self.n * ALPHA_DEN < self.q * ALPHA_NUM {
+            Self::rebuild(&mut self.root);
+            self.q = self.n;
+        }
+    }
+
     fn remove_node(root: Link<K, V>, key: &K) -> (Link<K, V>, Option<V>, bool) {
         let Some(mut node) = root else {
             return (None, None, false);
@@ -140,6 +163,96 @@ impl<K: Ord, V> ScapegoatTreeMap<K, V> {
             }
         }
     }
+
+    /// Builds a perfectly balanced tree from the next `n` entries of a sorted stream.
+    fn build_sorted<I: Iterator<Item = (K, V)>>(entries: &mut I, n: usize) -> Link<K, V> {
+        if n == 0 {
+            return None;
+        }
+        let left = Self::build_sorted(entries, n / 2);
+        let (key, value) = entries.next().expect("stream holds n entries");
+        let mut node = Box::new(Node::new(key, value));
+        node.left = left;
+        node.right = Self::build_sorted(entries, n - n / 2 - 1);
+        node.recalc();
+        Some(node)
+    }
+
+    /// Dismantles a tree into its entries in ascending key order.
+    fn into_sorted(root: Link<K, V>, len: usize) -> Vec<(K, V)> {
+        let mut entries = Vec::with_capacity(len);
+        let mut stack = Vec::new();
+        let mut cur = root;
+        loop {
+            while let Some(mut node) = cur {
+                cur = node.left.take();
+                stack.push(node);
+            }
+            let Some(node) = stack.pop() else {
+                return entries;
+            };
+            let Node {
+                key, value, right, ..
+            } = *node;
+            entries.push((key, value));
+            cur = right;
+        }
+    }
+
+    fn iter_from<'a>(
+        &'a self,
+        start: Bound<&K>,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + use<'a, K, V> {
+        // Seed the stack with the ancestors at or after `start`; whatever
+        // hangs to their left is skipped.
+        let mut stack = Vec::new();
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            if after_start(start, &node.key) {
+                stack.push(node);
+                cur = node.left.as_deref();
+            } else {
+                cur = node.right.as_deref();
+            }
+        }
+        let mut cur = None;
+        std::iter::from_fn(move || {
+            while let Some(node) = cur {
+                stack.push(node);
+                cur = node.left.as_deref();
+            }
+            let node = stack.pop()?;
+            cur = node.right.as_deref();
+            Some((&node.key, &node.value))
+        })
+    }
+
+    /// Checks sizes, the `alpha * q <= n <= q` counter bounds, and the
+    /// alpha-height bound that triggers rebuilds; returns the height.
+    #[cfg(test)]
+    pub(crate) fn check_balanced(&self) -> usize {
+        fn walk<K, V>(link: &Link<K, V>) -> (usize, usize) {
+            let Some(node) = link else {
+                return (0, 0);
+            };
+            let (lh, ls) = walk(&node.left);
+            let (rh, rs) = walk(&node.right);
+            assert_eq!(node.size, ls + rs + 1);
+            (lh.max(rh) + 1, node.size)
+        }
+        let (height, size) = walk(&self.root);
+        assert_eq!(size, self.n);
+        assert!(self.n <= self.q, "q below n");
+        assert!(
+            self.n * ALPHA_DEN >= self.q * ALPHA_NUM,
+            "q above n / alpha"
+        );
+        assert!(
+            height <= Self::allowed_depth(self.q) + 1,
+            "alpha-height bound violated"
+        );
+        height
+    }
 }
 
 impl<K: Ord, V> OrderedMap for ScapegoatTreeMap<K, V> {
@@ -156,6 +269,19 @@ impl<K: Ord, V> OrderedMap for ScapegoatTreeMap<K, V> {
         }
     }
 
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self {
+        debug_assert!(
+            strictly_ascending(&entries),
+            "from_sorted needs strictly ascending keys"
+        );
+        let len = entries.len();
+        let mut map = Self::new();
+        map.root = Self::build_sorted(&mut entries.into_iter(), len);
+        map.n = len;
+        map.q = len;
+        map
+    }
+
     fn len(&self) -> usize {
         self.n
     }
@@ -238,11 +364,7 @@ impl<K: Ord, V> OrderedMap for ScapegoatTreeMap<K, V> {
         let (root, removed, did_remove) = Self::remove_node(self.root.take(), key);
         self.root = root;
         if did_remove {
-            self.n -= 1;
-            if self.n * ALPHA_DEN < self.q * ALPHA_NUM {
-                Self::rebuild(&mut self.root);
-                self.q = self.n;
-            }
+            self.note_removed();
         }
         removed
     }
@@ -261,4 +383,132 @@ impl<K: Ord, V> OrderedMap for ScapegoatTreeMap<K, V> {
         }
         candidate.map(|n| (&n.key, &n.value))
     }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Less => {
+                    candidate = Some(node);
+                    cur = node.left.as_deref();
+                }
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    cur = node.right.as_deref()
+                }
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less | std::cmp::Ordering::Equal => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(Bound::Unbounded)
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(range.start_bound())
+            .take_while(move |(k, _)| before_end(range.end_bound(), k))
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        let mut cur = self.root.as_deref();
+        let mut rank = 0;
+        while let Some(node) = cur {
+            if node.key < *key {
+                rank += Node::size(&node.left) + 1;
+                cur = node.right.as_deref();
+            } else {
+                cur = node.left.as_deref();
+            }
+        }
+        rank
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut k = k;
+        while let Some(node) = cur {
+            let left = Node::size(&node.left);
+            match k.cmp(&left) {
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+                std::cmp::Ordering::Equal => return Some((&node.key, &node.value)),
+                std::cmp::Ordering::Greater => {
+                    k -= left + 1;
+                    cur = node.right.as_deref();
+                }
+            }
+        }
+        None
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let (root, min_node) = Self::pop_min(self.root.take()?);
+        self.root = root;
+        self.note_removed();
+        Some((min_node.key, min_node.value))
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let (root, max_node) = Self::pop_max(self.root.take()?);
+        self.root = root;
+        self.note_removed();
+        Some((max_node.key, max_node.value))
+    }
+
+    fn retain<F: FnMut(&Self::Key, &mut Self::Value) -> bool>(&mut self, mut f: F) {
+        let mut entries = Self::into_sorted(self.root.take(), self.n);
+        entries.retain_mut(|(key, value)| f(key, value));
+        self.n = entries.len();
+        self.q = self.n;
+        self.root = Self::build_sorted(&mut entries.into_iter(), self.n);
+    }
 }
@@ -0,0 +1,99 @@
+//! Maps serialize as their logical `(key, value)` pairs in ascending key
+//! order and deserialize through `from_sorted`; balancing state (heights,
+//! colors, priorities, levels) is rebuilt rather than stored.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::OrderedMap;
+
+use super::{
+    AaTreeMap, AvlTreeMap, BTreeMapCustom, FusionTreeMap, LlrbTreeMap, RbTreeMap, ScapegoatTreeMap,
+    SkipListMap, SortedVecMap, SplayTreeMap, StdBTreeMap, TreapMap, VebMap, WbtTreeMap,
+    XFastTrieMap, YFastTrieMap, ZipTreeMap,
+};
+
+struct SortedMapVisitor<M>(PhantomData<M>);
+
+impl<'de, M> Visitor<'de> for SortedMapVisitor<M>
+where
+    M: OrderedMap,
+    M::Key: Deserialize<'de>,
+    M::Value: Deserialize<'de>,
+{
+    type Value = M;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map with strictly ascending keys")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<M, A::Error> {
+        // Cap the preallocation; the hint comes from untrusted input.
+        let mut entries = Vec::with_capacity(access.size_hint().unwrap_or(0).min(1 << 12));
+        while let Some((key, value)) = access.next_entry::<M::Key, M::Value>()? {
+            if entries.last().is_some_and(|(last, _)| *last >= key) {
+                return Err(de::Error::custom("map keys must be strictly ascending"));
+            }
+            entries.push((key, value));
+        }
+        Ok(M::from_sorted(entries))
+    }
+}
+
+macro_rules! impl_serde {
+    ($($map:ident<K, V>),* ; $($int_map:ident<V>),* $(,)?) => {
+        $(
+            impl<K: Ord + Serialize, V: Serialize> Serialize for $map<K, V> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_map(self.iter())
+                }
+            }
+
+            impl<'de, K, V> Deserialize<'de> for $map<K, V>
+            where
+                K: Ord + Deserialize<'de>,
+                V: Deserialize<'de>,
+            {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    deserializer.deserialize_map(SortedMapVisitor(PhantomData))
+                }
+            }
+        )*
+        $(
+            impl<V: Serialize> Serialize for $int_map<V> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_map(self.iter())
+                }
+            }
+
+            impl<'de, V: Deserialize<'de>> Deserialize<'de> for $int_map<V> {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    deserializer.deserialize_map(SortedMapVisitor(PhantomData))
+                }
+            }
+        )*
+    };
+}
+
+impl_serde!(
+    AaTreeMap<K, V>,
+    AvlTreeMap<K, V>,
+    BTreeMapCustom<K, V>,
+    LlrbTreeMap<K, V>,
+    RbTreeMap<K, V>,
+    ScapegoatTreeMap<K, V>,
+    SkipListMap<K, V>,
+    SortedVecMap<K, V>,
+    SplayTreeMap<K, V>,
+    StdBTreeMap<K, V>,
+    TreapMap<K, V>,
+    WbtTreeMap<K, V>,
+    ZipTreeMap<K, V>;
+    FusionTreeMap<V>,
+    VebMap<V>,
+    XFastTrieMap<V>,
+    YFastTrieMap<V>,
+);
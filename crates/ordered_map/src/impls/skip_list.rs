@@ -1,11 +1,28 @@
 use std::alloc::{self, Layout};
+use std::cell::Cell;
+use std::ops::{Bound, RangeBounds};
 use std::ptr::NonNull;
 
 use crate::OrderedMap;
 
+use super::{after_start, before_end, strictly_ascending};
+
 const MAX_LEVEL: usize = 32;
 const DEFAULT_SEED: u64 = 0x5EED_5A1B_2026;
 
+thread_local! {
+    // Number of maps `new()` has created on this thread.
+    static MAPS_CREATED: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Seed for the next `new()` on this thread: `DEFAULT_SEED` for the first
+/// map, then spread by a Weyl step, so maps get independent level sequences
+/// while a single-threaded run stays reproducible.
+fn next_default_seed() -> u64 {
+    let k = MAPS_CREATED.replace(MAPS_CREATED.get() + 1);
+    DEFAULT_SEED ^ k.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
 #[derive(Clone, Copy)]
 struct XorShift64 {
     state: u64,
@@ -43,15 +60,21 @@ struct Node<K, V> {
 
 pub struct SkipListMap<K: Ord, V> {
     head: [Link<K, V>; MAX_LEVEL],
+    // `width[lvl]` of a node (or the head) is the rank distance to its `lvl`
+    // successor, counting the end of the list as rank `len + 1`.
+    head_width: [usize; MAX_LEVEL],
     level: usize,
     len: usize,
     rng: XorShift64,
 }
 
 impl<K: Ord, V> SkipListMap<K, V> {
+    /// Empty map whose node levels come from `seed`; the same seed and the
+    /// same operation sequence give the same structure.
     pub fn with_seed(seed: u64) -> Self {
         Self {
             head: [None; MAX_LEVEL],
+            head_width: [1; MAX_LEVEL],
             level: 1,
             len: 0,
             rng: XorShift64::new(seed),
@@ -71,10 +94,19 @@ impl<K: Ord, V> SkipListMap<K, V> {
         (header_size + align - 1) & !(align - 1)
     }
 
+    #[inline(always)]
+    fn widths_offset(level: usize) -> usize {
+        let end = Self::links_offset() + level * std::mem::size_of::<Link<K, V>>();
+        let align = std::mem::align_of::<usize>();
+        (end + align - 1) & !(align - 1)
+    }
+
     #[inline(always)]
     fn node_layout(level: usize) -> Layout {
-        let align = std::mem::align_of::<Node<K, V>>().max(std::mem::align_of::<Link<K, V>>());
-        let size = Self::links_offset() + level * std::mem::size_of::<Link<K, V>>();
+        let align = std::mem::align_of::<Node<K, V>>()
+            .max(std::mem::align_of::<Link<K, V>>())
+            .max(std::mem::align_of::<usize>());
+        let size = Self::widths_offset(level) + level * std::mem::size_of::<usize>();
         Layout::from_size_align(size, align).unwrap()
     }
 
@@ -99,6 +131,36 @@ impl<K: Ord, V> SkipListMap<K, V> {
         }
     }
 
+    #[inline(always)]
+    unsafe fn node_widths_ptr(node: *mut Node<K, V>) -> *mut usize {
+        unsafe {
+            let level = (*node).level as usize;
+            (node as *mut u8).add(Self::widths_offset(level)) as *mut usize
+        }
+    }
+
+    fn width(head_width: &[usize], prev: *mut Node<K, V>, lvl: usize) -> usize {
+        if prev.is_null() {
+            head_width[lvl]
+        } else {
+            unsafe {
+                debug_assert!(lvl < (*prev).level as usize);
+                *Self::node_widths_ptr(prev).add(lvl)
+            }
+        }
+    }
+
+    fn width_mut(head_width: &mut [usize], prev: *mut Node<K, V>, lvl: usize) -> &mut usize {
+        if prev.is_null() {
+            &mut head_width[lvl]
+        } else {
+            unsafe {
+                debug_assert!(lvl < (*prev).level as usize);
+                &mut *Self::node_widths_ptr(prev).add(lvl)
+            }
+        }
+    }
+
     unsafe fn alloc_node(key: K, value: V, level: usize) -> NonNull<Node<K, V>> {
         debug_assert!((1..=MAX_LEVEL).contains(&level));
         let layout = Self::node_layout(level);
@@ -121,6 +183,7 @@ impl<K: Ord, V> SkipListMap<K, V> {
             let links = Self::node_links_ptr(node);
             // Link is `Option<NonNull<_>>`, and all-zeros is `None`.
             std::ptr::write_bytes(links, 0, level);
+            std::ptr::write_bytes(Self::node_widths_ptr(node), 0, level);
 
             NonNull::new_unchecked(node)
         }
@@ -146,6 +209,18 @@ impl<K: Ord, V> SkipListMap<K, V> {
         }
     }
 
+    unsafe fn dealloc_node_take_entry(ptr: NonNull<Node<K, V>>) -> (K, V) {
+        unsafe {
+            let node = ptr.as_ptr();
+            let level = (*node).level as usize;
+
+            let key = std::ptr::read(std::ptr::addr_of!((*node).key));
+            let value = std::ptr::read(std::ptr::addr_of!((*node).value));
+            alloc::dealloc(node as *mut u8, Self::node_layout(level));
+            (key, value)
+        }
+    }
+
     fn find_update(&mut self, key: &K, update: &mut [*mut Node<K, V>]) -> Link<K, V> {
         debug_assert_eq!(update.len(), MAX_LEVEL);
         let mut cur: *mut Node<K, V> = std::ptr::null_mut();
@@ -174,6 +249,87 @@ impl<K: Ord, V> SkipListMap<K, V> {
         Self::get_next(&self.head, update[0], 0)
     }
 
+    /// First node not cut off by `start`, found without recording the path.
+    fn seek(&self, start: Bound<&K>) -> Link<K, V> {
+        let mut cur: *mut Node<K, V> = std::ptr::null_mut();
+        for lvl in (0..self.level).rev() {
+            while let Some(ptr) = Self::get_next(&self.head, cur, lvl) {
+                if after_start(start, unsafe { &*Self::node_key(ptr) }) {
+                    break;
+                }
+                cur = ptr.as_ptr();
+            }
+        }
+        Self::get_next(&self.head, cur, 0)
+    }
+
+    /// Like `find_update`, also recording the rank of each `update[lvl]`
+    /// (0 for the head).
+    fn find_update_ranked(
+        &mut self,
+        key: &K,
+        update: &mut [*mut Node<K, V>],
+        ranks: &mut [usize],
+    ) -> Link<K, V> {
+        debug_assert_eq!(update.len(), MAX_LEVEL);
+        let mut cur: *mut Node<K, V> = std::ptr::null_mut();
+        let mut rank = 0;
+
+        update[self.level..].fill(std::ptr::null_mut());
+        ranks[self.level..].fill(0);
+
+        for lvl in (0..self.level).rev() {
+            let mut next = Self::get_next(&self.head, cur, lvl);
+            while let Some(ptr) = next {
+                let nkey = unsafe { &*Self::node_key(ptr) };
+                if nkey < key {
+                    rank += Self::width(&self.head_width, cur, lvl);
+                    cur = ptr.as_ptr();
+                    next = unsafe { Self::node_get_next(cur, lvl) };
+                } else {
+                    break;
+                }
+            }
+            update[lvl] = cur;
+            ranks[lvl] = rank;
+        }
+
+        Self::get_next(&self.head, update[0], 0)
+    }
+
+    /// Splices `ptr` out of every level it occupies, given its predecessors
+    /// from `find_update`. The node itself is left allocated.
+    fn unlink(&mut self, ptr: NonNull<Node<K, V>>, update: &[*mut Node<K, V>]) {
+        let level = unsafe { (*ptr.as_ptr()).level as usize };
+        for (lvl, &prev) in update.iter().enumerate().take(level) {
+            let after = unsafe { Self::node_get_next(ptr.as_ptr(), lvl) };
+            Self::link_next(&mut self.head, prev, lvl, after);
+        }
+        for (lvl, &prev) in update.iter().enumerate() {
+            let prev_width = Self::width_mut(&mut self.head_width, prev, lvl);
+            if lvl < level {
+                *prev_width += unsafe { *Self::node_widths_ptr(ptr.as_ptr()).add(lvl) } - 1;
+            } else {
+                *prev_width -= 1;
+            }
+        }
+
+        self.len -= 1;
+        while self.level > 1 && self.head[self.level - 1].is_none() {
+            self.level -= 1;
+        }
+    }
+
+    fn last_node(&self) -> Link<K, V> {
+        let mut cur: *mut Node<K, V> = std::ptr::null_mut();
+        for lvl in (0..self.level).rev() {
+            while let Some(ptr) = Self::get_next(&self.head, cur, lvl) {
+                cur = ptr.as_ptr();
+            }
+        }
+        NonNull::new(cur)
+    }
+
     fn link_next(head: &mut [Link<K, V>], prev: *mut Node<K, V>, lvl: usize, next: Link<K, V>) {
         if prev.is_null() {
             head[lvl] = next;
@@ -191,6 +347,20 @@ impl<K: Ord, V> SkipListMap<K, V> {
             unsafe { Self::node_get_next(prev, lvl) }
         }
     }
+
+    /// Tower height of every node, in key order.
+    #[cfg(test)]
+    pub(crate) fn node_levels(&self) -> Vec<u8> {
+        let mut levels = Vec::with_capacity(self.len);
+        let mut cur = self.head[0];
+        while let Some(ptr) = cur {
+            unsafe {
+                levels.push((*ptr.as_ptr()).level);
+                cur = Self::node_get_next(ptr.as_ptr(), 0);
+            }
+        }
+        levels
+    }
 }
 
 impl<K: Ord, V> Drop for SkipListMap<K, V> {
@@ -210,7 +380,37 @@ impl<K: Ord, V> OrderedMap for SkipListMap<K, V> {
     type Value = V;
 
     fn new() -> Self {
-        Self::with_seed(DEFAULT_SEED)
+        Self::with_seed(next_default_seed())
+    }
+
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self {
+        debug_assert!(
+            strictly_ascending(&entries),
+            "from_sorted needs strictly ascending keys"
+        );
+        let mut map = Self::new();
+        // Append at the tail: `last[lvl]` is the rightmost node linked on
+        // level `lvl` so far (null for the head) and `last_rank[lvl]` its rank.
+        let mut last: [*mut Node<K, V>; MAX_LEVEL] = [std::ptr::null_mut(); MAX_LEVEL];
+        let mut last_rank = [0_usize; MAX_LEVEL];
+        for (key, value) in entries {
+            let level = map.rng.next_level();
+            map.level = map.level.max(level);
+            let ptr = unsafe { Self::alloc_node(key, value, level) };
+            map.len += 1;
+            for (lvl, (prev, prev_rank)) in
+                last.iter_mut().zip(&mut last_rank).enumerate().take(level)
+            {
+                Self::link_next(&mut map.head, *prev, lvl, Some(ptr));
+                *Self::width_mut(&mut map.head_width, *prev, lvl) = map.len - *prev_rank;
+                *prev = ptr.as_ptr();
+                *prev_rank = map.len;
+            }
+        }
+        for (lvl, (&prev, &prev_rank)) in last.iter().zip(&last_rank).enumerate() {
+            *Self::width_mut(&mut map.head_width, prev, lvl) = map.len + 1 - prev_rank;
+        }
+        map
     }
 
     fn len(&self) -> usize {
@@ -218,10 +418,11 @@ impl<K: Ord, V> OrderedMap for SkipListMap<K, V> {
     }
 
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
-        let mut update: [*mut Node<K, V>; MAX_LEVEL] =
-            std::array::from_fn(|_| std::ptr::null_mut());
-        let next = self.find_update(key, &mut update);
-        let ptr = next?;
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
+        let ptr = self.seek(Bound::Included(key))?;
         let node = unsafe { ptr.as_ref() };
         if &node.key == key {
             Some(&node.value)
@@ -233,7 +434,8 @@ impl<K: Ord, V> OrderedMap for SkipListMap<K, V> {
     fn insert(&mut self, key: Self::Key, value: Self::Value) -> Option<Self::Value> {
         let mut update: [*mut Node<K, V>; MAX_LEVEL] =
             std::array::from_fn(|_| std::ptr::null_mut());
-        let next = self.find_update(&key, &mut update);
+        let mut ranks = [0_usize; MAX_LEVEL];
+        let next = self.find_update_ranked(&key, &mut update, &mut ranks);
         if let Some(ptr) = next {
             let node = unsafe { ptr.as_ref() };
             if node.key == key {
@@ -258,6 +460,20 @@ impl<K: Ord, V> OrderedMap for SkipListMap<K, V> {
         for (lvl, &prev) in update.iter().enumerate().take(level) {
             Self::link_next(&mut self.head, prev, lvl, Some(ptr));
         }
+
+        let rank = ranks[0] + 1;
+        for (lvl, &prev) in update.iter().enumerate() {
+            let prev_width = Self::width_mut(&mut self.head_width, prev, lvl);
+            if lvl < level {
+                let next_rank = ranks[lvl] + *prev_width + 1;
+                *prev_width = rank - ranks[lvl];
+                unsafe {
+                    *Self::node_widths_ptr(ptr.as_ptr()).add(lvl) = next_rank - rank;
+                }
+            } else {
+                *prev_width += 1;
+            }
+        }
         self.len += 1;
         None
     }
@@ -273,26 +489,148 @@ impl<K: Ord, V> OrderedMap for SkipListMap<K, V> {
             return None;
         }
 
-        let level = unsafe { (*ptr.as_ptr()).level as usize };
-        for (lvl, &prev) in update.iter().enumerate().take(level) {
-            let after = unsafe { Self::node_get_next(ptr.as_ptr(), lvl) };
-            Self::link_next(&mut self.head, prev, lvl, after);
-        }
-
-        self.len -= 1;
-        while self.level > 1 && self.head[self.level - 1].is_none() {
-            self.level -= 1;
-        }
+        self.unlink(ptr, &update);
         let value = unsafe { Self::dealloc_node_take_value(ptr) };
         Some(value)
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let ptr = self.seek(Bound::Included(key))?;
+        let node = unsafe { ptr.as_ref() };
+        Some((&node.key, &node.value))
+    }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
         let mut update: [*mut Node<K, V>; MAX_LEVEL] =
             std::array::from_fn(|_| std::ptr::null_mut());
-        let next = self.find_update(key, &mut update);
+        let mut next = self.find_update(key, &mut update);
+        if let Some(ptr) = next
+            && unsafe { &*Self::node_key(ptr) } == key
+        {
+            next = unsafe { Self::node_get_next(ptr.as_ptr(), 0) };
+        }
         let ptr = next?;
         let node = unsafe { ptr.as_ref() };
         Some((&node.key, &node.value))
     }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut update: [*mut Node<K, V>; MAX_LEVEL] =
+            std::array::from_fn(|_| std::ptr::null_mut());
+        let next = self.find_update(key, &mut update);
+        let ptr = match next {
+            Some(ptr) if unsafe { &*Self::node_key(ptr) } == key => ptr,
+            _ => NonNull::new(update[0])?,
+        };
+        let node = unsafe { ptr.as_ref() };
+        Some((&node.key, &node.value))
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut update: [*mut Node<K, V>; MAX_LEVEL] =
+            std::array::from_fn(|_| std::ptr::null_mut());
+        self.find_update(key, &mut update);
+        let node = unsafe { NonNull::new(update[0])?.as_ref() };
+        Some((&node.key, &node.value))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        let mut cur = self.head[0];
+        std::iter::from_fn(move || {
+            let ptr = cur?;
+            unsafe {
+                cur = Self::node_get_next(ptr.as_ptr(), 0);
+                let node = ptr.as_ref();
+                Some((&node.key, &node.value))
+            }
+        })
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        let mut cur = self.seek(range.start_bound());
+        std::iter::from_fn(move || {
+            let ptr = cur?;
+            unsafe {
+                let node = ptr.as_ref();
+                if !before_end(range.end_bound(), &node.key) {
+                    return None;
+                }
+                cur = Self::node_get_next(ptr.as_ptr(), 0);
+                Some((&node.key, &node.value))
+            }
+        })
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        let mut cur: *mut Node<K, V> = std::ptr::null_mut();
+        let mut rank = 0;
+        for lvl in (0..self.level).rev() {
+            while let Some(ptr) = Self::get_next(&self.head, cur, lvl) {
+                if unsafe { &*Self::node_key(ptr) } >= key {
+                    break;
+                }
+                rank += Self::width(&self.head_width, cur, lvl);
+                cur = ptr.as_ptr();
+            }
+        }
+        rank
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        if k >= self.len {
+            return None;
+        }
+        let target = k + 1;
+        let mut cur: *mut Node<K, V> = std::ptr::null_mut();
+        let mut rank = 0;
+        for lvl in (0..self.level).rev() {
+            while let Some(ptr) = Self::get_next(&self.head, cur, lvl) {
+                let width = Self::width(&self.head_width, cur, lvl);
+                if rank + width > target {
+                    break;
+                }
+                rank += width;
+                cur = ptr.as_ptr();
+            }
+        }
+        debug_assert_eq!(rank, target);
+        let node = unsafe { &*cur };
+        Some((&node.key, &node.value))
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let node = unsafe { self.head[0]?.as_ref() };
+        Some((&node.key, &node.value))
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let node = unsafe { self.last_node()?.as_ref() };
+        Some((&node.key, &node.value))
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let ptr = self.head[0]?;
+        // The first node is preceded by the head on every level.
+        let update = [std::ptr::null_mut(); MAX_LEVEL];
+        self.unlink(ptr, &update);
+        Some(unsafe { Self::dealloc_node_take_entry(ptr) })
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let ptr = self.last_node()?;
+        let mut update: [*mut Node<K, V>; MAX_LEVEL] =
+            std::array::from_fn(|_| std::ptr::null_mut());
+        let key = unsafe { &*Self::node_key(ptr) };
+        let found = self.find_update(key, &mut update);
+        debug_assert_eq!(found, Some(ptr));
+        self.unlink(ptr, &update);
+        Some(unsafe { Self::dealloc_node_take_entry(ptr) })
+    }
 }
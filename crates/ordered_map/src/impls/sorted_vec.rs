@@ -1,7 +1,123 @@
-use crate::OrderedMap;
+use std::collections::VecDeque;
+use std::ops::RangeBounds;
 
+use crate::{Entry, OccupiedEntry, OrderedMap, OrderedMapEntry, VacantEntry, VacantSlot};
+
+use super::{after_start, before_end, strictly_ascending};
+
+/// Inserts landing within this many slots of the previous insertion move the
+/// gap there; anything farther shifts in place like a plain sorted vector.
+const GAP_WINDOW: usize = 32;
+
+/// Entries live in `data` followed by `tail` reversed. Without a gap buffer
+/// `tail` stays empty. With one, the boundary between the two vectors is a
+/// gap that follows nearby insertions, so a run of ascending inserts pushes
+/// onto `data` instead of shifting everything after it. `tail` is a deque so
+/// the largest entry, at its front, pops in O(1) wherever the gap sits.
 pub struct SortedVecMap<K: Ord, V> {
     data: Vec<(K, V)>,
+    tail: VecDeque<(K, V)>,
+    gap_buffer: bool,
+    last_insert: usize,
+}
+
+impl<K: Ord, V> SortedVecMap<K, V> {
+    /// Empty map that keeps a gap near the last insertion point.
+    ///
+    /// Inserts within a small window of the previous one move the gap and
+    /// cost O(distance) instead of O(n); random inserts fall back to shifting.
+    /// Lookups stay O(log n) via a binary search on each side of the gap.
+    pub fn with_gap_buffer() -> Self {
+        Self {
+            gap_buffer: true,
+            ..Self::new()
+        }
+    }
+
+    fn from_vec(data: Vec<(K, V)>, gap_buffer: bool) -> Self {
+        Self {
+            data,
+            tail: VecDeque::new(),
+            gap_buffer,
+            last_insert: 0,
+        }
+    }
+
+    /// First logical index whose key fails `pred`; `pred` must hold on a
+    /// prefix of the keys.
+    fn partition_point(&self, mut pred: impl FnMut(&K) -> bool) -> usize {
+        let idx = self.data.partition_point(|(k, _)| pred(k));
+        if idx < self.data.len() {
+            return idx;
+        }
+        idx + self.tail.len() - self.tail.partition_point(|(k, _)| !pred(k))
+    }
+
+    fn find(&self, key: &K) -> Result<usize, usize> {
+        let idx = self.partition_point(|k| k < key);
+        match self.at(idx) {
+            Some((k, _)) if k == key => Ok(idx),
+            _ => Err(idx),
+        }
+    }
+
+    fn tail_index(&self, idx: usize) -> Option<usize> {
+        self.tail.len().checked_sub(idx - self.data.len() + 1)
+    }
+
+    fn at(&self, idx: usize) -> Option<&(K, V)> {
+        if idx < self.data.len() {
+            return self.data.get(idx);
+        }
+        self.tail.get(self.tail_index(idx)?)
+    }
+
+    fn at_mut(&mut self, idx: usize) -> Option<&mut (K, V)> {
+        if idx < self.data.len() {
+            return self.data.get_mut(idx);
+        }
+        let t = self.tail_index(idx)?;
+        self.tail.get_mut(t)
+    }
+
+    /// Moves the gap so that exactly `idx` entries precede it.
+    fn move_gap(&mut self, idx: usize) {
+        if idx < self.data.len() {
+            self.tail.extend(self.data.drain(idx..).rev());
+        } else {
+            let from = self.tail.len() - (idx - self.data.len());
+            self.data.extend(self.tail.drain(from..).rev());
+        }
+    }
+
+    fn close_gap(&mut self) {
+        self.move_gap(self.len());
+    }
+
+    fn insert_at(&mut self, idx: usize, entry: (K, V)) -> &mut (K, V) {
+        let near = idx.abs_diff(self.last_insert) <= GAP_WINDOW;
+        self.last_insert = idx;
+        if self.gap_buffer && near {
+            self.move_gap(idx);
+            self.data.push(entry);
+            return self.data.last_mut().expect("just pushed");
+        }
+        if idx <= self.data.len() {
+            self.data.insert(idx, entry);
+            return &mut self.data[idx];
+        }
+        let t = self.tail.len() - (idx - self.data.len());
+        self.tail.insert(t, entry);
+        &mut self.tail[t]
+    }
+
+    fn remove_at(&mut self, idx: usize) -> (K, V) {
+        if idx < self.data.len() {
+            return self.data.remove(idx);
+        }
+        let t = self.tail_index(idx).expect("index in bounds");
+        self.tail.remove(t).expect("index in bounds")
+    }
 }
 
 impl<K: Ord, V> OrderedMap for SortedVecMap<K, V> {
@@ -9,38 +125,212 @@ impl<K: Ord, V> OrderedMap for SortedVecMap<K, V> {
     type Value = V;
 
     fn new() -> Self {
-        Self { data: Vec::new() }
+        Self::from_vec(Vec::new(), false)
+    }
+
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self {
+        debug_assert!(
+            strictly_ascending(&entries),
+            "from_sorted needs strictly ascending keys"
+        );
+        Self::from_vec(entries, false)
     }
 
     fn len(&self) -> usize {
-        self.data.len()
+        self.data.len() + self.tail.len()
     }
 
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
-        let idx = self.data.binary_search_by(|(k, _)| k.cmp(key)).ok()?;
-        self.data.get(idx).map(|(_, v)| v)
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
+        let idx = self.find(key).ok()?;
+        self.at(idx).map(|(_, v)| v)
     }
 
     fn insert(&mut self, key: Self::Key, value: Self::Value) -> Option<Self::Value> {
-        match self.data.binary_search_by(|(k, _)| k.cmp(&key)) {
+        match self.find(&key) {
             Ok(idx) => {
-                let old = std::mem::replace(&mut self.data[idx].1, value);
-                Some(old)
+                let slot = &mut self.at_mut(idx)?.1;
+                Some(std::mem::replace(slot, value))
             }
             Err(idx) => {
-                self.data.insert(idx, (key, value));
+                self.insert_at(idx, (key, value));
                 None
             }
         }
     }
 
     fn remove(&mut self, key: &Self::Key) -> Option<Self::Value> {
-        let idx = self.data.binary_search_by(|(k, _)| k.cmp(key)).ok()?;
-        Some(self.data.remove(idx).1)
+        let idx = self.find(key).ok()?;
+        Some(self.remove_at(idx).1)
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let idx = self.partition_point(|k| k < key);
+        self.at(idx).map(|(k, v)| (k, v))
+    }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let idx = self.partition_point(|k| k <= key);
+        self.at(idx).map(|(k, v)| (k, v))
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let idx = self.partition_point(|k| k <= key);
+        let (k, v) = self.at(idx.checked_sub(1)?)?;
+        Some((k, v))
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let idx = self.partition_point(|k| k < key);
+        let (k, v) = self.at(idx.checked_sub(1)?)?;
+        Some((k, v))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.data
+            .iter()
+            .chain(self.tail.iter().rev())
+            .map(|(k, v)| (k, v))
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        let start = self.partition_point(|k| !after_start(range.start_bound(), k));
+        let split = self.data.len();
+        let tail_end = self.tail.len() - start.saturating_sub(split);
+        self.data[start.min(split)..]
+            .iter()
+            .chain(self.tail.range(..tail_end).rev())
+            .take_while(move |(k, _)| before_end(range.end_bound(), k))
+            .map(|(k, v)| (k, v))
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        self.partition_point(|k| k < key)
+    }
+
+    fn count_range(&mut self, lo: &Self::Key, hi: &Self::Key) -> usize {
+        if hi <= lo {
+            return 0;
+        }
+        self.partition_point(|k| k < hi) - self.partition_point(|k| k < lo)
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        self.at(k).map(|(k, v)| (k, v))
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let (k, v) = self.data.first().or_else(|| self.tail.back())?;
+        Some((k, v))
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let (k, v) = self.tail.front().or_else(|| self.data.last())?;
+        Some((k, v))
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        if self.data.is_empty() {
+            return self.tail.pop_back();
+        }
+        Some(self.data.remove(0))
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        self.tail.pop_front().or_else(|| self.data.pop())
+    }
+
+    fn split_off(&mut self, key: &Self::Key) -> Self {
+        self.close_gap();
         let idx = self.data.partition_point(|(k, _)| k < key);
-        self.data.get(idx).map(|(k, v)| (k, v))
+        Self::from_vec(self.data.split_off(idx), self.gap_buffer)
+    }
+
+    fn append(&mut self, mut other: Self) {
+        self.close_gap();
+        other.close_gap();
+        if self
+            .data
+            .last()
+            .is_none_or(|(k, _)| other.data.first().is_none_or(|(o, _)| k < o))
+        {
+            self.data.extend(other.data);
+            return;
+        }
+        // Linear merge; on equal keys the entry from `other` wins.
+        let mine = std::mem::take(&mut self.data);
+        self.data.reserve(mine.len() + other.data.len());
+        let mut mine = mine.into_iter().peekable();
+        let mut theirs = other.data.into_iter().peekable();
+        loop {
+            let take_mine = match (mine.peek(), theirs.peek()) {
+                (Some((a, _)), Some((b, _))) => match a.cmp(b) {
+                    std::cmp::Ordering::Less => true,
+                    std::cmp::Ordering::Equal => {
+                        mine.next();
+                        false
+                    }
+                    std::cmp::Ordering::Greater => false,
+                },
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            let entry = if take_mine {
+                mine.next()
+            } else {
+                theirs.next()
+            };
+            self.data.extend(entry);
+        }
+    }
+
+    fn retain<F: FnMut(&Self::Key, &mut Self::Value) -> bool>(&mut self, mut f: F) {
+        self.close_gap();
+        self.data.retain_mut(|(key, value)| f(key, value));
+    }
+}
+
+/// Vacant [`SortedVecMap`] lookup: the logical index the key goes to.
+pub struct SortedVecVacantEntry<'a, K: Ord, V> {
+    map: &'a mut SortedVecMap<K, V>,
+    idx: usize,
+    key: K,
+}
+
+impl<'a, K: Ord, V> VacantSlot<'a, V> for SortedVecVacantEntry<'a, K, V> {
+    fn insert(self, value: V) -> &'a mut V {
+        &mut self.map.insert_at(self.idx, (self.key, value)).1
+    }
+}
+
+impl<K: Ord, V> OrderedMapEntry for SortedVecMap<K, V> {
+    type Vacant<'a>
+        = SortedVecVacantEntry<'a, K, V>
+    where
+        Self: 'a;
+
+    fn entry(&mut self, key: K) -> Entry<'_, Self> {
+        match self.find(&key) {
+            Ok(idx) => {
+                let (_, value) = self.at_mut(idx).expect("found index is in bounds");
+                Entry::Occupied(OccupiedEntry::new(value))
+            }
+            Err(idx) => Entry::Vacant(VacantEntry::new(SortedVecVacantEntry {
+                map: self,
+                idx,
+                key,
+            })),
+        }
     }
 }
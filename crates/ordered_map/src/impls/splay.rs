@@ -1,8 +1,14 @@
+use std::ops::{Bound, RangeBounds};
+
 use crate::OrderedMap;
 
+use super::{after_start, before_end, strictly_ascending};
+
 pub struct SplayTreeMap<K: Ord, V> {
     root: Link<K, V>,
     len: usize,
+    left_spine: Vec<*mut Node<K, V>>,
+    right_spine: Vec<*mut Node<K, V>>,
 }
 
 type Link<K, V> = Option<Box<Node<K, V>>>;
@@ -10,6 +16,7 @@ type Link<K, V> = Option<Box<Node<K, V>>>;
 struct Node<K, V> {
     key: K,
     value: V,
+    size: usize,
     left: Link<K, V>,
     right: Link<K, V>,
 }
@@ -19,10 +26,19 @@ impl<K, V> Node<K, V> {
         Self {
             key,
             value,
+            size: 1,
             left: None,
             right: None,
         }
     }
+
+    fn size(node: &Link<K, V>) -> usize {
+        node.as_ref().map(|n| n.size).unwrap_or(0)
+    }
+
+    fn recalc(&mut self) {
+        self.size = 1 + Self::size(&self.left) + Self::size(&self.right);
+    }
 }
 
 impl<K: Ord, V> SplayTreeMap<K, V> {
@@ -32,7 +48,9 @@ impl<K: Ord, V> SplayTreeMap<K, V> {
             None => return root,
         };
         root.left = left.right.take();
+        root.recalc();
         left.right = Some(root);
+        left.recalc();
         left
     }
 
@@ -42,25 +60,35 @@ impl<K: Ord, V> SplayTreeMap<K, V> {
             None => return root,
         };
         root.right = right.left.take();
+        root.recalc();
         right.left = Some(root);
+        right.recalc();
         right
     }
 
     fn splay(&mut self, root: Link<K, V>, key: &K) -> Link<K, V> {
+        self.splay_by(root, |k| key.cmp(k))
+    }
+
+    /// Top-down splay steered by `cmp(node_key)`, which orders the target
+    /// relative to `node_key`; a constant `Less`/`Greater` splays the min/max.
+    fn splay_by(&mut self, root: Link<K, V>, cmp: impl Fn(&K) -> std::cmp::Ordering) -> Link<K, V> {
         let mut root = root?;
         let mut left_head: Link<K, V> = None;
         let mut right_head: Link<K, V> = None;
         let mut left_tail: *mut Node<K, V> = std::ptr::null_mut();
         let mut right_tail: *mut Node<K, V> = std::ptr::null_mut();
+        self.left_spine.clear();
+        self.right_spine.clear();
 
         loop {
-            match key.cmp(&root.key) {
+            match cmp(&root.key) {
                 std::cmp::Ordering::Less => {
                     if root.left.is_none() {
                         break;
                     }
                     if let Some(left) = root.left.as_deref()
-                        && key < &left.key
+                        && cmp(&left.key) == std::cmp::Ordering::Less
                     {
                         root = Self::rotate_right(root);
                         if root.left.is_none() {
@@ -78,6 +106,7 @@ impl<K: Ord, V> SplayTreeMap<K, V> {
                             right_tail = (*right_tail).left.as_deref_mut().unwrap();
                         }
                     }
+                    self.right_spine.push(right_tail);
                     root = next;
                 }
                 std::cmp::Ordering::Greater => {
@@ -85,7 +114,7 @@ impl<K: Ord, V> SplayTreeMap<K, V> {
                         break;
                     }
                     if let Some(right) = root.right.as_deref()
-                        && key > &right.key
+                        && cmp(&right.key) == std::cmp::Ordering::Greater
                     {
                         root = Self::rotate_left(root);
                         if root.right.is_none() {
@@ -103,6 +132,7 @@ impl<K: Ord, V> SplayTreeMap<K, V> {
                             left_tail = (*left_tail).right.as_deref_mut().unwrap();
                         }
                     }
+                    self.left_spine.push(left_tail);
                     root = next;
                 }
                 std::cmp::Ordering::Equal => break,
@@ -123,10 +153,88 @@ impl<K: Ord, V> SplayTreeMap<K, V> {
                 (*right_tail).left = root.right.take();
             }
         }
+        // Spine nodes lost a child while being linked into the side trees;
+        // refresh their sizes bottom-up before hanging both trees off `root`.
+        for &ptr in self
+            .left_spine
+            .iter()
+            .rev()
+            .chain(self.right_spine.iter().rev())
+        {
+            unsafe {
+                (*ptr).recalc();
+            }
+        }
         root.left = left_head;
         root.right = right_head;
+        root.recalc();
         Some(root)
     }
+
+    /// Builds a perfectly balanced tree from the next `n` entries of a sorted stream.
+    fn build_sorted<I: Iterator<Item = (K, V)>>(entries: &mut I, n: usize) -> Link<K, V> {
+        if n == 0 {
+            return None;
+        }
+        let left = Self::build_sorted(entries, n / 2);
+        let (key, value) = entries.next().expect("stream holds n entries");
+        let mut node = Box::new(Node::new(key, value));
+        node.left = left;
+        node.right = Self::build_sorted(entries, n - n / 2 - 1);
+        node.recalc();
+        Some(node)
+    }
+
+    /// Dismantles a tree into its entries in ascending key order. A splay tree
+    /// can be a path, so this walks with an explicit stack.
+    fn into_sorted(root: Link<K, V>, len: usize) -> Vec<(K, V)> {
+        let mut entries = Vec::with_capacity(len);
+        let mut stack = Vec::new();
+        let mut cur = root;
+        loop {
+            while let Some(mut node) = cur {
+                cur = node.left.take();
+                stack.push(node);
+            }
+            let Some(node) = stack.pop() else {
+                return entries;
+            };
+            let Node {
+                key, value, right, ..
+            } = *node;
+            entries.push((key, value));
+            cur = right;
+        }
+    }
+
+    fn iter_from<'a>(
+        &'a self,
+        start: Bound<&K>,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + use<'a, K, V> {
+        // Plain in-order walk; unlike `get`, iteration never splays.
+        // Seed the stack with the ancestors at or after `start`; whatever
+        // hangs to their left is skipped.
+        let mut stack = Vec::new();
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            if after_start(start, &node.key) {
+                stack.push(node);
+                cur = node.left.as_deref();
+            } else {
+                cur = node.right.as_deref();
+            }
+        }
+        let mut cur = None;
+        std::iter::from_fn(move || {
+            while let Some(node) = cur {
+                stack.push(node);
+                cur = node.left.as_deref();
+            }
+            let node = stack.pop()?;
+            cur = node.right.as_deref();
+            Some((&node.key, &node.value))
+        })
+    }
 }
 
 impl<K: Ord, V> OrderedMap for SplayTreeMap<K, V> {
@@ -134,7 +242,24 @@ impl<K: Ord, V> OrderedMap for SplayTreeMap<K, V> {
     type Value = V;
 
     fn new() -> Self {
-        Self { root: None, len: 0 }
+        Self {
+            root: None,
+            len: 0,
+            left_spine: Vec::new(),
+            right_spine: Vec::new(),
+        }
+    }
+
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self {
+        debug_assert!(
+            strictly_ascending(&entries),
+            "from_sorted needs strictly ascending keys"
+        );
+        let len = entries.len();
+        let mut map = Self::new();
+        map.root = Self::build_sorted(&mut entries.into_iter(), len);
+        map.len = len;
+        map
     }
 
     fn len(&self) -> usize {
@@ -167,7 +292,9 @@ impl<K: Ord, V> OrderedMap for SplayTreeMap<K, V> {
             std::cmp::Ordering::Less => {
                 let mut new_root = Box::new(Node::new(key, value));
                 new_root.left = root.left.take();
+                root.recalc();
                 new_root.right = Some(root);
+                new_root.recalc();
                 self.root = Some(new_root);
                 self.len += 1;
                 None
@@ -175,7 +302,9 @@ impl<K: Ord, V> OrderedMap for SplayTreeMap<K, V> {
             std::cmp::Ordering::Greater => {
                 let mut new_root = Box::new(Node::new(key, value));
                 new_root.right = root.right.take();
+                root.recalc();
                 new_root.left = Some(root);
+                new_root.recalc();
                 self.root = Some(new_root);
                 self.len += 1;
                 None
@@ -200,6 +329,7 @@ impl<K: Ord, V> OrderedMap for SplayTreeMap<K, V> {
         } else {
             let mut new_root = self.splay(left, key).expect("left non-empty");
             new_root.right = right;
+            new_root.recalc();
             Some(new_root)
         };
 
@@ -221,4 +351,133 @@ impl<K: Ord, V> OrderedMap for SplayTreeMap<K, V> {
         }
         candidate.map(|n| (&n.key, &n.value))
     }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Less => {
+                    candidate = Some(node);
+                    cur = node.left.as_deref();
+                }
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    cur = node.right.as_deref()
+                }
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less | std::cmp::Ordering::Equal => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(Bound::Unbounded)
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(range.start_bound())
+            .take_while(move |(k, _)| before_end(range.end_bound(), k))
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        let mut cur = self.root.as_deref();
+        let mut rank = 0;
+        while let Some(node) = cur {
+            if node.key < *key {
+                rank += Node::size(&node.left) + 1;
+                cur = node.right.as_deref();
+            } else {
+                cur = node.left.as_deref();
+            }
+        }
+        rank
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut k = k;
+        while let Some(node) = cur {
+            let left = Node::size(&node.left);
+            match k.cmp(&left) {
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+                std::cmp::Ordering::Equal => return Some((&node.key, &node.value)),
+                std::cmp::Ordering::Greater => {
+                    k -= left + 1;
+                    cur = node.right.as_deref();
+                }
+            }
+        }
+        None
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let root = self.root.take();
+        let mut root = self.splay_by(root, |_| std::cmp::Ordering::Less)?;
+        self.root = root.right.take();
+        self.len -= 1;
+        Some((root.key, root.value))
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let root = self.root.take();
+        let mut root = self.splay_by(root, |_| std::cmp::Ordering::Greater)?;
+        self.root = root.left.take();
+        self.len -= 1;
+        Some((root.key, root.value))
+    }
+
+    fn retain<F: FnMut(&Self::Key, &mut Self::Value) -> bool>(&mut self, mut f: F) {
+        let mut entries = Self::into_sorted(self.root.take(), self.len);
+        entries.retain_mut(|(key, value)| f(key, value));
+        self.len = entries.len();
+        self.root = Self::build_sorted(&mut entries.into_iter(), self.len);
+    }
 }
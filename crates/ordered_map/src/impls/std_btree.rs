@@ -1,6 +1,10 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, btree_map};
+use std::ops::Bound::{Excluded, Unbounded};
+use std::ops::RangeBounds;
 
-use crate::OrderedMap;
+use crate::{Entry, OccupiedEntry, OrderedMap, OrderedMapEntry, VacantEntry, VacantSlot};
+
+use super::strictly_ascending;
 
 pub struct StdBTreeMap<K: Ord, V> {
     inner: BTreeMap<K, V>,
@@ -22,11 +26,26 @@ impl<K: Ord, V> OrderedMap for StdBTreeMap<K, V> {
         }
     }
 
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self {
+        debug_assert!(
+            strictly_ascending(&entries),
+            "from_sorted needs strictly ascending keys"
+        );
+        // std bulk-builds from sorted input in linear time.
+        Self {
+            inner: entries.into_iter().collect(),
+        }
+    }
+
     fn len(&self) -> usize {
         self.inner.len()
     }
 
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
         self.inner.get(key)
     }
 
@@ -39,6 +58,94 @@ impl<K: Ord, V> OrderedMap for StdBTreeMap<K, V> {
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
         self.inner.range(key..).next()
     }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.range((Excluded(key), Unbounded)).next()
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.range(..=key).next_back()
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.range(..key).next_back()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.inner.iter()
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.inner.range(range)
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        // std's B-tree keeps no subtree counts, so both queries are linear scans.
+        self.inner.range(..key).count()
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.iter().nth(k)
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.first_key_value()
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        self.inner.last_key_value()
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        self.inner.pop_first()
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        self.inner.pop_last()
+    }
+
+    fn split_off(&mut self, key: &Self::Key) -> Self {
+        Self {
+            inner: self.inner.split_off(key),
+        }
+    }
+
+    fn append(&mut self, mut other: Self) {
+        self.inner.append(&mut other.inner);
+    }
+
+    fn retain<F: FnMut(&Self::Key, &mut Self::Value) -> bool>(&mut self, f: F) {
+        self.inner.retain(f);
+    }
+}
+
+impl<'a, K: Ord, V> VacantSlot<'a, V> for btree_map::VacantEntry<'a, K, V> {
+    fn insert(self, value: V) -> &'a mut V {
+        btree_map::VacantEntry::insert(self, value)
+    }
+}
+
+impl<K: Ord, V> OrderedMapEntry for StdBTreeMap<K, V> {
+    type Vacant<'a>
+        = btree_map::VacantEntry<'a, K, V>
+    where
+        Self: 'a;
+
+    fn entry(&mut self, key: K) -> Entry<'_, Self> {
+        match self.inner.entry(key) {
+            btree_map::Entry::Occupied(entry) => {
+                Entry::Occupied(OccupiedEntry::new(entry.into_mut()))
+            }
+            btree_map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry::new(entry)),
+        }
+    }
 }
@@ -1,4 +1,8 @@
-use crate::OrderedMap;
+use std::ops::{Bound, RangeBounds};
+
+use crate::{Entry, OccupiedEntry, OrderedMap, OrderedMapEntry, VacantEntry, VacantSlot};
+
+use super::{after_start, before_end, strictly_ascending};
 
 const DEFAULT_SEED: u64 = 0x5EED_0ADE_2026;
 
@@ -38,6 +42,7 @@ struct Node<K, V> {
     key: K,
     value: V,
     prio: u32,
+    size: usize,
     left: Link<K, V>,
     right: Link<K, V>,
 }
@@ -48,10 +53,19 @@ impl<K, V> Node<K, V> {
             key,
             value,
             prio,
+            size: 1,
             left: None,
             right: None,
         }
     }
+
+    fn size(node: &Link<K, V>) -> usize {
+        node.as_ref().map(|n| n.size).unwrap_or(0)
+    }
+
+    fn recalc(&mut self) {
+        self.size = 1 + Self::size(&self.left) + Self::size(&self.right);
+    }
 }
 
 impl<K: Ord, V> TreapMap<K, V> {
@@ -63,6 +77,58 @@ impl<K: Ord, V> TreapMap<K, V> {
         }
     }
 
+    /// Concatenates two treaps, keeping `self`'s random generator.
+    ///
+    /// Every key in `self` must be less than every key in `other` (checked in
+    /// debug builds). Both are already heap-ordered by priority, so one merge
+    /// down the facing spines suffices: expected O(log n), nothing re-inserted.
+    pub fn join(mut self, other: Self) -> Self {
+        debug_assert!(
+            match (Self::max_key(&self.root), Self::min_key(&other.root)) {
+                (Some(a), Some(b)) => a < b,
+                _ => true,
+            },
+            "join needs every key of self below every key of other"
+        );
+        self.root = Self::merge(self.root.take(), other.root);
+        self.len += other.len;
+        self
+    }
+
+    fn min_key(root: &Link<K, V>) -> Option<&K> {
+        let mut node = root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some(&node.key)
+    }
+
+    fn max_key(root: &Link<K, V>) -> Option<&K> {
+        let mut node = root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some(&node.key)
+    }
+
+    fn rotate_right(mut root: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        let mut left = root.left.take().expect("rotate_right needs a left child");
+        root.left = left.right.take();
+        root.recalc();
+        left.right = Some(root);
+        left.recalc();
+        left
+    }
+
+    fn rotate_left(mut root: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        let mut right = root.right.take().expect("rotate_left needs a right child");
+        root.right = right.left.take();
+        root.recalc();
+        right.left = Some(root);
+        right.recalc();
+        right
+    }
+
     fn split_lt(root: Link<K, V>, key: &K) -> (Link<K, V>, Link<K, V>) {
         let Some(mut node) = root else {
             return (None, None);
@@ -70,10 +136,12 @@ impl<K: Ord, V> TreapMap<K, V> {
         if node.key < *key {
             let (a, b) = Self::split_lt(node.right.take(), key);
             node.right = a;
+            node.recalc();
             (Some(node), b)
         } else {
             let (a, b) = Self::split_lt(node.left.take(), key);
             node.left = b;
+            node.recalc();
             (a, Some(node))
         }
     }
@@ -85,14 +153,40 @@ impl<K: Ord, V> TreapMap<K, V> {
         if node.key <= *key {
             let (a, b) = Self::split_le(node.right.take(), key);
             node.right = a;
+            node.recalc();
             (Some(node), b)
         } else {
             let (a, b) = Self::split_le(node.left.take(), key);
             node.left = b;
+            node.recalc();
             (a, Some(node))
         }
     }
 
+    // The min/max node has at most one child, so splicing it out keeps the
+    // heap order intact.
+    fn pop_min(mut node: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+        if node.left.is_none() {
+            let right = node.right.take();
+            return (right, node);
+        }
+        let (new_left, min_node) = Self::pop_min(node.left.take().unwrap());
+        node.left = new_left;
+        node.recalc();
+        (Some(node), min_node)
+    }
+
+    fn pop_max(mut node: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+        if node.right.is_none() {
+            let left = node.left.take();
+            return (left, node);
+        }
+        let (new_right, max_node) = Self::pop_max(node.right.take().unwrap());
+        node.right = new_right;
+        node.recalc();
+        (Some(node), max_node)
+    }
+
     fn merge(a: Link<K, V>, b: Link<K, V>) -> Link<K, V> {
         match (a, b) {
             (None, b) => b,
@@ -101,10 +195,12 @@ impl<K: Ord, V> TreapMap<K, V> {
                 if a.prio <= b.prio {
                     let right = a.right.take();
                     a.right = Self::merge(right, Some(b));
+                    a.recalc();
                     Some(a)
                 } else {
                     let left = b.left.take();
                     b.left = Self::merge(Some(a), left);
+                    b.recalc();
                     Some(b)
                 }
             }
@@ -126,11 +222,13 @@ impl<K: Ord, V> TreapMap<K, V> {
             std::cmp::Ordering::Less => {
                 let (left, removed) = Self::remove_search(node.left.take(), key);
                 node.left = left;
+                node.recalc();
                 (Some(node), removed)
             }
             std::cmp::Ordering::Greater => {
                 let (right, removed) = Self::remove_search(node.right.take(), key);
                 node.right = right;
+                node.recalc();
                 (Some(node), removed)
             }
             std::cmp::Ordering::Equal => {
@@ -139,6 +237,135 @@ impl<K: Ord, V> TreapMap<K, V> {
             }
         }
     }
+
+    /// Builds the treap of a sorted stream in O(n), Cartesian-tree style: the
+    /// stack holds the right spine, and each new node adopts the popped part
+    /// of it as its left subtree.
+    /// Hangs `leaf` after the first `index` nodes, then rotates it up past
+    /// every ancestor with a larger priority.
+    fn insert_at(root: Link<K, V>, index: usize, leaf: Box<Node<K, V>>) -> Box<Node<K, V>> {
+        let Some(mut node) = root else {
+            return leaf;
+        };
+        let left = Node::size(&node.left);
+        if index <= left {
+            let child = Self::insert_at(node.left.take(), index, leaf);
+            let rotate = child.prio < node.prio;
+            node.left = Some(child);
+            if rotate {
+                return Self::rotate_right(node);
+            }
+        } else {
+            let child = Self::insert_at(node.right.take(), index - left - 1, leaf);
+            let rotate = child.prio < node.prio;
+            node.right = Some(child);
+            if rotate {
+                return Self::rotate_left(node);
+            }
+        }
+        node.recalc();
+        node
+    }
+
+    fn value_at_mut(mut node: &mut Node<K, V>, mut index: usize) -> &mut V {
+        loop {
+            let left = Node::size(&node.left);
+            match index.cmp(&left) {
+                std::cmp::Ordering::Less => node = node.left.as_deref_mut().expect("index < size"),
+                std::cmp::Ordering::Equal => return &mut node.value,
+                std::cmp::Ordering::Greater => {
+                    index -= left + 1;
+                    node = node.right.as_deref_mut().expect("index < size");
+                }
+            }
+        }
+    }
+
+    fn build_sorted<I: Iterator<Item = (K, V)>>(entries: I, rng: &mut XorShift64) -> Link<K, V> {
+        let mut spine: Vec<Box<Node<K, V>>> = Vec::new();
+        for (key, value) in entries {
+            let mut node = Box::new(Node::new(key, value, rng.next_u32()));
+            let mut below: Link<K, V> = None;
+            while let Some(mut top) = spine.pop_if(|top| top.prio > node.prio) {
+                top.right = below;
+                top.recalc();
+                below = Some(top);
+            }
+            node.left = below;
+            spine.push(node);
+        }
+        let mut root: Link<K, V> = None;
+        while let Some(mut top) = spine.pop() {
+            top.right = root;
+            top.recalc();
+            root = Some(top);
+        }
+        root
+    }
+
+    /// Dismantles a tree into its entries in ascending key order.
+    fn into_sorted(root: Link<K, V>, len: usize) -> Vec<(K, V)> {
+        let mut entries = Vec::with_capacity(len);
+        let mut stack = Vec::new();
+        let mut cur = root;
+        loop {
+            while let Some(mut node) = cur {
+                cur = node.left.take();
+                stack.push(node);
+            }
+            let Some(node) = stack.pop() else {
+                return entries;
+            };
+            let Node {
+                key, value, right, ..
+            } = *node;
+            entries.push((key, value));
+            cur = right;
+        }
+    }
+
+    /// Asserts heap order on priorities and the cached sizes.
+    #[cfg(test)]
+    pub(crate) fn check_heap(&self) {
+        fn walk<K, V>(link: &Link<K, V>, parent_prio: u32) -> usize {
+            let Some(node) = link else {
+                return 0;
+            };
+            assert!(node.prio >= parent_prio, "treap heap order violated");
+            let size = walk(&node.left, node.prio) + walk(&node.right, node.prio) + 1;
+            assert_eq!(node.size, size);
+            size
+        }
+        assert_eq!(walk(&self.root, 0), self.len);
+    }
+
+    fn iter_from<'a>(
+        &'a self,
+        start: Bound<&K>,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + use<'a, K, V> {
+        // Seed the stack with the ancestors at or after `start`; whatever
+        // hangs to their left is skipped.
+        let mut stack = Vec::new();
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            if after_start(start, &node.key) {
+                stack.push(node);
+                cur = node.left.as_deref();
+            } else {
+                cur = node.right.as_deref();
+            }
+        }
+        let mut cur = None;
+        std::iter::from_fn(move || {
+            while let Some(node) = cur {
+                stack.push(node);
+                cur = node.left.as_deref();
+            }
+            let node = stack.pop()?;
+            cur = node.right.as_deref();
+            Some((&node.key, &node.value))
+        })
+    }
 }
 
 impl<K: Ord, V> OrderedMap for TreapMap<K, V> {
@@ -149,11 +376,26 @@ impl<K: Ord, V> OrderedMap for TreapMap<K, V> {
         Self::with_seed(DEFAULT_SEED)
     }
 
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self {
+        debug_assert!(
+            strictly_ascending(&entries),
+            "from_sorted needs strictly ascending keys"
+        );
+        let mut map = Self::new();
+        map.len = entries.len();
+        map.root = Self::build_sorted(entries.into_iter(), &mut map.rng);
+        map
+    }
+
     fn len(&self) -> usize {
         self.len
     }
 
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
         let mut cur = self.root.as_deref();
         while let Some(node) = cur {
             match key.cmp(&node.key) {
@@ -193,6 +435,10 @@ impl<K: Ord, V> OrderedMap for TreapMap<K, V> {
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
         let mut cur = self.root.as_deref();
         let mut candidate = None;
         while let Some(node) = cur {
@@ -206,4 +452,184 @@ impl<K: Ord, V> OrderedMap for TreapMap<K, V> {
         }
         candidate.map(|n| (&n.key, &n.value))
     }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Less => {
+                    candidate = Some(node);
+                    cur = node.left.as_deref();
+                }
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    cur = node.right.as_deref()
+                }
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less | std::cmp::Ordering::Equal => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(Bound::Unbounded)
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(range.start_bound())
+            .take_while(move |(k, _)| before_end(range.end_bound(), k))
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        let mut cur = self.root.as_deref();
+        let mut rank = 0;
+        while let Some(node) = cur {
+            if node.key < *key {
+                rank += Node::size(&node.left) + 1;
+                cur = node.right.as_deref();
+            } else {
+                cur = node.left.as_deref();
+            }
+        }
+        rank
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut k = k;
+        while let Some(node) = cur {
+            let left = Node::size(&node.left);
+            match k.cmp(&left) {
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+                std::cmp::Ordering::Equal => return Some((&node.key, &node.value)),
+                std::cmp::Ordering::Greater => {
+                    k -= left + 1;
+                    cur = node.right.as_deref();
+                }
+            }
+        }
+        None
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let (root, min_node) = Self::pop_min(self.root.take()?);
+        self.root = root;
+        self.len -= 1;
+        Some((min_node.key, min_node.value))
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let (root, max_node) = Self::pop_max(self.root.take()?);
+        self.root = root;
+        self.len -= 1;
+        Some((max_node.key, max_node.value))
+    }
+
+    fn retain<F: FnMut(&Self::Key, &mut Self::Value) -> bool>(&mut self, mut f: F) {
+        let mut entries = Self::into_sorted(self.root.take(), self.len);
+        entries.retain_mut(|(key, value)| f(key, value));
+        self.len = entries.len();
+        self.root = Self::build_sorted(entries.into_iter(), &mut self.rng);
+    }
+}
+
+/// Vacant [`TreapMap`] lookup: the rank the key will take, which finds the
+/// new node again after it has been rotated up to its heap position.
+pub struct TreapVacantEntry<'a, K: Ord, V> {
+    key: K,
+    rank: usize,
+    root: &'a mut Link<K, V>,
+    len: &'a mut usize,
+    rng: &'a mut XorShift64,
+}
+
+impl<'a, K: Ord, V> VacantSlot<'a, V> for TreapVacantEntry<'a, K, V> {
+    fn insert(self, value: V) -> &'a mut V {
+        let prio = self.rng.next_u32();
+        let leaf = Box::new(Node::new(self.key, value, prio));
+        let root = TreapMap::insert_at(self.root.take(), self.rank, leaf);
+        *self.len += 1;
+        TreapMap::value_at_mut(self.root.insert(root), self.rank)
+    }
+}
+
+impl<K: Ord, V> OrderedMapEntry for TreapMap<K, V> {
+    type Vacant<'a>
+        = TreapVacantEntry<'a, K, V>
+    where
+        Self: 'a;
+
+    fn entry(&mut self, key: K) -> Entry<'_, Self> {
+        let mut cur = self.root.as_deref();
+        let mut rank = 0;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+                std::cmp::Ordering::Greater => {
+                    rank += Node::size(&node.left) + 1;
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Equal => {
+                    rank += Node::size(&node.left);
+                    let root = self.root.as_deref_mut().expect("key was found");
+                    return Entry::Occupied(OccupiedEntry::new(Self::value_at_mut(root, rank)));
+                }
+            }
+        }
+        Entry::Vacant(VacantEntry::new(TreapVacantEntry {
+            key,
+            rank,
+            root: &mut self.root,
+            len: &mut self.len,
+            rng: &mut self.rng,
+        }))
+    }
 }
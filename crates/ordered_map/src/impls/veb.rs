@@ -1,39 +1,76 @@
+use std::ops::{Bound, RangeBounds};
+
 use crate::OrderedMap;
 
-use super::FastHashMap;
+use super::{FastHashMap, before_end};
 use std::collections::hash_map::Entry;
 
 pub struct VebMap<V> {
     tree: VebNode,
+    // Key width; keys live in `[0, 2^bits)`.
+    bits: u32,
     entries: FastHashMap<u64, V>,
     len: usize,
 }
 
+impl<V> VebMap<V> {
+    /// Restricts keys to `[0, 2^bits)`, which cuts the recursion depth and
+    /// the number of cluster/summary nodes each insert creates.
+    ///
+    /// # Panics
+    /// If `bits` is not in `1..=64`.
+    pub fn with_universe_bits(bits: u32) -> Self {
+        assert!((1..=64).contains(&bits), "universe bits must be in 1..=64");
+        Self {
+            tree: VebNode::new(bits),
+            bits,
+            entries: FastHashMap::default(),
+            len: 0,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn tree_node_count(&self) -> usize {
+        self.tree.node_count()
+    }
+}
+
+/// `rank` and `select` read the per-node key counts, recursing into the
+/// summary and one cluster per level: O(log U) rather than O(log log U).
 impl<V> OrderedMap for VebMap<V> {
     type Key = u64;
     type Value = V;
 
     fn new() -> Self {
-        Self {
-            tree: VebNode::new(64),
-            entries: FastHashMap::default(),
-            len: 0,
-        }
+        Self::with_universe_bits(64)
     }
 
     fn len(&self) -> usize {
         self.len
     }
 
+    fn empty_like(&self) -> Self {
+        Self::with_universe_bits(self.bits)
+    }
+
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
         self.entries.get(key)
     }
 
     fn insert(&mut self, key: Self::Key, value: Self::Value) -> Option<Self::Value> {
+        assert!(
+            self.bits == 64 || key >> self.bits == 0,
+            "key {key} does not fit in a {}-bit universe",
+            self.bits
+        );
         match self.entries.entry(key) {
             Entry::Occupied(mut e) => Some(e.insert(value)),
             Entry::Vacant(e) => {
-                self.tree.insert(*e.key());
+                self.tree.insert(*e.key(), 1);
                 e.insert(value);
                 self.len += 1;
                 None
@@ -49,28 +86,122 @@ impl<V> OrderedMap for VebMap<V> {
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
         if let Some((k, v)) = self.entries.get_key_value(key) {
             return Some((k, v));
         }
         let succ = self.tree.successor(*key)?;
         self.entries.get_key_value(&succ)
     }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        // `VebNode::successor` is already strict.
+        let succ = self.tree.successor(*key)?;
+        self.entries.get_key_value(&succ)
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        if let Some((k, v)) = self.entries.get_key_value(key) {
+            return Some((k, v));
+        }
+        let pred = self.tree.predecessor(*key)?;
+        self.entries.get_key_value(&pred)
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let pred = self.tree.predecessor(*key)?;
+        self.entries.get_key_value(&pred)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        let mut next = self.tree.min;
+        std::iter::from_fn(move || {
+            let key = next?;
+            next = self.tree.successor(key);
+            self.entries.get_key_value(&key)
+        })
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        let mut next = match range.start_bound() {
+            Bound::Included(lo) if self.entries.contains_key(lo) => Some(*lo),
+            Bound::Included(lo) | Bound::Excluded(lo) => self.tree.successor(*lo),
+            Bound::Unbounded => self.tree.min,
+        };
+        std::iter::from_fn(move || {
+            let key = next.filter(|k| before_end(range.end_bound(), k))?;
+            next = self.tree.successor(key);
+            self.entries.get_key_value(&key)
+        })
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        self.tree.rank(*key)
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        let (key, _) = self.tree.select(k)?;
+        self.entries.get_key_value(&key)
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        self.entries.get_key_value(&self.tree.min?)
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        self.entries.get_key_value(&self.tree.max?)
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let key = *self.first()?.0;
+        self.remove(&key).map(|value| (key, value))
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let key = *self.last()?.0;
+        self.remove(&key).map(|value| (key, value))
+    }
 }
 
+/// A vEB node over weighted keys. The map gives every key weight 1; a
+/// summary weights each cluster index by that cluster's `total`, so sums over
+/// earlier clusters come out of a rank query on the summary.
 struct VebNode {
     w: u32,
     min: Option<u64>,
     max: Option<u64>,
+    min_weight: usize,
+    // Weight of every key here, `min` included. In a universe-2 node, which
+    // has no clusters, `max` (when distinct) weighs `total - min_weight`.
+    total: usize,
     summary: Option<Box<VebNode>>,
     clusters: FastHashMap<u64, Box<VebNode>>,
 }
 
 impl VebNode {
+    #[cfg(test)]
+    fn node_count(&self) -> usize {
+        1 + self.summary.as_ref().map_or(0, |s| s.node_count())
+            + self
+                .clusters
+                .values()
+                .map(|c| c.node_count())
+                .sum::<usize>()
+    }
+
     fn new(w: u32) -> Self {
         Self {
             w,
             min: None,
             max: None,
+            min_weight: 0,
+            total: 0,
             summary: None,
             clusters: FastHashMap::default(),
         }
@@ -106,21 +237,27 @@ impl VebNode {
         }
     }
 
-    fn empty_insert(&mut self, x: u64) {
+    fn empty_insert(&mut self, x: u64, weight: usize) {
         self.min = Some(x);
         self.max = Some(x);
+        self.min_weight = weight;
+        self.total = weight;
     }
 
-    fn insert(&mut self, mut x: u64) {
+    /// Inserts an absent `x`. Unlike a plain vEB, a non-empty cluster also
+    /// adds `weight` to its index in the summary, so this is O(log U).
+    fn insert(&mut self, mut x: u64, mut weight: usize) {
         if self.min.is_none() {
-            self.empty_insert(x);
+            self.empty_insert(x, weight);
             return;
         }
 
+        self.total += weight;
         let min = self.min.unwrap();
         if x < min {
             self.min = Some(x);
             x = min;
+            weight = std::mem::replace(&mut self.min_weight, weight);
         }
 
         if self.w > 1 {
@@ -132,14 +269,15 @@ impl VebNode {
                 .clusters
                 .entry(h)
                 .or_insert_with(|| Box::new(VebNode::new(lower)));
+            let summary = self
+                .summary
+                .get_or_insert_with(|| Box::new(VebNode::new(upper)));
             if cluster.min.is_none() {
-                if self.summary.is_none() {
-                    self.summary = Some(Box::new(VebNode::new(upper)));
-                }
-                self.summary.as_deref_mut().unwrap().insert(h);
-                cluster.empty_insert(l);
+                summary.insert(h, weight);
+                cluster.empty_insert(l, weight);
             } else {
-                cluster.insert(l);
+                summary.add_weight(h, weight as isize);
+                cluster.insert(l, weight);
             }
         }
 
@@ -148,32 +286,61 @@ impl VebNode {
         }
     }
 
-    fn remove(&mut self, mut x: u64) {
+    /// Adds `delta` to the weight of the present key `x`.
+    fn add_weight(&mut self, x: u64, delta: isize) {
+        self.total = self.total.wrapping_add_signed(delta);
+        if self.min == Some(x) {
+            self.min_weight = self.min_weight.wrapping_add_signed(delta);
+            return;
+        }
+        if self.w <= 1 {
+            return;
+        }
+        let h = self.high(x);
+        let l = self.low(x);
+        self.clusters
+            .get_mut(&h)
+            .expect("cluster exists")
+            .add_weight(l, delta);
+        self.summary
+            .as_deref_mut()
+            .expect("non-empty summary")
+            .add_weight(h, delta);
+    }
+
+    /// Removes the present key `x`, returning its weight.
+    fn remove(&mut self, mut x: u64) -> usize {
         let min = self.min.expect("remove on empty vEB");
         let max = self.max.expect("remove on empty vEB");
 
         if min == max {
             debug_assert_eq!(x, min);
+            let weight = self.total;
             self.min = None;
             self.max = None;
+            self.min_weight = 0;
+            self.total = 0;
             self.summary = None;
             self.clusters.clear();
-            return;
+            return weight;
         }
 
         if self.w <= 1 {
             // Universe size 2.
-            if x == 0 {
-                self.min = Some(1);
-                self.max = Some(1);
+            let max_weight = self.total - self.min_weight;
+            let (kept, kept_weight, weight) = if x == min {
+                (max, max_weight, self.min_weight)
             } else {
-                self.min = Some(0);
-                self.max = Some(0);
-            }
-            return;
+                (min, self.min_weight, max_weight)
+            };
+            self.empty_insert(kept, kept_weight);
+            return weight;
         }
 
+        let mut weight = None;
         if x == min {
+            // Promote the first clustered key to `min`; it is then removed
+            // from its cluster below.
             let first_cluster = self
                 .summary
                 .as_deref()
@@ -183,12 +350,15 @@ impl VebNode {
             let new_low = cluster.min.expect("non-empty cluster");
             x = self.index(first_cluster, new_low);
             self.min = Some(x);
+            weight = Some(std::mem::replace(&mut self.min_weight, cluster.min_weight));
         }
 
         let h = self.high(x);
         let l = self.low(x);
         let cluster = self.clusters.get_mut(&h).expect("cluster exists");
-        cluster.remove(l);
+        let moved = cluster.remove(l);
+        let weight = weight.unwrap_or(moved);
+        self.total -= weight;
 
         if cluster.min.is_none() {
             self.clusters.remove(&h);
@@ -202,10 +372,54 @@ impl VebNode {
                 let new_low = cluster.max.unwrap();
                 self.max = Some(self.index(summary_max, new_low));
             }
-        } else if x == max {
-            let new_low = cluster.max.unwrap();
-            self.max = Some(self.index(h, new_low));
+        } else {
+            self.summary
+                .as_deref_mut()
+                .unwrap()
+                .add_weight(h, -(moved as isize));
+            if x == max {
+                let new_low = cluster.max.unwrap();
+                self.max = Some(self.index(h, new_low));
+            }
+        }
+        weight
+    }
+
+    /// Total weight of the keys `< x`.
+    fn rank(&self, x: u64) -> usize {
+        let Some(min) = self.min.filter(|&min| min < x) else {
+            return 0;
+        };
+        if self.w <= 1 {
+            let max = self.max.unwrap();
+            return if max != min && max < x {
+                self.total
+            } else {
+                self.min_weight
+            };
         }
+        let h = self.high(x);
+        let before = self.summary.as_deref().map_or(0, |s| s.rank(h));
+        let inside = self.clusters.get(&h).map_or(0, |c| c.rank(self.low(x)));
+        self.min_weight + before + inside
+    }
+
+    /// The key whose weight covers position `k` in key order, with the
+    /// offset of `k` inside that weight.
+    fn select(&self, k: usize) -> Option<(u64, usize)> {
+        if k >= self.total {
+            return None;
+        }
+        if k < self.min_weight {
+            return Some((self.min?, k));
+        }
+        let k = k - self.min_weight;
+        if self.w <= 1 {
+            return Some((self.max?, k));
+        }
+        let (h, k) = self.summary.as_deref()?.select(k)?;
+        let (l, k) = self.clusters.get(&h)?.select(k)?;
+        Some((self.index(h, l), k))
     }
 
     fn successor(&self, x: u64) -> Option<u64> {
@@ -236,4 +450,40 @@ impl VebNode {
         let offset = cluster.min?;
         Some(self.index(succ_cluster, offset))
     }
+
+    fn predecessor(&self, x: u64) -> Option<u64> {
+        let min = self.min?;
+        let max = self.max?;
+        if x > max {
+            return Some(max);
+        }
+        if self.w <= 1 {
+            if x == 1 && min == 0 {
+                return Some(0);
+            }
+            return None;
+        }
+
+        let h = self.high(x);
+        let l = self.low(x);
+        if let Some(cluster) = self.clusters.get(&h)
+            && cluster.min.is_some()
+            && l > cluster.min.unwrap()
+        {
+            let offset = cluster.predecessor(l).unwrap();
+            return Some(self.index(h, offset));
+        }
+
+        // `min` lives outside the clusters, so it is the fallback when no
+        // earlier cluster exists.
+        match self.summary.as_deref().and_then(|s| s.predecessor(h)) {
+            Some(pred_cluster) => {
+                let cluster = self.clusters.get(&pred_cluster)?;
+                let offset = cluster.max?;
+                Some(self.index(pred_cluster, offset))
+            }
+            None if x > min => Some(min),
+            None => None,
+        }
+    }
 }
@@ -1,6 +1,15 @@
+use std::ops::{Bound, RangeBounds};
+
 use crate::OrderedMap;
 
-const BALANCE_NUM: usize = 16;
+use super::{after_start, before_end, strictly_ascending};
+
+// Weight-balance parameters from Hirai and Yamamoto, "Balancing weight-balanced
+// trees": with weight = size + 1, a node is balanced while neither child is more
+// than `DELTA` times as heavy as the other, and `GAMMA` picks a single or double
+// rotation so that one rotation restores balance after an update.
+const DELTA: u64 = 3;
+const GAMMA: u64 = 2;
 
 pub struct WbtTreeMap<K: Ord, V> {
     root: Link<K, V>,
@@ -32,6 +41,11 @@ impl<K, V> Node<K, V> {
         node.as_ref().map(|n| n.size).unwrap_or(0)
     }
 
+    // sizes are u32; promote to u64 so the balance checks cannot overflow.
+    fn weight(node: &Link<K, V>) -> u64 {
+        Self::size(node) as u64 + 1
+    }
+
     fn recalc(&mut self) {
         self.size = 1 + Self::size(&self.left) + Self::size(&self.right);
     }
@@ -62,29 +76,31 @@ impl<K: Ord, V> WbtTreeMap<K, V> {
         right
     }
 
+    fn is_balanced(light: u64, heavy: u64) -> bool {
+        DELTA * light >= heavy
+    }
+
+    fn is_single(inner: u64, outer: u64) -> bool {
+        inner < GAMMA * outer
+    }
+
     fn rebalance(mut root: Box<Node<K, V>>) -> Box<Node<K, V>> {
         root.recalc();
-        // sizes are u32; promote to u64 to avoid saturating ops in balance checks.
-        let left_size = Node::size(&root.left) as u64;
-        let right_size = Node::size(&root.right) as u64;
-        let balance_num = BALANCE_NUM as u64;
+        let left_weight = Node::weight(&root.left);
+        let right_weight = Node::weight(&root.right);
 
-        if left_size > right_size * balance_num + 1 {
+        if !Self::is_balanced(right_weight, left_weight) {
             let mut left = root.left.take().unwrap();
-            let ll = Node::size(&left.left);
-            let lr = Node::size(&left.right);
-            if lr > ll {
+            if !Self::is_single(Node::weight(&left.right), Node::weight(&left.left)) {
                 left = Self::rotate_left(left);
             }
             root.left = Some(left);
             return Self::rotate_right(root);
         }
 
-        if right_size > left_size * balance_num + 1 {
+        if !Self::is_balanced(left_weight, right_weight) {
             let mut right = root.right.take().unwrap();
-            let rl = Node::size(&right.left);
-            let rr = Node::size(&right.right);
-            if rl > rr {
+            if !Self::is_single(Node::weight(&right.left), Node::weight(&right.right)) {
                 right = Self::rotate_right(right);
             }
             root.right = Some(right);
@@ -105,6 +121,17 @@ impl<K: Ord, V> WbtTreeMap<K, V> {
         (Some(node), min_node)
     }
 
+    fn pop_max(mut node: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+        if node.right.is_none() {
+            let left = node.left.take();
+            return (left, node);
+        }
+        let (new_right, max_node) = Self::pop_max(node.right.take().unwrap());
+        node.right = new_right;
+        let node = Self::rebalance(node);
+        (Some(node), max_node)
+    }
+
     fn insert_node(root: Link<K, V>, key: K, value: V) -> (Link<K, V>, Option<V>, bool) {
         let Some(mut node) = root else {
             return (Some(Box::new(Node::new(key, value))), None, true);
@@ -166,6 +193,148 @@ impl<K: Ord, V> WbtTreeMap<K, V> {
             }
         }
     }
+
+    /// Builds a perfectly balanced tree from the next `n` entries of a sorted stream.
+    fn build_sorted<I: Iterator<Item = (K, V)>>(entries: &mut I, n: usize) -> Link<K, V> {
+        if n == 0 {
+            return None;
+        }
+        let left = Self::build_sorted(entries, n / 2);
+        let (key, value) = entries.next().expect("stream holds n entries");
+        let mut node = Box::new(Node::new(key, value));
+        node.left = left;
+        node.right = Self::build_sorted(entries, n - n / 2 - 1);
+        node.recalc();
+        Some(node)
+    }
+
+    /// Dismantles a tree into its entries in ascending key order.
+    fn into_sorted(root: Link<K, V>, len: usize) -> Vec<(K, V)> {
+        let mut entries = Vec::with_capacity(len);
+        let mut stack = Vec::new();
+        let mut cur = root;
+        loop {
+            while let Some(mut node) = cur {
+                cur = node.left.take();
+                stack.push(node);
+            }
+            let Some(node) = stack.pop() else {
+                return entries;
+            };
+            let Node {
+                key, value, right, ..
+            } = *node;
+            entries.push((key, value));
+            cur = right;
+        }
+    }
+
+    /// Joins `left < mid < right`, descending into the heavier side until the
+    /// two are within the balance ratio and rebalancing on the way back.
+    fn join(left: Link<K, V>, mut mid: Box<Node<K, V>>, right: Link<K, V>) -> Box<Node<K, V>> {
+        let left_weight = Node::weight(&left);
+        let right_weight = Node::weight(&right);
+
+        if !Self::is_balanced(right_weight, left_weight) {
+            let mut left = left.unwrap();
+            let inner = left.right.take();
+            left.right = Some(Self::join(inner, mid, right));
+            return Self::rebalance(left);
+        }
+        if !Self::is_balanced(left_weight, right_weight) {
+            let mut right = right.unwrap();
+            let inner = right.left.take();
+            right.left = Some(Self::join(left, mid, inner));
+            return Self::rebalance(right);
+        }
+        mid.left = left;
+        mid.right = right;
+        mid.recalc();
+        mid
+    }
+
+    /// Splits `root` into the keys `< key`, the node holding `key` (detached
+    /// and with stale fields), and the keys `> key`.
+    fn split(root: Link<K, V>, key: &K) -> (Link<K, V>, Link<K, V>, Link<K, V>) {
+        let Some(mut node) = root else {
+            return (None, None, None);
+        };
+        let left = node.left.take();
+        let right = node.right.take();
+        match key.cmp(&node.key) {
+            std::cmp::Ordering::Less => {
+                let (lt, eq, gt) = Self::split(left, key);
+                (lt, eq, Some(Self::join(gt, node, right)))
+            }
+            std::cmp::Ordering::Greater => {
+                let (lt, eq, gt) = Self::split(right, key);
+                (Some(Self::join(left, node, lt)), eq, gt)
+            }
+            std::cmp::Ordering::Equal => (left, Some(node), right),
+        }
+    }
+
+    /// Union of two trees; on equal keys `b`'s entry wins.
+    fn union(a: Link<K, V>, b: Link<K, V>) -> Link<K, V> {
+        let Some(mut mid) = b else {
+            return a;
+        };
+        if a.is_none() {
+            return Some(mid);
+        }
+        let left = mid.left.take();
+        let right = mid.right.take();
+        let (lt, _, gt) = Self::split(a, &mid.key);
+        let left = Self::union(lt, left);
+        let right = Self::union(gt, right);
+        Some(Self::join(left, mid, right))
+    }
+
+    fn iter_from<'a>(
+        &'a self,
+        start: Bound<&K>,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + use<'a, K, V> {
+        // Seed the stack with the ancestors at or after `start`; whatever
+        // hangs to their left is skipped.
+        let mut stack = Vec::new();
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            if after_start(start, &node.key) {
+                stack.push(node);
+                cur = node.left.as_deref();
+            } else {
+                cur = node.right.as_deref();
+            }
+        }
+        let mut cur = None;
+        std::iter::from_fn(move || {
+            while let Some(node) = cur {
+                stack.push(node);
+                cur = node.left.as_deref();
+            }
+            let node = stack.pop()?;
+            cur = node.right.as_deref();
+            Some((&node.key, &node.value))
+        })
+    }
+
+    #[cfg(test)]
+    pub(crate) fn check_balanced(&self) -> usize {
+        fn walk<K, V>(link: &Link<K, V>) -> (usize, u32) {
+            let Some(node) = link else {
+                return (0, 0);
+            };
+            let (lh, ls) = walk(&node.left);
+            let (rh, rs) = walk(&node.right);
+            let (light, heavy) = (ls.min(rs) as u64 + 1, ls.max(rs) as u64 + 1);
+            assert!(DELTA * light >= heavy, "weight balance violated");
+            assert_eq!(node.size, ls + rs + 1);
+            (lh.max(rh) + 1, node.size)
+        }
+        let (height, size) = walk(&self.root);
+        assert_eq!(size as usize, self.len);
+        height
+    }
 }
 
 impl<K: Ord, V> OrderedMap for WbtTreeMap<K, V> {
@@ -176,11 +345,27 @@ impl<K: Ord, V> OrderedMap for WbtTreeMap<K, V> {
         Self { root: None, len: 0 }
     }
 
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self {
+        debug_assert!(
+            strictly_ascending(&entries),
+            "from_sorted needs strictly ascending keys"
+        );
+        let len = entries.len();
+        let mut map = Self::new();
+        map.root = Self::build_sorted(&mut entries.into_iter(), len);
+        map.len = len;
+        map
+    }
+
     fn len(&self) -> usize {
         self.len
     }
 
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
         let mut cur = self.root.as_deref();
         while let Some(node) = cur {
             match key.cmp(&node.key) {
@@ -211,6 +396,10 @@ impl<K: Ord, V> OrderedMap for WbtTreeMap<K, V> {
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
         let mut cur = self.root.as_deref();
         let mut candidate = None;
         while let Some(node) = cur {
@@ -224,4 +413,151 @@ impl<K: Ord, V> OrderedMap for WbtTreeMap<K, V> {
         }
         candidate.map(|n| (&n.key, &n.value))
     }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Less => {
+                    candidate = Some(node);
+                    cur = node.left.as_deref();
+                }
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    cur = node.right.as_deref()
+                }
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less | std::cmp::Ordering::Equal => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(Bound::Unbounded)
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(range.start_bound())
+            .take_while(move |(k, _)| before_end(range.end_bound(), k))
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        let mut cur = self.root.as_deref();
+        let mut rank = 0;
+        while let Some(node) = cur {
+            if node.key < *key {
+                rank += Node::size(&node.left) as usize + 1;
+                cur = node.right.as_deref();
+            } else {
+                cur = node.left.as_deref();
+            }
+        }
+        rank
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut k = k;
+        while let Some(node) = cur {
+            let left = Node::size(&node.left) as usize;
+            match k.cmp(&left) {
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+                std::cmp::Ordering::Equal => return Some((&node.key, &node.value)),
+                std::cmp::Ordering::Greater => {
+                    k -= left + 1;
+                    cur = node.right.as_deref();
+                }
+            }
+        }
+        None
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let (root, min_node) = Self::pop_min(self.root.take()?);
+        self.root = root;
+        self.len -= 1;
+        Some((min_node.key, min_node.value))
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let (root, max_node) = Self::pop_max(self.root.take()?);
+        self.root = root;
+        self.len -= 1;
+        Some((max_node.key, max_node.value))
+    }
+
+    fn split_off(&mut self, key: &Self::Key) -> Self {
+        let (lt, eq, gt) = Self::split(self.root.take(), key);
+        let right = match eq {
+            Some(node) => Some(Self::join(None, node, gt)),
+            None => gt,
+        };
+        let right_len = Node::size(&right) as usize;
+        self.root = lt;
+        self.len -= right_len;
+        Self {
+            root: right,
+            len: right_len,
+        }
+    }
+
+    fn append(&mut self, other: Self) {
+        self.root = Self::union(self.root.take(), other.root);
+        self.len = Node::size(&self.root) as usize;
+    }
+
+    fn retain<F: FnMut(&Self::Key, &mut Self::Value) -> bool>(&mut self, mut f: F) {
+        let mut entries = Self::into_sorted(self.root.take(), self.len);
+        entries.retain_mut(|(key, value)| f(key, value));
+        self.len = entries.len();
+        self.root = Self::build_sorted(&mut entries.into_iter(), self.len);
+    }
 }
@@ -1,9 +1,11 @@
 use std::mem::MaybeUninit;
+use std::ops::{Bound, RangeBounds};
 
 use crate::OrderedMap;
 
-use super::FastHashMap;
+use super::{FastHashMap, before_end};
 
+/// Key width of the full `u64` universe.
 const W: usize = 64;
 const NIL: u32 = u32::MAX;
 
@@ -51,7 +53,8 @@ impl ChildId {
 struct InternalNode {
     parent: u32, // NIL for root
     child: [ChildId; 2],
-    jump: u32, // leaf id or NIL
+    jump: u32,    // leaf id or NIL
+    count: usize, // total leaf weight below this node
 }
 
 struct LeafNode<V> {
@@ -60,6 +63,7 @@ struct LeafNode<V> {
     parent: u32, // NIL => free slot
     prev: u32,   // leaf id or NIL
     next: u32,   // leaf id or NIL
+    weight: usize,
 }
 
 pub struct XFastTrieMap<V> {
@@ -68,6 +72,8 @@ pub struct XFastTrieMap<V> {
     free_internals: Vec<u32>,
     free_leaves: Vec<u32>,
     tables: Vec<FastHashMap<u64, u32>>,
+    // Key width; `tables[bits]` maps full keys to leaves.
+    bits: usize,
     head: u32,
     tail: u32,
     len: usize,
@@ -75,15 +81,33 @@ pub struct XFastTrieMap<V> {
 
 impl<V> XFastTrieMap<V> {
     pub fn new_with_capacity(capacity: usize) -> Self {
+        Self::with_bits_and_capacity(W, capacity)
+    }
+
+    /// Restricts keys to `[0, 2^bits)`. The trie then has `bits` levels: a new
+    /// key allocates at most `bits` internal nodes and one level table each,
+    /// and searches binary-search over `bits` levels instead of 64.
+    ///
+    /// # Panics
+    /// If `bits` is not in `1..=64`.
+    pub fn with_universe_bits(bits: u32) -> Self {
+        assert!((1..=64).contains(&bits), "universe bits must be in 1..=64");
+        Self::with_bits_and_capacity(bits as usize, 0)
+    }
+
+    fn with_bits_and_capacity(bits: usize, capacity: usize) -> Self {
         let internals = vec![InternalNode {
             parent: NIL,
             child: [ChildId::none(), ChildId::none()],
             jump: NIL,
+            count: 0,
         }];
 
-        let mut tables = (0..=W).map(|_| FastHashMap::default()).collect::<Vec<_>>();
+        let mut tables = (0..=bits)
+            .map(|_| FastHashMap::default())
+            .collect::<Vec<_>>();
         tables[0].insert(0, 0);
-        tables[W].reserve(capacity);
+        tables[bits].reserve(capacity);
 
         Self {
             internals,
@@ -91,19 +115,33 @@ impl<V> XFastTrieMap<V> {
             free_internals: Vec::new(),
             free_leaves: Vec::new(),
             tables,
+            bits,
             head: NIL,
             tail: NIL,
             len: 0,
         }
     }
 
-    fn prefix(key: u64, depth: usize) -> u64 {
-        if depth == 0 { 0 } else { key >> (W - depth) }
+    fn prefix(&self, key: u64, depth: usize) -> u64 {
+        if depth == 0 {
+            0
+        } else {
+            key >> (self.bits - depth)
+        }
+    }
+
+    fn bit_at(&self, key: u64, depth: usize) -> usize {
+        debug_assert!(depth < self.bits);
+        ((key >> (self.bits - 1 - depth)) & 1) as usize
+    }
+
+    #[cfg(test)]
+    pub(crate) fn internal_node_count(&self) -> usize {
+        self.internals.len() - self.free_internals.len()
     }
 
-    fn bit_at(key: u64, depth: usize) -> usize {
-        debug_assert!(depth < W);
-        ((key >> (W - 1 - depth)) & 1) as usize
+    pub(crate) fn in_universe(&self, key: u64) -> bool {
+        self.bits == W || key >> self.bits == 0
     }
 
     pub(crate) fn max_entry(&mut self) -> Option<(&u64, &V)> {
@@ -123,6 +161,7 @@ impl<V> XFastTrieMap<V> {
                 parent,
                 child: [ChildId::none(), ChildId::none()],
                 jump: NIL,
+                count: 0,
             };
             id
         } else {
@@ -131,6 +170,7 @@ impl<V> XFastTrieMap<V> {
                 parent,
                 child: [ChildId::none(), ChildId::none()],
                 jump: NIL,
+                count: 0,
             });
             id
         }
@@ -144,6 +184,7 @@ impl<V> XFastTrieMap<V> {
             slot.parent = parent;
             slot.prev = prev;
             slot.next = next;
+            slot.weight = 1;
             id
         } else {
             let id = self.leaves.len() as u32;
@@ -153,6 +194,7 @@ impl<V> XFastTrieMap<V> {
                 parent,
                 prev,
                 next,
+                weight: 1,
             });
             id
         }
@@ -214,21 +256,75 @@ impl<V> XFastTrieMap<V> {
         }
     }
 
-    fn successor_leaf_id(&mut self, key: u64) -> Option<u32> {
-        if self.len == 0 {
+    fn child_count(&self, child: ChildId) -> usize {
+        if child.is_none() {
+            0
+        } else if child.is_leaf() {
+            self.leaves[child.as_leaf() as usize].weight
+        } else {
+            self.internals[child.as_internal() as usize].count
+        }
+    }
+
+    fn add_count_upwards(&mut self, mut internal: u32, delta: isize) {
+        while internal != NIL {
+            let node = &mut self.internals[internal as usize];
+            node.count = node.count.wrapping_add_signed(delta);
+            internal = node.parent;
+        }
+    }
+
+    /// Sets the weight `rank`/`select` count for `key` (1 by default).
+    ///
+    /// Lets `YFastTrieMap` weight each representative by its bucket size.
+    pub(crate) fn set_weight(&mut self, key: u64, weight: usize) {
+        let Some(&leaf_id) = self.tables[self.bits].get(&key) else {
+            return;
+        };
+        let leaf = &mut self.leaves[leaf_id as usize];
+        let delta = weight as isize - leaf.weight as isize;
+        leaf.weight = weight;
+        let parent = leaf.parent;
+        self.add_count_upwards(parent, delta);
+    }
+
+    /// Finds the leaf covering weighted position `k`, returning it with the
+    /// offset of `k` inside that leaf's weight.
+    pub(crate) fn select_weighted(&mut self, mut k: usize) -> Option<(&u64, &V, usize)> {
+        if k >= self.internals[0].count {
             return None;
         }
-        if let Some(&leaf) = self.tables[W].get(&key) {
+        let mut child = ChildId::internal(0);
+        while !child.is_leaf() {
+            let node = &self.internals[child.as_internal() as usize];
+            let left = self.child_count(node.child[0]);
+            if k < left {
+                child = node.child[0];
+            } else {
+                k -= left;
+                child = node.child[1];
+            }
+        }
+        let leaf = &self.leaves[child.as_leaf() as usize];
+        debug_assert_ne!(leaf.parent, NIL);
+        Some((&leaf.key, unsafe { &*leaf.value.as_ptr() }, k))
+    }
+
+    fn successor_leaf_id(&self, key: u64) -> Option<u32> {
+        if self.len == 0 || !self.in_universe(key) {
+            return None;
+        }
+        if let Some(&leaf) = self.tables[self.bits].get(&key) {
             return Some(leaf);
         }
 
         let mut l = 0_usize;
-        let mut h = W;
+        let mut h = self.bits;
         let mut u = 0_u32; // root
 
         while h - l > 1 {
             let i = (l + h) / 2;
-            let p = Self::prefix(key, i);
+            let p = self.prefix(key, i);
             if let Some(&id) = self.tables[i].get(&p) {
                 u = id;
                 l = i;
@@ -237,8 +333,8 @@ impl<V> XFastTrieMap<V> {
             }
         }
 
-        debug_assert!(l < W);
-        let bit = ((key >> (W - l - 1)) & 1) as usize;
+        debug_assert!(l < self.bits);
+        let bit = ((key >> (self.bits - l - 1)) & 1) as usize;
         let jump = self.internals[u as usize].jump;
         debug_assert_ne!(jump, NIL, "jump must exist on search boundary");
 
@@ -256,6 +352,26 @@ impl<V> XFastTrieMap<V> {
 
         if succ == NIL { None } else { Some(succ) }
     }
+
+    fn strict_predecessor_leaf_id(&self, key: u64) -> Option<u32> {
+        let pred = if let Some(&leaf) = self.tables[self.bits].get(&key) {
+            self.leaves[leaf as usize].prev
+        } else {
+            match self.successor_leaf_id(key) {
+                Some(succ) => self.leaves[succ as usize].prev,
+                None => self.tail,
+            }
+        };
+        if pred == NIL { None } else { Some(pred) }
+    }
+
+    fn strict_successor_leaf_id(&self, key: u64) -> Option<u32> {
+        if let Some(&leaf) = self.tables[self.bits].get(&key) {
+            let next = self.leaves[leaf as usize].next;
+            return if next == NIL { None } else { Some(next) };
+        }
+        self.successor_leaf_id(key)
+    }
 }
 
 impl<V> Drop for XFastTrieMap<V> {
@@ -283,15 +399,28 @@ impl<V> OrderedMap for XFastTrieMap<V> {
         self.len
     }
 
+    fn empty_like(&self) -> Self {
+        Self::with_bits_and_capacity(self.bits, 0)
+    }
+
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
-        let leaf_id = *self.tables[W].get(key)?;
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
+        let leaf_id = *self.tables[self.bits].get(key)?;
         let leaf = &self.leaves[leaf_id as usize];
         debug_assert_ne!(leaf.parent, NIL);
         Some(unsafe { &*leaf.value.as_ptr() })
     }
 
     fn insert(&mut self, key: Self::Key, value: Self::Value) -> Option<Self::Value> {
-        if let Some(&leaf_id) = self.tables[W].get(&key) {
+        assert!(
+            self.in_universe(key),
+            "key {key} does not fit in a {}-bit universe",
+            self.bits
+        );
+        if let Some(&leaf_id) = self.tables[self.bits].get(&key) {
             let leaf = &mut self.leaves[leaf_id as usize];
             debug_assert_ne!(leaf.parent, NIL);
             let old = unsafe { std::mem::replace(&mut *leaf.value.as_mut_ptr(), value) };
@@ -321,8 +450,8 @@ impl<V> OrderedMap for XFastTrieMap<V> {
         // 1) walk down until falling out of the trie
         let mut u = 0_u32;
         let mut depth = 0_usize;
-        while depth < W {
-            let bit = Self::bit_at(key, depth);
+        while depth < self.bits {
+            let bit = self.bit_at(key, depth);
             let child = self.internals[u as usize].child[bit];
             if child.is_none() {
                 break;
@@ -337,33 +466,34 @@ impl<V> OrderedMap for XFastTrieMap<V> {
 
         // 2) add path to the leaf
         let mut parent = u;
-        for d in depth..W {
-            let bit = Self::bit_at(key, d);
-            if d == W - 1 {
+        for d in depth..self.bits {
+            let bit = self.bit_at(key, d);
+            if d == self.bits - 1 {
                 self.internals[parent as usize].child[bit] = ChildId::leaf(leaf_id);
                 self.leaves[leaf_id as usize].parent = parent;
-                self.tables[W].insert(key, leaf_id);
+                self.tables[self.bits].insert(key, leaf_id);
                 break;
             }
 
             let new_internal = self.alloc_internal(parent);
             self.internals[parent as usize].child[bit] = ChildId::internal(new_internal);
             let depth_next = d + 1;
-            let pfx = Self::prefix(key, depth_next);
+            let pfx = self.prefix(key, depth_next);
             self.tables[depth_next].insert(pfx, new_internal);
             parent = new_internal;
         }
 
         self.len += 1;
         let start = self.leaves[leaf_id as usize].parent;
+        self.add_count_upwards(start, 1);
         self.fix_jumps_upwards(start);
         None
     }
 
     fn remove(&mut self, key: &Self::Key) -> Option<Self::Value> {
-        let leaf_id = *self.tables[W].get(key)?;
+        let leaf_id = *self.tables[self.bits].get(key)?;
 
-        let (parent, prev, next, value) = {
+        let (parent, prev, next, value, weight) = {
             let leaf = &mut self.leaves[leaf_id as usize];
             debug_assert_ne!(leaf.parent, NIL);
             let value = unsafe { leaf.value.assume_init_read() };
@@ -371,8 +501,9 @@ impl<V> OrderedMap for XFastTrieMap<V> {
             let prev = leaf.prev;
             let next = leaf.next;
             leaf.parent = NIL;
-            (parent, prev, next, value)
+            (parent, prev, next, value, leaf.weight)
         };
+        self.add_count_upwards(parent, -(weight as isize));
 
         if prev == NIL {
             self.head = next;
@@ -385,15 +516,16 @@ impl<V> OrderedMap for XFastTrieMap<V> {
             self.leaves[next as usize].prev = prev;
         }
 
-        self.tables[W].remove(key);
+        self.tables[self.bits].remove(key);
         self.free_leaves.push(leaf_id);
 
         let mut current = ChildId::leaf(leaf_id);
-        let mut depth = W;
+        let mut depth = self.bits;
         let mut parent_id = parent;
         let mut fix_from = 0_u32;
 
         while parent_id != NIL {
+            let pfx = self.prefix(*key, depth);
             let parent_node = &mut self.internals[parent_id as usize];
             if parent_node.child[0] == current {
                 parent_node.child[0] = ChildId::none();
@@ -401,8 +533,7 @@ impl<V> OrderedMap for XFastTrieMap<V> {
                 parent_node.child[1] = ChildId::none();
             }
 
-            if depth < W {
-                let pfx = Self::prefix(*key, depth);
+            if depth < self.bits {
                 self.tables[depth].remove(&pfx);
                 self.free_internals.push(current.as_internal());
             }
@@ -433,9 +564,121 @@ impl<V> OrderedMap for XFastTrieMap<V> {
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
         let leaf_id = self.successor_leaf_id(*key)?;
         let leaf = &self.leaves[leaf_id as usize];
         debug_assert_ne!(leaf.parent, NIL);
         Some((&leaf.key, unsafe { &*leaf.value.as_ptr() }))
     }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let leaf_id = self.strict_successor_leaf_id(*key)?;
+        let leaf = &self.leaves[leaf_id as usize];
+        debug_assert_ne!(leaf.parent, NIL);
+        Some((&leaf.key, unsafe { &*leaf.value.as_ptr() }))
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let leaf_id = match self.tables[self.bits].get(key) {
+            Some(&leaf) => leaf,
+            None => self.strict_predecessor_leaf_id(*key)?,
+        };
+        let leaf = &self.leaves[leaf_id as usize];
+        debug_assert_ne!(leaf.parent, NIL);
+        Some((&leaf.key, unsafe { &*leaf.value.as_ptr() }))
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let leaf_id = self.strict_predecessor_leaf_id(*key)?;
+        let leaf = &self.leaves[leaf_id as usize];
+        debug_assert_ne!(leaf.parent, NIL);
+        Some((&leaf.key, unsafe { &*leaf.value.as_ptr() }))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        let mut cur = self.head;
+        std::iter::from_fn(move || {
+            if cur == NIL {
+                return None;
+            }
+            let leaf = &self.leaves[cur as usize];
+            debug_assert_ne!(leaf.parent, NIL);
+            cur = leaf.next;
+            Some((&leaf.key, unsafe { &*leaf.value.as_ptr() }))
+        })
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        let start = match range.start_bound() {
+            Bound::Included(&lo) => self.successor_leaf_id(lo),
+            Bound::Excluded(&lo) => self.strict_successor_leaf_id(lo),
+            Bound::Unbounded => Some(self.head).filter(|&h| h != NIL),
+        };
+        let mut cur = start.unwrap_or(NIL);
+        std::iter::from_fn(move || {
+            if cur == NIL {
+                return None;
+            }
+            let leaf = &self.leaves[cur as usize];
+            debug_assert_ne!(leaf.parent, NIL);
+            if !before_end(range.end_bound(), &leaf.key) {
+                return None;
+            }
+            cur = leaf.next;
+            Some((&leaf.key, unsafe { &*leaf.value.as_ptr() }))
+        })
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        if !self.in_universe(*key) {
+            return self.internals[0].count;
+        }
+        let mut rank = 0;
+        let mut child = ChildId::internal(0);
+        for depth in 0..self.bits {
+            let node = &self.internals[child.as_internal() as usize];
+            let bit = self.bit_at(*key, depth);
+            if bit == 1 {
+                rank += self.child_count(node.child[0]);
+            }
+            child = node.child[bit];
+            if child.is_none() || child.is_leaf() {
+                break;
+            }
+        }
+        rank
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        self.select_weighted(k).map(|(k, v, _)| (k, v))
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        if self.len == 0 {
+            return None;
+        }
+        let leaf = &self.leaves[self.head as usize];
+        debug_assert_ne!(leaf.parent, NIL);
+        Some((&leaf.key, unsafe { &*leaf.value.as_ptr() }))
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        self.max_entry()
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let key = *self.first()?.0;
+        self.remove(&key).map(|value| (key, value))
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let key = *self.last()?.0;
+        self.remove(&key).map(|value| (key, value))
+    }
 }
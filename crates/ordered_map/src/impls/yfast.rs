@@ -1,8 +1,10 @@
+use std::ops::{Bound, RangeBounds};
 use std::ptr::NonNull;
 
 use crate::OrderedMap;
 
 use super::xfast::XFastTrieMap;
+use super::{after_start, before_end};
 
 const W: u64 = 64;
 const DEFAULT_SEED: u64 = 0x5EED_FA55_2026;
@@ -69,6 +71,31 @@ impl<V> Bucket<V> {
         self.data.get(idx).map(|(k, v)| (k, v))
     }
 
+    fn upper_bound(&self, key: &u64) -> Option<(&u64, &V)> {
+        let idx = self.data.partition_point(|(k, _)| k <= key);
+        self.data.get(idx).map(|(k, v)| (k, v))
+    }
+
+    fn floor(&self, key: &u64) -> Option<(&u64, &V)> {
+        let idx = self.data.partition_point(|(k, _)| k <= key);
+        let (k, v) = self.data.get(idx.checked_sub(1)?)?;
+        Some((k, v))
+    }
+
+    fn predecessor(&self, key: &u64) -> Option<(&u64, &V)> {
+        let idx = self.data.partition_point(|(k, _)| k < key);
+        let (k, v) = self.data.get(idx.checked_sub(1)?)?;
+        Some((k, v))
+    }
+
+    fn last_entry(&self) -> Option<(&u64, &V)> {
+        self.data.last().map(|(k, v)| (k, v))
+    }
+
+    fn rank(&self, key: &u64) -> usize {
+        self.data.partition_point(|(k, _)| k < key)
+    }
+
     fn first_entry(&self) -> Option<(&u64, &V)> {
         self.data.first().map(|(k, v)| (k, v))
     }
@@ -85,11 +112,32 @@ impl<V> Bucket<V> {
 }
 
 pub struct YFastTrieMap<V> {
+    // Each representative is weighted by its bucket's size, so the x-fast
+    // rank/select count entries rather than buckets.
     reps: XFastTrieMap<NonNull<Bucket<V>>>,
     len: usize,
     rng: XorShift64,
 }
 
+impl<V> YFastTrieMap<V> {
+    /// Restricts keys to `[0, 2^bits)`; the representative x-fast trie then
+    /// spends one level per bit instead of 64.
+    ///
+    /// # Panics
+    /// If `bits` is not in `1..=64`.
+    pub fn with_universe_bits(bits: u32) -> Self {
+        Self::with_reps(XFastTrieMap::with_universe_bits(bits))
+    }
+
+    fn with_reps(reps: XFastTrieMap<NonNull<Bucket<V>>>) -> Self {
+        Self {
+            reps,
+            len: 0,
+            rng: XorShift64::new(DEFAULT_SEED),
+        }
+    }
+}
+
 impl<V> Drop for YFastTrieMap<V> {
     fn drop(&mut self) {
         while let Some((k, v)) = self.reps.lower_bound(&0) {
@@ -107,24 +155,33 @@ impl<V> OrderedMap for YFastTrieMap<V> {
     type Value = V;
 
     fn new() -> Self {
-        Self {
-            reps: XFastTrieMap::new(),
-            len: 0,
-            rng: XorShift64::new(DEFAULT_SEED),
-        }
+        Self::with_reps(XFastTrieMap::new())
     }
 
     fn len(&self) -> usize {
         self.len
     }
 
+    fn empty_like(&self) -> Self {
+        Self::with_reps(self.reps.empty_like())
+    }
+
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
-        let (_, bucket_ptr) = self.reps.lower_bound(key)?;
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
+        let (_, bucket_ptr) = self.reps.lower_bound_ref(key)?;
         let bucket = unsafe { bucket_ptr.as_ref() };
         bucket.get(key)
     }
 
     fn insert(&mut self, key: Self::Key, value: Self::Value) -> Option<Self::Value> {
+        // Check up front: a bucket must not take a key its representative can't.
+        assert!(
+            self.reps.in_universe(key),
+            "key {key} does not fit in the universe"
+        );
         if self.len == 0 {
             let mut bucket = Box::new(Bucket::new());
             let old = bucket.insert(key, value);
@@ -164,15 +221,20 @@ impl<V> OrderedMap for YFastTrieMap<V> {
             let mut left = bucket.split_le(key);
             if !bucket.is_empty() {
                 let rep_left = left.max_key().unwrap();
+                let left_len = left.len();
                 let left_ptr = unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(left))) };
                 let prev = self.reps.insert(rep_left, left_ptr);
                 debug_assert!(prev.is_none(), "duplicate representative");
+                self.reps.set_weight(rep_left, left_len);
             } else {
                 // Split produced an empty right bucket; undo.
                 bucket.data.append(&mut left.data);
             }
         }
 
+        if old.is_none() {
+            self.reps.set_weight(new_rep, bucket.len());
+        }
         old
     }
 
@@ -197,12 +259,18 @@ impl<V> OrderedMap for YFastTrieMap<V> {
             debug_assert_eq!(got, Some(bucket_ptr));
             self.reps.insert(new_rep, bucket_ptr);
         }
+        self.reps
+            .set_weight(bucket.max_key().unwrap(), bucket.len());
 
         Some(removed)
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
-        let (rep_key, bucket_ptr) = self.reps.lower_bound(key).map(|(k, v)| (*k, *v))?;
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let (rep_key, bucket_ptr) = self.reps.lower_bound_ref(key).map(|(k, v)| (*k, *v))?;
         let bucket = unsafe { bucket_ptr.as_ref() };
         if let Some((k, v)) = bucket.lower_bound(key) {
             return Some((k, v));
@@ -212,8 +280,105 @@ impl<V> OrderedMap for YFastTrieMap<V> {
             return None;
         }
         let next_key = rep_key + 1;
-        let (_, next_bucket_ptr) = self.reps.lower_bound(&next_key)?;
+        let (_, next_bucket_ptr) = self.reps.lower_bound_ref(&next_key)?;
         let next_bucket = unsafe { next_bucket_ptr.as_ref() };
         next_bucket.first_entry()
     }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        // The first bucket whose representative (its max) exceeds `key` holds the answer.
+        let bucket_ptr = *self.reps.upper_bound(key)?.1;
+        let bucket = unsafe { bucket_ptr.as_ref() };
+        bucket.upper_bound(key)
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        // Only the first bucket reaching `key` can hold a floor above the
+        // previous bucket's maximum.
+        if let Some((_, bucket_ptr)) = self.reps.lower_bound(key) {
+            let bucket = unsafe { bucket_ptr.as_ref() };
+            if let Some(entry) = bucket.floor(key) {
+                return Some(entry);
+            }
+        }
+        let bucket_ptr = *self.reps.predecessor(key)?.1;
+        let bucket = unsafe { bucket_ptr.as_ref() };
+        bucket.last_entry()
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        if let Some((_, bucket_ptr)) = self.reps.lower_bound(key) {
+            let bucket = unsafe { bucket_ptr.as_ref() };
+            if let Some(entry) = bucket.predecessor(key) {
+                return Some(entry);
+            }
+        }
+        let bucket_ptr = *self.reps.predecessor(key)?.1;
+        let bucket = unsafe { bucket_ptr.as_ref() };
+        bucket.last_entry()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.reps.iter().flat_map(|(_, bucket_ptr)| {
+            let bucket = unsafe { bucket_ptr.as_ref() };
+            bucket.data.iter().map(|(k, v)| (k, v))
+        })
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        // A bucket's representative is its maximum, so the buckets reaching
+        // `start` are exactly those whose representative does.
+        let start = range.start_bound().cloned();
+        self.reps
+            .range((start, Bound::Unbounded))
+            .flat_map(move |(_, bucket_ptr)| {
+                let bucket = unsafe { bucket_ptr.as_ref() };
+                let from = bucket
+                    .data
+                    .partition_point(|(k, _)| !after_start(start.as_ref(), k));
+                bucket.data[from..].iter().map(|(k, v)| (k, v))
+            })
+            .take_while(move |(k, _)| before_end(range.end_bound(), k))
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        // Buckets whose representative is below `key` lie entirely before it.
+        let before = self.reps.rank(key);
+        let Some((_, bucket_ptr)) = self.reps.lower_bound(key) else {
+            return before;
+        };
+        let bucket = unsafe { bucket_ptr.as_ref() };
+        before + bucket.rank(key)
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        let (_, &bucket_ptr, offset) = self.reps.select_weighted(k)?;
+        let bucket = unsafe { bucket_ptr.as_ref() };
+        bucket.data.get(offset).map(|(k, v)| (k, v))
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let bucket_ptr = *self.reps.first()?.1;
+        let bucket = unsafe { bucket_ptr.as_ref() };
+        bucket.first_entry()
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let bucket_ptr = *self.reps.max_entry()?.1;
+        let bucket = unsafe { bucket_ptr.as_ref() };
+        bucket.last_entry()
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let key = *self.first()?.0;
+        self.remove(&key).map(|value| (key, value))
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let key = *self.last()?.0;
+        self.remove(&key).map(|value| (key, value))
+    }
 }
@@ -1,5 +1,9 @@
+use std::ops::{Bound, RangeBounds};
+
 use crate::OrderedMap;
 
+use super::{after_start, before_end, strictly_ascending};
+
 const DEFAULT_SEED: u64 = 0x5EED_21B7_2026;
 
 #[derive(Clone, Copy)]
@@ -41,6 +45,7 @@ struct Node<K, V> {
     key: K,
     value: V,
     rank: u8,
+    size: usize,
     left: Link<K, V>,
     right: Link<K, V>,
 }
@@ -51,10 +56,19 @@ impl<K, V> Node<K, V> {
             key,
             value,
             rank,
+            size: 1,
             left: None,
             right: None,
         }
     }
+
+    fn size(node: &Link<K, V>) -> usize {
+        node.as_ref().map(|n| n.size).unwrap_or(0)
+    }
+
+    fn recalc(&mut self) {
+        self.size = 1 + Self::size(&self.left) + Self::size(&self.right);
+    }
 }
 
 impl<K: Ord, V> ZipTreeMap<K, V> {
@@ -66,6 +80,26 @@ impl<K: Ord, V> ZipTreeMap<K, V> {
         }
     }
 
+    /// Average and maximum node depth, counting the root as depth 0.
+    #[cfg(test)]
+    pub(crate) fn depth_stats(&self) -> (f64, usize) {
+        let mut total = 0_usize;
+        let mut max = 0_usize;
+        let mut stack: Vec<(&Node<K, V>, usize)> =
+            self.root.as_deref().map(|n| (n, 0)).into_iter().collect();
+        while let Some((node, depth)) = stack.pop() {
+            total += depth;
+            max = max.max(depth);
+            for child in [&node.left, &node.right].into_iter().flatten() {
+                stack.push((child, depth + 1));
+            }
+        }
+        if self.len == 0 {
+            return (0.0, 0);
+        }
+        (total as f64 / self.len as f64, max)
+    }
+
     fn split_lt(root: Link<K, V>, key: &K) -> (Link<K, V>, Link<K, V>) {
         let Some(mut node) = root else {
             return (None, None);
@@ -73,10 +107,12 @@ impl<K: Ord, V> ZipTreeMap<K, V> {
         if node.key < *key {
             let (a, b) = Self::split_lt(node.right.take(), key);
             node.right = a;
+            node.recalc();
             (Some(node), b)
         } else {
             let (a, b) = Self::split_lt(node.left.take(), key);
             node.left = b;
+            node.recalc();
             (a, Some(node))
         }
     }
@@ -88,14 +124,40 @@ impl<K: Ord, V> ZipTreeMap<K, V> {
         if node.key <= *key {
             let (a, b) = Self::split_le(node.right.take(), key);
             node.right = a;
+            node.recalc();
             (Some(node), b)
         } else {
             let (a, b) = Self::split_le(node.left.take(), key);
             node.left = b;
+            node.recalc();
             (a, Some(node))
         }
     }
 
+    // The min/max node has at most one child, so splicing it out keeps the
+    // heap order intact.
+    fn pop_min(mut node: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+        if node.left.is_none() {
+            let right = node.right.take();
+            return (right, node);
+        }
+        let (new_left, min_node) = Self::pop_min(node.left.take().unwrap());
+        node.left = new_left;
+        node.recalc();
+        (Some(node), min_node)
+    }
+
+    fn pop_max(mut node: Box<Node<K, V>>) -> (Link<K, V>, Box<Node<K, V>>) {
+        if node.right.is_none() {
+            let left = node.left.take();
+            return (left, node);
+        }
+        let (new_right, max_node) = Self::pop_max(node.right.take().unwrap());
+        node.right = new_right;
+        node.recalc();
+        (Some(node), max_node)
+    }
+
     fn merge(a: Link<K, V>, b: Link<K, V>) -> Link<K, V> {
         match (a, b) {
             (None, b) => b,
@@ -104,10 +166,12 @@ impl<K: Ord, V> ZipTreeMap<K, V> {
                 if a.rank >= b.rank {
                     let right = a.right.take();
                     a.right = Self::merge(right, Some(b));
+                    a.recalc();
                     Some(a)
                 } else {
                     let left = b.left.take();
                     b.left = Self::merge(Some(a), left);
+                    b.recalc();
                     Some(b)
                 }
             }
@@ -131,6 +195,80 @@ impl<K: Ord, V> ZipTreeMap<K, V> {
         debug_assert!(eq_removed.is_none(), "duplicate keys in zip tree");
         (Self::merge(lt, gt), Some(value))
     }
+
+    /// Builds the zip tree of a sorted stream in O(n), Cartesian-tree style: the
+    /// stack holds the right spine, and each new node adopts the popped part
+    /// of it as its left subtree.
+    fn build_sorted<I: Iterator<Item = (K, V)>>(entries: I, rng: &mut XorShift64) -> Link<K, V> {
+        let mut spine: Vec<Box<Node<K, V>>> = Vec::new();
+        for (key, value) in entries {
+            let mut node = Box::new(Node::new(key, value, rng.next_rank()));
+            let mut below: Link<K, V> = None;
+            while let Some(mut top) = spine.pop_if(|top| top.rank < node.rank) {
+                top.right = below;
+                top.recalc();
+                below = Some(top);
+            }
+            node.left = below;
+            spine.push(node);
+        }
+        let mut root: Link<K, V> = None;
+        while let Some(mut top) = spine.pop() {
+            top.right = root;
+            top.recalc();
+            root = Some(top);
+        }
+        root
+    }
+
+    /// Dismantles a tree into its entries in ascending key order.
+    fn into_sorted(root: Link<K, V>, len: usize) -> Vec<(K, V)> {
+        let mut entries = Vec::with_capacity(len);
+        let mut stack = Vec::new();
+        let mut cur = root;
+        loop {
+            while let Some(mut node) = cur {
+                cur = node.left.take();
+                stack.push(node);
+            }
+            let Some(node) = stack.pop() else {
+                return entries;
+            };
+            let Node {
+                key, value, right, ..
+            } = *node;
+            entries.push((key, value));
+            cur = right;
+        }
+    }
+
+    fn iter_from<'a>(
+        &'a self,
+        start: Bound<&K>,
+    ) -> impl Iterator<Item = (&'a K, &'a V)> + use<'a, K, V> {
+        // Seed the stack with the ancestors at or after `start`; whatever
+        // hangs to their left is skipped.
+        let mut stack = Vec::new();
+        let mut cur = self.root.as_deref();
+        while let Some(node) = cur {
+            if after_start(start, &node.key) {
+                stack.push(node);
+                cur = node.left.as_deref();
+            } else {
+                cur = node.right.as_deref();
+            }
+        }
+        let mut cur = None;
+        std::iter::from_fn(move || {
+            while let Some(node) = cur {
+                stack.push(node);
+                cur = node.left.as_deref();
+            }
+            let node = stack.pop()?;
+            cur = node.right.as_deref();
+            Some((&node.key, &node.value))
+        })
+    }
 }
 
 impl<K: Ord, V> OrderedMap for ZipTreeMap<K, V> {
@@ -141,11 +279,26 @@ impl<K: Ord, V> OrderedMap for ZipTreeMap<K, V> {
         Self::with_seed(DEFAULT_SEED)
     }
 
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self {
+        debug_assert!(
+            strictly_ascending(&entries),
+            "from_sorted needs strictly ascending keys"
+        );
+        let mut map = Self::new();
+        map.len = entries.len();
+        map.root = Self::build_sorted(entries.into_iter(), &mut map.rng);
+        map
+    }
+
     fn len(&self) -> usize {
         self.len
     }
 
     fn get(&mut self, key: &Self::Key) -> Option<&Self::Value> {
+        self.get_ref(key)
+    }
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
         let mut cur = self.root.as_deref();
         while let Some(node) = cur {
             match key.cmp(&node.key) {
@@ -185,6 +338,10 @@ impl<K: Ord, V> OrderedMap for ZipTreeMap<K, V> {
     }
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.lower_bound_ref(key)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
         let mut cur = self.root.as_deref();
         let mut candidate = None;
         while let Some(node) = cur {
@@ -198,4 +355,131 @@ impl<K: Ord, V> OrderedMap for ZipTreeMap<K, V> {
         }
         candidate.map(|n| (&n.key, &n.value))
     }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Less => {
+                    candidate = Some(node);
+                    cur = node.left.as_deref();
+                }
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    cur = node.right.as_deref()
+                }
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater | std::cmp::Ordering::Equal => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut candidate = None;
+        while let Some(node) = cur {
+            match key.cmp(&node.key) {
+                std::cmp::Ordering::Greater => {
+                    candidate = Some(node);
+                    cur = node.right.as_deref();
+                }
+                std::cmp::Ordering::Less | std::cmp::Ordering::Equal => cur = node.left.as_deref(),
+            }
+        }
+        candidate.map(|n| (&n.key, &n.value))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(Bound::Unbounded)
+    }
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)> {
+        self.iter_from(range.start_bound())
+            .take_while(move |(k, _)| before_end(range.end_bound(), k))
+    }
+
+    fn rank(&mut self, key: &Self::Key) -> usize {
+        let mut cur = self.root.as_deref();
+        let mut rank = 0;
+        while let Some(node) = cur {
+            if node.key < *key {
+                rank += Node::size(&node.left) + 1;
+                cur = node.right.as_deref();
+            } else {
+                cur = node.left.as_deref();
+            }
+        }
+        rank
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)> {
+        let mut cur = self.root.as_deref();
+        let mut k = k;
+        while let Some(node) = cur {
+            let left = Node::size(&node.left);
+            match k.cmp(&left) {
+                std::cmp::Ordering::Less => cur = node.left.as_deref(),
+                std::cmp::Ordering::Equal => return Some((&node.key, &node.value)),
+                std::cmp::Ordering::Greater => {
+                    k -= left + 1;
+                    cur = node.right.as_deref();
+                }
+            }
+        }
+        None
+    }
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let (root, min_node) = Self::pop_min(self.root.take()?);
+        self.root = root;
+        self.len -= 1;
+        Some((min_node.key, min_node.value))
+    }
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)> {
+        let (root, max_node) = Self::pop_max(self.root.take()?);
+        self.root = root;
+        self.len -= 1;
+        Some((max_node.key, max_node.value))
+    }
+
+    fn retain<F: FnMut(&Self::Key, &mut Self::Value) -> bool>(&mut self, mut f: F) {
+        let mut entries = Self::into_sorted(self.root.take(), self.len);
+        entries.retain_mut(|(key, value)| f(key, value));
+        self.len = entries.len();
+        self.root = Self::build_sorted(entries.into_iter(), &mut self.rng);
+    }
 }
@@ -1,16 +1,53 @@
+use std::ops::{Bound, RangeBounds};
+
+mod entry;
 pub mod impls;
+mod set;
 
 /// Ordered map interface.
 ///
 /// - Keys are unique.
 /// - `insert` overwrites the existing value and returns the old one.
 /// - `lower_bound` returns the smallest `(k, v)` with `k >= key`.
+/// - `get_ref`/`lower_bound_ref` answer the same queries through `&self`; maps that
+///   restructure on access (splay) fall back to a `range` scan that leaves them untouched.
+/// - `upper_bound` returns the smallest `(k, v)` with `k > key`.
+/// - `floor` returns the largest `(k, v)` with `k <= key`; `predecessor` the largest with `k < key`.
+/// - `from_sorted` builds a map from strictly ascending entries; the balanced trees,
+///   treaps and skip list do it in O(n), the others fall back to repeated `insert`.
+/// - `iter` yields every entry in ascending key order without restructuring the map.
+/// - `range` yields the entries whose keys fall within the bounds, in ascending order.
+/// - `rank` counts the keys `< key`; `select(k)` returns the `k`-th smallest entry (0-based).
+/// - `count_range(lo, hi)` counts the keys in `[lo, hi)` as `rank(hi) - rank(lo)`, and 0 when
+///   `hi <= lo`.
+/// - `first`/`last` return the extreme entries; `pop_first`/`pop_last` also remove them.
+/// - `split_off(key)` keeps the entries `< key` and returns those `>= key` in a map from
+///   `empty_like`, so it keeps `self`'s configuration; `append(other)`
+///   moves every entry of `other` in, with `other`'s value winning on equal keys. The
+///   join-based trees (WBT, RB) do both by split/join; the others re-insert entry by entry.
+/// - `retain(f)` keeps exactly the entries for which `f` returns `true`. The binary trees
+///   flatten, filter and rebuild in O(n); the others drain and re-insert the survivors.
 pub trait OrderedMap {
     type Key: Ord;
     type Value;
 
     fn new() -> Self;
 
+    fn from_sorted(entries: Vec<(Self::Key, Self::Value)>) -> Self
+    where
+        Self: Sized,
+    {
+        debug_assert!(
+            impls::strictly_ascending(&entries),
+            "from_sorted needs strictly ascending keys"
+        );
+        let mut map = Self::new();
+        for (key, value) in entries {
+            map.insert(key, value);
+        }
+        map
+    }
+
     fn len(&self) -> usize;
 
     fn is_empty(&self) -> bool {
@@ -24,23 +61,128 @@ pub trait OrderedMap {
     fn remove(&mut self, key: &Self::Key) -> Option<Self::Value>;
 
     fn lower_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)>;
+
+    fn get_ref(&self, key: &Self::Key) -> Option<&Self::Value> {
+        self.range((Bound::Included(key), Bound::Included(key)))
+            .next()
+            .map(|(_, v)| v)
+    }
+
+    fn lower_bound_ref(&self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)> {
+        self.range((Bound::Included(key), Bound::Unbounded)).next()
+    }
+
+    fn upper_bound(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)>;
+
+    fn floor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)>;
+
+    fn predecessor(&mut self, key: &Self::Key) -> Option<(&Self::Key, &Self::Value)>;
+
+    fn iter(&self) -> impl Iterator<Item = (&Self::Key, &Self::Value)>;
+
+    fn range(
+        &self,
+        range: impl RangeBounds<Self::Key>,
+    ) -> impl Iterator<Item = (&Self::Key, &Self::Value)>;
+
+    fn rank(&mut self, key: &Self::Key) -> usize;
+
+    fn count_range(&mut self, lo: &Self::Key, hi: &Self::Key) -> usize {
+        if hi <= lo {
+            return 0;
+        }
+        self.rank(hi) - self.rank(lo)
+    }
+
+    fn select(&mut self, k: usize) -> Option<(&Self::Key, &Self::Value)>;
+
+    fn first(&mut self) -> Option<(&Self::Key, &Self::Value)>;
+
+    fn last(&mut self) -> Option<(&Self::Key, &Self::Value)>;
+
+    fn pop_first(&mut self) -> Option<(Self::Key, Self::Value)>;
+
+    fn pop_last(&mut self) -> Option<(Self::Key, Self::Value)>;
+
+    /// An empty map configured like `self` (a trie keeps its universe size),
+    /// which the default `split_off` fills with the right half.
+    fn empty_like(&self) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new()
+    }
+
+    fn split_off(&mut self, key: &Self::Key) -> Self
+    where
+        Self: Sized,
+    {
+        let mut right = self.empty_like();
+        while self.last().is_some_and(|(k, _)| k >= key) {
+            let (k, v) = self.pop_last().expect("last entry exists");
+            right.insert(k, v);
+        }
+        right
+    }
+
+    fn append(&mut self, mut other: Self)
+    where
+        Self: Sized,
+    {
+        while let Some((key, value)) = other.pop_first() {
+            self.insert(key, value);
+        }
+    }
+
+    fn retain<F: FnMut(&Self::Key, &mut Self::Value) -> bool>(&mut self, mut f: F)
+    where
+        Self: Sized,
+    {
+        // Re-inserting into `self` rather than rebuilding keeps per-instance
+        // configuration such as a trie's universe size.
+        let mut kept = Vec::with_capacity(self.len());
+        while let Some((key, mut value)) = self.pop_first() {
+            if f(&key, &mut value) {
+                kept.push((key, value));
+            }
+        }
+        for (key, value) in kept {
+            self.insert(key, value);
+        }
+    }
 }
 
+/// Ordered maps with an [`Entry`] API: `entry` finds the key once, and the
+/// returned entry either exposes the value in place or inserts at the spot the
+/// lookup already reached.
+pub trait OrderedMapEntry: OrderedMap {
+    /// What a missed lookup keeps for the insert: an index, a rank, ...
+    type Vacant<'a>: VacantSlot<'a, Self::Value>
+    where
+        Self: 'a;
+
+    fn entry(&mut self, key: Self::Key) -> Entry<'_, Self>;
+}
+
+pub use entry::{Entry, OccupiedEntry, VacantEntry, VacantSlot};
 pub use impls::{
     AaTreeMap, AvlTreeMap, BTreeMapCustom, FusionTreeMap, LlrbTreeMap, RbTreeMap, ScapegoatTreeMap,
     SkipListMap, SortedVecMap, SplayTreeMap, StdBTreeMap, TreapMap, VebMap, WbtTreeMap,
     XFastTrieMap, YFastTrieMap, ZipTreeMap,
 };
+pub use set::{OrderedSet, Set};
 
 #[cfg(test)]
 mod tests {
-    use super::OrderedMap;
     use super::{
         AaTreeMap, AvlTreeMap, BTreeMapCustom, FusionTreeMap, LlrbTreeMap, RbTreeMap,
         ScapegoatTreeMap, SkipListMap, SortedVecMap, SplayTreeMap, StdBTreeMap, TreapMap, VebMap,
         WbtTreeMap, XFastTrieMap, YFastTrieMap, ZipTreeMap,
     };
-    use std::collections::BTreeMap;
+    use super::{Entry, OrderedMap, OrderedMapEntry, OrderedSet, Set};
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::ops::Bound::{Excluded, Included, Unbounded};
+    use std::panic::AssertUnwindSafe;
 
     #[derive(Clone)]
     struct XorShift64 {
@@ -77,12 +219,39 @@ mod tests {
         map.range(key..).next().map(|(&k, &v)| (k, v))
     }
 
+    fn oracle_upper_bound(map: &BTreeMap<u64, u64>, key: u64) -> Option<(u64, u64)> {
+        map.range((Excluded(key), Unbounded))
+            .next()
+            .map(|(&k, &v)| (k, v))
+    }
+
+    fn oracle_floor(map: &BTreeMap<u64, u64>, key: u64) -> Option<(u64, u64)> {
+        map.range(..=key).next_back().map(|(&k, &v)| (k, v))
+    }
+
+    fn oracle_predecessor(map: &BTreeMap<u64, u64>, key: u64) -> Option<(u64, u64)> {
+        map.range(..key).next_back().map(|(&k, &v)| (k, v))
+    }
+
+    fn oracle_rank(keys: &[u64], key: u64) -> usize {
+        keys.partition_point(|&k| k < key)
+    }
+
     fn check_basic<M: OrderedMap<Key = u64, Value = u64>>() {
         let mut map = M::new();
         assert_eq!(map.len(), 0);
         assert!(map.is_empty());
         assert_eq!(map.get(&0), None);
         assert_eq!(map.lower_bound(&0), None);
+        assert_eq!(map.upper_bound(&0), None);
+        assert_eq!(map.floor(&u64::MAX), None);
+        assert_eq!(map.predecessor(&u64::MAX), None);
+        assert_eq!(map.rank(&0), 0);
+        assert_eq!(map.select(0), None);
+        assert_eq!(map.first(), None);
+        assert_eq!(map.last(), None);
+        assert_eq!(map.pop_first(), None);
+        assert_eq!(map.pop_last(), None);
         assert_eq!(map.remove(&0), None);
 
         assert_eq!(map.insert(1, 10), None);
@@ -91,6 +260,18 @@ mod tests {
         assert_eq!(map.lower_bound(&0).map(|(k, v)| (*k, *v)), Some((1, 10)));
         assert_eq!(map.lower_bound(&1).map(|(k, v)| (*k, *v)), Some((1, 10)));
         assert_eq!(map.lower_bound(&2), None);
+        assert_eq!(map.upper_bound(&0).map(|(k, v)| (*k, *v)), Some((1, 10)));
+        assert_eq!(map.upper_bound(&1), None);
+        assert_eq!(map.floor(&0), None);
+        assert_eq!(map.floor(&1).map(|(k, v)| (*k, *v)), Some((1, 10)));
+        assert_eq!(map.predecessor(&1), None);
+        assert_eq!(map.predecessor(&2).map(|(k, v)| (*k, *v)), Some((1, 10)));
+        assert_eq!(map.rank(&1), 0);
+        assert_eq!(map.rank(&2), 1);
+        assert_eq!(map.select(0).map(|(k, v)| (*k, *v)), Some((1, 10)));
+        assert_eq!(map.select(1), None);
+        assert_eq!(map.first().map(|(k, v)| (*k, *v)), Some((1, 10)));
+        assert_eq!(map.last().map(|(k, v)| (*k, *v)), Some((1, 10)));
 
         assert_eq!(map.insert(1, 99), Some(10));
         assert_eq!(map.len(), 1);
@@ -100,16 +281,38 @@ mod tests {
         assert_eq!(map.len(), 0);
         assert_eq!(map.get(&1), None);
         assert_eq!(map.lower_bound(&0), None);
+
+        assert_eq!(map.insert(3, 30), None);
+        assert_eq!(map.insert(2, 20), None);
+        assert_eq!(map.pop_last(), Some((3, 30)));
+        assert_eq!(map.pop_first(), Some((2, 20)));
+        assert!(map.is_empty());
+        assert_eq!(map.pop_first(), None);
     }
 
     fn check_bounds_edges<M: OrderedMap<Key = u64, Value = u64>>() {
         let mut map = M::new();
-        let keys = [0, 1, u64::MAX - 1, u64::MAX];
+        let mid = 1_u64 << 32;
+        let keys = [0, 1, mid, mid + 2, u64::MAX - 1, u64::MAX];
         for (i, &k) in keys.iter().enumerate() {
             assert_eq!(map.insert(k, i as u64), None);
         }
 
-        for &query in [0, 1, 2, u64::MAX - 1, u64::MAX].iter() {
+        let queries = [
+            0,
+            1,
+            2,
+            mid - 1,
+            mid,
+            mid + 1,
+            mid + 2,
+            mid + 3,
+            1 << 63,
+            u64::MAX - 2,
+            u64::MAX - 1,
+            u64::MAX,
+        ];
+        for &query in queries.iter() {
             let got = map.lower_bound(&query).map(|(k, v)| (*k, *v));
             let mut oracle = BTreeMap::new();
             for (i, &k) in keys.iter().enumerate() {
@@ -117,7 +320,31 @@ mod tests {
             }
             let expect = oracle_lower_bound(&oracle, query);
             assert_eq!(got, expect, "query={query}");
+
+            let got = map.upper_bound(&query).map(|(k, v)| (*k, *v));
+            let expect = oracle_upper_bound(&oracle, query);
+            assert_eq!(got, expect, "upper query={query}");
+
+            let got = map.floor(&query).map(|(k, v)| (*k, *v));
+            let expect = oracle_floor(&oracle, query);
+            assert_eq!(got, expect, "floor query={query}");
+
+            let got = map.predecessor(&query).map(|(k, v)| (*k, *v));
+            let expect = oracle_predecessor(&oracle, query);
+            assert_eq!(got, expect, "predecessor query={query}");
+
+            let sorted: Vec<u64> = oracle.keys().copied().collect();
+            assert_eq!(
+                map.rank(&query),
+                oracle_rank(&sorted, query),
+                "rank query={query}"
+            );
         }
+
+        for (i, &k) in keys.iter().enumerate() {
+            assert_eq!(map.select(i).map(|(k, _)| *k), Some(k));
+        }
+        assert_eq!(map.select(keys.len()), None);
     }
 
     fn check_random<M: OrderedMap<Key = u64, Value = u64>>() {
@@ -138,14 +365,46 @@ mod tests {
                 let got = map.remove(&key);
                 let expect = oracle.remove(&key);
                 assert_eq!(got, expect);
-            } else if roll < 80 {
-                let got = map.get(&key).copied();
+            } else if roll < 75 {
                 let expect = oracle.get(&key).copied();
-                assert_eq!(got, expect);
-            } else {
-                let got = map.lower_bound(&key).map(|(k, v)| (*k, *v));
+                assert_eq!(map.get_ref(&key).copied(), expect);
+                assert_eq!(map.get(&key).copied(), expect);
+            } else if roll < 80 {
                 let expect = oracle_lower_bound(&oracle, key);
+                assert_eq!(map.lower_bound_ref(&key).map(|(k, v)| (*k, *v)), expect);
+                assert_eq!(map.lower_bound(&key).map(|(k, v)| (*k, *v)), expect);
+            } else if roll < 85 {
+                // Query an existing key half the time so `floor` hits exactly.
+                let key = match oracle.keys().nth(rng.gen_usize(0..oracle.len().max(1))) {
+                    Some(&k) if roll.is_multiple_of(2) => k,
+                    _ => key,
+                };
+                let got = map.floor(&key).map(|(k, v)| (*k, *v));
+                assert_eq!(got, oracle_floor(&oracle, key));
+                let got = map.predecessor(&key).map(|(k, v)| (*k, *v));
+                assert_eq!(got, oracle_predecessor(&oracle, key));
+            } else if roll < 92 {
+                // Query an existing key half the time so the strict bound is exercised.
+                let key = match oracle.keys().nth(rng.gen_usize(0..oracle.len().max(1))) {
+                    Some(&k) if roll.is_multiple_of(2) => k,
+                    _ => key,
+                };
+                let got = map.upper_bound(&key).map(|(k, v)| (*k, *v));
+                let expect = oracle_upper_bound(&oracle, key);
                 assert_eq!(got, expect);
+            } else {
+                let sorted: Vec<u64> = oracle.keys().copied().collect();
+                if roll.is_multiple_of(2) {
+                    assert_eq!(map.rank(&key), oracle_rank(&sorted, key));
+                    if let Some(&k) = sorted.get(rng.gen_usize(0..sorted.len().max(1))) {
+                        assert_eq!(map.rank(&k), oracle_rank(&sorted, k));
+                    }
+                } else {
+                    let k = rng.gen_usize(0..sorted.len() + 1);
+                    let got = map.select(k).map(|(k, v)| (*k, *v));
+                    let expect = sorted.get(k).map(|k| (*k, oracle[k]));
+                    assert_eq!(got, expect, "select k={k}");
+                }
             }
 
             assert_eq!(map.len(), oracle.len());
@@ -157,6 +416,478 @@ mod tests {
         }
     }
 
+    fn check_count_range<M: OrderedMap<Key = u64, Value = u64>>() {
+        let mut rng = XorShift64::new(0xC0_07_7A_17);
+        let mut map = M::new();
+        let mut oracle = BTreeMap::new();
+        for _ in 0..2_000 {
+            let key = rng.next_u64() % 10_000;
+            map.insert(key, key);
+            oracle.insert(key, key);
+        }
+        for _ in 0..2_000 {
+            let a = rng.next_u64() % 10_100;
+            let b = rng.next_u64() % 10_100;
+            let (lo, hi) = (a.min(b), a.max(b));
+            assert_eq!(map.count_range(&lo, &hi), oracle.range(lo..hi).count());
+            assert_eq!(map.count_range(&hi, &lo), 0);
+        }
+        assert_eq!(map.count_range(&0, &u64::MAX), oracle.len());
+    }
+
+    fn check_iter<M: OrderedMap<Key = u64, Value = u64>>() {
+        let mut rng = XorShift64::new(0x17E2_A7E5_0DD5_EED5);
+        let mut map = M::new();
+        let mut oracle = BTreeMap::new();
+        assert_eq!(map.iter().next(), None);
+
+        for round in 0..4_000_usize {
+            // Narrow key space so removes and overwrites actually hit.
+            let key = rng.gen_u64() % 2_048;
+            if rng.next_u64().is_multiple_of(4) {
+                assert_eq!(map.remove(&key), oracle.remove(&key));
+            } else {
+                let value = rng.gen_u64();
+                assert_eq!(map.insert(key, value), oracle.insert(key, value));
+            }
+
+            if round.is_multiple_of(500) {
+                let got: Vec<(u64, u64)> = map.iter().map(|(k, v)| (*k, *v)).collect();
+                let expect: Vec<(u64, u64)> = oracle.iter().map(|(k, v)| (*k, *v)).collect();
+                assert_eq!(got, expect, "round={round}");
+            }
+        }
+
+        for &k in &[0, u64::MAX] {
+            map.insert(k, k);
+            oracle.insert(k, k);
+        }
+        let got: Vec<(u64, u64)> = map.iter().map(|(k, v)| (*k, *v)).collect();
+        let expect: Vec<(u64, u64)> = oracle.iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(got, expect);
+    }
+
+    fn check_pop<M: OrderedMap<Key = u64, Value = u64>>() {
+        let mut rng = XorShift64::new(0x9E37_79B9_7F4A_7C15);
+        let mut map = M::new();
+        let mut oracle = BTreeMap::new();
+
+        for step in 0..20_000_usize {
+            let roll = rng.next_u64() % 100;
+            if roll < 55 {
+                let key = rng.gen_u64() % 50_000;
+                let value = rng.gen_u64();
+                assert_eq!(map.insert(key, value), oracle.insert(key, value));
+            } else if roll < 80 {
+                assert_eq!(map.pop_first(), oracle.pop_first());
+            } else {
+                assert_eq!(map.pop_last(), oracle.pop_last());
+            }
+
+            assert_eq!(map.len(), oracle.len());
+            let first = oracle.first_key_value().map(|(&k, &v)| (k, v));
+            let last = oracle.last_key_value().map(|(&k, &v)| (k, v));
+            assert_eq!(map.first().map(|(k, v)| (*k, *v)), first);
+            assert_eq!(map.last().map(|(k, v)| (*k, *v)), last);
+            if step.is_multiple_of(64) {
+                // Pops must keep the order-statistics bookkeeping in sync.
+                let mid = oracle.len() / 2;
+                let expect = oracle.iter().nth(mid).map(|(&k, &v)| (k, v));
+                assert_eq!(map.select(mid).map(|(k, v)| (*k, *v)), expect);
+            }
+        }
+
+        while let Some(expect) = oracle.pop_first() {
+            assert_eq!(map.pop_first(), Some(expect));
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.pop_last(), None);
+    }
+
+    fn check_range<M: OrderedMap<Key = u64, Value = u64>>() {
+        let mut rng = XorShift64::new(0x2A6E_5EED_0F0F_1234);
+        let mut map = M::new();
+        let mut oracle = BTreeMap::new();
+        for &k in &[0, u64::MAX] {
+            map.insert(k, k);
+            oracle.insert(k, k);
+        }
+        for _ in 0..1_500 {
+            let key = rng.next_u64() % 4_096;
+            map.insert(key, key ^ 0x55);
+            oracle.insert(key, key ^ 0x55);
+        }
+
+        let pick = |rng: &mut XorShift64| match rng.next_u64() % 8 {
+            0 => 0,
+            1 => u64::MAX,
+            _ => rng.next_u64() % 4_200,
+        };
+        let bound = |rng: &mut XorShift64, key: u64| match rng.next_u64() % 3 {
+            0 => Included(key),
+            1 => Excluded(key),
+            _ => Unbounded,
+        };
+        for round in 0..2_000 {
+            let (a, b) = (pick(&mut rng), pick(&mut rng));
+            let (lo, hi) = (a.min(b), a.max(b));
+            let (start, end) = (bound(&mut rng, lo), bound(&mut rng, hi));
+            // `BTreeMap::range` panics on an empty range excluded at both ends.
+            if lo == hi && matches!((start, end), (Excluded(_), Excluded(_))) {
+                continue;
+            }
+            let got: Vec<(u64, u64)> = map.range((start, end)).map(|(k, v)| (*k, *v)).collect();
+            let expect: Vec<(u64, u64)> =
+                oracle.range((start, end)).map(|(k, v)| (*k, *v)).collect();
+            assert_eq!(got, expect, "round={round} start={start:?} end={end:?}");
+        }
+
+        let got: Vec<u64> = map.range(10..20).map(|(k, _)| *k).collect();
+        let expect: Vec<u64> = oracle.range(10..20).map(|(k, _)| *k).collect();
+        assert_eq!(got, expect);
+        let got: Vec<u64> = map.range(..=100).map(|(k, _)| *k).collect();
+        let expect: Vec<u64> = oracle.range(..=100).map(|(k, _)| *k).collect();
+        assert_eq!(got, expect);
+        assert_eq!(map.range(..).count(), oracle.len());
+    }
+
+    fn check_from_sorted<M: OrderedMap<Key = u64, Value = u64>>() {
+        let mut rng = XorShift64::new(0x5017_ED00_B11D_0001);
+        for n in [0_u64, 1, 2, 3, 7, 8, 100, 1_000, 4_097] {
+            let entries: Vec<(u64, u64)> = (0..n).map(|i| (3 * i + 1, i)).collect();
+            let mut map = M::from_sorted(entries.clone());
+            let mut oracle: BTreeMap<u64, u64> = entries.iter().copied().collect();
+            assert_eq!(map.len(), oracle.len());
+            let got: Vec<(u64, u64)> = map.iter().map(|(k, v)| (*k, *v)).collect();
+            assert_eq!(got, entries, "n={n}");
+            for key in 0..3 * n + 3 {
+                let got = map.lower_bound(&key).map(|(k, v)| (*k, *v));
+                assert_eq!(got, oracle_lower_bound(&oracle, key), "n={n} key={key}");
+            }
+            for (i, (k, v)) in entries.iter().enumerate() {
+                assert_eq!(map.rank(k), i);
+                assert_eq!(map.select(i), Some((k, v)));
+            }
+
+            // Keep mutating so a malformed build would trip later rebalancing.
+            for _ in 0..2 * n {
+                let key = rng.next_u64() % (3 * n + 3);
+                if rng.next_u64().is_multiple_of(2) {
+                    assert_eq!(map.remove(&key), oracle.remove(&key));
+                } else {
+                    assert_eq!(map.insert(key, key), oracle.insert(key, key));
+                }
+            }
+            let got: Vec<(u64, u64)> = map.iter().map(|(k, v)| (*k, *v)).collect();
+            let expect: Vec<(u64, u64)> = oracle.iter().map(|(k, v)| (*k, *v)).collect();
+            assert_eq!(got, expect, "n={n}");
+        }
+    }
+
+    fn check_split_append<M: OrderedMap<Key = u64, Value = u64>>() {
+        let mut rng = XorShift64::new(0x5B1_17A9_9E4D_0042);
+        let to_vec = |map: &M| map.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+        let oracle_vec =
+            |map: &BTreeMap<u64, u64>| map.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+        for round in 0..200_u64 {
+            let span = 1 + rng.next_u64() % 2_000;
+            let (mut a, mut b) = (M::new(), M::new());
+            let (mut oa, mut ob) = (BTreeMap::new(), BTreeMap::new());
+            for _ in 0..rng.next_u64() % 300 {
+                let (key, value) = (rng.next_u64() % span, rng.next_u64());
+                a.insert(key, value);
+                oa.insert(key, value);
+            }
+            for _ in 0..rng.next_u64() % 300 {
+                let (key, value) = (rng.next_u64() % span, rng.next_u64());
+                b.insert(key, value);
+                ob.insert(key, value);
+            }
+
+            // Split both at random points and check the halves are disjoint.
+            let cut = rng.next_u64() % (span + 1);
+            let mut a_hi = a.split_off(&cut);
+            let mut oa_hi = oa.split_off(&cut);
+            assert_eq!(to_vec(&a), oracle_vec(&oa), "round={round}");
+            assert_eq!(to_vec(&a_hi), oracle_vec(&oa_hi), "round={round}");
+            assert_eq!((a.len(), a_hi.len()), (oa.len(), oa_hi.len()));
+            assert!(a.last().is_none_or(|(k, _)| *k < cut));
+            assert!(a_hi.first().is_none_or(|(k, _)| *k >= cut));
+
+            // Gluing the halves back restores the original map.
+            a.append(a_hi);
+            oa.append(&mut oa_hi);
+            assert_eq!(to_vec(&a), oracle_vec(&oa), "round={round}");
+
+            // Overlapping append: `b`'s values win.
+            a.append(b);
+            oa.append(&mut ob);
+            assert_eq!(to_vec(&a), oracle_vec(&oa), "round={round}");
+            assert_eq!(a.len(), oa.len());
+
+            // The merged map must keep working.
+            for _ in 0..50 {
+                let key = rng.next_u64() % span;
+                if rng.next_u64().is_multiple_of(2) {
+                    assert_eq!(a.remove(&key), oa.remove(&key));
+                } else {
+                    assert_eq!(a.insert(key, key), oa.insert(key, key));
+                }
+                assert_eq!(a.rank(&key), oa.range(..key).count());
+            }
+        }
+    }
+
+    fn check_retain<M: OrderedMap<Key = u64, Value = u64>>() {
+        let mut rng = XorShift64::new(0x4E7A_1B0D_D5EE_D001);
+        let mut map = M::new();
+        let mut oracle = BTreeMap::new();
+        for _ in 0..3_000 {
+            let (key, value) = (rng.next_u64() % 5_000, rng.next_u64() % 1_000);
+            map.insert(key, value);
+            oracle.insert(key, value);
+        }
+
+        // Drop the odd keys and bump the survivors through the `&mut` value.
+        let bump = |k: &u64, v: &mut u64| {
+            *v += 1;
+            k.is_multiple_of(2)
+        };
+        map.retain(bump);
+        oracle.retain(bump);
+        assert_eq!(map.len(), oracle.len());
+        assert!(map.iter().eq(oracle.iter()));
+
+        // The filtered map must keep working.
+        for _ in 0..2_000 {
+            let key = rng.next_u64() % 5_000;
+            match rng.next_u64() % 3 {
+                0 => assert_eq!(map.remove(&key), oracle.remove(&key)),
+                1 => assert_eq!(map.insert(key, key), oracle.insert(key, key)),
+                _ => assert_eq!(map.rank(&key), oracle.range(..key).count()),
+            }
+        }
+        assert!(map.iter().eq(oracle.iter()));
+
+        map.retain(|_, _| false);
+        assert!(map.is_empty());
+        assert_eq!(map.first(), None);
+    }
+
+    fn check_entry_word_count<M: OrderedMapEntry<Key = String, Value = usize>>() -> M {
+        const WORDS: [&str; 12] = [
+            "the", "quick", "brown", "fox", "jumps", "over", "lazy", "dog", "and", "a", "cat",
+            "sleeps",
+        ];
+        let mut rng = XorShift64::new(0xC0_FFEE_1234);
+        let mut map = M::new();
+        let mut oracle: BTreeMap<String, usize> = BTreeMap::new();
+        for round in 0..5_000_usize {
+            // Skewed picks so a few words dominate, like real text.
+            let word = WORDS[rng
+                .gen_usize(0..WORDS.len())
+                .min(rng.gen_usize(0..WORDS.len()))];
+            let word = format!("{word}{}", rng.next_u64() % 40);
+            let count = map.entry(word.clone()).and_modify(|c| *c += 1).or_insert(1);
+            let expect = oracle
+                .entry(word.clone())
+                .and_modify(|c| *c += 1)
+                .or_insert(1);
+            assert_eq!(*count, *expect, "round={round} word={word}");
+        }
+        assert_eq!(map.len(), oracle.len());
+        let got: Vec<(String, usize)> = map.iter().map(|(k, v)| (k.clone(), *v)).collect();
+        let expect: Vec<(String, usize)> = oracle.into_iter().collect();
+        assert_eq!(got, expect);
+
+        match map.entry("fox0".to_string()) {
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() += 100;
+                assert!(*entry.get() > 100);
+            }
+            Entry::Vacant(_) => panic!("fox0 was counted"),
+        }
+        let fresh = map.entry("zebra".to_string()).or_insert_with(|| 7);
+        *fresh += 1;
+        assert_eq!(map.get(&"zebra".to_string()), Some(&8));
+        assert!(matches!(map.entry("zebra".to_string()), Entry::Occupied(_)));
+        map
+    }
+
+    #[test]
+    fn entry_word_count() {
+        check_entry_word_count::<StdBTreeMap<String, usize>>();
+        check_entry_word_count::<SortedVecMap<String, usize>>();
+        check_entry_word_count::<TreapMap<String, usize>>().check_heap();
+        check_entry_word_count::<AvlTreeMap<String, usize>>().check_balanced();
+    }
+
+    #[cfg(feature = "serde")]
+    fn check_serde_round_trip<M>()
+    where
+        M: OrderedMap<Key = u64, Value = u64> + serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let mut rng = XorShift64::new(0x5E2D_E5E2_0000_0001);
+        let mut map = M::new();
+        for _ in 0..1_000 {
+            let key = rng.next_u64() % 5_000;
+            map.insert(key, rng.next_u64());
+        }
+        for &k in &[0, u64::MAX] {
+            map.insert(k, k);
+        }
+        let before: Vec<(u64, u64)> = map.iter().map(|(k, v)| (*k, *v)).collect();
+
+        let json = serde_json::to_string(&map).expect("serialize");
+        let mut back: M = serde_json::from_str(&json).expect("deserialize");
+        let after: Vec<(u64, u64)> = back.iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(after, before);
+        assert_eq!(back.len(), map.len());
+        assert_eq!(
+            back.select(before.len() / 2).map(|(k, _)| *k),
+            Some(before[before.len() / 2].0)
+        );
+
+        let empty: M = serde_json::from_str(&serde_json::to_string(&M::new()).unwrap()).unwrap();
+        assert!(empty.is_empty());
+        assert!(serde_json::from_str::<M>(r#"{"2":1,"1":1}"#).is_err());
+    }
+
+    fn check_universe_queries<M: OrderedMap<Key = u64, Value = u64>>(
+        map: &mut M,
+        oracle: &BTreeMap<u64, u64>,
+        queries: &[u64],
+    ) {
+        let got: Vec<(u64, u64)> = map.iter().map(|(k, v)| (*k, *v)).collect();
+        let expect: Vec<(u64, u64)> = oracle.iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(got, expect);
+        for &q in queries {
+            let pair = |e: Option<(&u64, &u64)>| e.map(|(k, v)| (*k, *v));
+            assert_eq!(
+                pair(map.lower_bound(&q)),
+                oracle_lower_bound(oracle, q),
+                "q={q}"
+            );
+            assert_eq!(
+                pair(map.upper_bound(&q)),
+                oracle_upper_bound(oracle, q),
+                "q={q}"
+            );
+            assert_eq!(pair(map.floor(&q)), oracle_floor(oracle, q), "q={q}");
+            assert_eq!(
+                pair(map.predecessor(&q)),
+                oracle_predecessor(oracle, q),
+                "q={q}"
+            );
+            assert_eq!(map.rank(&q), oracle.range(..q).count(), "q={q}");
+            assert_eq!(map.get(&q), oracle.get(&q), "q={q}");
+        }
+    }
+
+    #[test]
+    fn universe_bits_bound_trie_size() {
+        const BITS: u32 = 20;
+        let mut rng = XorShift64::new(0x0B17_5000_0000_0020);
+        let mut small_x = XFastTrieMap::with_universe_bits(BITS);
+        let mut full_x = XFastTrieMap::new();
+        let mut small_veb = VebMap::with_universe_bits(BITS);
+        let mut full_veb = VebMap::new();
+        let mut small_y = YFastTrieMap::with_universe_bits(BITS);
+        let mut oracle = BTreeMap::new();
+        for &k in &[0, (1 << BITS) - 1] {
+            small_x.insert(k, k);
+            small_veb.insert(k, k);
+            small_y.insert(k, k);
+            oracle.insert(k, k);
+        }
+        for _ in 0..3_000 {
+            let key = rng.next_u64() & ((1 << BITS) - 1);
+            let value = rng.next_u64();
+            small_x.insert(key, value);
+            full_x.insert(key, value);
+            small_veb.insert(key, value);
+            full_veb.insert(key, value);
+            small_y.insert(key, value);
+            oracle.insert(key, value);
+        }
+        for _ in 0..500 {
+            let key = rng.next_u64() & ((1 << BITS) - 1);
+            assert_eq!(small_x.remove(&key), oracle.get(&key).copied());
+            assert_eq!(full_x.remove(&key), oracle.get(&key).copied());
+            assert_eq!(small_veb.remove(&key), oracle.get(&key).copied());
+            assert_eq!(full_veb.remove(&key), oracle.get(&key).copied());
+            assert_eq!(small_y.remove(&key), oracle.remove(&key));
+        }
+
+        let mut queries = vec![0, 1, (1 << BITS) - 2, (1 << BITS) - 1, 1 << BITS, u64::MAX];
+        queries.extend((0..500).map(|_| rng.next_u64() & ((1 << (BITS + 1)) - 1)));
+        check_universe_queries(&mut small_x, &oracle, &queries);
+        check_universe_queries(&mut small_veb, &oracle, &queries);
+        check_universe_queries(&mut small_y, &oracle, &queries);
+
+        // At most one internal node per trie level per key, and fewer than the
+        // 64-level trie needs for the same keys.
+        assert!(small_x.internal_node_count() <= 1 + BITS as usize * small_x.len());
+        assert!(small_x.internal_node_count() < full_x.internal_node_count());
+        assert!(small_veb.tree_node_count() < full_veb.tree_node_count());
+    }
+
+    #[test]
+    fn veb_rank_and_select_follow_dense_updates() {
+        // A 5-bit universe fills up, so promotions of `min` and universe-2
+        // leaves are hit constantly.
+        let mut rng = XorShift64::new(0x007E_B0C0_0175);
+        let mut map = VebMap::with_universe_bits(5);
+        let mut oracle = BTreeMap::new();
+        for round in 0..2_000 {
+            let key = rng.next_u64() % 32;
+            if rng.next_u64().is_multiple_of(3) {
+                assert_eq!(map.remove(&key), oracle.remove(&key));
+            } else {
+                assert_eq!(map.insert(key, round), oracle.insert(key, round));
+            }
+            for q in 0..=32 {
+                assert_eq!(
+                    map.rank(&q),
+                    oracle.range(..q).count(),
+                    "round={round} q={q}"
+                );
+            }
+            for (i, (k, v)) in oracle.iter().enumerate() {
+                assert_eq!(map.select(i), Some((k, v)), "round={round} i={i}");
+            }
+            assert_eq!(map.select(oracle.len()), None);
+        }
+    }
+
+    #[test]
+    fn split_off_keeps_universe_bits() {
+        fn check<M: OrderedMap<Key = u64, Value = u64>>(mut map: M) {
+            for k in 0..100 {
+                map.insert(k << 10, k);
+            }
+            let mut right = map.split_off(&(50 << 10));
+            assert_eq!((map.len(), right.len()), (50, 50));
+            for half in [&mut map, &mut right] {
+                let wide = std::panic::catch_unwind(AssertUnwindSafe(|| half.insert(1 << 20, 0)));
+                assert!(
+                    wide.is_err(),
+                    "a split half accepted a key outside the universe"
+                );
+            }
+        }
+        check(VebMap::with_universe_bits(20));
+        check(XFastTrieMap::with_universe_bits(20));
+        check(YFastTrieMap::with_universe_bits(20));
+    }
+
+    #[test]
+    #[should_panic(expected = "universe")]
+    fn universe_bits_reject_wide_keys() {
+        let mut map = YFastTrieMap::with_universe_bits(20);
+        map.insert(1 << 20, 0);
+    }
+
     macro_rules! test_all {
         ($name:ident, $func:ident) => {
             #[test]
@@ -185,4 +916,357 @@ mod tests {
     test_all!(basic_all_impls, check_basic);
     test_all!(bounds_edges_all_impls, check_bounds_edges);
     test_all!(random_all_impls, check_random);
+    test_all!(count_range_all_impls, check_count_range);
+    test_all!(iter_all_impls, check_iter);
+    test_all!(pop_all_impls, check_pop);
+    test_all!(range_all_impls, check_range);
+    test_all!(from_sorted_all_impls, check_from_sorted);
+    test_all!(split_append_all_impls, check_split_append);
+    test_all!(retain_all_impls, check_retain);
+    #[cfg(feature = "serde")]
+    test_all!(serde_round_trip_all_impls, check_serde_round_trip);
+
+    #[test]
+    fn fusion_lower_bound_many_matches_single_queries() {
+        let mut rng = XorShift64::new(0xF05E_0B47_C4ED);
+        for &(n, universe) in &[(0_usize, 100_u64), (1, 100), (50, 200), (5_000, 1 << 20)] {
+            let mut map = FusionTreeMap::new();
+            for _ in 0..n {
+                let key = rng.next_u64() % universe;
+                map.insert(key, key);
+            }
+            let mut keys: Vec<u64> = (0..2_000)
+                .map(|_| rng.next_u64() % (universe + 10))
+                .collect();
+            keys.extend([0, u64::MAX]);
+            keys.extend(map.iter().map(|(&k, _)| k).take(100));
+
+            let mut out = vec![Some(7)];
+            map.lower_bound_many(&keys, &mut out);
+            assert_eq!(out.len(), keys.len());
+            for (&key, &got) in keys.iter().zip(&out) {
+                assert_eq!(
+                    got,
+                    map.lower_bound(&key).map(|(&k, _)| k),
+                    "n={n} key={key}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn skip_list_seed_pins_structure() {
+        let mut rng = XorShift64::new(0x5EED_5E1F);
+        let keys: Vec<u64> = (0..2_000).map(|_| rng.next_u64() % 5_000).collect();
+        let build = |mut map: SkipListMap<u64, u64>| {
+            for &k in &keys {
+                map.insert(k, k);
+            }
+            for &k in keys.iter().step_by(3) {
+                map.remove(&k);
+            }
+            map
+        };
+
+        let a = build(SkipListMap::with_seed(42));
+        let b = build(SkipListMap::with_seed(42));
+        let c = build(SkipListMap::with_seed(43));
+        assert_eq!(a.node_levels(), b.node_levels());
+        assert_ne!(a.node_levels(), c.node_levels());
+        assert!(a.iter().eq(c.iter()));
+
+        // Default maps on one thread draw different seeds.
+        let d = build(SkipListMap::new());
+        let e = build(SkipListMap::new());
+        assert_ne!(d.node_levels(), e.node_levels());
+    }
+
+    #[test]
+    fn treap_join_concatenates_disjoint_ranges() {
+        let mut rng = XorShift64::new(0x7EA9_7015);
+        for &(n_low, n_high) in &[(0_usize, 0_usize), (0, 10), (10, 0), (1, 1), (500, 3_000)] {
+            let mut low = TreapMap::with_seed(1);
+            let mut high = TreapMap::with_seed(2);
+            for _ in 0..n_low {
+                let key = rng.next_u64() % 10_000;
+                low.insert(key, key);
+            }
+            for _ in 0..n_high {
+                let key = 10_000 + rng.next_u64() % 10_000;
+                high.insert(key, key);
+            }
+            let expected: Vec<(u64, u64)> = low
+                .iter()
+                .chain(high.iter())
+                .map(|(&k, &v)| (k, v))
+                .collect();
+
+            let mut joined = low.join(high);
+            assert_eq!(joined.len(), expected.len());
+            assert!(
+                joined
+                    .iter()
+                    .map(|(&k, &v)| (k, v))
+                    .eq(expected.iter().copied())
+            );
+            for (i, &(k, _)) in expected.iter().enumerate().step_by(97) {
+                assert_eq!(joined.rank(&k), i);
+                assert_eq!(joined.select(i).map(|(&k, _)| k), Some(k));
+            }
+            assert_eq!(joined.insert(20_000, 0), None);
+            assert_eq!(joined.last(), Some((&20_000, &0)));
+        }
+    }
+
+    #[test]
+    fn zip_tree_depth_is_logarithmic() {
+        let n = 1_usize << 16;
+        let log_n = n.ilog2() as f64;
+        let mut rng = XorShift64::new(0x21B_DE97);
+        let mut random = ZipTreeMap::with_seed(7);
+        let mut ascending = ZipTreeMap::with_seed(8);
+        for i in 0..n as u64 {
+            random.insert(rng.next_u64(), i);
+            ascending.insert(i, i);
+        }
+        for map in [&random, &ascending] {
+            let (avg, max) = map.depth_stats();
+            // Any binary tree on n nodes averages at least about log2(n) - 2;
+            // zip trees expect about 1.5 * log2(n).
+            assert!(avg >= log_n - 2.0, "avg={avg}");
+            assert!(avg <= 3.0 * log_n, "avg={avg}");
+            assert!((max as f64) <= 6.0 * log_n, "max={max}");
+        }
+        assert_eq!(ZipTreeMap::<u64, u64>::new().depth_stats(), (0.0, 0));
+    }
+
+    fn check_set_adapter<M: OrderedMap<Key = u64, Value = ()>>() {
+        let mut rng = XorShift64::new(0x5E7_ADA7);
+        let mut set = Set::<M>::new();
+        let mut oracle = BTreeSet::new();
+        for _ in 0..5_000 {
+            let key = rng.next_u64() % 1_000;
+            match rng.gen_usize(0..4) {
+                0 => assert_eq!(set.insert(key), oracle.insert(key)),
+                1 => assert_eq!(set.remove(&key), oracle.remove(&key)),
+                2 => assert_eq!(set.contains(&key), oracle.contains(&key)),
+                _ => assert_eq!(set.lower_bound(&key), oracle.range(key..).next()),
+            }
+            assert_eq!(set.len(), oracle.len());
+        }
+        assert!(set.iter().eq(oracle.iter()));
+        assert!(!set.is_empty());
+    }
+
+    #[test]
+    fn set_adapter_matches_btreeset() {
+        check_set_adapter::<AvlTreeMap<u64, ()>>();
+        check_set_adapter::<VebMap<()>>();
+    }
+
+    #[test]
+    fn sorted_vec_gap_buffer_matches_oracle() {
+        let mut rng = XorShift64::new(0x6A9_B0FF);
+        let mut map = SortedVecMap::with_gap_buffer();
+        let mut oracle = BTreeMap::new();
+        for step in 0..3_000_u64 {
+            let key = rng.next_u64() % 100_000;
+            match rng.gen_usize(0..100) {
+                // Ascending runs, the case the gap is for.
+                0..30 => {
+                    for i in 0..rng.gen_usize(1..40) as u64 {
+                        let k = key + 3 * i;
+                        assert_eq!(map.insert(k, step), oracle.insert(k, step));
+                    }
+                }
+                30..50 => assert_eq!(map.insert(key, step), oracle.insert(key, step)),
+                50..65 => {
+                    let key = oracle.keys().nth(rng.gen_usize(0..oracle.len().max(1)));
+                    let key = key.copied().unwrap_or(0);
+                    assert_eq!(map.remove(&key), oracle.remove(&key));
+                }
+                65..70 => assert_eq!(map.pop_first(), oracle.pop_first()),
+                70..75 => assert_eq!(map.pop_last(), oracle.pop_last()),
+                75..80 => {
+                    *map.entry(key).or_insert(0) += 1;
+                    *oracle.entry(key).or_insert(0) += 1;
+                }
+                80..90 => {
+                    let got = map.lower_bound_ref(&key).map(|(&k, &v)| (k, v));
+                    assert_eq!(got, oracle_lower_bound(&oracle, key));
+                    let got = map.floor(&key).map(|(&k, &v)| (k, v));
+                    assert_eq!(got, oracle_floor(&oracle, key));
+                    assert_eq!(map.get_ref(&key), oracle.get(&key));
+                }
+                90..95 => {
+                    let i = rng.gen_usize(0..oracle.len() + 1);
+                    let expect = oracle.iter().nth(i).map(|(&k, &v)| (k, v));
+                    assert_eq!(map.select(i).map(|(&k, &v)| (k, v)), expect);
+                    assert_eq!(map.rank(&key), oracle.range(..key).count());
+                }
+                _ => {
+                    let hi = key + 5_000;
+                    assert!(
+                        map.range(key..hi)
+                            .map(|(&k, &v)| (k, v))
+                            .eq(oracle.range(key..hi).map(|(&k, &v)| (k, v)))
+                    );
+                }
+            }
+            assert_eq!(map.len(), oracle.len(), "step={step}");
+            assert_eq!(map.first().map(|(&k, _)| k), oracle.keys().next().copied());
+            assert_eq!(
+                map.last().map(|(&k, _)| k),
+                oracle.keys().next_back().copied()
+            );
+        }
+        assert!(map.iter().map(|(&k, &v)| (k, v)).eq(oracle.clone()));
+
+        let high = map.split_off(&50_000);
+        let high_oracle = oracle.split_off(&50_000);
+        assert!(high.iter().map(|(&k, &v)| (k, v)).eq(high_oracle));
+        map.retain(|k, _| k % 2 == 0);
+        oracle.retain(|k, _| k % 2 == 0);
+        assert!(map.iter().map(|(&k, &v)| (k, v)).eq(oracle));
+    }
+
+    #[test]
+    fn sorted_vec_gap_buffer_pops_last_behind_the_gap() {
+        let mut map = SortedVecMap::with_gap_buffer();
+        let mut oracle = BTreeMap::new();
+        for key in (0..2_000_u64).step_by(2) {
+            map.insert(key, key);
+            oracle.insert(key, key);
+        }
+        // An ascending run in the middle leaves the gap there, with the
+        // upper half of the keys behind it.
+        for key in (1_001..1_101_u64).step_by(2) {
+            map.insert(key, key);
+            oracle.insert(key, key);
+        }
+        while !oracle.is_empty() {
+            assert_eq!(map.pop_last(), oracle.pop_last());
+            let key = oracle.keys().nth(oracle.len() / 2).copied();
+            if let Some(key) = key.filter(|key| key % 7 == 0) {
+                assert_eq!(map.remove(&key), oracle.remove(&key));
+            }
+        }
+        assert_eq!(map.pop_last(), None);
+        assert!(map.is_empty());
+    }
+
+    fn check_invariants_after_each_op<M, C>(seed: u64, check: C)
+    where
+        M: OrderedMap<Key = u64, Value = u64>,
+        C: Fn(&M),
+    {
+        let mut rng = XorShift64::new(seed);
+        let mut map = M::new();
+        let mut oracle = BTreeMap::new();
+        for step in 0..4_000 {
+            let key = rng.next_u64() % 2_000;
+            match rng.gen_usize(0..100) {
+                0..50 => assert_eq!(map.insert(key, step), oracle.insert(key, step)),
+                50..85 => assert_eq!(map.remove(&key), oracle.remove(&key)),
+                85..90 => assert_eq!(map.pop_first(), oracle.pop_first()),
+                90..95 => assert_eq!(map.pop_last(), oracle.pop_last()),
+                95..98 => {
+                    let mut high = map.split_off(&key);
+                    let high_oracle = oracle.split_off(&key);
+                    check(&map);
+                    check(&high);
+                    high.insert(key, step);
+                    map.append(high);
+                    oracle.extend(high_oracle);
+                    oracle.insert(key, step);
+                }
+                _ => {
+                    map.retain(|k, _| k % 7 != key % 7);
+                    oracle.retain(|k, _| k % 7 != key % 7);
+                }
+            }
+            check(&map);
+            assert_eq!(map.len(), oracle.len(), "step={step}");
+        }
+        assert!(map.iter().map(|(&k, &v)| (k, v)).eq(oracle.into_iter()));
+    }
+
+    #[test]
+    fn balance_invariants_hold_after_each_op() {
+        check_invariants_after_each_op::<AvlTreeMap<u64, u64>, _>(0xA71, |m| {
+            m.check_balanced();
+        });
+        check_invariants_after_each_op::<RbTreeMap<u64, u64>, _>(0x4B, |m| {
+            m.check_balanced();
+        });
+        check_invariants_after_each_op::<WbtTreeMap<u64, u64>, _>(0x3B7, |m| {
+            m.check_balanced();
+        });
+        check_invariants_after_each_op::<AaTreeMap<u64, u64>, _>(0xAA, |m| {
+            m.check_balanced();
+        });
+        check_invariants_after_each_op::<ScapegoatTreeMap<u64, u64>, _>(0x5CA9, |m| {
+            m.check_balanced();
+        });
+    }
+
+    #[test]
+    fn split_append_keep_llrb_balanced() {
+        let mut rng = XorShift64::new(0x11AB_5EED);
+        let mut map = LlrbTreeMap::new();
+        for _ in 0..3_000 {
+            let key = rng.next_u64() % 10_000;
+            map.insert(key, key);
+        }
+        for _ in 0..100 {
+            let cut = rng.next_u64() % 10_000;
+            let mut high = map.split_off(&cut);
+            map.check_balanced();
+            high.check_balanced();
+            let mut extra = LlrbTreeMap::new();
+            for _ in 0..rng.next_u64() % 50 {
+                let key = rng.next_u64() % 10_000;
+                extra.insert(key, key);
+            }
+            high.append(extra);
+            high.check_balanced();
+            map.append(high);
+            map.check_balanced();
+        }
+    }
+
+    #[test]
+    fn from_sorted_builds_balanced_trees() {
+        for n in 0..600_usize {
+            let entries: Vec<(usize, usize)> = (0..n).map(|i| (i, i)).collect();
+            // A perfectly balanced tree on `n` nodes has height ceil(log2(n + 1)).
+            let optimal = (usize::BITS - n.leading_zeros()) as i16;
+            let avl = AvlTreeMap::from_sorted(entries.clone());
+            assert_eq!(avl.check_balanced(), optimal, "n={n}");
+            let llrb = LlrbTreeMap::from_sorted(entries.clone());
+            assert_eq!(llrb.check_balanced(), (n + 1).ilog2() as usize, "n={n}");
+            let aa = AaTreeMap::from_sorted(entries);
+            assert_eq!(aa.check_balanced(), (n + 1).ilog2() as u8, "n={n}");
+        }
+    }
+
+    #[test]
+    fn retain_rebuilds_balanced_trees() {
+        let entries: Vec<(usize, usize)> = (0..1_000).map(|i| (i, i)).collect();
+        let mut avl = AvlTreeMap::from_sorted(entries.clone());
+        let mut llrb = LlrbTreeMap::from_sorted(entries.clone());
+        let mut aa = AaTreeMap::from_sorted(entries);
+        // Keep a lopsided tenth of the keys; the rebuild is perfectly balanced.
+        let keep = |k: &usize, _: &mut usize| *k < 50 || k.is_multiple_of(19);
+        avl.retain(keep);
+        llrb.retain(keep);
+        aa.retain(keep);
+        let n = avl.len();
+        assert_eq!(
+            avl.check_balanced(),
+            (usize::BITS - n.leading_zeros()) as i16
+        );
+        assert_eq!(llrb.check_balanced(), (n + 1).ilog2() as usize);
+        assert_eq!(aa.check_balanced(), (n + 1).ilog2() as u8);
+    }
 }
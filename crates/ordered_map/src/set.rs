@@ -0,0 +1,78 @@
+use crate::OrderedMap;
+
+/// Ordered set interface, the key-only counterpart of [`OrderedMap`].
+///
+/// - `insert` returns `true` if the key was not present.
+/// - `remove` returns `true` if the key was present.
+/// - `lower_bound` returns the smallest key `>= key`.
+/// - Queries take `&mut self` for the same reason as on [`OrderedMap`]: a
+///   splay-backed set restructures on access.
+pub trait OrderedSet {
+    type Key: Ord;
+
+    fn new() -> Self;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn insert(&mut self, key: Self::Key) -> bool;
+
+    fn contains(&mut self, key: &Self::Key) -> bool;
+
+    fn remove(&mut self, key: &Self::Key) -> bool;
+
+    fn lower_bound(&mut self, key: &Self::Key) -> Option<&Self::Key>;
+
+    fn iter(&self) -> impl Iterator<Item = &Self::Key>;
+}
+
+/// Turns any map with `()` values into an [`OrderedSet`].
+pub struct Set<M: OrderedMap<Value = ()>> {
+    map: M,
+}
+
+impl<M: OrderedMap<Value = ()>> Set<M> {
+    /// Wraps an existing map, keeping its keys.
+    pub fn from_map(map: M) -> Self {
+        Self { map }
+    }
+
+    pub fn into_map(self) -> M {
+        self.map
+    }
+}
+
+impl<M: OrderedMap<Value = ()>> OrderedSet for Set<M> {
+    type Key = M::Key;
+
+    fn new() -> Self {
+        Self { map: M::new() }
+    }
+
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn insert(&mut self, key: Self::Key) -> bool {
+        self.map.insert(key, ()).is_none()
+    }
+
+    fn contains(&mut self, key: &Self::Key) -> bool {
+        self.map.get(key).is_some()
+    }
+
+    fn remove(&mut self, key: &Self::Key) -> bool {
+        self.map.remove(key).is_some()
+    }
+
+    fn lower_bound(&mut self, key: &Self::Key) -> Option<&Self::Key> {
+        self.map.lower_bound(key).map(|(k, _)| k)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Self::Key> {
+        self.map.iter().map(|(k, _)| k)
+    }
+}
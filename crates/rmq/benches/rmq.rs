@@ -1,7 +1,7 @@
 use bench::apply_large_runtime_config;
 use bench::apply_medium_runtime_config;
 use bench::apply_small_runtime_config;
-use bench::default_rng;
+use bench::{bench_seed, rng_with_seed};
 use criterion::BenchmarkGroup;
 use criterion::BenchmarkId;
 use criterion::Criterion;
@@ -15,6 +15,7 @@ use rmq::SegmentTreeRmq;
 use rmq::SparseTableRmq;
 use rmq::StaticRmq;
 use std::hint::black_box;
+use std::ops::Range;
 
 const SIZES: [usize; 4] = [1_024, 4_096, 16_384, 65_536];
 const VALUE_RANGE: std::ops::RangeInclusive<i64> = -1_000_000_000..=1_000_000_000;
@@ -98,6 +99,55 @@ fn bench_impl<M, R>(
     });
 }
 
+fn bench_batch_impl<M, R>(
+    group: &mut BenchmarkGroup<'_, M>,
+    name: &str,
+    size: usize,
+    values: &[i64],
+    queries: &[Range<usize>],
+) where
+    M: Measurement,
+    R: StaticRmq,
+{
+    let rmq = R::new(values);
+    group.bench_function(BenchmarkId::new(format!("{name}/loop"), size), |bencher| {
+        bencher.iter(|| {
+            let mut acc = 0_usize;
+            for range in queries {
+                acc ^= rmq.argmin(black_box(range.clone())).unwrap();
+            }
+            black_box(acc);
+        })
+    });
+    let mut out = Vec::with_capacity(queries.len());
+    group.bench_function(BenchmarkId::new(format!("{name}/batch"), size), |bencher| {
+        bencher.iter(|| {
+            rmq.argmin_batch(black_box(queries), &mut out);
+            black_box(&out);
+        })
+    });
+}
+
+fn bench_batch(c: &mut Criterion) {
+    let mut rng = rng_with_seed(bench_seed());
+    let mut group = c.benchmark_group("rmq/batch/16n");
+
+    for &size in &SIZES {
+        apply_runtime_config_for_size(&mut group, size);
+        let values = generate_values(&mut rng, size);
+        let queries: Vec<Range<usize>> =
+            generate_queries(&mut rng, size, Workload::NTimes16.query_count(size))
+                .into_iter()
+                .map(|(l, r)| l..r)
+                .collect();
+
+        bench_batch_impl::<_, SegmentTreeRmq>(&mut group, "segtree", size, &values, &queries);
+        bench_batch_impl::<_, SparseTableRmq>(&mut group, "sparse", size, &values, &queries);
+    }
+
+    group.finish();
+}
+
 fn bench_rmq(c: &mut Criterion) {
     let workloads = [
         Workload::NDiv4,
@@ -105,7 +155,7 @@ fn bench_rmq(c: &mut Criterion) {
         Workload::NTimes4,
         Workload::NTimes16,
     ];
-    let mut rng = default_rng();
+    let mut rng = rng_with_seed(bench_seed());
 
     for workload in workloads {
         let mut group = c.benchmark_group(format!("rmq/workload/{}", workload.label()));
@@ -126,5 +176,5 @@ fn bench_rmq(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, bench_rmq);
+criterion_group!(benches, bench_rmq, bench_batch);
 criterion_main!(benches);
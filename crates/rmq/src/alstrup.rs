@@ -1,10 +1,14 @@
 use std::ops::Range;
+use std::sync::OnceLock;
 
+use crate::Mode;
 use crate::StaticRmq;
+use crate::TieBreak;
 use crate::util::better_index;
 use crate::util::better_index_ordered;
 use crate::util::floor_log2_nonzero;
 use crate::util::is_strictly_less;
+use crate::util::vec_bytes;
 
 #[derive(Clone, Debug)]
 struct IndexSparseTable {
@@ -15,7 +19,7 @@ struct IndexSparseTable {
 }
 
 impl IndexSparseTable {
-    fn new(values: &[i64], indices: Vec<usize>) -> Self {
+    fn new<T: Ord>(values: &[T], mode: Mode, tie: TieBreak, indices: Vec<usize>) -> Self {
         let n = indices.len();
 
         if n == 0 {
@@ -57,7 +61,7 @@ impl IndexSparseTable {
             for i in 0..len {
                 let a = unsafe { *prev_ptr.add(i) };
                 let b = unsafe { *prev_ptr.add(i + half) };
-                table.push(better_index_ordered(values, a, b));
+                table.push(better_index_ordered(values, mode, tie, a, b));
             }
         }
 
@@ -70,7 +74,14 @@ impl IndexSparseTable {
     }
 
     #[inline(always)]
-    fn argmin_assume_valid(&self, values: &[i64], start: usize, end: usize) -> usize {
+    fn argmin_assume_valid<T: Ord>(
+        &self,
+        values: &[T],
+        mode: Mode,
+        tie: TieBreak,
+        start: usize,
+        end: usize,
+    ) -> usize {
         debug_assert!(start < end);
         debug_assert!(end <= self.n);
 
@@ -80,62 +91,25 @@ impl IndexSparseTable {
         let base = self.row_offsets[k];
         let a = self.table[base + start];
         let b = self.table[base + end - span];
-        better_index(values, a, b)
+        better_index(values, mode, tie, a, b)
     }
 }
 
+/// In-block stack masks and the sparse table over block extrema, for one
+/// tie rule.
 #[derive(Clone, Debug)]
-pub struct AlstrupRmq {
-    values: Vec<i64>,
-    block_size: usize,
+struct AlstrupTables {
     l_masks: Vec<u64>,
     block_mins_st: IndexSparseTable,
 }
 
-impl AlstrupRmq {
-    fn choose_block_size(n: usize) -> usize {
-        if n <= 1 {
-            return 1;
-        }
-        let lg = floor_log2_nonzero(n) as usize;
-        let m = (lg / 2).max(1);
-        m.min(63)
-    }
-
-    #[inline(always)]
-    fn block_argmin(
-        block_start: usize,
-        block_len: usize,
-        l_masks: &[u64],
-        local_l: usize,
-        local_r: usize,
-    ) -> usize {
-        debug_assert!(local_l <= local_r);
-        debug_assert!(local_r < block_len);
-
-        let w = l_masks[block_start + local_r] >> local_l;
-        let pos = if w == 0 {
-            local_r
-        } else {
-            local_l + (w.trailing_zeros() as usize)
-        };
-
-        block_start + pos
-    }
-}
-
-impl StaticRmq for AlstrupRmq {
-    fn new(values: &[i64]) -> Self {
+impl AlstrupTables {
+    fn new<T: Ord>(values: &[T], mode: Mode, tie: TieBreak, block_size: usize) -> Self {
         let n = values.len();
-        let values = values.to_vec();
-        let block_size = Self::choose_block_size(n);
-
         if n == 0 {
             return Self {
-                values,
-                block_size,
                 l_masks: Vec::new(),
-                block_mins_st: IndexSparseTable::new(&[], Vec::new()),
+                block_mins_st: IndexSparseTable::new::<T>(&[], mode, tie, Vec::new()),
             };
         }
 
@@ -156,7 +130,7 @@ impl StaticRmq for AlstrupRmq {
                     let global_q = start + q;
                     while let Some(&top) = stack.last() {
                         let global_top = start + top;
-                        if is_strictly_less(&values, global_top, global_q) {
+                        if is_strictly_less(values, mode, tie, global_top, global_q) {
                             break;
                         }
                         stack.pop();
@@ -175,29 +149,93 @@ impl StaticRmq for AlstrupRmq {
                 stack.push(q);
             }
 
-            let slice = &values[start..end];
-            let mut best_local = 0_usize;
-            let mut best_val = slice[0];
-            for (i, &v) in slice.iter().enumerate().skip(1) {
-                if v < best_val {
-                    best_local = i;
-                    best_val = v;
-                }
-            }
-            block_mins.push(start + best_local);
+            let best = (start + 1..end).fold(start, |best, i| {
+                better_index_ordered(values, mode, tie, best, i)
+            });
+            block_mins.push(best);
         }
 
-        let block_mins_st = IndexSparseTable::new(&values, block_mins);
+        let block_mins_st = IndexSparseTable::new(values, mode, tie, block_mins);
 
         Self {
-            values,
-            block_size,
             l_masks,
             block_mins_st,
         }
     }
 
-    fn argmin(&self, range: Range<usize>) -> Option<usize> {
+    fn bytes(&self) -> usize {
+        let st = &self.block_mins_st;
+        vec_bytes(&self.l_masks)
+            + vec_bytes(&st.log2)
+            + vec_bytes(&st.row_offsets)
+            + vec_bytes(&st.table)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AlstrupRmq<T = i64> {
+    values: Vec<T>,
+    mode: Mode,
+    block_size: usize,
+    tables: AlstrupTables,
+    last_tables: OnceLock<AlstrupTables>,
+}
+
+impl<T> AlstrupRmq<T> {
+    fn choose_block_size(n: usize) -> usize {
+        if n <= 1 {
+            return 1;
+        }
+        let lg = floor_log2_nonzero(n) as usize;
+        let m = (lg / 2).max(1);
+        m.min(63)
+    }
+
+    #[inline(always)]
+    fn block_argmin(
+        block_start: usize,
+        block_len: usize,
+        l_masks: &[u64],
+        local_l: usize,
+        local_r: usize,
+    ) -> usize {
+        debug_assert!(local_l <= local_r);
+        debug_assert!(local_r < block_len);
+
+        let w = l_masks[block_start + local_r] >> local_l;
+        let pos = if w == 0 {
+            local_r
+        } else {
+            local_l + (w.trailing_zeros() as usize)
+        };
+
+        block_start + pos
+    }
+}
+
+impl<T: Ord + Copy> StaticRmq<T> for AlstrupRmq<T> {
+    fn with_mode(values: &[T], mode: Mode) -> Self {
+        let values = values.to_vec();
+        let block_size = Self::choose_block_size(values.len());
+        let tables = AlstrupTables::new(&values, mode, TieBreak::First, block_size);
+        Self {
+            values,
+            mode,
+            block_size,
+            tables,
+            last_tables: OnceLock::new(),
+        }
+    }
+
+    fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn arg_extremum(&self, range: Range<usize>) -> Option<usize> {
+        self.arg_extremum_with(range, TieBreak::First)
+    }
+
+    fn arg_extremum_with(&self, range: Range<usize>, tie: TieBreak) -> Option<usize> {
         let n = self.values.len();
         if range.start >= range.end || range.end > n {
             return None;
@@ -206,6 +244,12 @@ impl StaticRmq for AlstrupRmq {
             return None;
         }
 
+        let tables = match tie {
+            TieBreak::First => &self.tables,
+            TieBreak::Last => self
+                .last_tables
+                .get_or_init(|| AlstrupTables::new(&self.values, self.mode, tie, self.block_size)),
+        };
         let l = range.start;
         let r = range.end - 1;
         let bl = l / self.block_size;
@@ -220,7 +264,7 @@ impl StaticRmq for AlstrupRmq {
             return Some(Self::block_argmin(
                 block_start,
                 block_len,
-                &self.l_masks,
+                &tables.l_masks,
                 local_l,
                 local_r,
             ));
@@ -233,7 +277,7 @@ impl StaticRmq for AlstrupRmq {
         let left = Self::block_argmin(
             left_start,
             left_len,
-            &self.l_masks,
+            &tables.l_masks,
             left_local_l,
             left_len - 1,
         );
@@ -242,17 +286,23 @@ impl StaticRmq for AlstrupRmq {
         let right_end = ((br + 1) * self.block_size).min(n);
         let right_len = right_end - right_start;
         let right_local_r = r - right_start;
-        let right = Self::block_argmin(right_start, right_len, &self.l_masks, 0, right_local_r);
+        let right = Self::block_argmin(right_start, right_len, &tables.l_masks, 0, right_local_r);
 
-        let mut ans = better_index_ordered(&self.values, left, right);
+        let mut ans = better_index_ordered(&self.values, self.mode, tie, left, right);
 
         if bl + 1 < br {
-            let mid = self
-                .block_mins_st
-                .argmin_assume_valid(&self.values, bl + 1, br);
-            ans = better_index(&self.values, ans, mid);
+            let mid =
+                tables
+                    .block_mins_st
+                    .argmin_assume_valid(&self.values, self.mode, tie, bl + 1, br);
+            ans = better_index(&self.values, self.mode, tie, ans, mid);
         }
 
         Some(ans)
     }
+
+    fn memory_bytes(&self) -> usize {
+        let last = self.last_tables.get().map_or(0, AlstrupTables::bytes);
+        vec_bytes(&self.values) + self.tables.bytes() + last
+    }
 }
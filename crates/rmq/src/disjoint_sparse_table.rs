@@ -1,9 +1,13 @@
 use std::mem::{ManuallyDrop, MaybeUninit};
 use std::ops::Range;
+use std::sync::OnceLock;
 
+use crate::Mode;
 use crate::StaticRmq;
+use crate::TieBreak;
 use crate::util::better_index_ordered;
 use crate::util::floor_log2_nonzero;
+use crate::util::vec_bytes;
 
 #[derive(Clone, Debug)]
 enum DstTable {
@@ -12,47 +16,12 @@ enum DstTable {
     Usize(Vec<usize>),
 }
 
-#[derive(Clone, Debug)]
-pub struct DisjointSparseTableRmq {
-    values: Vec<i64>,
-    n: usize,
-    levels: usize,
-    table: DstTable,
-}
-
-impl DisjointSparseTableRmq {
-    pub fn len(&self) -> usize {
-        self.n
-    }
-
-    pub fn is_empty(&self) -> bool {
-        self.n == 0
-    }
-}
-
-impl StaticRmq for DisjointSparseTableRmq {
-    fn new(values: &[i64]) -> Self {
+impl DstTable {
+    /// Level `k` holds, for every index, the extremum between it and the
+    /// middle of its block of length `2^(k+1)`, with ties resolved by `tie`.
+    fn build<T: Ord>(values: &[T], mode: Mode, tie: TieBreak, levels: usize) -> Self {
         let n = values.len();
-        let values = values.to_vec();
-        if n == 0 {
-            return Self {
-                values,
-                n,
-                levels: 0,
-                table: DstTable::U32(Vec::new()),
-            };
-        }
-        if n == 1 {
-            return Self {
-                values,
-                n,
-                levels: 0,
-                table: DstTable::U32(Vec::new()),
-            };
-        }
-
-        let levels = (floor_log2_nonzero(n - 1) as usize) + 1;
-        let table = if n <= (u16::MAX as usize) + 1 {
+        if n <= (u16::MAX as usize) + 1 {
             let mut table = Vec::<MaybeUninit<u16>>::with_capacity(levels * n);
             unsafe {
                 table.set_len(levels * n);
@@ -71,7 +40,7 @@ impl StaticRmq for DisjointSparseTableRmq {
                         level_table[mid - 1].write((mid - 1) as u16);
                         for i in (block_start..(mid - 1)).rev() {
                             let right = unsafe { level_table[i + 1].assume_init() } as usize;
-                            let best = better_index_ordered(&values, i, right);
+                            let best = better_index_ordered(values, mode, tie, i, right);
                             level_table[i].write(best as u16);
                         }
                     }
@@ -79,7 +48,7 @@ impl StaticRmq for DisjointSparseTableRmq {
                         level_table[mid].write(mid as u16);
                         for i in (mid + 1)..block_end {
                             let left = unsafe { level_table[i - 1].assume_init() } as usize;
-                            let best = better_index_ordered(&values, left, i);
+                            let best = better_index_ordered(values, mode, tie, left, i);
                             level_table[i].write(best as u16);
                         }
                     }
@@ -90,7 +59,7 @@ impl StaticRmq for DisjointSparseTableRmq {
             let len = table.len();
             let cap = table.capacity();
             let table = unsafe { Vec::from_raw_parts(ptr, len, cap) };
-            DstTable::U16(table)
+            Self::U16(table)
         } else if n <= (u32::MAX as usize) {
             let mut table = Vec::<MaybeUninit<u32>>::with_capacity(levels * n);
             unsafe {
@@ -110,7 +79,7 @@ impl StaticRmq for DisjointSparseTableRmq {
                         level_table[mid - 1].write((mid - 1) as u32);
                         for i in (block_start..(mid - 1)).rev() {
                             let right = unsafe { level_table[i + 1].assume_init() } as usize;
-                            let best = better_index_ordered(&values, i, right);
+                            let best = better_index_ordered(values, mode, tie, i, right);
                             level_table[i].write(best as u32);
                         }
                     }
@@ -118,7 +87,7 @@ impl StaticRmq for DisjointSparseTableRmq {
                         level_table[mid].write(mid as u32);
                         for i in (mid + 1)..block_end {
                             let left = unsafe { level_table[i - 1].assume_init() } as usize;
-                            let best = better_index_ordered(&values, left, i);
+                            let best = better_index_ordered(values, mode, tie, left, i);
                             level_table[i].write(best as u32);
                         }
                     }
@@ -129,7 +98,7 @@ impl StaticRmq for DisjointSparseTableRmq {
             let len = table.len();
             let cap = table.capacity();
             let table = unsafe { Vec::from_raw_parts(ptr, len, cap) };
-            DstTable::U32(table)
+            Self::U32(table)
         } else {
             let mut table = Vec::<MaybeUninit<usize>>::with_capacity(levels * n);
             unsafe {
@@ -149,14 +118,14 @@ impl StaticRmq for DisjointSparseTableRmq {
                         level_table[mid - 1].write(mid - 1);
                         for i in (block_start..(mid - 1)).rev() {
                             let right = unsafe { level_table[i + 1].assume_init() };
-                            level_table[i].write(better_index_ordered(&values, i, right));
+                            level_table[i].write(better_index_ordered(values, mode, tie, i, right));
                         }
                     }
                     if mid < block_end {
                         level_table[mid].write(mid);
                         for i in (mid + 1)..block_end {
                             let left = unsafe { level_table[i - 1].assume_init() };
-                            level_table[i].write(better_index_ordered(&values, left, i));
+                            level_table[i].write(better_index_ordered(values, mode, tie, left, i));
                         }
                     }
                 }
@@ -166,18 +135,97 @@ impl StaticRmq for DisjointSparseTableRmq {
             let len = table.len();
             let cap = table.capacity();
             let table = unsafe { Vec::from_raw_parts(ptr, len, cap) };
-            DstTable::Usize(table)
+            Self::Usize(table)
+        }
+    }
+
+    /// The better of the entries at `a` and `b`, for `a < b`.
+    #[inline(always)]
+    fn pick<T: Ord>(&self, values: &[T], mode: Mode, tie: TieBreak, a: usize, b: usize) -> usize {
+        let (a, b) = match self {
+            Self::U16(table) => (table[a] as usize, table[b] as usize),
+            Self::U32(table) => (table[a] as usize, table[b] as usize),
+            Self::Usize(table) => (table[a], table[b]),
         };
+        better_index_ordered(values, mode, tie, a, b)
+    }
+
+    fn bytes(&self) -> usize {
+        match self {
+            Self::U16(table) => vec_bytes(table),
+            Self::U32(table) => vec_bytes(table),
+            Self::Usize(table) => vec_bytes(table),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DisjointSparseTableRmq<T = i64> {
+    values: Vec<T>,
+    mode: Mode,
+    n: usize,
+    levels: usize,
+    table: DstTable,
+    last_table: OnceLock<DstTable>,
+}
+
+impl<T> DisjointSparseTableRmq<T> {
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+}
+
+impl<T: Ord + Copy> StaticRmq<T> for DisjointSparseTableRmq<T> {
+    fn with_mode(values: &[T], mode: Mode) -> Self {
+        let n = values.len();
+        let values = values.to_vec();
+        if n == 0 {
+            return Self {
+                values,
+                mode,
+                n,
+                levels: 0,
+                table: DstTable::U32(Vec::new()),
+                last_table: OnceLock::new(),
+            };
+        }
+        if n == 1 {
+            return Self {
+                values,
+                mode,
+                n,
+                levels: 0,
+                table: DstTable::U32(Vec::new()),
+                last_table: OnceLock::new(),
+            };
+        }
+
+        let levels = (floor_log2_nonzero(n - 1) as usize) + 1;
+        let table = DstTable::build(&values, mode, TieBreak::First, levels);
 
         Self {
             values,
+            mode,
             n,
             levels,
             table,
+            last_table: OnceLock::new(),
         }
     }
 
-    fn argmin(&self, range: Range<usize>) -> Option<usize> {
+    fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn arg_extremum(&self, range: Range<usize>) -> Option<usize> {
+        self.arg_extremum_with(range, TieBreak::First)
+    }
+
+    fn arg_extremum_with(&self, range: Range<usize>, tie: TieBreak) -> Option<usize> {
         let n = self.n;
         if range.start >= range.end || range.end > n {
             return None;
@@ -189,26 +237,21 @@ impl StaticRmq for DisjointSparseTableRmq {
             return Some(l);
         }
 
+        let table = match tie {
+            TieBreak::First => &self.table,
+            TieBreak::Last => self
+                .last_table
+                .get_or_init(|| DstTable::build(&self.values, self.mode, tie, self.levels)),
+        };
         let x = l ^ r;
         let level = (usize::BITS - 1 - x.leading_zeros()) as usize;
         debug_assert!(level < self.levels);
         let base = level * n;
-        match &self.table {
-            DstTable::U16(table) => {
-                let a = table[base + l] as usize;
-                let b = table[base + r] as usize;
-                Some(better_index_ordered(&self.values, a, b))
-            }
-            DstTable::U32(table) => {
-                let a = table[base + l] as usize;
-                let b = table[base + r] as usize;
-                Some(better_index_ordered(&self.values, a, b))
-            }
-            DstTable::Usize(table) => {
-                let a = table[base + l];
-                let b = table[base + r];
-                Some(better_index_ordered(&self.values, a, b))
-            }
-        }
+        Some(table.pick(&self.values, self.mode, tie, base + l, base + r))
+    }
+
+    fn memory_bytes(&self) -> usize {
+        let last = self.last_table.get().map_or(0, DstTable::bytes);
+        vec_bytes(&self.values) + self.table.bytes() + last
     }
 }
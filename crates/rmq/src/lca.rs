@@ -0,0 +1,98 @@
+use crate::AlstrupRmq;
+use crate::StaticRmq;
+
+/// Lowest common ancestors on a rooted tree by RMQ over its Euler tour.
+///
+/// The tour lists a vertex every time the DFS enters or returns to it, so it has
+/// `2n - 1` entries and adjacent depths differ by exactly one. The LCA of `u` and
+/// `v` is the shallowest vertex between their first occurrences, which the
+/// [`AlstrupRmq`] over the depths finds in O(1) after O(n) preprocessing.
+#[derive(Clone, Debug)]
+pub struct LcaRmq {
+    tour: Vec<usize>,
+    first: Vec<usize>,
+    rmq: AlstrupRmq<u32>,
+}
+
+impl LcaRmq {
+    /// Builds from `n` vertices, the `n - 1` undirected `edges` of a tree and its `root`.
+    ///
+    /// Panics if `root >= n` or the edges do not connect every vertex to `root`.
+    pub fn new(n: usize, edges: &[(usize, usize)], root: usize) -> Self {
+        assert!(root < n, "root {root} out of range for {n} vertices");
+
+        let mut degree = vec![0_usize; n + 1];
+        for &(u, v) in edges {
+            degree[u] += 1;
+            degree[v] += 1;
+        }
+        let mut start = vec![0_usize; n + 1];
+        for i in 0..n {
+            start[i + 1] = start[i] + degree[i];
+        }
+        let mut fill = start.clone();
+        let mut adj = vec![0_usize; 2 * edges.len()];
+        for &(u, v) in edges {
+            adj[fill[u]] = v;
+            fill[u] += 1;
+            adj[fill[v]] = u;
+            fill[v] += 1;
+        }
+
+        const UNSEEN: usize = usize::MAX;
+        let mut first = vec![UNSEEN; n];
+        let mut tour = Vec::with_capacity(2 * n - 1);
+        let mut depths = Vec::with_capacity(2 * n - 1);
+        // Each frame is (vertex, parent, next adjacency slot to look at).
+        let mut stack = vec![(root, UNSEEN, start[root])];
+        first[root] = 0;
+        tour.push(root);
+        depths.push(0_u32);
+        while let Some(&mut (u, parent, ref mut next)) = stack.last_mut() {
+            if *next == start[u + 1] {
+                stack.pop();
+                if let Some(&(p, _, _)) = stack.last() {
+                    tour.push(p);
+                    depths.push(stack.len() as u32 - 1);
+                }
+                continue;
+            }
+            let v = adj[*next];
+            *next += 1;
+            if v == parent {
+                continue;
+            }
+            assert!(first[v] == UNSEEN, "edges contain a cycle through {v}");
+            first[v] = tour.len();
+            tour.push(v);
+            depths.push(stack.len() as u32);
+            stack.push((v, u, start[v]));
+        }
+        assert!(
+            first.iter().all(|&f| f != UNSEEN),
+            "edges do not connect every vertex to the root"
+        );
+
+        Self {
+            tour,
+            first,
+            rmq: AlstrupRmq::new(&depths),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.first.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.first.is_empty()
+    }
+
+    /// Lowest common ancestor of `u` and `v`. Panics if either is out of range.
+    pub fn lca(&self, u: usize, v: usize) -> usize {
+        let (a, b) = (self.first[u], self.first[v]);
+        let (l, r) = if a <= b { (a, b) } else { (b, a) };
+        let i = self.rmq.argmin(l..r + 1).expect("range is non-empty");
+        self.tour[i]
+    }
+}
@@ -1,31 +1,164 @@
 mod alstrup;
 mod disjoint_sparse_table;
+mod lca;
+mod point_update;
 mod segment_tree;
+mod sliding_window;
 mod sparse_table;
+mod sparse_table_2d;
 mod util;
 
+use std::cmp::Ordering;
 use std::ops::Range;
 
 pub use alstrup::AlstrupRmq;
 pub use disjoint_sparse_table::DisjointSparseTableRmq;
+pub use lca::LcaRmq;
+pub use point_update::PointUpdateRmq;
 pub use segment_tree::SegmentTreeRmq;
+pub use sliding_window::sliding_window_argmin;
 pub use sparse_table::SparseTableRmq;
+pub use sparse_table_2d::SparseTable2D;
+
+/// Which extremum a structure answers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Min,
+    Max,
+}
+
+impl Mode {
+    /// Compares so that the preferred value of this mode orders first.
+    #[inline(always)]
+    pub(crate) fn cmp<T: Ord>(self, a: &T, b: &T) -> Ordering {
+        match self {
+            Self::Min => a.cmp(b),
+            Self::Max => b.cmp(a),
+        }
+    }
+}
+
+/// Which index to report when several elements share the extremum.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// The smallest index, as `argmin`/`argmax` do.
+    #[default]
+    First,
+    /// The largest index.
+    Last,
+}
+
+impl TieBreak {
+    /// Compares indices so that the preferred one orders first.
+    #[inline(always)]
+    pub(crate) fn cmp(self, a: usize, b: usize) -> Ordering {
+        match self {
+            Self::First => a.cmp(&b),
+            Self::Last => b.cmp(&a),
+        }
+    }
+}
 
 /// Static RMQ (Range Minimum Query) interface.
 ///
+/// - Elements are any `T: Ord + Copy`; `T` defaults to `i64` on the trait and
+///   every structure, so `SparseTableRmq` alone still means `SparseTableRmq<i64>`.
 /// - Query ranges are half-open: `[l, r)`.
-/// - The answer is `Some(argmin_index)` when the range is valid and non-empty.
-/// - Ties are broken by the smallest index.
-pub trait StaticRmq: Sized {
-    fn new(values: &[i64]) -> Self;
+/// - A structure answers one [`Mode`], fixed at construction; `new` builds for
+///   minima, `with_mode(values, Mode::Max)` runs the same build with the order inverted.
+/// - The answer is `Some(index)` of the minimum (`argmin`) or maximum (`argmax`)
+///   when the range is valid and non-empty.
+/// - Ties are broken by the smallest index; `argmin_with`/`argmax_with` take a
+///   [`TieBreak`] to ask for the largest instead. `SegmentTreeRmq` answers that
+///   with one more `O(log n)` walk. The `O(1)` structures build a second set of
+///   tables ordered by largest index on the first such query, which costs as
+///   much as construction and shows up in `memory_bytes` from then on.
+/// - `memory_bytes` reports the heap footprint, for space/time comparisons.
+/// - `argmin_batch` answers many queries into a reused buffer, in input order;
+///   structures may reorder the work internally for locality.
+pub trait StaticRmq<T: Ord + Copy = i64>: Sized {
+    fn new(values: &[T]) -> Self {
+        Self::with_mode(values, Mode::Min)
+    }
+
+    fn with_mode(values: &[T], mode: Mode) -> Self;
+
+    fn mode(&self) -> Mode;
+
+    /// Index of the extremum of [`Self::mode`] in `range`.
+    fn arg_extremum(&self, range: Range<usize>) -> Option<usize>;
+
+    /// Like [`Self::arg_extremum`], resolving ties per `tie`.
+    fn arg_extremum_with(&self, range: Range<usize>, tie: TieBreak) -> Option<usize>;
+
+    /// Heap bytes owned by the structure: the copied values plus every table,
+    /// including spare `Vec` capacity.
+    fn memory_bytes(&self) -> usize;
+
+    /// Panics if the structure was built for maxima.
+    fn argmin(&self, range: Range<usize>) -> Option<usize> {
+        self.argmin_with(range, TieBreak::First)
+    }
+
+    /// Panics if the structure was built for maxima.
+    fn argmin_with(&self, range: Range<usize>, tie: TieBreak) -> Option<usize> {
+        assert_eq!(
+            self.mode(),
+            Mode::Min,
+            "argmin on a structure built for maxima"
+        );
+        self.arg_extremum_with(range, tie)
+    }
+
+    /// Panics if the structure was built for minima.
+    fn argmax(&self, range: Range<usize>) -> Option<usize> {
+        self.argmax_with(range, TieBreak::First)
+    }
+
+    /// Panics if the structure was built for minima.
+    fn argmax_with(&self, range: Range<usize>, tie: TieBreak) -> Option<usize> {
+        assert_eq!(
+            self.mode(),
+            Mode::Max,
+            "argmax on a structure built for minima"
+        );
+        self.arg_extremum_with(range, tie)
+    }
+
+    /// Clears `out` and fills it with `argmin` of each query, in order.
+    /// Panics if the structure was built for maxima.
+    fn argmin_batch(&self, queries: &[Range<usize>], out: &mut Vec<Option<usize>>) {
+        assert_eq!(
+            self.mode(),
+            Mode::Min,
+            "argmin on a structure built for maxima"
+        );
+        out.clear();
+        out.extend(queries.iter().map(|range| self.arg_extremum(range.clone())));
+    }
+}
+
+/// RMQ over values that change between queries.
+///
+/// - `set(i, value)` replaces one element; `get(i)` reads it back. Both panic
+///   when `i` is out of range.
+/// - `argmin` follows the [`StaticRmq`] conventions: half-open ranges, `None` for
+///   empty or out-of-range queries, ties broken by the smallest index.
+pub trait DynamicRmq<T: Ord + Copy = i64>: Sized {
+    fn new(values: &[T]) -> Self;
+    fn get(&self, i: usize) -> T;
+    fn set(&mut self, i: usize, value: T);
     fn argmin(&self, range: Range<usize>) -> Option<usize>;
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{AlstrupRmq, DisjointSparseTableRmq, SegmentTreeRmq, SparseTableRmq, StaticRmq};
+    use super::{
+        AlstrupRmq, DisjointSparseTableRmq, DynamicRmq, LcaRmq, Mode, PointUpdateRmq,
+        SegmentTreeRmq, SparseTable2D, SparseTableRmq, StaticRmq, TieBreak, sliding_window_argmin,
+    };
 
-    fn brute_force_argmin(values: &[i64], l: usize, r: usize) -> usize {
+    fn brute_force_argmin<T: Ord + Copy>(values: &[T], l: usize, r: usize) -> usize {
         debug_assert!(l < r);
         let mut best = l;
         for i in (l + 1)..r {
@@ -38,6 +171,17 @@ mod tests {
         best
     }
 
+    fn brute_force_argmax<T: Ord>(values: &[T], l: usize, r: usize) -> usize {
+        debug_assert!(l < r);
+        let mut best = l;
+        for i in (l + 1)..r {
+            if values[i] > values[best] {
+                best = i;
+            }
+        }
+        best
+    }
+
     #[derive(Clone)]
     struct XorShift64 {
         state: u64,
@@ -127,6 +271,7 @@ mod tests {
             &[3, 2, 1, 0],
             &[0, 1, 2, 3],
             &[7, 7, 7, 7],
+            &[i64::MIN, i64::MAX, 0, i64::MIN, i64::MAX],
         ];
 
         for &values in cases {
@@ -134,6 +279,10 @@ mod tests {
             let st = SparseTableRmq::new(values);
             let dst = DisjointSparseTableRmq::new(values);
             let al = AlstrupRmq::new(values);
+            let seg_max = SegmentTreeRmq::with_mode(values, Mode::Max);
+            let st_max = SparseTableRmq::with_mode(values, Mode::Max);
+            let dst_max = DisjointSparseTableRmq::with_mode(values, Mode::Max);
+            let al_max = AlstrupRmq::with_mode(values, Mode::Max);
 
             let n = values.len();
             for l in 0..n {
@@ -143,6 +292,12 @@ mod tests {
                     assert_eq!(st.argmin(l..r), Some(expected), "st l={l} r={r}");
                     assert_eq!(dst.argmin(l..r), Some(expected), "dst l={l} r={r}");
                     assert_eq!(al.argmin(l..r), Some(expected), "al l={l} r={r}");
+
+                    let expected = brute_force_argmax(values, l, r);
+                    assert_eq!(seg_max.argmax(l..r), Some(expected), "seg l={l} r={r}");
+                    assert_eq!(st_max.argmax(l..r), Some(expected), "st l={l} r={r}");
+                    assert_eq!(dst_max.argmax(l..r), Some(expected), "dst l={l} r={r}");
+                    assert_eq!(al_max.argmax(l..r), Some(expected), "al l={l} r={r}");
                 }
             }
         }
@@ -179,4 +334,384 @@ mod tests {
             }
         }
     }
+
+    fn check_tie_breaks<R: StaticRmq>(values: &[i64]) {
+        let min = R::new(values);
+        let max = R::with_mode(values, Mode::Max);
+        let n = values.len();
+        for l in 0..n {
+            for r in (l + 1)..=n {
+                let lo = *values[l..r].iter().min().unwrap();
+                let hi = *values[l..r].iter().max().unwrap();
+                let first_min = (l..r).find(|&i| values[i] == lo);
+                let last_min = (l..r).rfind(|&i| values[i] == lo);
+                let first_max = (l..r).find(|&i| values[i] == hi);
+                let last_max = (l..r).rfind(|&i| values[i] == hi);
+                assert_eq!(min.argmin_with(l..r, TieBreak::First), first_min);
+                assert_eq!(
+                    min.argmin_with(l..r, TieBreak::Last),
+                    last_min,
+                    "l={l} r={r}"
+                );
+                assert_eq!(max.argmax_with(l..r, TieBreak::First), first_max);
+                assert_eq!(
+                    max.argmax_with(l..r, TieBreak::Last),
+                    last_max,
+                    "l={l} r={r}"
+                );
+            }
+        }
+        assert_eq!(min.argmin_with(0..0, TieBreak::Last), None);
+        assert_eq!(min.argmin_with(0..n + 1, TieBreak::Last), None);
+    }
+
+    #[test]
+    fn tie_breaks_match_bruteforce() {
+        let mut rng = XorShift64::new(0x71E0_B4EA_0000_1378);
+        for n in [0, 1, 2, 3, 5, 16, 33, 70, 130] {
+            for spread in [0, 1, 2] {
+                let values: Vec<i64> = (0..n).map(|_| rng.gen_i64(0..=spread)).collect();
+                check_tie_breaks::<SegmentTreeRmq>(&values);
+                check_tie_breaks::<SparseTableRmq>(&values);
+                check_tie_breaks::<DisjointSparseTableRmq>(&values);
+                check_tie_breaks::<AlstrupRmq>(&values);
+            }
+        }
+    }
+
+    #[test]
+    fn last_tie_tables_are_built_on_first_use() {
+        fn grown<R: StaticRmq>(values: &[i64]) -> usize {
+            let rmq = R::new(values);
+            let before = rmq.memory_bytes();
+            rmq.argmin_with(0..values.len(), TieBreak::First);
+            assert_eq!(rmq.memory_bytes(), before);
+            rmq.argmin_with(0..values.len(), TieBreak::Last);
+            rmq.memory_bytes() - before
+        }
+
+        let values: Vec<i64> = (0..1_000).map(|i| i % 3).collect();
+        assert_eq!(grown::<SegmentTreeRmq>(&values), 0);
+        assert!(grown::<SparseTableRmq>(&values) > 0);
+        assert!(grown::<DisjointSparseTableRmq>(&values) > 0);
+        assert!(grown::<AlstrupRmq>(&values) > 0);
+    }
+
+    #[test]
+    fn memory_bytes_track_table_sizes() {
+        const WORD: usize = std::mem::size_of::<usize>();
+        for n in [4_096, 5_000] {
+            let values: Vec<i64> = (0..n as i64).map(|i| (i * 7919) % 1009).collect();
+            let value_bytes = n * std::mem::size_of::<i64>();
+            let lg = n.ilog2() as usize;
+
+            // Segment tree: `2 * next_power_of_two(n)` node indices.
+            let seg = SegmentTreeRmq::new(&values).memory_bytes() - value_bytes;
+            assert_eq!(seg, 2 * n.next_power_of_two() * WORD, "n={n}");
+            assert!((2 * n * WORD..4 * n * WORD).contains(&seg), "n={n}");
+
+            // Sparse table: `lg + 1` rows of at most `n` `u32` entries, each
+            // row shorter by its span, plus one offset per row.
+            let st = SparseTableRmq::new(&values).memory_bytes() - value_bytes;
+            let entries = (st - (lg + 1) * WORD) / std::mem::size_of::<u32>();
+            assert!((lg * n - n..=(lg + 1) * n).contains(&entries), "n={n}");
+
+            let dst = DisjointSparseTableRmq::new(&values).memory_bytes() - value_bytes;
+            assert!(dst > n, "n={n}");
+            let al = AlstrupRmq::new(&values).memory_bytes() - value_bytes;
+            assert!(al < st, "n={n} al={al} st={st}");
+        }
+        assert_eq!(SparseTableRmq::<i64>::new(&[]).memory_bytes(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "argmin on a structure built for maxima")]
+    fn argmin_rejects_max_mode() {
+        SparseTableRmq::with_mode(&[1, 2], Mode::Max).argmin(0..2);
+    }
+
+    fn check_all_ranges<T: Ord + Copy + std::fmt::Debug>(values: &[T]) {
+        let seg = SegmentTreeRmq::new(values);
+        let st = SparseTableRmq::new(values);
+        let dst = DisjointSparseTableRmq::new(values);
+        let al = AlstrupRmq::new(values);
+        let seg_max = SegmentTreeRmq::with_mode(values, Mode::Max);
+        let al_max = AlstrupRmq::with_mode(values, Mode::Max);
+
+        let n = values.len();
+        for l in 0..n {
+            for r in (l + 1)..=n {
+                let expected = Some(brute_force_argmin(values, l, r));
+                assert_eq!(seg.argmin(l..r), expected, "seg l={l} r={r}");
+                assert_eq!(st.argmin(l..r), expected, "st l={l} r={r}");
+                assert_eq!(dst.argmin(l..r), expected, "dst l={l} r={r}");
+                assert_eq!(al.argmin(l..r), expected, "al l={l} r={r}");
+
+                let expected = Some(brute_force_argmax(values, l, r));
+                assert_eq!(seg_max.argmax(l..r), expected, "seg l={l} r={r}");
+                assert_eq!(al_max.argmax(l..r), expected, "al l={l} r={r}");
+            }
+        }
+    }
+
+    #[test]
+    fn u32_timestamps() {
+        let mut rng = XorShift64::new(0x7157_A3C5_0000_0001);
+        let values: Vec<u32> = (0..200)
+            .map(|_| rng.gen_usize(0..16) as u32 * (u32::MAX / 16))
+            .collect();
+        check_all_ranges(&values);
+    }
+
+    /// Orders by `priority` only, so distinct payloads compare equal and the
+    /// answer must fall back to the smallest index.
+    #[derive(Clone, Copy, Debug)]
+    struct Job {
+        priority: i64,
+        id: usize,
+    }
+
+    impl PartialEq for Job {
+        fn eq(&self, other: &Self) -> bool {
+            self.priority == other.priority
+        }
+    }
+
+    impl Eq for Job {}
+
+    impl PartialOrd for Job {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Job {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.priority.cmp(&other.priority)
+        }
+    }
+
+    #[test]
+    fn custom_ord_keeps_smallest_index_ties() {
+        let mut rng = XorShift64::new(0x0B5E_55ED_7AB1_E5E5);
+        let jobs: Vec<Job> = (0..150)
+            .map(|id| Job {
+                priority: rng.gen_i64(-3..=3),
+                id,
+            })
+            .collect();
+        check_all_ranges(&jobs);
+
+        let st = SparseTableRmq::new(&jobs);
+        let ans = st.argmin(0..jobs.len()).unwrap();
+        assert_eq!(jobs[ans].id, ans);
+        assert!(jobs[..ans].iter().all(|j| j.priority > jobs[ans].priority));
+
+        let pairs: Vec<(i64, usize)> = jobs.iter().map(|j| (j.priority, 149 - j.id)).collect();
+        check_all_ranges(&pairs);
+    }
+
+    /// Binary-lifting LCA oracle over a parent array (`parent[root] == root`).
+    fn binary_lifting_lca(parent: &[usize], depth: &[usize], mut u: usize, mut v: usize) -> usize {
+        let n = parent.len();
+        let mut up = vec![parent.to_vec()];
+        while (1 << up.len()) < n {
+            let prev = up.last().unwrap();
+            let next = (0..n).map(|i| prev[prev[i]]).collect();
+            up.push(next);
+        }
+        if depth[u] < depth[v] {
+            std::mem::swap(&mut u, &mut v);
+        }
+        for (k, row) in up.iter().enumerate().rev() {
+            if depth[u] - depth[v] >= 1 << k {
+                u = row[u];
+            }
+        }
+        if u == v {
+            return u;
+        }
+        for row in up.iter().rev() {
+            if row[u] != row[v] {
+                u = row[u];
+                v = row[v];
+            }
+        }
+        parent[u]
+    }
+
+    #[test]
+    fn lca_matches_binary_lifting() {
+        let mut rng = XorShift64::new(0x1CA0_F00D_2468_ACE1);
+        for n in 1..80 {
+            // Random recursive tree on a shuffled labelling, rooted anywhere.
+            let mut label: Vec<usize> = (0..n).collect();
+            for i in (1..n).rev() {
+                label.swap(i, rng.gen_usize(0..i + 1));
+            }
+            let mut edges = Vec::with_capacity(n - 1);
+            for i in 1..n {
+                let j = if rng.next_u64().is_multiple_of(4) {
+                    i - 1
+                } else {
+                    rng.gen_usize(0..i)
+                };
+                edges.push((label[i], label[j]));
+            }
+            let root = rng.gen_usize(0..n);
+            let lca = LcaRmq::new(n, &edges, root);
+            assert_eq!(lca.len(), n);
+
+            let mut adj = vec![Vec::new(); n];
+            for &(u, v) in &edges {
+                adj[u].push(v);
+                adj[v].push(u);
+            }
+            let (mut parent, mut depth) = (vec![root; n], vec![0; n]);
+            let mut order = vec![root];
+            let mut seen = vec![false; n];
+            seen[root] = true;
+            while let Some(u) = order.pop() {
+                for &v in &adj[u] {
+                    if !seen[v] {
+                        seen[v] = true;
+                        parent[v] = u;
+                        depth[v] = depth[u] + 1;
+                        order.push(v);
+                    }
+                }
+            }
+
+            for _ in 0..300 {
+                let (u, v) = (rng.gen_usize(0..n), rng.gen_usize(0..n));
+                assert_eq!(
+                    lca.lca(u, v),
+                    binary_lifting_lca(&parent, &depth, u, v),
+                    "n={n} u={u} v={v}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "do not connect")]
+    fn lca_rejects_forest() {
+        LcaRmq::new(4, &[(0, 1), (2, 3)], 0);
+    }
+
+    #[test]
+    fn sparse_table_2d_matches_bruteforce() {
+        let mut rng = XorShift64::new(0x2D5A_A5E5_0000_7777);
+        for rows in 0..9 {
+            for cols in 0..9 {
+                let grid: Vec<Vec<i64>> = (0..rows)
+                    .map(|_| (0..cols).map(|_| rng.gen_i64(-4..=4)).collect())
+                    .collect();
+                let st = SparseTable2D::new(&grid);
+                assert_eq!(st.argmin_rect(0..rows, 0..0), None);
+                assert_eq!(st.argmin_rect(0..0, 0..cols), None);
+                assert_eq!(st.argmin_rect(0..rows + 1, 0..cols), None);
+                assert_eq!(st.argmin_rect(0..rows, 0..cols + 1), None);
+
+                for r0 in 0..rows {
+                    for r1 in (r0 + 1)..=rows {
+                        for c0 in 0..cols {
+                            for c1 in (c0 + 1)..=cols {
+                                // Row-major scan with strict `<` keeps the
+                                // lexicographically smallest cell on ties.
+                                let mut best = (r0, c0);
+                                for r in r0..r1 {
+                                    for c in c0..c1 {
+                                        if grid[r][c] < grid[best.0][best.1] {
+                                            best = (r, c);
+                                        }
+                                    }
+                                }
+                                assert_eq!(
+                                    st.argmin_rect(r0..r1, c0..c1),
+                                    Some(best),
+                                    "{rows}x{cols} rect=({r0}..{r1}, {c0}..{c1})"
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sliding_window_matches_sparse_table() {
+        let mut rng = XorShift64::new(0x5117_D1E6_0000_0042);
+        for n in 0..64 {
+            let values: Vec<i64> = (0..n).map(|_| rng.gen_i64(-5..=5)).collect();
+            let st = SparseTableRmq::new(&values);
+            assert!(sliding_window_argmin(&values, 0).is_empty());
+            assert!(sliding_window_argmin(&values, n + 1).is_empty());
+            for w in 1..=n {
+                let expected: Vec<usize> =
+                    (0..=n - w).map(|i| st.argmin(i..i + w).unwrap()).collect();
+                assert_eq!(sliding_window_argmin(&values, w), expected, "n={n} w={w}");
+            }
+        }
+    }
+
+    #[test]
+    fn point_updates_match_bruteforce() {
+        let mut rng = XorShift64::new(0x5E70_BEEF_0000_1309);
+
+        for n in 0..48 {
+            let mut values: Vec<i64> = (0..n).map(|_| rng.gen_i64(-8..=8)).collect();
+            let mut rmq = PointUpdateRmq::new(&values);
+            assert_eq!(rmq.argmin(0..0), None);
+            assert_eq!(rmq.argmin(0..n + 1), None);
+
+            if n == 0 {
+                continue;
+            }
+
+            for _ in 0..400 {
+                if rng.next_u64().is_multiple_of(3) {
+                    let i = rng.gen_usize(0..n);
+                    let value = rng.gen_i64(-8..=8);
+                    rmq.set(i, value);
+                    values[i] = value;
+                    assert_eq!(rmq.get(i), value);
+                } else {
+                    let l = rng.gen_usize(0..n);
+                    let r = rng.gen_usize((l + 1)..(n + 1));
+                    let expected = brute_force_argmin(&values, l, r);
+                    assert_eq!(rmq.argmin(l..r), Some(expected), "n={n} l={l} r={r}");
+                }
+            }
+        }
+    }
+
+    fn check_batch<R: StaticRmq>(values: &[i64], queries: &[std::ops::Range<usize>]) {
+        let rmq = R::new(values);
+        let mut out = vec![Some(usize::MAX); 3];
+        rmq.argmin_batch(queries, &mut out);
+        let naive: Vec<Option<usize>> = queries.iter().map(|q| rmq.argmin(q.clone())).collect();
+        assert_eq!(out, naive);
+    }
+
+    #[test]
+    fn batch_matches_single_queries() {
+        let mut rng = XorShift64::new(0xBA7C_4ED0_0000_1310);
+        for n in [0, 1, 2, 7, 64, 301] {
+            let values: Vec<i64> = (0..n).map(|_| rng.gen_i64(-8..=8)).collect();
+            // Mix valid, empty and out-of-range queries in random order.
+            let queries: Vec<_> = (0..500)
+                .map(|_| {
+                    let l = rng.gen_usize(0..n + 2);
+                    let r = rng.gen_usize(0..n + 2);
+                    l..r
+                })
+                .collect();
+            check_batch::<SegmentTreeRmq>(&values, &queries);
+            check_batch::<SparseTableRmq>(&values, &queries);
+            check_batch::<DisjointSparseTableRmq>(&values, &queries);
+            check_batch::<AlstrupRmq>(&values, &queries);
+        }
+    }
 }
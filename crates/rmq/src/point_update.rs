@@ -0,0 +1,90 @@
+use std::ops::Range;
+
+use crate::DynamicRmq;
+use crate::Mode;
+use crate::segment_tree::{NONE, better_or_none_ordered};
+
+/// Bottom-up segment tree over indices, like [`crate::SegmentTreeRmq`], that
+/// also supports point assignment in O(log n).
+#[derive(Clone, Debug)]
+pub struct PointUpdateRmq<T = i64> {
+    values: Vec<T>,
+    size: usize,
+    tree: Vec<usize>,
+}
+
+impl<T> PointUpdateRmq<T> {
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<T: Ord + Copy> DynamicRmq<T> for PointUpdateRmq<T> {
+    fn new(values: &[T]) -> Self {
+        let n = values.len();
+        let values = values.to_vec();
+        let size = n.next_power_of_two();
+        let mut tree = vec![NONE; 2 * size];
+        for i in 0..n {
+            tree[size + i] = i;
+        }
+        for i in (1..size).rev() {
+            tree[i] = better_or_none_ordered(&values, Mode::Min, tree[2 * i], tree[2 * i + 1]);
+        }
+
+        Self { values, size, tree }
+    }
+
+    fn get(&self, i: usize) -> T {
+        self.values[i]
+    }
+
+    fn set(&mut self, i: usize, value: T) {
+        self.values[i] = value;
+        let mut node = (self.size + i) >> 1;
+        while node > 0 {
+            self.tree[node] = better_or_none_ordered(
+                &self.values,
+                Mode::Min,
+                self.tree[2 * node],
+                self.tree[2 * node + 1],
+            );
+            node >>= 1;
+        }
+    }
+
+    fn argmin(&self, range: Range<usize>) -> Option<usize> {
+        let n = self.values.len();
+        if range.start >= range.end || range.end > n {
+            return None;
+        }
+
+        let mut l = range.start + self.size;
+        let mut r = range.end + self.size;
+        let mut left = NONE;
+        let mut right = NONE;
+
+        let values = &self.values;
+        let tree = &self.tree;
+
+        while l < r {
+            if (l & 1) == 1 {
+                left = better_or_none_ordered(values, Mode::Min, left, tree[l]);
+                l += 1;
+            }
+            if (r & 1) == 1 {
+                r -= 1;
+                right = better_or_none_ordered(values, Mode::Min, tree[r], right);
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+
+        let ans = better_or_none_ordered(values, Mode::Min, left, right);
+        (ans != NONE).then_some(ans)
+    }
+}
@@ -1,29 +1,38 @@
 use std::ops::Range;
 
+use crate::Mode;
 use crate::StaticRmq;
+use crate::TieBreak;
 use crate::util::better_index_ordered;
+use crate::util::vec_bytes;
 
-const NONE: usize = usize::MAX;
+pub(crate) const NONE: usize = usize::MAX;
 
 #[inline(always)]
-fn better_or_none_ordered(values: &[i64], a: usize, b: usize) -> usize {
+pub(crate) fn better_or_none_ordered<T: Ord>(
+    values: &[T],
+    mode: Mode,
+    a: usize,
+    b: usize,
+) -> usize {
     if a == NONE {
         return b;
     }
     if b == NONE {
         return a;
     }
-    better_index_ordered(values, a, b)
+    better_index_ordered(values, mode, TieBreak::First, a, b)
 }
 
 #[derive(Clone, Debug)]
-pub struct SegmentTreeRmq {
-    values: Vec<i64>,
+pub struct SegmentTreeRmq<T = i64> {
+    values: Vec<T>,
+    mode: Mode,
     size: usize,
     tree: Vec<usize>,
 }
 
-impl SegmentTreeRmq {
+impl<T> SegmentTreeRmq<T> {
     pub fn len(&self) -> usize {
         self.values.len()
     }
@@ -33,13 +42,60 @@ impl SegmentTreeRmq {
     }
 }
 
-impl StaticRmq for SegmentTreeRmq {
-    fn new(values: &[i64]) -> Self {
+impl<T: Ord + Copy> SegmentTreeRmq<T> {
+    /// `TieBreak::Last` from the smallest-index tree: find the extremum, take
+    /// the rightmost canonical node of `range` holding it, then descend that
+    /// node preferring the right child whenever it holds the extremum too.
+    fn arg_extremum_last(&self, range: Range<usize>) -> Option<usize> {
+        let best = self.values[self.arg_extremum(range.clone())?];
+        let holds = |node: usize| self.tree[node] != NONE && self.values[self.tree[node]] == best;
+
+        // Right-side nodes come right to left, left-side ones left to right.
+        let mut l = range.start + self.size;
+        let mut r = range.end + self.size;
+        let mut rightmost_right = NONE;
+        let mut rightmost_left = NONE;
+        while l < r {
+            if (l & 1) == 1 {
+                if holds(l) {
+                    rightmost_left = l;
+                }
+                l += 1;
+            }
+            if (r & 1) == 1 {
+                r -= 1;
+                if rightmost_right == NONE && holds(r) {
+                    rightmost_right = r;
+                }
+            }
+            l >>= 1;
+            r >>= 1;
+        }
+
+        let mut node = if rightmost_right != NONE {
+            rightmost_right
+        } else {
+            rightmost_left
+        };
+        while node < self.size {
+            node = if holds(2 * node + 1) {
+                2 * node + 1
+            } else {
+                2 * node
+            };
+        }
+        Some(node - self.size)
+    }
+}
+
+impl<T: Ord + Copy> StaticRmq<T> for SegmentTreeRmq<T> {
+    fn with_mode(values: &[T], mode: Mode) -> Self {
         let n = values.len();
         let values = values.to_vec();
         if n == 0 {
             return Self {
                 values,
+                mode,
                 size: 0,
                 tree: Vec::new(),
             };
@@ -51,13 +107,22 @@ impl StaticRmq for SegmentTreeRmq {
             tree[size + i] = i;
         }
         for i in (1..size).rev() {
-            tree[i] = better_or_none_ordered(&values, tree[2 * i], tree[2 * i + 1]);
+            tree[i] = better_or_none_ordered(&values, mode, tree[2 * i], tree[2 * i + 1]);
         }
 
-        Self { values, size, tree }
+        Self {
+            values,
+            mode,
+            size,
+            tree,
+        }
+    }
+
+    fn mode(&self) -> Mode {
+        self.mode
     }
 
-    fn argmin(&self, range: Range<usize>) -> Option<usize> {
+    fn arg_extremum(&self, range: Range<usize>) -> Option<usize> {
         let n = self.values.len();
         if range.start >= range.end || range.end > n {
             return None;
@@ -69,22 +134,51 @@ impl StaticRmq for SegmentTreeRmq {
         let mut right = NONE;
 
         let values = &self.values;
+        let mode = self.mode;
         let tree = &self.tree;
 
         while l < r {
             if (l & 1) == 1 {
-                left = better_or_none_ordered(values, left, tree[l]);
+                left = better_or_none_ordered(values, mode, left, tree[l]);
                 l += 1;
             }
             if (r & 1) == 1 {
                 r -= 1;
-                right = better_or_none_ordered(values, tree[r], right);
+                right = better_or_none_ordered(values, mode, tree[r], right);
             }
             l >>= 1;
             r >>= 1;
         }
 
-        let ans = better_or_none_ordered(values, left, right);
+        let ans = better_or_none_ordered(values, mode, left, right);
         (ans != NONE).then_some(ans)
     }
+
+    fn arg_extremum_with(&self, range: Range<usize>, tie: TieBreak) -> Option<usize> {
+        match tie {
+            TieBreak::First => self.arg_extremum(range),
+            TieBreak::Last => self.arg_extremum_last(range),
+        }
+    }
+
+    fn memory_bytes(&self) -> usize {
+        vec_bytes(&self.values) + vec_bytes(&self.tree)
+    }
+
+    /// Answers the queries in order of their left end, so consecutive walks
+    /// share the leaves and ancestors they touch.
+    fn argmin_batch(&self, queries: &[Range<usize>], out: &mut Vec<Option<usize>>) {
+        assert_eq!(
+            self.mode,
+            Mode::Min,
+            "argmin on a structure built for maxima"
+        );
+        out.clear();
+        out.resize(queries.len(), None);
+        let mut order: Vec<usize> = (0..queries.len()).collect();
+        order.sort_unstable_by_key(|&i| queries[i].start);
+        for i in order {
+            out[i] = self.arg_extremum(queries[i].clone());
+        }
+    }
 }
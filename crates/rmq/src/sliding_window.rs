@@ -0,0 +1,29 @@
+use std::collections::VecDeque;
+
+/// Argmin of every length-`w` window `[i, i + w)`, in O(n) overall.
+///
+/// The deque holds indices of the current window with strictly increasing
+/// values; a new element evicts only strictly larger ones, so among equal values
+/// the earliest survives and ties go to the smallest index as in [`crate::StaticRmq`].
+/// Returns an empty vector when `w == 0` or `w > values.len()`.
+pub fn sliding_window_argmin<T: Ord>(values: &[T], w: usize) -> Vec<usize> {
+    if w == 0 || w > values.len() {
+        return Vec::new();
+    }
+
+    let mut result = Vec::with_capacity(values.len() + 1 - w);
+    let mut deque: VecDeque<usize> = VecDeque::with_capacity(w);
+    for (i, value) in values.iter().enumerate() {
+        while deque.back().is_some_and(|&j| values[j] > *value) {
+            deque.pop_back();
+        }
+        deque.push_back(i);
+        if deque.front().is_some_and(|&j| j + w <= i) {
+            deque.pop_front();
+        }
+        if i + 1 >= w {
+            result.push(*deque.front().expect("window is non-empty"));
+        }
+    }
+    result
+}
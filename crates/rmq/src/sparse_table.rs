@@ -1,10 +1,14 @@
 use std::mem::{ManuallyDrop, MaybeUninit};
 use std::ops::Range;
+use std::sync::OnceLock;
 
+use crate::Mode;
 use crate::StaticRmq;
+use crate::TieBreak;
 use crate::util::better_index;
 use crate::util::better_index_ordered;
 use crate::util::floor_log2_nonzero;
+use crate::util::vec_bytes;
 
 #[derive(Clone, Debug)]
 enum SparseTable {
@@ -12,52 +16,19 @@ enum SparseTable {
     Usize(Vec<usize>),
 }
 
-#[derive(Clone, Debug)]
-pub struct SparseTableRmq {
-    values: Vec<i64>,
-    row_offsets: Vec<usize>,
-    table: SparseTable,
-}
-
-impl SparseTableRmq {
-    pub fn len(&self) -> usize {
-        self.values.len()
-    }
-
-    pub fn is_empty(&self) -> bool {
-        self.values.is_empty()
-    }
-}
-
-impl StaticRmq for SparseTableRmq {
-    fn new(values: &[i64]) -> Self {
+impl SparseTable {
+    /// Row `k` starts at `row_offsets[k]` and holds the extremum of every
+    /// window of length `2^k`, with ties resolved by `tie`.
+    fn build<T: Ord>(
+        values: &[T],
+        mode: Mode,
+        tie: TieBreak,
+        row_offsets: &[usize],
+        total_len: usize,
+    ) -> Self {
         let n = values.len();
-        let values = values.to_vec();
-
-        if n == 0 {
-            return Self {
-                values,
-                row_offsets: Vec::new(),
-                table: SparseTable::U32(Vec::new()),
-            };
-        }
-
-        let levels = (floor_log2_nonzero(n) as usize) + 1;
-
-        let mut total_len = 0_usize;
-        for k in 0..levels {
-            total_len += n + 1 - (1_usize << k);
-        }
-
-        let mut row_offsets = Vec::with_capacity(levels);
-        let mut offset = 0_usize;
-        for k in 0..levels {
-            row_offsets.push(offset);
-            offset += n + 1 - (1_usize << k);
-        }
-        debug_assert_eq!(offset, total_len);
-
-        let table = if n <= (u32::MAX as usize) {
+        let levels = row_offsets.len();
+        if n <= (u32::MAX as usize) {
             let mut table = Vec::<MaybeUninit<u32>>::with_capacity(total_len);
             // We fully initialize the buffer before converting it to `Vec<u32>`.
             unsafe {
@@ -81,7 +52,7 @@ impl StaticRmq for SparseTableRmq {
                 for i in 0..len {
                     let a = unsafe { (*ptr.add(prev_base + i)).assume_init() as usize };
                     let b = unsafe { (*ptr.add(prev_base + i + half)).assume_init() as usize };
-                    let best = better_index_ordered(&values, a, b);
+                    let best = better_index_ordered(values, mode, tie, a, b);
                     unsafe {
                         ptr.add(base + i).write(MaybeUninit::new(best as u32));
                     }
@@ -93,7 +64,7 @@ impl StaticRmq for SparseTableRmq {
             let len = table.len();
             let cap = table.capacity();
             let table = unsafe { Vec::from_raw_parts(ptr, len, cap) };
-            SparseTable::U32(table)
+            Self::U32(table)
         } else {
             let mut table = Vec::<MaybeUninit<usize>>::with_capacity(total_len);
             // We fully initialize the buffer before converting it to `Vec<usize>`.
@@ -120,7 +91,9 @@ impl StaticRmq for SparseTableRmq {
                     let b = unsafe { (*ptr.add(prev_base + i + half)).assume_init() };
                     unsafe {
                         ptr.add(base + i)
-                            .write(MaybeUninit::new(better_index_ordered(&values, a, b)));
+                            .write(MaybeUninit::new(better_index_ordered(
+                                values, mode, tie, a, b,
+                            )));
                     }
                 }
             }
@@ -130,17 +103,105 @@ impl StaticRmq for SparseTableRmq {
             let len = table.len();
             let cap = table.capacity();
             let table = unsafe { Vec::from_raw_parts(ptr, len, cap) };
-            SparseTable::Usize(table)
-        };
+            Self::Usize(table)
+        }
+    }
+
+    /// The better of the entries at `a` and `b`.
+    #[inline(always)]
+    fn pick<T: Ord>(&self, values: &[T], mode: Mode, tie: TieBreak, a: usize, b: usize) -> usize {
+        match self {
+            Self::U32(table) => {
+                better_index(values, mode, tie, table[a] as usize, table[b] as usize)
+            }
+            Self::Usize(table) => better_index(values, mode, tie, table[a], table[b]),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::U32(table) => table.len(),
+            Self::Usize(table) => table.len(),
+        }
+    }
+
+    fn bytes(&self) -> usize {
+        match self {
+            Self::U32(table) => vec_bytes(table),
+            Self::Usize(table) => vec_bytes(table),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SparseTableRmq<T = i64> {
+    values: Vec<T>,
+    mode: Mode,
+    row_offsets: Vec<usize>,
+    table: SparseTable,
+    last_table: OnceLock<SparseTable>,
+}
+
+impl<T> SparseTableRmq<T> {
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl<T: Ord + Copy> StaticRmq<T> for SparseTableRmq<T> {
+    fn with_mode(values: &[T], mode: Mode) -> Self {
+        let n = values.len();
+        let values = values.to_vec();
+
+        if n == 0 {
+            return Self {
+                values,
+                mode,
+                row_offsets: Vec::new(),
+                table: SparseTable::U32(Vec::new()),
+                last_table: OnceLock::new(),
+            };
+        }
+
+        let levels = (floor_log2_nonzero(n) as usize) + 1;
+
+        let mut total_len = 0_usize;
+        for k in 0..levels {
+            total_len += n + 1 - (1_usize << k);
+        }
+
+        let mut row_offsets = Vec::with_capacity(levels);
+        let mut offset = 0_usize;
+        for k in 0..levels {
+            row_offsets.push(offset);
+            offset += n + 1 - (1_usize << k);
+        }
+        debug_assert_eq!(offset, total_len);
+
+        let table = SparseTable::build(&values, mode, TieBreak::First, &row_offsets, total_len);
 
         Self {
             values,
+            mode,
             row_offsets,
             table,
+            last_table: OnceLock::new(),
         }
     }
 
-    fn argmin(&self, range: Range<usize>) -> Option<usize> {
+    fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn arg_extremum(&self, range: Range<usize>) -> Option<usize> {
+        self.arg_extremum_with(range, TieBreak::First)
+    }
+
+    fn arg_extremum_with(&self, range: Range<usize>, tie: TieBreak) -> Option<usize> {
         let n = self.values.len();
         if range.start >= range.end || range.end > n {
             return None;
@@ -150,20 +211,32 @@ impl StaticRmq for SparseTableRmq {
             return Some(range.start);
         }
 
+        let table = match tie {
+            TieBreak::First => &self.table,
+            TieBreak::Last => self.last_table.get_or_init(|| {
+                SparseTable::build(
+                    &self.values,
+                    self.mode,
+                    TieBreak::Last,
+                    &self.row_offsets,
+                    self.table.len(),
+                )
+            }),
+        };
         let k = floor_log2_nonzero(len) as usize;
         let span = 1_usize << k;
         let base = self.row_offsets[k];
-        match &self.table {
-            SparseTable::U32(table) => {
-                let a = table[base + range.start] as usize;
-                let b = table[base + range.end - span] as usize;
-                Some(better_index(&self.values, a, b))
-            }
-            SparseTable::Usize(table) => {
-                let a = table[base + range.start];
-                let b = table[base + range.end - span];
-                Some(better_index(&self.values, a, b))
-            }
-        }
+        Some(table.pick(
+            &self.values,
+            self.mode,
+            tie,
+            base + range.start,
+            base + range.end - span,
+        ))
+    }
+
+    fn memory_bytes(&self) -> usize {
+        let last = self.last_table.get().map_or(0, SparseTable::bytes);
+        vec_bytes(&self.values) + vec_bytes(&self.row_offsets) + self.table.bytes() + last
     }
 }
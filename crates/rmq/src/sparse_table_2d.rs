@@ -0,0 +1,128 @@
+use std::ops::Range;
+
+use crate::Mode;
+use crate::TieBreak;
+use crate::util::better_index;
+use crate::util::floor_log2_nonzero;
+
+/// Sparse table over a matrix: O(1) argmin of any submatrix after
+/// O(nm log n log m) preprocessing.
+///
+/// Level `(a, b)` stores, for every top-left corner, the argmin of the
+/// `2^a x 2^b` block there; a query covers its rectangle with four overlapping
+/// blocks. Cells are flattened row-major, so ties go to the smallest
+/// `(row, col)` in lexicographic order.
+#[derive(Clone, Debug)]
+pub struct SparseTable2D {
+    values: Vec<i64>,
+    rows: usize,
+    cols: usize,
+    col_levels: usize,
+    table: Vec<Vec<usize>>,
+}
+
+impl SparseTable2D {
+    /// Panics if the rows have different lengths.
+    pub fn new(grid: &[Vec<i64>]) -> Self {
+        let rows = grid.len();
+        let cols = grid.first().map_or(0, Vec::len);
+        assert!(
+            grid.iter().all(|row| row.len() == cols),
+            "grid rows must all have the same length"
+        );
+        let values: Vec<i64> = grid.concat();
+        if rows == 0 || cols == 0 {
+            return Self {
+                values,
+                rows,
+                cols,
+                col_levels: 0,
+                table: Vec::new(),
+            };
+        }
+
+        let row_levels = floor_log2_nonzero(rows) as usize + 1;
+        let col_levels = floor_log2_nonzero(cols) as usize + 1;
+        let mut table: Vec<Vec<usize>> = Vec::with_capacity(row_levels * col_levels);
+        for a in 0..row_levels {
+            for b in 0..col_levels {
+                let mut level = vec![0_usize; rows * cols];
+                let height = rows + 1 - (1 << a);
+                let width = cols + 1 - (1 << b);
+                for i in 0..height {
+                    for j in 0..width {
+                        level[i * cols + j] = if a == 0 && b == 0 {
+                            i * cols + j
+                        } else if b > 0 {
+                            // Join two horizontally adjacent 2^a x 2^(b-1) blocks.
+                            let prev = &table[a * col_levels + b - 1];
+                            let half = 1 << (b - 1);
+                            better_index(
+                                &values,
+                                Mode::Min,
+                                TieBreak::First,
+                                prev[i * cols + j],
+                                prev[i * cols + j + half],
+                            )
+                        } else {
+                            // Join two vertically adjacent 2^(a-1) x 1 blocks.
+                            let prev = &table[(a - 1) * col_levels];
+                            let half = 1 << (a - 1);
+                            better_index(
+                                &values,
+                                Mode::Min,
+                                TieBreak::First,
+                                prev[i * cols + j],
+                                prev[(i + half) * cols + j],
+                            )
+                        };
+                    }
+                }
+                table.push(level);
+            }
+        }
+
+        Self {
+            values,
+            rows,
+            cols,
+            col_levels,
+            table,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// `(row, col)` of the minimum in `rows x cols`, or `None` when the
+    /// rectangle is empty or out of bounds.
+    pub fn argmin_rect(&self, rows: Range<usize>, cols: Range<usize>) -> Option<(usize, usize)> {
+        if rows.start >= rows.end || rows.end > self.rows {
+            return None;
+        }
+        if cols.start >= cols.end || cols.end > self.cols {
+            return None;
+        }
+
+        let a = floor_log2_nonzero(rows.end - rows.start) as usize;
+        let b = floor_log2_nonzero(cols.end - cols.start) as usize;
+        let level = &self.table[a * self.col_levels + b];
+        let (top, bottom) = (rows.start, rows.end - (1 << a));
+        let (left, right) = (cols.start, cols.end - (1 << b));
+        let best = [
+            level[top * self.cols + left],
+            level[top * self.cols + right],
+            level[bottom * self.cols + left],
+            level[bottom * self.cols + right],
+        ]
+        .into_iter()
+        .reduce(|x, y| better_index(&self.values, Mode::Min, TieBreak::First, x, y))
+        .expect("four candidates");
+        Some((best / self.cols, best % self.cols))
+    }
+}
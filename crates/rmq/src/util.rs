@@ -1,21 +1,54 @@
+use crate::{Mode, TieBreak};
+
+/// The better of `a` and `b`: the preferred value of `mode`, with equal
+/// values resolved by `tie`.
 #[inline(always)]
-pub(crate) fn better_index(values: &[i64], a: usize, b: usize) -> usize {
-    let va = values[a];
-    let vb = values[b];
-    if va < vb || (va == vb && a < b) { a } else { b }
+pub(crate) fn better_index<T: Ord>(
+    values: &[T],
+    mode: Mode,
+    tie: TieBreak,
+    a: usize,
+    b: usize,
+) -> usize {
+    if is_strictly_less(values, mode, tie, a, b) {
+        a
+    } else {
+        b
+    }
 }
 
+/// [`better_index`] for `a < b`, which settles ties without comparing indices.
 #[inline(always)]
-pub(crate) fn better_index_ordered(values: &[i64], a: usize, b: usize) -> usize {
+pub(crate) fn better_index_ordered<T: Ord>(
+    values: &[T],
+    mode: Mode,
+    tie: TieBreak,
+    a: usize,
+    b: usize,
+) -> usize {
     debug_assert!(a < b);
-    if values[a] <= values[b] { a } else { b }
+    let order = mode.cmp(&values[a], &values[b]);
+    let keep_a = match tie {
+        TieBreak::First => order.is_le(),
+        TieBreak::Last => order.is_lt(),
+    };
+    if keep_a { a } else { b }
 }
 
 #[inline(always)]
-pub(crate) fn is_strictly_less(values: &[i64], a: usize, b: usize) -> bool {
-    let va = values[a];
-    let vb = values[b];
-    va < vb || (va == vb && a < b)
+pub(crate) fn is_strictly_less<T: Ord>(
+    values: &[T],
+    mode: Mode,
+    tie: TieBreak,
+    a: usize,
+    b: usize,
+) -> bool {
+    mode.cmp(&values[a], &values[b]).then(tie.cmp(a, b)).is_lt()
+}
+
+/// Heap bytes held by `v`, counting its spare capacity.
+pub(crate) fn vec_bytes<T>(v: &Vec<T>) -> usize {
+    v.capacity() * std::mem::size_of::<T>()
 }
 
 #[inline(always)]
@@ -0,0 +1,24 @@
+use std::ops::Add;
+
+/// Codomain of the DP routines: totally ordered, closed under addition, with
+/// a zero for the start and a large "infinity" for forbidden transitions.
+///
+/// `INF` is a quarter of the maximum so that `INF + INF` and a finite DP value
+/// plus `INF` still do not overflow.
+pub trait Cost: Ord + Copy + Add<Output = Self> {
+    const ZERO: Self;
+    const INF: Self;
+}
+
+macro_rules! impl_cost {
+    ($($t:ty),*) => {
+        $(
+            impl Cost for $t {
+                const ZERO: Self = 0;
+                const INF: Self = <$t>::MAX / 4;
+            }
+        )*
+    };
+}
+
+impl_cost!(u32, u64, u128, usize, i32, i64, i128, isize);
@@ -0,0 +1,44 @@
+use crate::Cost;
+
+/// Interval DP `dp[i][i] = 0`, `dp[i][j] = min over i <= k < j of
+/// dp[i][k] + dp[k + 1][j] + cost(i, j)`, returning `dp[0][n - 1]`.
+///
+/// Knuth's optimization: when `cost` satisfies the quadrangle inequality and
+/// is monotone on nested intervals, the optimal split obeys
+/// `opt[i][j - 1] <= opt[i][j] <= opt[i + 1][j]`, so scanning only that window
+/// makes each diagonal O(n) in total and the whole DP O(n^2).
+pub fn knuth_dp<T, F>(n: usize, cost: F) -> T
+where
+    T: Cost,
+    F: Fn(usize, usize) -> T,
+{
+    if n == 0 {
+        return T::ZERO;
+    }
+
+    let mut dp = vec![T::ZERO; n * n];
+    let mut opt = vec![0; n * n];
+    for i in 0..n {
+        opt[i * n + i] = i;
+    }
+
+    for len in 2..=n {
+        for i in 0..=n - len {
+            let j = i + len - 1;
+            let lo = opt[i * n + j - 1];
+            let hi = opt[(i + 1) * n + j].min(j - 1);
+            let mut best = T::INF;
+            let mut best_k = lo;
+            for k in lo..=hi {
+                let value = dp[i * n + k] + dp[(k + 1) * n + j];
+                if value < best {
+                    best = value;
+                    best_k = k;
+                }
+            }
+            dp[i * n + j] = best + cost(i, j);
+            opt[i * n + j] = best_k;
+        }
+    }
+    dp[n - 1]
+}
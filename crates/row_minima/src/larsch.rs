@@ -1,11 +1,13 @@
-pub struct Larsch {
-    base: ReduceRow,
+use crate::Cost;
+
+pub struct Larsch<T> {
+    base: ReduceRow<T>,
 }
 
-impl Larsch {
+impl<T: Ord + Copy + 'static> Larsch<T> {
     pub fn new<F>(rows: usize, cost: F) -> Self
     where
-        F: Fn(usize, usize) -> u64 + 'static,
+        F: Fn(usize, usize) -> T + 'static,
     {
         let mut base = ReduceRow::new(rows);
         base.set_f(std::rc::Rc::new(cost));
@@ -17,17 +19,30 @@ impl Larsch {
     }
 }
 
-pub fn larsch_shortest_path<F>(n: usize, cost: F) -> Vec<u64>
+pub fn larsch_shortest_path<T, F>(n: usize, cost: F) -> Vec<T>
+where
+    T: Cost + 'static,
+    F: Fn(usize, usize) -> T + 'static,
+{
+    larsch_shortest_path_with_path(n, cost).0
+}
+
+/// Like [`larsch_shortest_path`], but also returns the chosen predecessor of
+/// every index: `dp[row] == dp[preds[row]] + cost(row, preds[row])` for
+/// `row >= 1`, and `preds[0] == 0`.
+pub fn larsch_shortest_path_with_path<T, F>(n: usize, cost: F) -> (Vec<T>, Vec<usize>)
 where
-    F: Fn(usize, usize) -> u64 + 'static,
+    T: Cost + 'static,
+    F: Fn(usize, usize) -> T + 'static,
 {
     if n == 0 {
-        return Vec::new();
+        return (Vec::new(), Vec::new());
     }
 
-    let inf = u64::MAX / 4;
+    let inf = T::INF;
     let dp = std::rc::Rc::new(std::cell::RefCell::new(vec![inf; n]));
-    dp.borrow_mut()[0] = 0;
+    dp.borrow_mut()[0] = T::ZERO;
+    let mut preds = vec![0; n];
 
     let dp_for_cost = std::rc::Rc::clone(&dp);
     let cost = std::rc::Rc::new(cost);
@@ -46,20 +61,36 @@ where
         let mut dp = dp.borrow_mut();
         let value = dp[col] + cost(row, col);
         dp[row] = value;
+        preds[row] = col;
     }
 
-    dp.take()
+    (dp.take(), preds)
+}
+
+/// Indices on the optimal path from `0` to the last index, in increasing
+/// order, following `preds` as returned by [`larsch_shortest_path_with_path`].
+pub fn reconstruct(preds: &[usize]) -> Vec<usize> {
+    let Some(mut cur) = preds.len().checked_sub(1) else {
+        return Vec::new();
+    };
+    let mut path = vec![cur];
+    while cur != 0 {
+        cur = preds[cur];
+        path.push(cur);
+    }
+    path.reverse();
+    path
 }
 
-struct ReduceRow {
+struct ReduceRow<T> {
     n: usize,
-    f: std::rc::Rc<dyn Fn(usize, usize) -> u64>,
+    f: std::rc::Rc<dyn Fn(usize, usize) -> T>,
     cur_row: usize,
     state: usize,
-    rec: Option<Box<ReduceCol>>,
+    rec: Option<Box<ReduceCol<T>>>,
 }
 
-impl ReduceRow {
+impl<T: Ord + Copy + 'static> ReduceRow<T> {
     fn new(n: usize) -> Self {
         let rec = if n / 2 == 0 {
             None
@@ -68,14 +99,14 @@ impl ReduceRow {
         };
         Self {
             n,
-            f: std::rc::Rc::new(|_, _| 0),
+            f: std::rc::Rc::new(|_, _| unreachable!("cost is set before use")),
             cur_row: 0,
             state: 0,
             rec,
         }
     }
 
-    fn set_f(&mut self, f: std::rc::Rc<dyn Fn(usize, usize) -> u64>) {
+    fn set_f(&mut self, f: std::rc::Rc<dyn Fn(usize, usize) -> T>) {
         self.f = f.clone();
         if let Some(rec) = &mut self.rec {
             let f2 = std::rc::Rc::new(move |i: usize, j: usize| (f)(2 * i + 1, j));
@@ -116,27 +147,27 @@ impl ReduceRow {
     }
 }
 
-struct ReduceCol {
+struct ReduceCol<T> {
     n: usize,
-    f: std::rc::Rc<dyn Fn(usize, usize) -> u64>,
+    f: std::rc::Rc<dyn Fn(usize, usize) -> T>,
     cur_row: usize,
     cols: std::rc::Rc<std::cell::RefCell<Vec<usize>>>,
-    rec: Box<ReduceRow>,
+    rec: Box<ReduceRow<T>>,
 }
 
-impl ReduceCol {
+impl<T: Ord + Copy + 'static> ReduceCol<T> {
     fn new(n: usize) -> Self {
         let cols = std::rc::Rc::new(std::cell::RefCell::new(Vec::with_capacity(n)));
         Self {
             n,
-            f: std::rc::Rc::new(|_, _| 0),
+            f: std::rc::Rc::new(|_, _| unreachable!("cost is set before use")),
             cur_row: 0,
             cols,
             rec: Box::new(ReduceRow::new(n)),
         }
     }
 
-    fn set_f(&mut self, f: std::rc::Rc<dyn Fn(usize, usize) -> u64>) {
+    fn set_f(&mut self, f: std::rc::Rc<dyn Fn(usize, usize) -> T>) {
         self.f = f.clone();
         let cols = self.cols.clone();
         let f2 = std::rc::Rc::new(move |i: usize, j: usize| {
@@ -1,20 +1,39 @@
+mod cost;
+mod knuth;
 mod larsch;
 mod monotone_minima;
+mod monotone_minima_2d;
+mod online_larsch;
 mod simple_larsch;
 mod smawk;
 
-pub use larsch::{Larsch, larsch_shortest_path};
-pub use monotone_minima::monotone_minima;
+pub use cost::Cost;
+pub use knuth::knuth_dp;
+pub use larsch::{Larsch, larsch_shortest_path, larsch_shortest_path_with_path, reconstruct};
+pub use monotone_minima::{monotone_minima, monotone_minima_with_values};
+pub use monotone_minima_2d::monotone_minima_2d;
+pub use online_larsch::OnlineLarsch;
 pub use simple_larsch::simple_larsch_shortest_path;
-pub use smawk::smawk;
+pub use smawk::{smawk, smawk_max};
 
 #[cfg(test)]
 mod tests {
-    use super::{larsch_shortest_path, monotone_minima, simple_larsch_shortest_path, smawk};
+    use std::cell::Cell;
+    use std::fmt::Debug;
 
-    fn assert_row_minima<F>(rows: usize, cols: usize, cost: &F, argmins: &[usize])
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::{
+        Cost, OnlineLarsch, knuth_dp, larsch_shortest_path, larsch_shortest_path_with_path,
+        monotone_minima, monotone_minima_2d, monotone_minima_with_values, reconstruct,
+        simple_larsch_shortest_path, smawk, smawk_max,
+    };
+
+    fn assert_row_minima<T, F>(rows: usize, cols: usize, cost: &F, argmins: &[usize])
     where
-        F: Fn(usize, usize) -> u64,
+        T: Ord + Copy + Debug,
+        F: Fn(usize, usize) -> T,
     {
         for (row, &argmin) in argmins.iter().enumerate().take(rows) {
             let mut best_val = cost(row, 0);
@@ -29,19 +48,31 @@ mod tests {
         }
     }
 
-    fn brute_force_dp<F>(n: usize, cost: &F) -> Vec<u64>
+    fn assert_row_maxima<F>(rows: usize, cols: usize, cost: &F, argmaxes: &[usize])
     where
         F: Fn(usize, usize) -> u64,
     {
-        let inf = u64::MAX / 4;
-        let mut dp = vec![inf; n];
+        assert_eq!(argmaxes.len(), rows);
+        for (row, &argmax) in argmaxes.iter().enumerate() {
+            let best_val = (0..cols).map(|col| cost(row, col)).max().unwrap();
+            let got_val = cost(row, argmax);
+            assert_eq!(got_val, best_val, "row {row} argmax mismatch");
+        }
+    }
+
+    fn brute_force_dp<T, F>(n: usize, cost: &F) -> Vec<T>
+    where
+        T: Cost,
+        F: Fn(usize, usize) -> T,
+    {
+        let mut dp = vec![T::INF; n];
         if n == 0 {
             return dp;
         }
-        dp[0] = 0;
+        dp[0] = T::ZERO;
         for row in 1..n {
-            let mut best = inf;
-            for (col, value) in dp.iter().enumerate().take(row) {
+            let mut best = T::INF;
+            for (col, &value) in dp.iter().enumerate().take(row) {
                 let candidate = value + cost(row, col);
                 if candidate < best {
                     best = candidate;
@@ -64,6 +95,123 @@ mod tests {
         assert_row_minima(rows, cols, &cost, &got);
     }
 
+    /// Calls the divide-and-conquer search makes when single-column ranges
+    /// are filled in without evaluating `cost`, replayed from its argmins.
+    fn argmin_only_calls(
+        row_start: usize,
+        row_end: usize,
+        col_start: usize,
+        col_end: usize,
+        argmins: &[usize],
+    ) -> usize {
+        if row_start >= row_end || col_end - col_start <= 1 {
+            return 0;
+        }
+        let mid = (row_start + row_end) / 2;
+        let best_col = argmins[mid];
+        let mut calls = col_end - col_start;
+        if row_end - row_start > 1 {
+            calls += argmin_only_calls(row_start, mid, col_start, best_col + 1, argmins);
+            calls += argmin_only_calls(mid + 1, row_end, best_col, col_end, argmins);
+        }
+        calls
+    }
+
+    #[test]
+    fn monotone_minima_skips_cost_in_single_column_ranges() {
+        let calls = Cell::new(0);
+        let counted = |cost: fn(usize, usize) -> u64| {
+            let calls = &calls;
+            move |i: usize, k: usize| {
+                calls.set(calls.get() + 1);
+                cost(i, k)
+            }
+        };
+
+        let column = counted(|i, _| i as u64);
+        assert_eq!(monotone_minima(50, 1, &column), vec![0; 50]);
+        assert_eq!(calls.get(), 0);
+
+        let mut rng = StdRng::seed_from_u64(0x4D4D_0001);
+        for _ in 0..50 {
+            let rows = rng.random_range(1..40);
+            let cols = rng.random_range(1..40);
+            let cost = counted(|i, k| {
+                let diff = (3 * i).abs_diff(2 * k) as u64;
+                diff * diff + (k % 3) as u64
+            });
+            calls.set(0);
+            let argmins = monotone_minima(rows, cols, &cost);
+            assert_eq!(calls.get(), argmin_only_calls(0, rows, 0, cols, &argmins));
+            assert_row_minima(rows, cols, &cost, &argmins);
+        }
+    }
+
+    #[test]
+    fn monotone_minima_values_match_cost() {
+        let rows = 21;
+        let cols = 13;
+        let cost = |i: usize, k: usize| {
+            let diff = (2 * i).abs_diff(3 * k) as u64;
+            diff * diff + k as u64
+        };
+        let (argmins, values) = monotone_minima_with_values(rows, cols, &cost);
+        assert_row_minima(rows, cols, &cost, &argmins);
+        assert_eq!(argmins, monotone_minima(rows, cols, &cost));
+        for (row, (&argmin, &value)) in argmins.iter().zip(&values).enumerate() {
+            assert_eq!(value, cost(row, argmin), "row {row} value mismatch");
+        }
+        assert_eq!(
+            monotone_minima_with_values(0, 5, &cost),
+            (Vec::new(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn monotone_minima_2d_matches_bruteforce() {
+        let mut rng = StdRng::seed_from_u64(0x2D2D);
+        for (rows, cols, depth) in [(1, 1, 1), (1, 9, 4), (7, 1, 12), (9, 13, 20), (16, 16, 40)] {
+            for _ in 0..10 {
+                // A random table nondecreasing in both directions, used as the unique argmin.
+                let mut target = vec![0_i64; rows * cols];
+                for i in 0..rows {
+                    for j in 0..cols {
+                        let up = if i > 0 {
+                            target[(i - 1) * cols + j]
+                        } else {
+                            -3
+                        };
+                        let left = if j > 0 { target[i * cols + j - 1] } else { -3 };
+                        target[i * cols + j] = up.max(left) + rng.random_range(0..3);
+                    }
+                }
+                let cost = |i: usize, j: usize, k: usize| {
+                    let diff = k as i64 - target[i * cols + j];
+                    diff * diff
+                };
+
+                let got = monotone_minima_2d(rows, cols, depth, &cost);
+                assert_eq!(got.len(), rows * cols);
+                for i in 0..rows {
+                    for j in 0..cols {
+                        let best = (0..depth).map(|k| cost(i, j, k)).min().unwrap();
+                        assert_eq!(cost(i, j, got[i * cols + j]), best, "cell ({i}, {j})");
+                    }
+                }
+            }
+        }
+
+        // Ties keep the leftmost argmin, which is still monotone here.
+        let cost = |i: usize, j: usize, k: usize| (2 * k).abs_diff(i + j) / 2;
+        let got = monotone_minima_2d(6, 5, 8, &cost);
+        for i in 0..6 {
+            for j in 0..5 {
+                assert_eq!(got[i * 5 + j], ((i + j) / 2).min(7));
+            }
+        }
+        assert!(monotone_minima_2d(0, 5, 3, &cost).is_empty());
+    }
+
     #[test]
     fn smawk_matches_bruteforce() {
         let rows = 18;
@@ -76,6 +224,29 @@ mod tests {
         assert_row_minima(rows, cols, &cost, &got);
     }
 
+    #[test]
+    fn smawk_max_matches_bruteforce() {
+        let rows = 18;
+        let cols = 23;
+        // -(i - k)^2, shifted to stay non-negative.
+        let cost = |i: usize, k: usize| {
+            let diff = i.abs_diff(k) as u64;
+            1_000 - diff * diff
+        };
+        let got = smawk_max(rows, cols, &cost);
+        assert_row_maxima(rows, cols, &cost, &got);
+
+        let rows = 31;
+        let cols = 9;
+        let cost = |i: usize, k: usize| {
+            let diff = i.abs_diff(4 * k) as u64;
+            10_000 - diff * diff
+        };
+        let got = smawk_max(rows, cols, &cost);
+        assert_row_maxima(rows, cols, &cost, &got);
+        assert!(got.is_sorted());
+    }
+
     #[test]
     fn simple_larsch_matches_bruteforce() {
         let n = 20;
@@ -106,6 +277,24 @@ mod tests {
         assert_eq!(got, expected);
     }
 
+    #[test]
+    fn simple_larsch_last_row_can_jump_from_column_zero() {
+        // Convex in the jump length with its minimum at `n`, so every row's
+        // best predecessor is as far back as possible: column 0.
+        for n in [3, 4, 5, 8, 13, 32] {
+            let cost = |row: usize, col: usize| {
+                if col >= row {
+                    return u64::MAX / 4;
+                }
+                let slack = (n - (row - col)) as u64;
+                slack * slack
+            };
+            let expected = brute_force_dp(n, &cost);
+            assert_eq!(expected[n - 1], 1, "n {n}");
+            assert_eq!(simple_larsch_shortest_path(n, &cost), expected, "n {n}");
+        }
+    }
+
     #[test]
     fn larsch_matches_bruteforce() {
         let n = 22;
@@ -120,4 +309,126 @@ mod tests {
         let got = larsch_shortest_path(n, cost);
         assert_eq!(got, expected);
     }
+
+    #[test]
+    fn negative_costs_match_bruteforce() {
+        // Monge stays Monge under a constant shift, so these are all valid inputs.
+        let cost = |i: usize, k: usize| {
+            let diff = i.abs_diff(k) as i64;
+            diff * diff - 40
+        };
+        let (rows, cols) = (17, 24);
+        assert_row_minima(rows, cols, &cost, &monotone_minima(rows, cols, &cost));
+        assert_row_minima(rows, cols, &cost, &smawk(rows, cols, &cost));
+        let (argmins, values) = monotone_minima_with_values(rows, cols, &cost);
+        assert!(values.iter().all(|&v| v == -40));
+        assert_eq!(argmins, (0..rows).collect::<Vec<_>>());
+
+        let concave = |i: usize, k: usize| -cost(i, k);
+        assert_eq!(smawk_max(rows, cols, &concave), argmins);
+
+        let n = 25;
+        let dp_cost = move |i: usize, k: usize| {
+            if k >= i {
+                return i64::INF;
+            }
+            let diff = i.abs_diff(k) as i64;
+            diff * diff - 7 * diff
+        };
+        let expected = brute_force_dp(n, &dp_cost);
+        assert!(expected[n - 1] < 0);
+        assert_eq!(simple_larsch_shortest_path(n, &dp_cost), expected);
+        assert_eq!(larsch_shortest_path(n, dp_cost), expected);
+    }
+
+    #[test]
+    fn online_larsch_matches_batch() {
+        for n in [1, 2, 3, 7, 64, 301] {
+            let weight = |i: usize, k: usize| {
+                let diff = i.abs_diff(k) as u64;
+                diff * diff + 5 * (i as u64 % 7) + 11
+            };
+            let batch_cost = move |i: usize, k: usize| {
+                if k >= i { u64::MAX / 4 } else { weight(i, k) }
+            };
+            let expected = simple_larsch_shortest_path(n, &batch_cost);
+            assert_eq!(expected, brute_force_dp(n, &batch_cost), "n {n}");
+
+            let mut online = OnlineLarsch::new(n, move |i, k, dp: &[u64]| dp[k] + weight(i, k));
+            for (row, &value) in expected.iter().enumerate().skip(1) {
+                assert_eq!(online.push(), value, "n {n} row {row}");
+            }
+            assert_eq!(online.into_dp(), expected, "n {n}");
+        }
+    }
+
+    #[test]
+    fn knuth_dp_matches_bruteforce() {
+        let mut rng = StdRng::seed_from_u64(0x4B4E);
+        for n in [1, 2, 3, 5, 12, 30] {
+            for _ in 0..5 {
+                // Merging adjacent piles: the cost of an interval is its total size.
+                let weights: Vec<u64> = (0..n).map(|_| rng.random_range(0..100)).collect();
+                let mut prefix = vec![0_u64; n + 1];
+                for (i, &w) in weights.iter().enumerate() {
+                    prefix[i + 1] = prefix[i] + w;
+                }
+                let cost = |i: usize, j: usize| prefix[j + 1] - prefix[i];
+
+                let mut dp = vec![vec![0_u64; n]; n];
+                for len in 2..=n {
+                    for i in 0..=n - len {
+                        let j = i + len - 1;
+                        dp[i][j] =
+                            (i..j).map(|k| dp[i][k] + dp[k + 1][j]).min().unwrap() + cost(i, j);
+                    }
+                }
+                assert_eq!(knuth_dp(n, cost), dp[0][n - 1], "n {n} weights {weights:?}");
+            }
+        }
+        assert_eq!(knuth_dp(0, |_, _| 1_u64), 0);
+    }
+
+    #[test]
+    fn larsch_path_reproduces_dp() {
+        // Text justification: words of these widths, squared slack per line.
+        let widths = [3_u64, 2, 5, 1, 4, 4, 2, 6, 1, 3, 2, 5, 3];
+        let n = widths.len() + 1;
+        let mut prefix = vec![0_u64; n];
+        for (i, &w) in widths.iter().enumerate() {
+            prefix[i + 1] = prefix[i] + w + 1;
+        }
+        let line = 12_u64;
+        let cost = move |i: usize, k: usize| {
+            if k >= i {
+                return u64::MAX / 4;
+            }
+            let used = prefix[i] - prefix[k] - 1;
+            if used > line {
+                1_000_000 * (used - line)
+            } else {
+                (line - used) * (line - used)
+            }
+        };
+
+        let (dp, preds) = larsch_shortest_path_with_path(n, cost.clone());
+        assert_eq!(dp, brute_force_dp(n, &cost));
+        assert_eq!(dp, larsch_shortest_path(n, cost.clone()));
+        for row in 1..n {
+            assert_eq!(dp[row], dp[preds[row]] + cost(row, preds[row]), "row {row}");
+        }
+
+        let path = reconstruct(&preds);
+        assert_eq!(path.first(), Some(&0));
+        assert_eq!(path.last(), Some(&(n - 1)));
+        let total: u64 = path.windows(2).map(|w| cost(w[1], w[0])).sum();
+        assert_eq!(total, dp[n - 1]);
+
+        assert_eq!(
+            larsch_shortest_path_with_path(0, cost.clone()),
+            (Vec::new(), Vec::new())
+        );
+        assert_eq!(reconstruct(&[]), Vec::<usize>::new());
+        assert_eq!(reconstruct(&[0]), vec![0]);
+    }
 }
@@ -1,6 +1,7 @@
-pub fn monotone_minima<F>(rows: usize, cols: usize, cost: &F) -> Vec<usize>
+pub fn monotone_minima<T, F>(rows: usize, cols: usize, cost: &F) -> Vec<usize>
 where
-    F: Fn(usize, usize) -> u64,
+    T: Ord + Copy,
+    F: Fn(usize, usize) -> T,
 {
     if rows == 0 {
         return Vec::new();
@@ -8,26 +9,65 @@ where
     assert!(cols > 0, "cols must be positive");
 
     let mut argmins = vec![0; rows];
-    monotone_minima_recursive_range(0, rows, 0, cols, cost, &mut argmins);
+    monotone_minima_recursive_range::<false, _, _>(0, rows, 0, cols, cost, &mut argmins, &mut []);
     argmins
 }
 
+/// Like [`monotone_minima`], but also returns `cost(row, argmin[row])` for
+/// every row, taken from the evaluations the search already made.
+pub fn monotone_minima_with_values<T, F>(rows: usize, cols: usize, cost: &F) -> (Vec<usize>, Vec<T>)
+where
+    T: Ord + Copy,
+    F: Fn(usize, usize) -> T,
+{
+    if rows == 0 {
+        return (Vec::new(), Vec::new());
+    }
+    assert!(cols > 0, "cols must be positive");
+
+    let mut argmins = vec![0; rows];
+    let mut values = vec![None; rows];
+    monotone_minima_recursive_range::<true, _, _>(
+        0,
+        rows,
+        0,
+        cols,
+        cost,
+        &mut argmins,
+        &mut values,
+    );
+    let values = values
+        .into_iter()
+        .map(|v| v.expect("every row is visited"))
+        .collect();
+    (argmins, values)
+}
+
+/// `VALUES` selects whether `values` is filled; without it `values` may be
+/// empty and single-column ranges are resolved without calling `cost`.
 #[inline]
-fn monotone_minima_recursive_range<F>(
+fn monotone_minima_recursive_range<const VALUES: bool, T, F>(
     row_start: usize,
     row_end: usize,
     col_start: usize,
     col_end: usize,
     cost: &F,
     argmins: &mut [usize],
+    values: &mut [Option<T>],
 ) where
-    F: Fn(usize, usize) -> u64,
+    T: Ord + Copy,
+    F: Fn(usize, usize) -> T,
 {
     if row_start >= row_end || col_start >= col_end {
         return;
     }
     if col_end - col_start == 1 {
         argmins[row_start..row_end].fill(col_start);
+        if VALUES {
+            for (row, value) in values[row_start..row_end].iter_mut().enumerate() {
+                *value = Some(cost(row_start + row, col_start));
+            }
+        }
         return;
     }
     let mid = (row_start + row_end) / 2;
@@ -41,11 +81,30 @@ fn monotone_minima_recursive_range<F>(
         }
     }
     argmins[mid] = best_col;
+    if VALUES {
+        values[mid] = Some(best_val);
+    }
 
     if row_end - row_start == 1 {
         return;
     }
 
-    monotone_minima_recursive_range(row_start, mid, col_start, best_col + 1, cost, argmins);
-    monotone_minima_recursive_range(mid + 1, row_end, best_col, col_end, cost, argmins);
+    monotone_minima_recursive_range::<VALUES, _, _>(
+        row_start,
+        mid,
+        col_start,
+        best_col + 1,
+        cost,
+        argmins,
+        values,
+    );
+    monotone_minima_recursive_range::<VALUES, _, _>(
+        mid + 1,
+        row_end,
+        best_col,
+        col_end,
+        cost,
+        argmins,
+        values,
+    );
 }
@@ -0,0 +1,86 @@
+/// Argmin over `k in 0..depth` of `cost(i, j, k)` for every cell of a
+/// `rows x cols` grid, returned in row-major order.
+///
+/// Requires the leftmost argmin `opt(i, j)` to be nondecreasing in both `i`
+/// and `j`, the shape that Knuth-style interval DPs and many DP-on-matrices
+/// problems have. Rows are solved middle-first, so every row is bounded by
+/// the nearest solved rows above and below; within a row the columns are
+/// solved middle-first as in [`crate::monotone_minima`], also bounded by the
+/// nearest solved columns. Per level of the row recursion each column scans
+/// O(depth + rows) candidates in total, so `cost` is called
+/// O((rows + depth) * cols * log rows) times instead of `rows * cols * depth`.
+pub fn monotone_minima_2d<T, F>(rows: usize, cols: usize, depth: usize, cost: &F) -> Vec<usize>
+where
+    T: Ord + Copy,
+    F: Fn(usize, usize, usize) -> T,
+{
+    if rows == 0 || cols == 0 {
+        return Vec::new();
+    }
+    assert!(depth > 0, "depth must be positive");
+
+    let mut grid = Grid {
+        cols,
+        cost,
+        argmins: vec![0; rows * cols],
+    };
+    grid.solve_rows(0, rows, &vec![0; cols], &vec![depth - 1; cols]);
+    grid.argmins
+}
+
+struct Grid<'a, F> {
+    cols: usize,
+    cost: &'a F,
+    argmins: Vec<usize>,
+}
+
+impl<T, F> Grid<'_, F>
+where
+    T: Ord + Copy,
+    F: Fn(usize, usize, usize) -> T,
+{
+    /// Solves rows `row_start..row_end`, whose argmins lie between `lower`
+    /// and `upper` column by column.
+    fn solve_rows(&mut self, row_start: usize, row_end: usize, lower: &[usize], upper: &[usize]) {
+        if row_start >= row_end {
+            return;
+        }
+        let mid = (row_start + row_end) / 2;
+        self.solve_cols(mid, (0, self.cols), (0, usize::MAX), (lower, upper));
+
+        let mid_row = self.argmins[mid * self.cols..(mid + 1) * self.cols].to_vec();
+        self.solve_rows(row_start, mid, lower, &mid_row);
+        self.solve_rows(mid + 1, row_end, &mid_row, upper);
+    }
+
+    fn solve_cols(
+        &mut self,
+        row: usize,
+        (col_start, col_end): (usize, usize),
+        (k_start, k_end): (usize, usize),
+        (lower, upper): (&[usize], &[usize]),
+    ) {
+        if col_start >= col_end {
+            return;
+        }
+        let mid = (col_start + col_end) / 2;
+        let lo = k_start.max(lower[mid]);
+        let hi = k_end.min(upper[mid]);
+        debug_assert!(lo <= hi, "argmins must be monotone in both dimensions");
+
+        let mut best_k = lo;
+        let mut best_val = (self.cost)(row, mid, lo);
+        for k in lo + 1..=hi {
+            let value = (self.cost)(row, mid, k);
+            if value < best_val {
+                best_val = value;
+                best_k = k;
+            }
+        }
+        self.argmins[row * self.cols + mid] = best_k;
+
+        let bounds = (lower, upper);
+        self.solve_cols(row, (col_start, mid), (k_start, best_k), bounds);
+        self.solve_cols(row, (mid + 1, col_end), (best_k, k_end), bounds);
+    }
+}
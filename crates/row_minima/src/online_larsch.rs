@@ -0,0 +1,68 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::{Cost, Larsch};
+
+type DpCost<T> = Rc<dyn Fn(usize, usize, &[T]) -> T>;
+
+/// LARSCH driven one DP index at a time, for transitions that read earlier
+/// DP values: `dp[0] = 0` and `dp[row] = min over col < row of
+/// cost(row, col, dp)`, where `dp` holds `dp[0..row]`.
+///
+/// LARSCH looks ahead at rows whose value is not known yet, but only ever at
+/// columns that are, so `cost` is a fixed closure that receives the values
+/// computed so far rather than one supplied per row. The matrix it describes
+/// must be totally monotone. Each [`Self::push`] is amortized O(1) evaluations.
+pub struct OnlineLarsch<T> {
+    n: usize,
+    dp: Rc<RefCell<Vec<T>>>,
+    cost: DpCost<T>,
+    larsch: Larsch<T>,
+}
+
+impl<T: Cost + 'static> OnlineLarsch<T> {
+    /// Prepares `dp[0..n]` with `dp[0] = 0` already known.
+    pub fn new<F>(n: usize, cost: F) -> Self
+    where
+        F: Fn(usize, usize, &[T]) -> T + 'static,
+    {
+        assert!(n > 0, "n must be positive");
+        let dp = Rc::new(RefCell::new(vec![T::ZERO]));
+        let cost: DpCost<T> = Rc::new(cost);
+
+        let dp_for_cost = Rc::clone(&dp);
+        let cost_for_cost = Rc::clone(&cost);
+        let larsch = Larsch::new(n - 1, move |i, col| {
+            let row = i + 1;
+            if row <= col {
+                return T::INF;
+            }
+            let dp = dp_for_cost.borrow();
+            debug_assert!(col < dp.len(), "LARSCH queried an unknown column");
+            cost_for_cost(row, col, &dp)
+        });
+
+        Self {
+            n,
+            dp,
+            cost,
+            larsch,
+        }
+    }
+
+    /// Computes, stores, and returns the next DP value. Panics once all `n`
+    /// values are known.
+    pub fn push(&mut self) -> T {
+        let row = self.dp.borrow().len();
+        assert!(row < self.n, "all {} values are already computed", self.n);
+        let col = self.larsch.get_argmin();
+        let value = (self.cost)(row, col, &self.dp.borrow());
+        self.dp.borrow_mut().push(value);
+        value
+    }
+
+    /// The DP values computed so far.
+    pub fn into_dp(self) -> Vec<T> {
+        self.dp.take()
+    }
+}
@@ -1,19 +1,21 @@
-pub fn simple_larsch_shortest_path<F>(n: usize, cost: &F) -> Vec<u64>
+use crate::Cost;
+
+pub fn simple_larsch_shortest_path<T, F>(n: usize, cost: &F) -> Vec<T>
 where
-    F: Fn(usize, usize) -> u64,
+    T: Cost,
+    F: Fn(usize, usize) -> T,
 {
     if n == 0 {
         return Vec::new();
     }
 
-    let inf = u64::MAX / 4;
-    let mut dist = vec![inf; n];
-    dist[0] = 0;
+    let mut dist = vec![T::INF; n];
+    dist[0] = T::ZERO;
     let mut argmins = vec![0; n];
 
-    fn update<F>(row: usize, col: usize, cost: &F, dist: &mut [u64], argmins: &mut [usize])
+    fn update<T: Cost, F>(row: usize, col: usize, cost: &F, dist: &mut [T], argmins: &mut [usize])
     where
-        F: Fn(usize, usize) -> u64,
+        F: Fn(usize, usize) -> T,
     {
         if row <= col {
             return;
@@ -25,14 +27,14 @@ where
         }
     }
 
-    fn update_unchecked<F>(
+    fn update_unchecked<T: Cost, F>(
         row: usize,
         col: usize,
         cost: &F,
-        dist: &mut [u64],
+        dist: &mut [T],
         argmins: &mut [usize],
     ) where
-        F: Fn(usize, usize) -> u64,
+        F: Fn(usize, usize) -> T,
     {
         let candidate = dist[col] + cost(row, col);
         if candidate < dist[row] {
@@ -41,9 +43,9 @@ where
         }
     }
 
-    fn dfs<F>(l: usize, r: usize, cost: &F, dist: &mut [u64], argmins: &mut [usize])
+    fn dfs<T: Cost, F>(l: usize, r: usize, cost: &F, dist: &mut [T], argmins: &mut [usize])
     where
-        F: Fn(usize, usize) -> u64,
+        F: Fn(usize, usize) -> T,
     {
         if r == l + 1 {
             update_unchecked(r, l, cost, dist, argmins);
@@ -66,6 +68,9 @@ where
     }
 
     if n > 1 {
+        // `dfs` scans row `r` only from columns inside `(l, m]`, so seed the last row with
+        // column 0 before recursing.
+        update_unchecked(n - 1, 0, cost, &mut dist, &mut argmins);
         dfs(0, n - 1, cost, &mut dist, &mut argmins);
     }
     dist
@@ -1,6 +1,9 @@
-pub fn smawk<F>(rows: usize, cols: usize, cost: &F) -> Vec<usize>
+use std::cmp::Reverse;
+
+pub fn smawk<T, F>(rows: usize, cols: usize, cost: &F) -> Vec<usize>
 where
-    F: Fn(usize, usize) -> u64,
+    T: Ord + Copy,
+    F: Fn(usize, usize) -> T,
 {
     if rows == 0 {
         return Vec::new();
@@ -11,13 +14,27 @@ where
     solver.solve(rows, cols, cost)
 }
 
-struct SmawkFast {
+/// Row argmaxes of a matrix that is totally monotone for maxima, e.g. a
+/// concave cost such as `-(i - k)^2`.
+///
+/// Maximizing `cost` is minimizing `Reverse(cost)`, and the flip turns total
+/// monotonicity for maxima into total monotonicity for minima, so this reuses
+/// [`smawk`] and breaks ties the same way.
+pub fn smawk_max<T, F>(rows: usize, cols: usize, cost: &F) -> Vec<usize>
+where
+    T: Ord + Copy,
+    F: Fn(usize, usize) -> T,
+{
+    smawk(rows, cols, &|row, col| Reverse(cost(row, col)))
+}
+
+struct SmawkFast<T> {
     cols: Vec<usize>,
     row_argmin: Vec<usize>,
-    stack: Vec<u64>,
+    stack: Vec<T>,
 }
 
-impl SmawkFast {
+impl<T: Ord + Copy> SmawkFast<T> {
     fn new() -> Self {
         Self {
             cols: Vec::new(),
@@ -28,13 +45,14 @@ impl SmawkFast {
 
     fn solve<F>(&mut self, rows: usize, cols: usize, cost: &F) -> Vec<usize>
     where
-        F: Fn(usize, usize) -> u64,
+        F: Fn(usize, usize) -> T,
     {
         self.cols.clear();
         self.cols.reserve(cols + rows + rows);
         self.cols.extend(0..cols);
         self.row_argmin.resize(rows, 0);
-        self.stack.resize(rows, 0);
+        self.stack.clear();
+        self.stack.reserve(rows);
         self.recur(rows, cols, 0, 0, cols, cost);
         std::mem::take(&mut self.row_argmin)
     }
@@ -48,7 +66,7 @@ impl SmawkFast {
         end: usize,
         cost: &F,
     ) where
-        F: Fn(usize, usize) -> u64,
+        F: Fn(usize, usize) -> T,
     {
         if rows < (2usize << level) {
             let row = (1usize << level) - 1;
@@ -88,7 +106,8 @@ impl SmawkFast {
                 } else {
                     self.cols[end + top] = col;
                 }
-                self.stack[top] = cost(row, col);
+                self.stack.truncate(top);
+                self.stack.push(cost(row, col));
                 top += 1;
             }
         }
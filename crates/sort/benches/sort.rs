@@ -1,6 +1,7 @@
 use std::hint::black_box;
 use std::time::Duration;
 
+use bench::bench_seed;
 use criterion::measurement::Measurement;
 use criterion::{
     BenchmarkGroup, BenchmarkId, Criterion, SamplingMode, criterion_group, criterion_main,
@@ -8,8 +9,8 @@ use criterion::{
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use sort::{
-    DataTrack, SortAlgorithm, SortContext, algorithm_name, all_algorithms, sort_u64_with_ctx,
-    supports_track,
+    DataTrack, SortAlgorithm, SortContext, algorithm_name, all_algorithms, sort_u32,
+    sort_u64_with_ctx, supports_track,
 };
 
 const BENCH_SIZES: [usize; 4] = [4096, 16384, 65536, 262144];
@@ -19,6 +20,15 @@ const BENCH_WARMUP_MS: u64 = 80;
 const BENCH_MEASURE_MS_SMALL: u64 = 120;
 const BENCH_MEASURE_MS_LARGE: u64 = 300;
 const BENCH_MEASURE_MS_XL: u64 = 500;
+const SMALL_BENCH_SIZES: [usize; 4] = [8, 16, 64, 256];
+const SMALL_BENCH_BATCH: usize = 1024;
+// Introsort and pdqsort_like finish with sorting networks; quick_sort_median3
+// keeps the insertion-sort base case as a reference point.
+const SMALL_BENCH_ALGORITHMS: [SortAlgorithm; 3] = [
+    SortAlgorithm::Introsort,
+    SortAlgorithm::PdqsortLike,
+    SortAlgorithm::QuickSortMedian3,
+];
 
 #[derive(Clone, Copy)]
 enum Distribution {
@@ -134,6 +144,107 @@ fn bench_sort(c: &mut Criterion) {
     }
 }
 
+fn bench_small_sort(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sort_small/full_u64/random_uniform");
+    for &size in &SMALL_BENCH_SIZES {
+        apply_runtime(&mut group, size);
+        let seed = seed_for(
+            DataTrack::FullU64,
+            Distribution::RandomUniform,
+            size,
+            0xBA5E_0003,
+        );
+        let base = generate_dataset(
+            DataTrack::FullU64,
+            Distribution::RandomUniform,
+            size * SMALL_BENCH_BATCH,
+            seed,
+        );
+
+        for &algo in &SMALL_BENCH_ALGORITHMS {
+            group.bench_function(BenchmarkId::new(algorithm_name(algo), size), |bencher| {
+                bencher.iter_custom(|iters| {
+                    let mut total = Duration::ZERO;
+                    let mut ctx = SortContext::default();
+                    for _ in 0..iters {
+                        let mut data = base.clone();
+                        let start = std::time::Instant::now();
+                        for chunk in data.chunks_mut(size) {
+                            sort_u64_with_ctx(algo, chunk, &mut ctx);
+                        }
+                        total += start.elapsed();
+                        black_box(&data);
+                    }
+                    total
+                });
+            });
+        }
+
+        group.bench_function(BenchmarkId::new("std_unstable", size), |bencher| {
+            bencher.iter_custom(|iters| {
+                let mut total = Duration::ZERO;
+                for _ in 0..iters {
+                    let mut data = base.clone();
+                    let start = std::time::Instant::now();
+                    for chunk in data.chunks_mut(size) {
+                        chunk.sort_unstable();
+                    }
+                    total += start.elapsed();
+                    black_box(&data);
+                }
+                total
+            });
+        });
+    }
+    group.finish();
+}
+
+fn bench_radix_width(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sort_radix_width/random_uniform_u32");
+    for &size in &BENCH_SIZES {
+        apply_runtime(&mut group, size);
+        let mut rng = StdRng::seed_from_u64(seed_for(
+            DataTrack::FullU64,
+            Distribution::RandomUniform,
+            size,
+            0xBA5E_0004,
+        ));
+        let base_u32: Vec<u32> = (0..size).map(|_| rng.random::<u32>()).collect();
+        let base_u64: Vec<u64> = base_u32.iter().map(|&x| u64::from(x)).collect();
+
+        group.bench_function(BenchmarkId::new("radix_u64", size), |bencher| {
+            bencher.iter_custom(|iters| {
+                let mut total = Duration::ZERO;
+                let mut ctx = SortContext::default();
+                for _ in 0..iters {
+                    let mut data = base_u64.clone();
+                    let start = std::time::Instant::now();
+                    sort_u64_with_ctx(SortAlgorithm::RadixSortLsdBase256, &mut data, &mut ctx);
+                    total += start.elapsed();
+                    black_box(&data);
+                }
+                total
+            });
+        });
+
+        group.bench_function(BenchmarkId::new("radix_u32", size), |bencher| {
+            bencher.iter_custom(|iters| {
+                let mut total = Duration::ZERO;
+                let mut ctx = SortContext::default();
+                for _ in 0..iters {
+                    let mut data = base_u32.clone();
+                    let start = std::time::Instant::now();
+                    sort_u32(SortAlgorithm::RadixSortLsdBase256, &mut data, &mut ctx);
+                    total += start.elapsed();
+                    black_box(&data);
+                }
+                total
+            });
+        });
+    }
+    group.finish();
+}
+
 #[inline]
 fn is_benchmark_target(algo: SortAlgorithm) -> bool {
     !matches!(
@@ -205,7 +316,7 @@ fn seed_for(track: DataTrack, dist: Distribution, size: usize, salt: u64) -> u64
         Distribution::NearlySorted1pctSwaps => 12_u64,
     };
 
-    mix_seed(0x5EED_2026 ^ (t << 56) ^ (d << 48) ^ (size as u64) ^ salt)
+    mix_seed(bench_seed() ^ 0x5EED_2026 ^ (t << 56) ^ (d << 48) ^ (size as u64) ^ salt)
 }
 
 #[inline]
@@ -215,5 +326,5 @@ fn mix_seed(mut z: u64) -> u64 {
     z ^ (z >> 31)
 }
 
-criterion_group!(benches, bench_sort);
+criterion_group!(benches, bench_sort, bench_small_sort, bench_radix_width);
 criterion_main!(benches);
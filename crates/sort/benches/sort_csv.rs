@@ -0,0 +1,60 @@
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use bench::{bench_seed, median_time, write_results_csv};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sort::{
+    DataTrack, SortContext, algorithm_name, all_algorithms, sort_u64_with_ctx, supports_track,
+};
+
+const CSV_SIZES: [usize; 3] = [1024, 4096, 16384];
+const CSV_RUNS: usize = 7;
+const BOUNDED_MAX: u64 = (1 << 20) - 1;
+const SEED: u64 = 0xC5F0_2026;
+
+const TRACKS: [(DataTrack, &str); 2] = [
+    (DataTrack::FullU64, "full_u64"),
+    (DataTrack::BoundedU20, "bounded_u20"),
+];
+
+fn main() {
+    let path = std::env::var_os("SORT_CSV_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../target/sort_results.csv")
+        });
+
+    let mut rows: Vec<(String, Duration)> = Vec::new();
+    for &(track, label) in &TRACKS {
+        for &size in &CSV_SIZES {
+            let mut rng = StdRng::seed_from_u64(bench_seed() ^ SEED ^ size as u64);
+            let base: Vec<u64> = (0..size)
+                .map(|_| match track {
+                    DataTrack::FullU64 => rng.random(),
+                    DataTrack::BoundedU20 => rng.random_range(0..=BOUNDED_MAX),
+                })
+                .collect();
+
+            for &algo in all_algorithms() {
+                if !supports_track(algo, track) {
+                    continue;
+                }
+                let mut ctx = SortContext::default();
+                let time = median_time(CSV_RUNS, || {
+                    let mut data = base.clone();
+                    let start = Instant::now();
+                    sort_u64_with_ctx(algo, &mut data, &mut ctx);
+                    let elapsed = start.elapsed();
+                    black_box(&data);
+                    elapsed
+                });
+                rows.push((format!("{label}/{}/{size}", algorithm_name(algo)), time));
+            }
+        }
+    }
+
+    write_results_csv(&path, &rows).expect("failed to write the results CSV");
+    println!("wrote {} rows to {}", rows.len(), path.display());
+}
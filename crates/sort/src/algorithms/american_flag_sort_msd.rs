@@ -1,15 +1,16 @@
 use crate::{SortContext, TUNED_PARAMS};
 
 use super::common;
+use super::probe::Probe;
 
-pub fn sort(data: &mut [u64], _ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], _ctx: &mut SortContext, probe: P) {
     if data.len() < 2 {
         return;
     }
-    if common::is_sorted_non_decreasing(data) {
+    if common::is_sorted_non_decreasing(data, probe) {
         return;
     }
-    let Some((min, max)) = common::min_max(data) else {
+    let Some((min, max)) = common::min_max(data, probe) else {
         return;
     };
     let diff = min ^ max;
@@ -17,12 +18,12 @@ pub fn sort(data: &mut [u64], _ctx: &mut SortContext) {
         return;
     }
     let start_byte = ((63 - diff.leading_zeros()) / 8) as i32;
-    american_flag_sort_msd(data, start_byte);
+    american_flag_sort_msd(data, start_byte, probe);
 }
 
-fn american_flag_sort_msd(data: &mut [u64], byte: i32) {
+fn american_flag_sort_msd<P: Probe>(data: &mut [u64], byte: i32, probe: P) {
     if data.len() <= TUNED_PARAMS.insertion_threshold || byte < 0 {
-        common::insertion_sort(data);
+        common::insertion_sort(data, probe);
         return;
     }
 
@@ -35,7 +36,7 @@ fn american_flag_sort_msd(data: &mut [u64], byte: i32) {
     let non_zero_buckets = counts.iter().filter(|&&c| c > 0).count();
     if non_zero_buckets <= 1 {
         if byte > 0 {
-            american_flag_sort_msd(data, byte - 1);
+            american_flag_sort_msd(data, byte - 1, probe);
         }
         return;
     }
@@ -60,10 +61,12 @@ fn american_flag_sort_msd(data: &mut [u64], byte: i32) {
                 let to = next[d];
                 next[d] += 1;
                 std::mem::swap(&mut value, &mut data[to]);
+                probe.moved(1);
                 d = digit(value, shift);
             }
 
             data[from] = value;
+            probe.moved(1);
             next[bucket] += 1;
         }
     }
@@ -76,7 +79,7 @@ fn american_flag_sort_msd(data: &mut [u64], byte: i32) {
         let start = starts[bucket];
         let end = ends[bucket];
         if end - start > 1 {
-            american_flag_sort_msd(&mut data[start..end], byte - 1);
+            american_flag_sort_msd(&mut data[start..end], byte - 1, probe);
         }
     }
 }
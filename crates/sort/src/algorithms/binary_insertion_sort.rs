@@ -1,7 +1,8 @@
 use crate::SortContext;
 
 use super::common;
+use super::probe::Probe;
 
-pub fn sort(data: &mut [u64], _ctx: &mut SortContext) {
-    common::binary_insertion_sort(data);
+pub fn sort<P: Probe>(data: &mut [u64], _ctx: &mut SortContext, probe: P) {
+    common::binary_insertion_sort(data, probe);
 }
@@ -3,20 +3,21 @@ use std::ptr;
 use crate::{SortContext, TUNED_PARAMS};
 
 use super::common;
+use super::probe::Probe;
 
 const BLOCK: usize = crate::TUNED_PARAMS.block_partition_size;
 
-pub fn sort(data: &mut [u64], _ctx: &mut SortContext) {
-    block_quick_sort(data);
+pub fn sort<P: Probe>(data: &mut [u64], _ctx: &mut SortContext, probe: P) {
+    block_quick_sort(data, probe);
 }
 
-fn block_quick_sort(mut data: &mut [u64]) {
+fn block_quick_sort<P: Probe>(mut data: &mut [u64], probe: P) {
     while data.len() > TUNED_PARAMS.insertion_threshold {
-        let pivot = common::choose_pivot_ninther(data);
-        let split = block_partition(data, pivot);
+        let pivot = common::choose_pivot_ninther(data, probe);
+        let split = block_partition(data, pivot, probe);
 
         if split == 0 || split == data.len() {
-            let (lt, gt) = common::partition_3way(data, pivot);
+            let (lt, gt) = common::partition_3way(data, pivot, probe);
             if lt == 0 && gt == data.len() {
                 return;
             }
@@ -24,10 +25,10 @@ fn block_quick_sort(mut data: &mut [u64]) {
             let (left, rest) = data.split_at_mut(lt);
             let (_, right) = rest.split_at_mut(gt - lt);
             if left.len() < right.len() {
-                block_quick_sort(left);
+                block_quick_sort(left, probe);
                 data = right;
             } else {
-                block_quick_sort(right);
+                block_quick_sort(right, probe);
                 data = left;
             }
             continue;
@@ -35,18 +36,18 @@ fn block_quick_sort(mut data: &mut [u64]) {
 
         let (left, right) = data.split_at_mut(split);
         if left.len() < right.len() {
-            block_quick_sort(left);
+            block_quick_sort(left, probe);
             data = right;
         } else {
-            block_quick_sort(right);
+            block_quick_sort(right, probe);
             data = left;
         }
     }
 
-    common::insertion_sort(data);
+    common::insertion_sort(data, probe);
 }
 
-fn block_partition(data: &mut [u64], pivot: u64) -> usize {
+fn block_partition<P: Probe>(data: &mut [u64], pivot: u64, probe: P) -> usize {
     let len = data.len();
     if len <= 1 {
         return len;
@@ -69,7 +70,7 @@ fn block_partition(data: &mut [u64], pivot: u64) -> usize {
                 left_pos = 0;
                 left_count = 0;
                 for i in 0..BLOCK {
-                    if *ptr.add(left + i) >= pivot {
+                    if probe.ge(*ptr.add(left + i), pivot) {
                         left_offsets[left_count] = i;
                         left_count += 1;
                     }
@@ -85,7 +86,7 @@ fn block_partition(data: &mut [u64], pivot: u64) -> usize {
                 right_count = 0;
                 for i in 0..BLOCK {
                     let idx = right - 1 - i;
-                    if *ptr.add(idx) < pivot {
+                    if probe.lt(*ptr.add(idx), pivot) {
                         right_offsets[right_count] = i;
                         right_count += 1;
                     }
@@ -101,6 +102,7 @@ fn block_partition(data: &mut [u64], pivot: u64) -> usize {
                 let li = left + left_offsets[left_pos];
                 let ri = right - 1 - right_offsets[right_pos];
                 ptr::swap(ptr.add(li), ptr.add(ri));
+                probe.moved(2);
                 left_pos += 1;
                 right_pos += 1;
             }
@@ -116,14 +118,15 @@ fn block_partition(data: &mut [u64], pivot: u64) -> usize {
         let mut i = left;
         let mut j = right;
         while i < j {
-            while i < j && *ptr.add(i) < pivot {
+            while i < j && probe.lt(*ptr.add(i), pivot) {
                 i += 1;
             }
-            while i < j && *ptr.add(j - 1) >= pivot {
+            while i < j && probe.ge(*ptr.add(j - 1), pivot) {
                 j -= 1;
             }
             if i < j {
                 ptr::swap(ptr.add(i), ptr.add(j - 1));
+                probe.moved(2);
                 i += 1;
                 j -= 1;
             }
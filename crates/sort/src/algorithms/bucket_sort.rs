@@ -1,17 +1,18 @@
 use crate::{SortContext, TUNED_PARAMS};
 
 use super::common;
+use super::probe::Probe;
 
-pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], ctx: &mut SortContext, probe: P) {
     let len = data.len();
     if len < 2 {
         return;
     }
-    if common::is_sorted_non_decreasing(data) {
+    if common::is_sorted_non_decreasing(data, probe) {
         return;
     }
 
-    let Some((min, max)) = common::min_max(data) else {
+    let Some((min, max)) = common::min_max(data, probe) else {
         return;
     };
     if min == max {
@@ -59,6 +60,7 @@ pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
         scratch[pos] = x;
         heads[idx] += 1;
     }
+    probe.moved(len as u64);
 
     for i in 0..bucket_count {
         let start = starts[i];
@@ -71,13 +73,18 @@ pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
             continue;
         }
         if end - start <= TUNED_PARAMS.insertion_threshold {
-            common::insertion_sort(&mut scratch[start..end]);
+            common::insertion_sort(&mut scratch[start..end], probe);
         } else {
-            scratch[start..end].sort_unstable();
+            // Only comparisons are observable through std's sort; its internal
+            // element moves are not reported to the probe.
+            scratch[start..end].sort_unstable_by(|a, b| {
+                probe.compare(1);
+                a.cmp(b)
+            });
         }
     }
 
-    common::copy_u64_slice(data, &scratch[..len]);
+    common::copy_u64_slice(data, &scratch[..len], probe);
 }
 
 #[inline]
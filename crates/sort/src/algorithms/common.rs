@@ -2,8 +2,10 @@ use std::ptr;
 
 use crate::TUNED_PARAMS;
 
+use super::probe::Probe;
+
 #[inline]
-pub fn insertion_sort(data: &mut [u64]) {
+pub fn insertion_sort<P: Probe>(data: &mut [u64], probe: P) {
     let len = data.len();
     if len < 2 {
         return;
@@ -16,19 +18,20 @@ pub fn insertion_sort(data: &mut [u64]) {
         unsafe {
             while j > 0 {
                 let prev = *data.get_unchecked(j - 1);
-                if prev <= key {
+                if probe.le(prev, key) {
                     break;
                 }
                 *data.get_unchecked_mut(j) = prev;
                 j -= 1;
             }
             *data.get_unchecked_mut(j) = key;
+            probe.moved((i - j) as u64 + 1);
         }
     }
 }
 
 #[inline]
-pub fn binary_insertion_sort(data: &mut [u64]) {
+pub fn binary_insertion_sort<P: Probe>(data: &mut [u64], probe: P) {
     let len = data.len();
     if len < 2 {
         return;
@@ -42,7 +45,7 @@ pub fn binary_insertion_sort(data: &mut [u64]) {
         while left < right {
             let mid = left + ((right - left) >> 1);
             unsafe {
-                if *ptr.add(mid) <= key {
+                if probe.le(*ptr.add(mid), key) {
                     left = mid + 1;
                 } else {
                     right = mid;
@@ -56,18 +59,19 @@ pub fn binary_insertion_sort(data: &mut [u64]) {
             ptr::copy(ptr.add(pos), ptr.add(pos + 1), i - pos);
             *ptr.add(pos) = key;
         }
+        probe.moved((i - pos) as u64 + 1);
     }
 }
 
 #[inline]
-pub fn is_sorted_non_decreasing(data: &[u64]) -> bool {
+pub fn is_sorted_non_decreasing<P: Probe>(data: &[u64], probe: P) -> bool {
     if data.len() < 2 {
         return true;
     }
     let ptr = data.as_ptr();
     unsafe {
         for i in 1..data.len() {
-            if *ptr.add(i - 1) > *ptr.add(i) {
+            if probe.gt(*ptr.add(i - 1), *ptr.add(i)) {
                 return false;
             }
         }
@@ -91,18 +95,18 @@ pub fn introsort_depth_limit(n: usize) -> usize {
 }
 
 #[inline]
-pub fn median3(a: u64, b: u64, c: u64) -> u64 {
-    if a < b {
-        if b < c {
+pub fn median3<P: Probe>(a: u64, b: u64, c: u64, probe: P) -> u64 {
+    if probe.lt(a, b) {
+        if probe.lt(b, c) {
             b
-        } else if a < c {
+        } else if probe.lt(a, c) {
             c
         } else {
             a
         }
-    } else if a < c {
+    } else if probe.lt(a, c) {
         a
-    } else if b < c {
+    } else if probe.lt(b, c) {
         c
     } else {
         b
@@ -110,39 +114,39 @@ pub fn median3(a: u64, b: u64, c: u64) -> u64 {
 }
 
 #[inline]
-pub fn choose_pivot_median3(data: &[u64]) -> u64 {
+pub fn choose_pivot_median3<P: Probe>(data: &[u64], probe: P) -> u64 {
     let len = data.len();
     let a = data[0];
     let b = data[len >> 1];
     let c = data[len - 1];
-    median3(a, b, c)
+    median3(a, b, c, probe)
 }
 
 #[inline]
-pub fn choose_pivot_ninther(data: &[u64]) -> u64 {
+pub fn choose_pivot_ninther<P: Probe>(data: &[u64], probe: P) -> u64 {
     if data.len() < 64 {
-        return choose_pivot_median3(data);
+        return choose_pivot_median3(data, probe);
     }
 
     let step = data.len() / 8;
-    let m1 = median3(data[0], data[step], data[step * 2]);
+    let m1 = median3(data[0], data[step], data[step * 2], probe);
     let mid = data.len() / 2;
-    let m2 = median3(data[mid - step], data[mid], data[mid + step]);
+    let m2 = median3(data[mid - step], data[mid], data[mid + step], probe);
     let r = data.len() - 1;
-    let m3 = median3(data[r - step * 2], data[r - step], data[r]);
-    median3(m1, m2, m3)
+    let m3 = median3(data[r - step * 2], data[r - step], data[r], probe);
+    median3(m1, m2, m3, probe)
 }
 
 #[inline]
-pub fn min_max(data: &[u64]) -> Option<(u64, u64)> {
+pub fn min_max<P: Probe>(data: &[u64], probe: P) -> Option<(u64, u64)> {
     let (&first, rest) = data.split_first()?;
     let mut min = first;
     let mut max = first;
     for &x in rest {
-        if x < min {
+        if probe.lt(x, min) {
             min = x;
         }
-        if x > max {
+        if probe.gt(x, max) {
             max = x;
         }
     }
@@ -150,7 +154,7 @@ pub fn min_max(data: &[u64]) -> Option<(u64, u64)> {
 }
 
 #[inline]
-pub fn partition_hoare(data: &mut [u64], pivot: u64) -> usize {
+pub fn partition_hoare<P: Probe>(data: &mut [u64], pivot: u64, probe: P) -> usize {
     debug_assert!(!data.is_empty());
 
     let ptr = data.as_mut_ptr();
@@ -159,11 +163,11 @@ pub fn partition_hoare(data: &mut [u64], pivot: u64) -> usize {
 
     unsafe {
         loop {
-            while *ptr.add(i) < pivot {
+            while probe.lt(*ptr.add(i), pivot) {
                 i += 1;
             }
 
-            while *ptr.add(j) > pivot {
+            while probe.gt(*ptr.add(j), pivot) {
                 j -= 1;
             }
 
@@ -172,6 +176,7 @@ pub fn partition_hoare(data: &mut [u64], pivot: u64) -> usize {
             }
 
             ptr::swap(ptr.add(i), ptr.add(j));
+            probe.moved(2);
             i += 1;
             j -= 1;
         }
@@ -179,7 +184,7 @@ pub fn partition_hoare(data: &mut [u64], pivot: u64) -> usize {
 }
 
 #[inline]
-pub fn partition_3way(data: &mut [u64], pivot: u64) -> (usize, usize) {
+pub fn partition_3way<P: Probe>(data: &mut [u64], pivot: u64, probe: P) -> (usize, usize) {
     let ptr = data.as_mut_ptr();
     let mut lt = 0usize;
     let mut i = 0usize;
@@ -188,13 +193,15 @@ pub fn partition_3way(data: &mut [u64], pivot: u64) -> (usize, usize) {
     unsafe {
         while i < gt {
             let v = *ptr.add(i);
-            if v < pivot {
+            if probe.lt(v, pivot) {
                 ptr::swap(ptr.add(i), ptr.add(lt));
+                probe.moved(2);
                 i += 1;
                 lt += 1;
-            } else if v > pivot {
+            } else if probe.gt(v, pivot) {
                 gt -= 1;
                 ptr::swap(ptr.add(i), ptr.add(gt));
+                probe.moved(2);
             } else {
                 i += 1;
             }
@@ -205,13 +212,20 @@ pub fn partition_3way(data: &mut [u64], pivot: u64) -> (usize, usize) {
 }
 
 #[inline]
-pub fn merge_ranges(src: &[u64], dst: &mut [u64], left: usize, mid: usize, right: usize) {
+pub fn merge_ranges<P: Probe>(
+    src: &[u64],
+    dst: &mut [u64],
+    left: usize,
+    mid: usize,
+    right: usize,
+    probe: P,
+) {
     let mut i = left;
     let mut j = mid;
     let mut k = left;
 
     while i < mid && j < right {
-        if src[i] <= src[j] {
+        if probe.le(src[i], src[j]) {
             dst[k] = src[i];
             i += 1;
         } else {
@@ -220,17 +234,19 @@ pub fn merge_ranges(src: &[u64], dst: &mut [u64], left: usize, mid: usize, right
         }
         k += 1;
     }
+    probe.moved((k - left) as u64);
 
     if i < mid {
-        copy_u64_slice(&mut dst[k..(k + (mid - i))], &src[i..mid]);
+        copy_u64_slice(&mut dst[k..(k + (mid - i))], &src[i..mid], probe);
     } else if j < right {
-        copy_u64_slice(&mut dst[k..(k + (right - j))], &src[j..right]);
+        copy_u64_slice(&mut dst[k..(k + (right - j))], &src[j..right], probe);
     }
 }
 
 #[inline]
-pub fn copy_u64_slice(dst: &mut [u64], src: &[u64]) {
+pub fn copy_u64_slice<P: Probe>(dst: &mut [u64], src: &[u64], probe: P) {
     debug_assert_eq!(dst.len(), src.len());
+    probe.moved(dst.len() as u64);
     unsafe {
         copy_u64_ptr(dst.as_mut_ptr(), src.as_ptr(), dst.len());
     }
@@ -1,16 +1,17 @@
 use crate::SortContext;
 
+use super::probe::Probe;
 use super::{common, radix_sort_lsd_base256};
 
 const MAX_COUNTING_RANGE: usize = 1 << 20;
 
-pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], ctx: &mut SortContext, probe: P) {
     let len = data.len();
     if len < 2 {
         return;
     }
 
-    let Some((min, max)) = common::min_max(data) else {
+    let Some((min, max)) = common::min_max(data, probe) else {
         return;
     };
     if min == max {
@@ -19,13 +20,13 @@ pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
 
     let range_u128 = (max as u128) - (min as u128) + 1;
     if range_u128 > usize::MAX as u128 {
-        radix_sort_lsd_base256::sort(data, ctx);
+        radix_sort_lsd_base256::sort(data, ctx, probe);
         return;
     }
 
     let range = range_u128 as usize;
     if range > MAX_COUNTING_RANGE || range > len.saturating_mul(24) {
-        radix_sort_lsd_base256::sort(data, ctx);
+        radix_sort_lsd_base256::sort(data, ctx, probe);
         return;
     }
 
@@ -44,6 +45,35 @@ pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
         }
         let value = min + i as u64;
         data[out..(out + count)].fill(value);
+        probe.moved(count as u64);
         out += count;
     }
 }
+
+/// Counting sort over the fixed range `0..=max_value` that leaves the
+/// per-value counts in `ctx.var_counts[..=max_value]`.
+///
+/// Unlike [`sort`] there is no radix fallback: the histogram needs one slot
+/// per value, so the caller picks the range.
+pub fn sort_with_histogram<'a>(
+    data: &mut [u64],
+    max_value: u64,
+    ctx: &'a mut SortContext,
+) -> &'a [usize] {
+    assert!(max_value < usize::MAX as u64, "max_value is too large");
+    let range = max_value as usize + 1;
+    let counts = ctx.ensure_var_counts(range);
+    counts.fill(0);
+
+    for &x in data.iter() {
+        assert!(x <= max_value, "value {x} exceeds max_value {max_value}");
+        counts[x as usize] += 1;
+    }
+
+    let mut out = 0usize;
+    for (value, &count) in counts.iter().enumerate() {
+        data[out..(out + count)].fill(value as u64);
+        out += count;
+    }
+    counts
+}
@@ -1,12 +1,13 @@
 use crate::{SortContext, TUNED_PARAMS};
 
 use super::common;
+use super::probe::Probe;
 
-pub fn sort(data: &mut [u64], _ctx: &mut SortContext) {
-    dual_pivot_quick_sort(data);
+pub fn sort<P: Probe>(data: &mut [u64], _ctx: &mut SortContext, probe: P) {
+    dual_pivot_quick_sort(data, probe);
 }
 
-fn dual_pivot_quick_sort(mut data: &mut [u64]) {
+fn dual_pivot_quick_sort<P: Probe>(mut data: &mut [u64], probe: P) {
     while data.len() > TUNED_PARAMS.insertion_threshold {
         let len = data.len();
         let i1 = len / 3;
@@ -17,22 +18,22 @@ fn dual_pivot_quick_sort(mut data: &mut [u64]) {
 
         let mut p = data[i1];
         let mut q = data[i2];
-        if p > q {
+        if probe.gt(p, q) {
             std::mem::swap(&mut p, &mut q);
         }
 
         if p == q {
-            let (lt, gt) = common::partition_3way(data, p);
+            let (lt, gt) = common::partition_3way(data, p, probe);
             if lt == 0 && gt == len {
                 return;
             }
             let (left, rest) = data.split_at_mut(lt);
             let (_, right) = rest.split_at_mut(gt - lt);
             if left.len() < right.len() {
-                dual_pivot_quick_sort(left);
+                dual_pivot_quick_sort(left, probe);
                 data = right;
             } else {
-                dual_pivot_quick_sort(right);
+                dual_pivot_quick_sort(right, probe);
                 data = left;
             }
             continue;
@@ -43,31 +44,31 @@ fn dual_pivot_quick_sort(mut data: &mut [u64]) {
         let mut gt = len;
         while i < gt {
             let v = data[i];
-            if v < p {
-                data.swap(i, lt);
+            if probe.lt(v, p) {
+                probe.swap(data, i, lt);
                 lt += 1;
                 i += 1;
-            } else if v > q {
+            } else if probe.gt(v, q) {
                 gt -= 1;
-                data.swap(i, gt);
+                probe.swap(data, i, gt);
             } else {
                 i += 1;
             }
         }
 
         if lt == 0 && gt == len {
-            let pivot = common::choose_pivot_ninther(data);
-            let (eq_left, eq_right) = common::partition_3way(data, pivot);
+            let pivot = common::choose_pivot_ninther(data, probe);
+            let (eq_left, eq_right) = common::partition_3way(data, pivot, probe);
             if eq_left == 0 && eq_right == len {
                 return;
             }
             let (left, rest) = data.split_at_mut(eq_left);
             let (_, right) = rest.split_at_mut(eq_right - eq_left);
             if left.len() < right.len() {
-                dual_pivot_quick_sort(left);
+                dual_pivot_quick_sort(left, probe);
                 data = right;
             } else {
-                dual_pivot_quick_sort(right);
+                dual_pivot_quick_sort(right, probe);
                 data = left;
             }
             continue;
@@ -78,19 +79,19 @@ fn dual_pivot_quick_sort(mut data: &mut [u64]) {
         let (middle, right) = rest.split_at_mut(mid_len);
 
         if left.len() >= middle.len() && left.len() >= right.len() {
-            dual_pivot_quick_sort(middle);
-            dual_pivot_quick_sort(right);
+            dual_pivot_quick_sort(middle, probe);
+            dual_pivot_quick_sort(right, probe);
             data = left;
         } else if middle.len() >= right.len() {
-            dual_pivot_quick_sort(left);
-            dual_pivot_quick_sort(right);
+            dual_pivot_quick_sort(left, probe);
+            dual_pivot_quick_sort(right, probe);
             data = middle;
         } else {
-            dual_pivot_quick_sort(left);
-            dual_pivot_quick_sort(middle);
+            dual_pivot_quick_sort(left, probe);
+            dual_pivot_quick_sort(middle, probe);
             data = right;
         }
     }
 
-    common::insertion_sort(data);
+    common::insertion_sort(data, probe);
 }
@@ -1,10 +1,12 @@
 use crate::SortContext;
 
-pub fn sort(data: &mut [u64], _ctx: &mut SortContext) {
-    heap_sort(data);
+use super::probe::Probe;
+
+pub fn sort<P: Probe>(data: &mut [u64], _ctx: &mut SortContext, probe: P) {
+    heap_sort(data, probe);
 }
 
-pub fn heap_sort(data: &mut [u64]) {
+pub fn heap_sort<P: Probe>(data: &mut [u64], probe: P) {
     let len = data.len();
     if len < 2 {
         return;
@@ -12,7 +14,7 @@ pub fn heap_sort(data: &mut [u64]) {
 
     let mut start = (len - 2) / 2;
     loop {
-        sift_down(data, start, len);
+        sift_down(data, start, len, probe);
         if start == 0 {
             break;
         }
@@ -21,14 +23,14 @@ pub fn heap_sort(data: &mut [u64]) {
 
     let mut end = len - 1;
     while end > 0 {
-        data.swap(0, end);
-        sift_down(data, 0, end);
+        probe.swap(data, 0, end);
+        sift_down(data, 0, end, probe);
         end -= 1;
     }
 }
 
 #[inline]
-fn sift_down(data: &mut [u64], mut root: usize, end: usize) {
+fn sift_down<P: Probe>(data: &mut [u64], mut root: usize, end: usize, probe: P) {
     let ptr = data.as_mut_ptr();
     unsafe {
         loop {
@@ -38,15 +40,16 @@ fn sift_down(data: &mut [u64], mut root: usize, end: usize) {
             }
 
             let mut swap_idx = child;
-            if child + 1 < end && *ptr.add(child) < *ptr.add(child + 1) {
+            if child + 1 < end && probe.lt(*ptr.add(child), *ptr.add(child + 1)) {
                 swap_idx = child + 1;
             }
 
-            if *ptr.add(root) >= *ptr.add(swap_idx) {
+            if probe.ge(*ptr.add(root), *ptr.add(swap_idx)) {
                 break;
             }
 
             std::ptr::swap(ptr.add(root), ptr.add(swap_idx));
+            probe.moved(2);
             root = swap_idx;
         }
     }
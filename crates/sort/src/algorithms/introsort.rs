@@ -1,37 +1,45 @@
 use crate::{SortContext, TUNED_PARAMS};
 
-use super::{common, heap_sort};
+use super::probe::Probe;
+use super::{common, heap_sort, sorting_network};
 
-pub fn sort(data: &mut [u64], _ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], ctx: &mut SortContext, probe: P) {
+    sort_reporting(data, ctx, probe);
+}
+
+/// Same as [`sort`], returning whether any subarray hit the depth limit and
+/// was finished by heapsort.
+pub fn sort_reporting<P: Probe>(data: &mut [u64], _ctx: &mut SortContext, probe: P) -> bool {
     if data.len() < 2 {
-        return;
+        return false;
     }
     let depth_limit = common::introsort_depth_limit(data.len()) + 1;
-    introsort_recursive(data, depth_limit);
+    introsort_recursive(data, depth_limit, probe)
 }
 
-fn introsort_recursive(mut data: &mut [u64], mut depth_limit: usize) {
-    while data.len() > TUNED_PARAMS.insertion_threshold {
+fn introsort_recursive<P: Probe>(mut data: &mut [u64], mut depth_limit: usize, probe: P) -> bool {
+    let mut fell_back = false;
+    while data.len() > TUNED_PARAMS.sorting_network_threshold {
         if depth_limit == 0 {
-            heap_sort::heap_sort(data);
-            return;
+            heap_sort::heap_sort(data, probe);
+            return true;
         }
         depth_limit -= 1;
 
-        let pivot = common::choose_pivot_ninther(data);
-        let split = common::partition_hoare(data, pivot);
+        let pivot = common::choose_pivot_ninther(data, probe);
+        let split = common::partition_hoare(data, pivot, probe);
         if split == 0 || split + 1 == data.len() {
-            let (lt, gt) = common::partition_3way(data, pivot);
+            let (lt, gt) = common::partition_3way(data, pivot, probe);
             if lt == 0 && gt == data.len() {
-                return;
+                return fell_back;
             }
             let (left, rest) = data.split_at_mut(lt);
             let (_, right) = rest.split_at_mut(gt - lt);
             if left.len() < right.len() {
-                introsort_recursive(left, depth_limit);
+                fell_back |= introsort_recursive(left, depth_limit, probe);
                 data = right;
             } else {
-                introsort_recursive(right, depth_limit);
+                fell_back |= introsort_recursive(right, depth_limit, probe);
                 data = left;
             }
             continue;
@@ -39,13 +47,14 @@ fn introsort_recursive(mut data: &mut [u64], mut depth_limit: usize) {
         let (left, right) = data.split_at_mut(split + 1);
 
         if left.len() < right.len() {
-            introsort_recursive(left, depth_limit);
+            fell_back |= introsort_recursive(left, depth_limit, probe);
             data = right;
         } else {
-            introsort_recursive(right, depth_limit);
+            fell_back |= introsort_recursive(right, depth_limit, probe);
             data = left;
         }
     }
 
-    common::insertion_sort(data);
+    sorting_network::sort(data, probe);
+    fell_back
 }
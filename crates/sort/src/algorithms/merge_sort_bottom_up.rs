@@ -1,57 +1,58 @@
 use crate::{SortContext, TUNED_PARAMS};
 
 use super::common;
+use super::probe::Probe;
 
-pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], ctx: &mut SortContext, probe: P) {
     let len = data.len();
     if len < 2 {
         return;
     }
-    if common::is_sorted_non_decreasing(data) {
+    if common::is_sorted_non_decreasing(data, probe) {
         return;
     }
 
     let run = TUNED_PARAMS.insertion_threshold.max(8);
     if len <= run {
-        common::insertion_sort(data);
+        common::insertion_sort(data, probe);
         return;
     }
 
     for chunk in data.chunks_mut(run) {
-        common::insertion_sort(chunk);
+        common::insertion_sort(chunk, probe);
     }
 
     let buf = ctx.ensure_scratch(len);
-    common::copy_u64_slice(buf, data);
+    common::copy_u64_slice(buf, data, probe);
 
     let mut width = run;
     let mut src_is_buf = true;
     while width < len {
         if src_is_buf {
-            merge_pass(&buf[..len], data, width);
+            merge_pass(&buf[..len], data, width, probe);
         } else {
-            merge_pass(data, &mut buf[..len], width);
+            merge_pass(data, &mut buf[..len], width, probe);
         }
         src_is_buf = !src_is_buf;
         width <<= 1;
     }
 
     if src_is_buf {
-        common::copy_u64_slice(data, &buf[..len]);
+        common::copy_u64_slice(data, &buf[..len], probe);
     }
 }
 
-fn merge_pass(src: &[u64], dst: &mut [u64], width: usize) {
+fn merge_pass<P: Probe>(src: &[u64], dst: &mut [u64], width: usize, probe: P) {
     let len = src.len();
     let mut left = 0usize;
     while left < len {
         let mid = (left + width).min(len);
         let right = (mid + width).min(len);
 
-        if mid >= right || src[mid - 1] <= src[mid] {
-            common::copy_u64_slice(&mut dst[left..right], &src[left..right]);
+        if mid >= right || probe.le(src[mid - 1], src[mid]) {
+            common::copy_u64_slice(&mut dst[left..right], &src[left..right], probe);
         } else {
-            common::merge_ranges(src, dst, left, mid, right);
+            common::merge_ranges(src, dst, left, mid, right, probe);
         }
 
         left = right;
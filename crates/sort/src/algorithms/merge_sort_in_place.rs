@@ -0,0 +1,97 @@
+use crate::{SortContext, TUNED_PARAMS};
+
+use super::common;
+use super::probe::Probe;
+
+pub fn sort<P: Probe>(data: &mut [u64], _ctx: &mut SortContext, probe: P) {
+    if data.len() < 2 {
+        return;
+    }
+    if common::is_sorted_non_decreasing(data, probe) {
+        return;
+    }
+    merge_sort_in_place(data, probe);
+}
+
+fn merge_sort_in_place<P: Probe>(data: &mut [u64], probe: P) {
+    let len = data.len();
+    if len <= TUNED_PARAMS.insertion_threshold {
+        common::insertion_sort(data, probe);
+        return;
+    }
+
+    let mid = len >> 1;
+    merge_sort_in_place(&mut data[..mid], probe);
+    merge_sort_in_place(&mut data[mid..], probe);
+    if probe.le(data[mid - 1], data[mid]) {
+        return;
+    }
+    merge_in_place(data, mid, probe);
+}
+
+// Rotation-based merge of `data[..mid]` and `data[mid..]`: split the longer run
+// in half, binary-search the matching cut in the other run, rotate the middle
+// so both cuts meet, and recurse on the two halves. Each level does O(n) moves
+// over O(log n) levels with no scratch buffer.
+fn merge_in_place<P: Probe>(data: &mut [u64], mid: usize, probe: P) {
+    let len = data.len();
+    if mid == 0 || mid == len {
+        return;
+    }
+    if len == 2 {
+        if probe.gt(data[0], data[1]) {
+            probe.swap(data, 0, 1);
+        }
+        return;
+    }
+
+    let (cut1, cut2) = if mid >= len - mid {
+        let cut1 = mid >> 1;
+        let pivot = data[cut1];
+        let cut2 = mid + lower_bound(&data[mid..], pivot, probe);
+        (cut1, cut2)
+    } else {
+        let cut2 = mid + ((len - mid) >> 1);
+        let pivot = data[cut2];
+        let cut1 = upper_bound(&data[..mid], pivot, probe);
+        (cut1, cut2)
+    };
+
+    data[cut1..cut2].rotate_left(mid - cut1);
+    probe.moved((cut2 - cut1) as u64);
+
+    let new_mid = cut1 + (cut2 - mid);
+    let (left, right) = data.split_at_mut(new_mid);
+    merge_in_place(left, cut1, probe);
+    merge_in_place(right, cut2 - new_mid, probe);
+}
+
+#[inline]
+fn lower_bound<P: Probe>(data: &[u64], key: u64, probe: P) -> usize {
+    let mut left = 0usize;
+    let mut right = data.len();
+    while left < right {
+        let mid = left + ((right - left) >> 1);
+        if probe.lt(data[mid], key) {
+            left = mid + 1;
+        } else {
+            right = mid;
+        }
+    }
+    left
+}
+
+#[inline]
+fn upper_bound<P: Probe>(data: &[u64], key: u64, probe: P) -> usize {
+    let mut left = 0usize;
+    let mut right = data.len();
+    while left < right {
+        let mid = left + ((right - left) >> 1);
+        if probe.le(data[mid], key) {
+            left = mid + 1;
+        } else {
+            right = mid;
+        }
+    }
+    left
+}
@@ -1,38 +1,45 @@
 use crate::{SortContext, TUNED_PARAMS};
 
 use super::common;
+use super::probe::Probe;
 
-pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], ctx: &mut SortContext, probe: P) {
     let len = data.len();
     if len < 2 {
         return;
     }
-    if common::is_sorted_non_decreasing(data) {
+    if common::is_sorted_non_decreasing(data, probe) {
         return;
     }
 
     let buf = ctx.ensure_scratch(len);
-    common::copy_u64_slice(buf, data);
-    merge_sort_recursive(buf, data, 0, len);
+    common::copy_u64_slice(buf, data, probe);
+    merge_sort_recursive(buf, data, 0, len, probe);
 }
 
-fn merge_sort_recursive(src: &mut [u64], dst: &mut [u64], left: usize, right: usize) {
+fn merge_sort_recursive<P: Probe>(
+    src: &mut [u64],
+    dst: &mut [u64],
+    left: usize,
+    right: usize,
+    probe: P,
+) {
     let len = right - left;
     if len <= TUNED_PARAMS.insertion_threshold {
-        common::copy_u64_slice(&mut dst[left..right], &src[left..right]);
-        common::insertion_sort(&mut dst[left..right]);
+        common::copy_u64_slice(&mut dst[left..right], &src[left..right], probe);
+        common::insertion_sort(&mut dst[left..right], probe);
         return;
     }
 
     let mid = left + (len >> 1);
 
-    merge_sort_recursive(dst, src, left, mid);
-    merge_sort_recursive(dst, src, mid, right);
+    merge_sort_recursive(dst, src, left, mid, probe);
+    merge_sort_recursive(dst, src, mid, right, probe);
 
-    if src[mid - 1] <= src[mid] {
-        common::copy_u64_slice(&mut dst[left..right], &src[left..right]);
+    if probe.le(src[mid - 1], src[mid]) {
+        common::copy_u64_slice(&mut dst[left..right], &src[left..right], probe);
         return;
     }
 
-    common::merge_ranges(src, dst, left, mid, right);
+    common::merge_ranges(src, dst, left, mid, right, probe);
 }
@@ -9,13 +9,19 @@ pub mod heap_sort;
 pub mod insertion_sort;
 pub mod introsort;
 pub mod merge_sort_bottom_up;
+pub mod merge_sort_in_place;
 pub mod merge_sort_top_down;
 pub mod natural_merge_sort;
+#[cfg(feature = "rayon")]
+pub mod par_merge_sort;
 pub mod pdqsort_like;
 pub mod pigeonhole_sort;
+pub mod presortedness;
+pub mod probe;
 pub mod quick_merge_sort;
 pub mod quick_sort_3way;
 pub mod quick_sort_median3;
 pub mod radix_sort_lsd_base256;
 pub mod shell_sort_ciura;
+pub mod sorting_network;
 pub mod timsort;
@@ -1,17 +1,18 @@
 use crate::{SortContext, TUNED_PARAMS};
 
 use super::common;
+use super::probe::Probe;
 
-pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], ctx: &mut SortContext, probe: P) {
     let len = data.len();
     if len < 2 {
         return;
     }
-    if common::is_sorted_non_decreasing(data) {
+    if common::is_sorted_non_decreasing(data, probe) {
         return;
     }
     if len <= TUNED_PARAMS.insertion_threshold {
-        common::insertion_sort(data);
+        common::insertion_sort(data, probe);
         return;
     }
 
@@ -20,7 +21,7 @@ pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
 
     loop {
         runs.clear();
-        collect_runs(data, &mut runs);
+        collect_runs(data, &mut runs, probe);
         if runs.len() <= 1 {
             break;
         }
@@ -36,23 +37,28 @@ pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
                     &data[l0..l1],
                     &data[r0..r1],
                     &mut scratch[write..(write + total)],
+                    probe,
                 );
                 write += total;
                 idx += 2;
             } else {
                 let (start, end) = runs[idx];
                 let run_len = end - start;
-                common::copy_u64_slice(&mut scratch[write..(write + run_len)], &data[start..end]);
+                common::copy_u64_slice(
+                    &mut scratch[write..(write + run_len)],
+                    &data[start..end],
+                    probe,
+                );
                 write += run_len;
                 idx += 1;
             }
         }
 
-        common::copy_u64_slice(data, &scratch[..len]);
+        common::copy_u64_slice(data, &scratch[..len], probe);
     }
 }
 
-fn collect_runs(data: &mut [u64], runs: &mut Vec<(usize, usize)>) {
+fn collect_runs<P: Probe>(data: &mut [u64], runs: &mut Vec<(usize, usize)>, probe: P) {
     let n = data.len();
     let mut i = 0usize;
 
@@ -64,15 +70,16 @@ fn collect_runs(data: &mut [u64], runs: &mut Vec<(usize, usize)>) {
             break;
         }
 
-        if data[i - 1] <= data[i] {
-            while i < n && data[i - 1] <= data[i] {
+        if probe.le(data[i - 1], data[i]) {
+            while i < n && probe.le(data[i - 1], data[i]) {
                 i += 1;
             }
         } else {
-            while i < n && data[i - 1] > data[i] {
+            while i < n && probe.gt(data[i - 1], data[i]) {
                 i += 1;
             }
             data[start..i].reverse();
+            probe.moved(((i - start) & !1) as u64);
         }
 
         runs.push((start, i));
@@ -80,13 +87,13 @@ fn collect_runs(data: &mut [u64], runs: &mut Vec<(usize, usize)>) {
 }
 
 #[inline]
-fn merge_two_runs(left: &[u64], right: &[u64], dst: &mut [u64]) {
+fn merge_two_runs<P: Probe>(left: &[u64], right: &[u64], dst: &mut [u64], probe: P) {
     let mut i = 0usize;
     let mut j = 0usize;
     let mut k = 0usize;
 
     while i < left.len() && j < right.len() {
-        if left[i] <= right[j] {
+        if probe.le(left[i], right[j]) {
             dst[k] = left[i];
             i += 1;
         } else {
@@ -95,10 +102,11 @@ fn merge_two_runs(left: &[u64], right: &[u64], dst: &mut [u64]) {
         }
         k += 1;
     }
+    probe.moved(k as u64);
 
     if i < left.len() {
-        common::copy_u64_slice(&mut dst[k..(k + (left.len() - i))], &left[i..]);
+        common::copy_u64_slice(&mut dst[k..(k + (left.len() - i))], &left[i..], probe);
     } else if j < right.len() {
-        common::copy_u64_slice(&mut dst[k..(k + (right.len() - j))], &right[j..]);
+        common::copy_u64_slice(&mut dst[k..(k + (right.len() - j))], &right[j..], probe);
     }
 }
@@ -0,0 +1,53 @@
+use crate::{SortContext, TUNED_PARAMS};
+
+use super::common;
+use super::probe::Silent;
+
+// Below this length the join overhead outweighs the parallel speedup, so the
+// recursion keeps going on the current thread.
+const PARALLEL_CUTOFF: usize = 1 << 13;
+
+pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
+    let len = data.len();
+    if len < 2 {
+        return;
+    }
+    if common::is_sorted_non_decreasing(data, Silent) {
+        return;
+    }
+
+    let buf = ctx.ensure_scratch(len);
+    par_merge_sort_recursive(data, buf);
+}
+
+// `buf` always mirrors `data`'s range, so each task owns a disjoint slice of
+// the scratch buffer and no synchronization is needed.
+fn par_merge_sort_recursive(data: &mut [u64], buf: &mut [u64]) {
+    let len = data.len();
+    if len <= TUNED_PARAMS.insertion_threshold {
+        common::insertion_sort(data, Silent);
+        return;
+    }
+
+    let mid = len >> 1;
+    {
+        let (data_left, data_right) = data.split_at_mut(mid);
+        let (buf_left, buf_right) = buf.split_at_mut(mid);
+        if len >= PARALLEL_CUTOFF {
+            rayon::join(
+                || par_merge_sort_recursive(data_left, buf_left),
+                || par_merge_sort_recursive(data_right, buf_right),
+            );
+        } else {
+            par_merge_sort_recursive(data_left, buf_left);
+            par_merge_sort_recursive(data_right, buf_right);
+        }
+    }
+
+    if data[mid - 1] <= data[mid] {
+        return;
+    }
+
+    common::copy_u64_slice(buf, data, Silent);
+    common::merge_ranges(buf, data, 0, mid, len, Silent);
+}
@@ -1,29 +1,35 @@
 use crate::{SortContext, TUNED_PARAMS};
 
-use super::{common, heap_sort};
+use super::probe::Probe;
+use super::{common, heap_sort, sorting_network};
 
-pub fn sort(data: &mut [u64], _ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], _ctx: &mut SortContext, probe: P) {
     if data.len() < 2 {
         return;
     }
     let bad_allowed = common::floor_log2(data.len()) + 2;
-    pdqsort_like(data, bad_allowed, true);
+    pdqsort_like(data, bad_allowed, true, probe);
 }
 
-fn pdqsort_like(mut data: &mut [u64], mut bad_allowed: usize, mut was_balanced: bool) {
-    while data.len() > TUNED_PARAMS.insertion_threshold {
+fn pdqsort_like<P: Probe>(
+    mut data: &mut [u64],
+    mut bad_allowed: usize,
+    mut was_balanced: bool,
+    probe: P,
+) {
+    while data.len() > TUNED_PARAMS.sorting_network_threshold {
         if bad_allowed == 0 {
-            heap_sort::heap_sort(data);
+            heap_sort::heap_sort(data, probe);
             return;
         }
 
         if !was_balanced {
-            break_patterns(data);
+            break_patterns(data, probe);
         }
 
         let len = data.len();
-        let pivot = common::choose_pivot_ninther(data);
-        let (lt, gt) = common::partition_3way(data, pivot);
+        let pivot = common::choose_pivot_ninther(data, probe);
+        let (lt, gt) = common::partition_3way(data, pivot, probe);
         if lt == 0 && gt == len {
             return;
         }
@@ -40,18 +46,18 @@ fn pdqsort_like(mut data: &mut [u64], mut bad_allowed: usize, mut was_balanced:
         let (_, right) = rest.split_at_mut(gt - lt);
 
         if left.len() < right.len() {
-            pdqsort_like(left, bad_allowed, was_balanced);
+            pdqsort_like(left, bad_allowed, was_balanced, probe);
             data = right;
         } else {
-            pdqsort_like(right, bad_allowed, was_balanced);
+            pdqsort_like(right, bad_allowed, was_balanced, probe);
             data = left;
         }
     }
 
-    common::insertion_sort(data);
+    sorting_network::sort(data, probe);
 }
 
-fn break_patterns(data: &mut [u64]) {
+fn break_patterns<P: Probe>(data: &mut [u64], probe: P) {
     if data.len() < 8 {
         return;
     }
@@ -61,7 +67,7 @@ fn break_patterns(data: &mut [u64]) {
     let a = len / 4;
     let b = (len * 3) / 4;
 
-    data.swap(0, mid);
-    data.swap(a, len - 1);
-    data.swap(b, (mid + 1).min(len - 1));
+    probe.swap(data, 0, mid);
+    probe.swap(data, a, len - 1);
+    probe.swap(data, b, (mid + 1).min(len - 1));
 }
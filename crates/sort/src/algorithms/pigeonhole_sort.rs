@@ -1,16 +1,17 @@
 use crate::SortContext;
 
+use super::probe::Probe;
 use super::{common, radix_sort_lsd_base256};
 
 const MAX_PIGEONHOLE_RANGE: usize = 1 << 21;
 
-pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], ctx: &mut SortContext, probe: P) {
     let len = data.len();
     if len < 2 {
         return;
     }
 
-    let Some((min, max)) = common::min_max(data) else {
+    let Some((min, max)) = common::min_max(data, probe) else {
         return;
     };
     if min == max {
@@ -19,13 +20,13 @@ pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
 
     let range_u128 = (max as u128) - (min as u128) + 1;
     if range_u128 > usize::MAX as u128 {
-        radix_sort_lsd_base256::sort(data, ctx);
+        radix_sort_lsd_base256::sort(data, ctx, probe);
         return;
     }
 
     let range = range_u128 as usize;
     if range > MAX_PIGEONHOLE_RANGE || range > len.saturating_mul(48) {
-        radix_sort_lsd_base256::sort(data, ctx);
+        radix_sort_lsd_base256::sort(data, ctx, probe);
         return;
     }
 
@@ -41,6 +42,7 @@ pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
         let value = min + offset as u64;
         if count > 0 {
             data[out..(out + count)].fill(value);
+            probe.moved(count as u64);
             out += count;
         }
     }
@@ -0,0 +1,73 @@
+use crate::SortContext;
+
+use super::common;
+use super::probe::Silent;
+
+pub fn count_runs(data: &[u64]) -> usize {
+    if data.is_empty() {
+        return 0;
+    }
+    1 + data.windows(2).filter(|w| w[0] > w[1]).count()
+}
+
+/// Counts pairs `i < j` with `data[i] > data[j]` by merge-sorting a copy held
+/// in `ctx.scratch`, ping-ponging with `ctx.aux`. Equal keys are not inversions.
+pub fn count_inversions(data: &[u64], ctx: &mut SortContext) -> u64 {
+    let len = data.len();
+    if len < 2 {
+        return 0;
+    }
+
+    let SortContext { scratch, aux, .. } = ctx;
+    if scratch.len() < len {
+        scratch.resize(len, 0);
+    }
+    if aux.len() < len {
+        aux.resize(len, 0);
+    }
+    common::copy_u64_slice(&mut scratch[..len], data, Silent);
+
+    let mut src = &mut scratch[..len];
+    let mut dst = &mut aux[..len];
+    let mut inversions = 0u64;
+    let mut width = 1usize;
+    while width < len {
+        let mut left = 0usize;
+        while left < len {
+            let mid = (left + width).min(len);
+            let right = (mid + width).min(len);
+            inversions += merge_counting(src, dst, left, mid, right);
+            left = right;
+        }
+        std::mem::swap(&mut src, &mut dst);
+        width <<= 1;
+    }
+    inversions
+}
+
+fn merge_counting(src: &[u64], dst: &mut [u64], left: usize, mid: usize, right: usize) -> u64 {
+    let mut i = left;
+    let mut j = mid;
+    let mut k = left;
+    let mut inversions = 0u64;
+
+    while i < mid && j < right {
+        if src[i] <= src[j] {
+            dst[k] = src[i];
+            i += 1;
+        } else {
+            // Every element still waiting in the left run is greater than src[j].
+            inversions += (mid - i) as u64;
+            dst[k] = src[j];
+            j += 1;
+        }
+        k += 1;
+    }
+
+    if i < mid {
+        dst[k..right].copy_from_slice(&src[i..mid]);
+    } else {
+        dst[k..right].copy_from_slice(&src[j..right]);
+    }
+    inversions
+}
@@ -0,0 +1,81 @@
+use std::cell::Cell;
+
+use crate::SortMetrics;
+
+/// Observation hook threaded through every algorithm.
+///
+/// `Silent` is zero-sized and every method is a no-op, so the regular entry
+/// points compile to the same code as an uninstrumented sort. `&Counter`
+/// tallies key comparisons and element writes for `sort_u64_instrumented`.
+pub trait Probe: Copy {
+    fn compare(self, n: u64);
+    fn moved(self, n: u64);
+
+    #[inline(always)]
+    fn lt(self, a: u64, b: u64) -> bool {
+        self.compare(1);
+        a < b
+    }
+
+    #[inline(always)]
+    fn le(self, a: u64, b: u64) -> bool {
+        self.compare(1);
+        a <= b
+    }
+
+    #[inline(always)]
+    fn gt(self, a: u64, b: u64) -> bool {
+        self.compare(1);
+        a > b
+    }
+
+    #[inline(always)]
+    fn ge(self, a: u64, b: u64) -> bool {
+        self.compare(1);
+        a >= b
+    }
+
+    #[inline(always)]
+    fn swap(self, data: &mut [u64], a: usize, b: usize) {
+        self.moved(2);
+        data.swap(a, b);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Silent;
+
+impl Probe for Silent {
+    #[inline(always)]
+    fn compare(self, _n: u64) {}
+
+    #[inline(always)]
+    fn moved(self, _n: u64) {}
+}
+
+#[derive(Debug, Default)]
+pub struct Counter {
+    comparisons: Cell<u64>,
+    moves: Cell<u64>,
+}
+
+impl Counter {
+    pub fn metrics(&self) -> SortMetrics {
+        SortMetrics {
+            comparisons: self.comparisons.get(),
+            moves: self.moves.get(),
+        }
+    }
+}
+
+impl Probe for &Counter {
+    #[inline]
+    fn compare(self, n: u64) {
+        self.comparisons.set(self.comparisons.get() + n);
+    }
+
+    #[inline]
+    fn moved(self, n: u64) {
+        self.moves.set(self.moves.get() + n);
+    }
+}
@@ -1,39 +1,45 @@
 use crate::{SortContext, TUNED_PARAMS};
 
+use super::probe::Probe;
 use super::{common, merge_sort_top_down};
 
-pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], ctx: &mut SortContext, probe: P) {
     if data.len() < 2 {
         return;
     }
     let depth_limit = common::introsort_depth_limit(data.len()) + 1;
-    quick_merge_sort(data, ctx, depth_limit);
+    quick_merge_sort(data, ctx, depth_limit, probe);
 }
 
-fn quick_merge_sort(mut data: &mut [u64], ctx: &mut SortContext, mut depth_limit: usize) {
+fn quick_merge_sort<P: Probe>(
+    mut data: &mut [u64],
+    ctx: &mut SortContext,
+    mut depth_limit: usize,
+    probe: P,
+) {
     while data.len() > TUNED_PARAMS.insertion_threshold {
         if depth_limit == 0 {
-            merge_sort_top_down::sort(data, ctx);
+            merge_sort_top_down::sort(data, ctx, probe);
             return;
         }
 
         depth_limit -= 1;
         let len = data.len();
-        let pivot = common::choose_pivot_ninther(data);
-        let cut = common::partition_hoare(data, pivot) + 1;
+        let pivot = common::choose_pivot_ninther(data, probe);
+        let cut = common::partition_hoare(data, pivot, probe) + 1;
 
         if cut == 0 || cut == len {
-            let (lt, gt) = common::partition_3way(data, pivot);
+            let (lt, gt) = common::partition_3way(data, pivot, probe);
             if lt == 0 && gt == len {
                 return;
             }
             let (left, rest) = data.split_at_mut(lt);
             let (_, right) = rest.split_at_mut(gt - lt);
             if left.len() < right.len() {
-                quick_merge_sort(left, ctx, depth_limit);
+                quick_merge_sort(left, ctx, depth_limit, probe);
                 data = right;
             } else {
-                quick_merge_sort(right, ctx, depth_limit);
+                quick_merge_sort(right, ctx, depth_limit, probe);
                 data = left;
             }
             continue;
@@ -43,23 +49,23 @@ fn quick_merge_sort(mut data: &mut [u64], ctx: &mut SortContext, mut depth_limit
         let unbalanced = left.len() < (len / 8) || right.len() < (len / 8);
         if unbalanced {
             if left.len() < right.len() {
-                quick_merge_sort(left, ctx, depth_limit);
-                merge_sort_top_down::sort(right, ctx);
+                quick_merge_sort(left, ctx, depth_limit, probe);
+                merge_sort_top_down::sort(right, ctx, probe);
             } else {
-                quick_merge_sort(right, ctx, depth_limit);
-                merge_sort_top_down::sort(left, ctx);
+                quick_merge_sort(right, ctx, depth_limit, probe);
+                merge_sort_top_down::sort(left, ctx, probe);
             }
             return;
         }
 
         if left.len() < right.len() {
-            quick_merge_sort(left, ctx, depth_limit);
+            quick_merge_sort(left, ctx, depth_limit, probe);
             data = right;
         } else {
-            quick_merge_sort(right, ctx, depth_limit);
+            quick_merge_sort(right, ctx, depth_limit, probe);
             data = left;
         }
     }
 
-    common::insertion_sort(data);
+    common::insertion_sort(data, probe);
 }
@@ -1,15 +1,16 @@
 use crate::{SortContext, TUNED_PARAMS};
 
 use super::common;
+use super::probe::Probe;
 
-pub fn sort(data: &mut [u64], _ctx: &mut SortContext) {
-    quick_sort_3way(data);
+pub fn sort<P: Probe>(data: &mut [u64], _ctx: &mut SortContext, probe: P) {
+    quick_sort_3way(data, probe);
 }
 
-fn quick_sort_3way(mut data: &mut [u64]) {
+fn quick_sort_3way<P: Probe>(mut data: &mut [u64], probe: P) {
     while data.len() > TUNED_PARAMS.insertion_threshold {
-        let pivot = common::choose_pivot_ninther(data);
-        let (lt, gt) = common::partition_3way(data, pivot);
+        let pivot = common::choose_pivot_ninther(data, probe);
+        let (lt, gt) = common::partition_3way(data, pivot, probe);
 
         if lt == 0 && gt == data.len() {
             return;
@@ -19,13 +20,13 @@ fn quick_sort_3way(mut data: &mut [u64]) {
         let (_, right) = rest.split_at_mut(gt - lt);
 
         if left.len() < right.len() {
-            quick_sort_3way(left);
+            quick_sort_3way(left, probe);
             data = right;
         } else {
-            quick_sort_3way(right);
+            quick_sort_3way(right, probe);
             data = left;
         }
     }
 
-    common::insertion_sort(data);
+    common::insertion_sort(data, probe);
 }
@@ -1,36 +1,37 @@
 use crate::{SortContext, TUNED_PARAMS};
 
 use super::common;
+use super::probe::Probe;
 
-pub fn sort(data: &mut [u64], _ctx: &mut SortContext) {
-    quick_sort(data);
+pub fn sort<P: Probe>(data: &mut [u64], _ctx: &mut SortContext, probe: P) {
+    quick_sort(data, probe);
 }
 
-pub fn quick_sort(data: &mut [u64]) {
-    quick_sort_recursive(data);
+pub fn quick_sort<P: Probe>(data: &mut [u64], probe: P) {
+    quick_sort_recursive(data, probe);
 }
 
-fn quick_sort_recursive(mut data: &mut [u64]) {
+fn quick_sort_recursive<P: Probe>(mut data: &mut [u64], probe: P) {
     while data.len() > TUNED_PARAMS.insertion_threshold {
         let len = data.len();
         let pivot = if data.len() >= 2048 {
-            common::choose_pivot_ninther(data)
+            common::choose_pivot_ninther(data, probe)
         } else {
-            common::choose_pivot_median3(data)
+            common::choose_pivot_median3(data, probe)
         };
-        let split = common::partition_hoare(data, pivot) + 1;
+        let split = common::partition_hoare(data, pivot, probe) + 1;
         if split == 0 || split == len {
-            let (lt, gt) = common::partition_3way(data, pivot);
+            let (lt, gt) = common::partition_3way(data, pivot, probe);
             if lt == 0 && gt == len {
                 return;
             }
             let (left, rest) = data.split_at_mut(lt);
             let (_, right) = rest.split_at_mut(gt - lt);
             if left.len() < right.len() {
-                quick_sort_recursive(left);
+                quick_sort_recursive(left, probe);
                 data = right;
             } else {
-                quick_sort_recursive(right);
+                quick_sort_recursive(right, probe);
                 data = left;
             }
             continue;
@@ -39,13 +40,13 @@ fn quick_sort_recursive(mut data: &mut [u64]) {
         let (left, right) = data.split_at_mut(split);
 
         if left.len() < right.len() {
-            quick_sort_recursive(left);
+            quick_sort_recursive(left, probe);
             data = right;
         } else {
-            quick_sort_recursive(right);
+            quick_sort_recursive(right, probe);
             data = left;
         }
     }
 
-    common::insertion_sort(data);
+    common::insertion_sort(data, probe);
 }
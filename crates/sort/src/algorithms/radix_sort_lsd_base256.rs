@@ -1,12 +1,13 @@
 use crate::SortContext;
 
 use super::common;
+use super::probe::Probe;
 
-pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], ctx: &mut SortContext, probe: P) {
     if data.len() < 2 {
         return;
     }
-    if common::is_sorted_non_decreasing(data) {
+    if common::is_sorted_non_decreasing(data, probe) {
         return;
     }
 
@@ -19,16 +20,65 @@ pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
     {
         if std::arch::is_x86_feature_detected!("avx2") {
             unsafe {
-                radix_sort_impl_avx2(data, ctx, passes);
+                radix_sort_impl_avx2(data, ctx, passes, probe);
             }
             return;
         }
     }
 
-    radix_sort_impl_scalar(data, ctx, passes);
+    radix_sort_impl_scalar(data, ctx, passes, probe);
 }
 
-fn radix_sort_impl_scalar(data: &mut [u64], ctx: &mut SortContext, passes: usize) {
+/// `u32` keys need at most four byte passes; the data ping-pongs between
+/// `data` and `SortContext::scratch_u32` while reusing `counts256`.
+pub fn sort_u32(data: &mut [u32], ctx: &mut SortContext) {
+    let len = data.len();
+    if len < 2 || data.is_sorted() {
+        return;
+    }
+
+    let passes = radix_passes(data);
+    if passes == 0 {
+        return;
+    }
+
+    let SortContext {
+        scratch_u32,
+        counts256,
+        ..
+    } = ctx;
+    if scratch_u32.len() < len {
+        scratch_u32.resize(len, 0);
+    }
+
+    let mut src_is_data = true;
+    for pass in 0..passes {
+        let shift = pass * 8;
+
+        if src_is_data {
+            count_digits(data, counts256, shift);
+            prefix_sum(counts256);
+            scatter_scalar(data, &mut scratch_u32[..len], counts256, shift);
+        } else {
+            count_digits(&scratch_u32[..len], counts256, shift);
+            prefix_sum(counts256);
+            scatter_scalar(&scratch_u32[..len], data, counts256, shift);
+        }
+
+        src_is_data = !src_is_data;
+    }
+
+    if !src_is_data {
+        data.copy_from_slice(&scratch_u32[..len]);
+    }
+}
+
+fn radix_sort_impl_scalar<P: Probe>(
+    data: &mut [u64],
+    ctx: &mut SortContext,
+    passes: usize,
+    probe: P,
+) {
     let len = data.len();
     let SortContext {
         scratch, counts256, ..
@@ -51,17 +101,23 @@ fn radix_sort_impl_scalar(data: &mut [u64], ctx: &mut SortContext, passes: usize
             scatter_scalar(&scratch[..len], data, counts256, shift);
         }
 
+        probe.moved(len as u64);
         src_is_data = !src_is_data;
     }
 
     if !src_is_data {
-        common::copy_u64_slice(data, &scratch[..len]);
+        common::copy_u64_slice(data, &scratch[..len], probe);
     }
 }
 
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
-unsafe fn radix_sort_impl_avx2(data: &mut [u64], ctx: &mut SortContext, passes: usize) {
+unsafe fn radix_sort_impl_avx2<P: Probe>(
+    data: &mut [u64],
+    ctx: &mut SortContext,
+    passes: usize,
+    probe: P,
+) {
     let len = data.len();
     let SortContext {
         scratch, counts256, ..
@@ -88,20 +144,21 @@ unsafe fn radix_sort_impl_avx2(data: &mut [u64], ctx: &mut SortContext, passes:
             scatter_scalar(&scratch[..len], data, counts256, shift);
         }
 
+        probe.moved(len as u64);
         src_is_data = !src_is_data;
     }
 
     if !src_is_data {
-        common::copy_u64_slice(data, &scratch[..len]);
+        common::copy_u64_slice(data, &scratch[..len], probe);
     }
 }
 
 #[inline]
-fn radix_passes(data: &[u64]) -> usize {
-    let first = data[0];
+fn radix_passes<T: Copy + Into<u64>>(data: &[T]) -> usize {
+    let first: u64 = data[0].into();
     let mut diff = 0_u64;
     for &x in data.iter().skip(1) {
-        diff |= first ^ x;
+        diff |= first ^ x.into();
     }
     if diff == 0 {
         return 0;
@@ -110,7 +167,7 @@ fn radix_passes(data: &[u64]) -> usize {
 }
 
 #[inline]
-fn count_digits(src: &[u64], counts: &mut [usize; 256], shift: usize) {
+fn count_digits<T: Copy + Into<u64>>(src: &[T], counts: &mut [usize; 256], shift: usize) {
     let mut c0 = [0usize; 256];
     let mut c1 = [0usize; 256];
     let mut c2 = [0usize; 256];
@@ -118,10 +175,10 @@ fn count_digits(src: &[u64], counts: &mut [usize; 256], shift: usize) {
 
     let mut i = 0usize;
     while i + 4 <= src.len() {
-        let x0 = unsafe { *src.get_unchecked(i) };
-        let x1 = unsafe { *src.get_unchecked(i + 1) };
-        let x2 = unsafe { *src.get_unchecked(i + 2) };
-        let x3 = unsafe { *src.get_unchecked(i + 3) };
+        let x0: u64 = unsafe { *src.get_unchecked(i) }.into();
+        let x1: u64 = unsafe { *src.get_unchecked(i + 1) }.into();
+        let x2: u64 = unsafe { *src.get_unchecked(i + 2) }.into();
+        let x3: u64 = unsafe { *src.get_unchecked(i + 3) }.into();
 
         c0[((x0 >> shift) & 0xFF) as usize] += 1;
         c1[((x1 >> shift) & 0xFF) as usize] += 1;
@@ -131,7 +188,8 @@ fn count_digits(src: &[u64], counts: &mut [usize; 256], shift: usize) {
     }
 
     while i < src.len() {
-        c0[((src[i] >> shift) & 0xFF) as usize] += 1;
+        let x: u64 = src[i].into();
+        c0[((x >> shift) & 0xFF) as usize] += 1;
         i += 1;
     }
 
@@ -184,11 +242,16 @@ fn prefix_sum(counts: &mut [usize; 256]) {
 }
 
 #[inline]
-fn scatter_scalar(src: &[u64], dst: &mut [u64], offsets: &mut [usize; 256], shift: usize) {
+fn scatter_scalar<T: Copy + Into<u64>>(
+    src: &[T],
+    dst: &mut [T],
+    offsets: &mut [usize; 256],
+    shift: usize,
+) {
     let dst_ptr = dst.as_mut_ptr();
     unsafe {
         for &x in src {
-            let digit = ((x >> shift) & 0xFF) as usize;
+            let digit = ((x.into() >> shift) & 0xFF) as usize;
             let pos = *offsets.get_unchecked(digit);
             *dst_ptr.add(pos) = x;
             *offsets.get_unchecked_mut(digit) = pos + 1;
@@ -1,11 +1,13 @@
 use crate::SortContext;
 
+use super::probe::Probe;
+
 const GAPS_DESC: [usize; 20] = [
     7_860_916, 3_493_740, 1_552_773, 690_121, 306_720, 136_320, 60_587, 26_928, 11_968, 5_319,
     2_364, 1_051, 701, 301, 132, 57, 23, 10, 4, 1,
 ];
 
-pub fn sort(data: &mut [u64], _ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], _ctx: &mut SortContext, probe: P) {
     let len = data.len();
     if len < 2 {
         return;
@@ -22,13 +24,14 @@ pub fn sort(data: &mut [u64], _ctx: &mut SortContext) {
                 let mut j = i;
                 while j >= gap {
                     let prev = *ptr.add(j - gap);
-                    if prev <= x {
+                    if probe.le(prev, x) {
                         break;
                     }
                     *ptr.add(j) = prev;
                     j -= gap;
                 }
                 *ptr.add(j) = x;
+                probe.moved(((i - j) / gap) as u64 + 1);
             }
         }
     }
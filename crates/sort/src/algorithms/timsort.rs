@@ -1,6 +1,7 @@
 use crate::{SortContext, TUNED_PARAMS};
 
 use super::common;
+use super::probe::Probe;
 
 #[derive(Clone, Copy)]
 struct Run {
@@ -8,12 +9,12 @@ struct Run {
     len: usize,
 }
 
-pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
+pub fn sort<P: Probe>(data: &mut [u64], ctx: &mut SortContext, probe: P) {
     let n = data.len();
     if n < 2 {
         return;
     }
-    if common::is_sorted_non_decreasing(data) {
+    if common::is_sorted_non_decreasing(data, probe) {
         return;
     }
 
@@ -22,11 +23,11 @@ pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
 
     let mut i = 0usize;
     while i < n {
-        let mut run_len = count_run_and_make_ascending(data, i);
+        let mut run_len = count_run_and_make_ascending(data, i, probe);
         let remaining = n - i;
         if run_len < min_run {
             let force = remaining.min(min_run);
-            binary_insertion_sort_range(data, i, i + force);
+            binary_insertion_sort_range(data, i, i + force, probe);
             run_len = force;
         }
 
@@ -34,11 +35,11 @@ pub fn sort(data: &mut [u64], ctx: &mut SortContext) {
             start: i,
             len: run_len,
         });
-        merge_collapse(data, &mut runs, ctx);
+        merge_collapse(data, &mut runs, ctx, probe);
         i += run_len;
     }
 
-    merge_force_collapse(data, &mut runs, ctx);
+    merge_force_collapse(data, &mut runs, ctx, probe);
 }
 
 fn min_run_length(mut n: usize) -> usize {
@@ -51,20 +52,21 @@ fn min_run_length(mut n: usize) -> usize {
     n + r
 }
 
-fn count_run_and_make_ascending(data: &mut [u64], start: usize) -> usize {
+fn count_run_and_make_ascending<P: Probe>(data: &mut [u64], start: usize, probe: P) -> usize {
     let n = data.len();
     let mut end = start + 1;
     if end >= n {
         return 1;
     }
 
-    if data[end] < data[start] {
-        while end < n && data[end] < data[end - 1] {
+    if probe.lt(data[end], data[start]) {
+        while end < n && probe.lt(data[end], data[end - 1]) {
             end += 1;
         }
         data[start..end].reverse();
+        probe.moved(((end - start) & !1) as u64);
     } else {
-        while end < n && data[end] >= data[end - 1] {
+        while end < n && probe.ge(data[end], data[end - 1]) {
             end += 1;
         }
     }
@@ -72,14 +74,14 @@ fn count_run_and_make_ascending(data: &mut [u64], start: usize) -> usize {
     end - start
 }
 
-fn binary_insertion_sort_range(data: &mut [u64], start: usize, end: usize) {
+fn binary_insertion_sort_range<P: Probe>(data: &mut [u64], start: usize, end: usize, probe: P) {
     for i in (start + 1)..end {
         let key = data[i];
         let mut left = start;
         let mut right = i;
         while left < right {
             let mid = left + ((right - left) >> 1);
-            if data[mid] <= key {
+            if probe.le(data[mid], key) {
                 left = mid + 1;
             } else {
                 right = mid;
@@ -92,10 +94,16 @@ fn binary_insertion_sort_range(data: &mut [u64], start: usize, end: usize) {
             std::ptr::copy(ptr.add(pos), ptr.add(pos + 1), i - pos);
             *ptr.add(pos) = key;
         }
+        probe.moved((i - pos) as u64 + 1);
     }
 }
 
-fn merge_collapse(data: &mut [u64], runs: &mut Vec<Run>, ctx: &mut SortContext) {
+fn merge_collapse<P: Probe>(
+    data: &mut [u64],
+    runs: &mut Vec<Run>,
+    ctx: &mut SortContext,
+    probe: P,
+) {
     while runs.len() > 1 {
         let n = runs.len();
 
@@ -103,15 +111,15 @@ fn merge_collapse(data: &mut [u64], runs: &mut Vec<Run>, ctx: &mut SortContext)
         let cond_b = n >= 4 && runs[n - 4].len <= runs[n - 3].len + runs[n - 2].len;
         if cond_a || cond_b {
             if n >= 3 && runs[n - 3].len < runs[n - 1].len {
-                merge_at(data, runs, n - 3, ctx);
+                merge_at(data, runs, n - 3, ctx, probe);
             } else {
-                merge_at(data, runs, n - 2, ctx);
+                merge_at(data, runs, n - 2, ctx, probe);
             }
             continue;
         }
 
         if runs[n - 2].len <= runs[n - 1].len {
-            merge_at(data, runs, n - 2, ctx);
+            merge_at(data, runs, n - 2, ctx, probe);
             continue;
         }
 
@@ -119,25 +127,36 @@ fn merge_collapse(data: &mut [u64], runs: &mut Vec<Run>, ctx: &mut SortContext)
     }
 }
 
-fn merge_force_collapse(data: &mut [u64], runs: &mut Vec<Run>, ctx: &mut SortContext) {
+fn merge_force_collapse<P: Probe>(
+    data: &mut [u64],
+    runs: &mut Vec<Run>,
+    ctx: &mut SortContext,
+    probe: P,
+) {
     while runs.len() > 1 {
         let n = runs.len();
         if n >= 3 && runs[n - 3].len < runs[n - 1].len {
-            merge_at(data, runs, n - 3, ctx);
+            merge_at(data, runs, n - 3, ctx, probe);
         } else {
-            merge_at(data, runs, n - 2, ctx);
+            merge_at(data, runs, n - 2, ctx, probe);
         }
     }
 }
 
-fn merge_at(data: &mut [u64], runs: &mut Vec<Run>, idx: usize, ctx: &mut SortContext) {
+fn merge_at<P: Probe>(
+    data: &mut [u64],
+    runs: &mut Vec<Run>,
+    idx: usize,
+    ctx: &mut SortContext,
+    probe: P,
+) {
     let left = runs[idx];
     let right = runs[idx + 1];
 
     debug_assert_eq!(left.start + left.len, right.start);
 
     let aux = ctx.ensure_aux(left.len);
-    common::copy_u64_slice(aux, &data[left.start..(left.start + left.len)]);
+    common::copy_u64_slice(aux, &data[left.start..(left.start + left.len)], probe);
 
     let mut i = 0usize;
     let mut j = right.start;
@@ -145,7 +164,7 @@ fn merge_at(data: &mut [u64], runs: &mut Vec<Run>, idx: usize, ctx: &mut SortCon
     let right_end = right.start + right.len;
 
     while i < left.len && j < right_end {
-        if aux[i] <= data[j] {
+        if probe.le(aux[i], data[j]) {
             data[out] = aux[i];
             i += 1;
         } else {
@@ -154,9 +173,14 @@ fn merge_at(data: &mut [u64], runs: &mut Vec<Run>, idx: usize, ctx: &mut SortCon
         }
         out += 1;
     }
+    probe.moved((out - left.start) as u64);
 
     if i < left.len {
-        common::copy_u64_slice(&mut data[out..(out + (left.len - i))], &aux[i..left.len]);
+        common::copy_u64_slice(
+            &mut data[out..(out + (left.len - i))],
+            &aux[i..left.len],
+            probe,
+        );
     }
 
     runs[idx] = Run {
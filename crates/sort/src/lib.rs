@@ -1,5 +1,7 @@
 mod algorithms;
 
+use algorithms::probe::{Counter, Probe, Silent};
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum DataTrack {
     FullU64,
@@ -28,9 +30,10 @@ pub enum SortAlgorithm {
     BucketSort,
     RadixSortLsdBase256,
     AmericanFlagSortMsd,
+    MergeSortInPlace,
 }
 
-pub const ALL_ALGORITHMS: [SortAlgorithm; 20] = [
+pub const ALL_ALGORITHMS: [SortAlgorithm; 21] = [
     SortAlgorithm::InsertionSort,
     SortAlgorithm::BinaryInsertionSort,
     SortAlgorithm::ShellSortCiura,
@@ -51,6 +54,7 @@ pub const ALL_ALGORITHMS: [SortAlgorithm; 20] = [
     SortAlgorithm::BucketSort,
     SortAlgorithm::RadixSortLsdBase256,
     SortAlgorithm::AmericanFlagSortMsd,
+    SortAlgorithm::MergeSortInPlace,
 ];
 
 pub fn all_algorithms() -> &'static [SortAlgorithm] {
@@ -79,6 +83,7 @@ pub fn algorithm_name(algo: SortAlgorithm) -> &'static str {
         SortAlgorithm::BucketSort => "bucket_sort",
         SortAlgorithm::RadixSortLsdBase256 => "radix_sort_lsd_base256",
         SortAlgorithm::AmericanFlagSortMsd => "american_flag_sort_msd",
+        SortAlgorithm::MergeSortInPlace => "merge_sort_in_place",
     }
 }
 
@@ -95,6 +100,7 @@ pub fn supports_track(algo: SortAlgorithm, track: DataTrack) -> bool {
 #[derive(Clone, Copy, Debug)]
 pub struct TunedParams {
     pub insertion_threshold: usize,
+    pub sorting_network_threshold: usize,
     pub block_partition_size: usize,
     pub introsort_depth_factor_num: usize,
     pub introsort_depth_factor_den: usize,
@@ -105,6 +111,7 @@ pub struct TunedParams {
 
 pub const TUNED_PARAMS: TunedParams = TunedParams {
     insertion_threshold: 24,
+    sorting_network_threshold: algorithms::sorting_network::MAX_NETWORK_LEN,
     block_partition_size: 64,
     introsort_depth_factor_num: 5,
     introsort_depth_factor_den: 2,
@@ -116,6 +123,9 @@ pub const TUNED_PARAMS: TunedParams = TunedParams {
 #[derive(Clone, Debug)]
 pub struct SortContext {
     pub scratch: Vec<u64>,
+    pub scratch_u32: Vec<u32>,
+    /// Widened copy of `u32` keys for algorithms without a native `u32` path.
+    pub widened: Vec<u64>,
     pub aux: Vec<u64>,
     pub counts256: [usize; 256],
     pub var_counts: Vec<usize>,
@@ -125,6 +135,8 @@ impl Default for SortContext {
     fn default() -> Self {
         Self {
             scratch: Vec::new(),
+            scratch_u32: Vec::new(),
+            widened: Vec::new(),
             aux: Vec::new(),
             counts256: [0; 256],
             var_counts: Vec::new(),
@@ -163,33 +175,324 @@ pub fn sort_u64(algo: SortAlgorithm, data: &mut [u64]) {
     sort_u64_with_ctx(algo, data, &mut ctx);
 }
 
+/// Debug builds check the result with `is_sorted_u64`, so a broken algorithm
+/// panics at the call that produced the bad output.
 pub fn sort_u64_with_ctx(algo: SortAlgorithm, data: &mut [u64], ctx: &mut SortContext) {
+    dispatch(algo, data, ctx, Silent);
+    debug_assert!(
+        is_sorted_u64(data),
+        "{} left data unsorted",
+        algorithm_name(algo)
+    );
+}
+
+/// Sorts like `sort_u64_with_ctx` and reports whether the depth limit forced
+/// a heapsort fallback, e.g. on a quicksort-killer input.
+///
+/// Only `SortAlgorithm::Introsort` tracks its fallback; every other algorithm
+/// returns `false`.
+pub fn sort_u64_with_ctx_reporting(
+    algo: SortAlgorithm,
+    data: &mut [u64],
+    ctx: &mut SortContext,
+) -> bool {
     match algo {
-        SortAlgorithm::InsertionSort => algorithms::insertion_sort::sort(data, ctx),
-        SortAlgorithm::BinaryInsertionSort => algorithms::binary_insertion_sort::sort(data, ctx),
-        SortAlgorithm::ShellSortCiura => algorithms::shell_sort_ciura::sort(data, ctx),
-        SortAlgorithm::HeapSort => algorithms::heap_sort::sort(data, ctx),
-        SortAlgorithm::MergeSortTopDown => algorithms::merge_sort_top_down::sort(data, ctx),
-        SortAlgorithm::MergeSortBottomUp => algorithms::merge_sort_bottom_up::sort(data, ctx),
-        SortAlgorithm::NaturalMergeSort => algorithms::natural_merge_sort::sort(data, ctx),
-        SortAlgorithm::Timsort => algorithms::timsort::sort(data, ctx),
-        SortAlgorithm::QuickSortMedian3 => algorithms::quick_sort_median3::sort(data, ctx),
-        SortAlgorithm::QuickSort3Way => algorithms::quick_sort_3way::sort(data, ctx),
-        SortAlgorithm::DualPivotQuickSort => algorithms::dual_pivot_quick_sort::sort(data, ctx),
-        SortAlgorithm::Introsort => algorithms::introsort::sort(data, ctx),
-        SortAlgorithm::PdqsortLike => algorithms::pdqsort_like::sort(data, ctx),
-        SortAlgorithm::BlockQuickSort => algorithms::block_quick_sort::sort(data, ctx),
-        SortAlgorithm::QuickMergeSort => algorithms::quick_merge_sort::sort(data, ctx),
-        SortAlgorithm::CountingSort => algorithms::counting_sort::sort(data, ctx),
-        SortAlgorithm::PigeonholeSort => algorithms::pigeonhole_sort::sort(data, ctx),
-        SortAlgorithm::BucketSort => algorithms::bucket_sort::sort(data, ctx),
-        SortAlgorithm::RadixSortLsdBase256 => algorithms::radix_sort_lsd_base256::sort(data, ctx),
-        SortAlgorithm::AmericanFlagSortMsd => algorithms::american_flag_sort_msd::sort(data, ctx),
+        SortAlgorithm::Introsort => algorithms::introsort::sort_reporting(data, ctx, Silent),
+        _ => {
+            dispatch(algo, data, ctx, Silent);
+            false
+        }
+    }
+}
+
+/// Multi-threaded top-down merge sort built on `rayon::join`.
+///
+/// Kept out of `ALL_ALGORITHMS` so the sequential correctness matrix and
+/// benches are unaffected by the `rayon` feature.
+#[cfg(feature = "rayon")]
+pub fn par_merge_sort(data: &mut [u64], ctx: &mut SortContext) {
+    algorithms::par_merge_sort::sort(data, ctx);
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+pub fn sort_u64_ordered(algo: SortAlgorithm, data: &mut [u64], order: SortOrder) {
+    let mut ctx = SortContext::default();
+    sort_u64_ordered_with_ctx(algo, data, order, &mut ctx);
+}
+
+/// Sorts `data` in the requested order without a trailing reverse.
+///
+/// Descending order runs the ascending algorithm on bitwise-complemented keys:
+/// `!x` reverses the `u64` order and maps every radix digit `d` to `255 - d`,
+/// so the radix/counting family fills its buckets from the top down instead of
+/// sorting ascending and flipping the result.
+pub fn sort_u64_ordered_with_ctx(
+    algo: SortAlgorithm,
+    data: &mut [u64],
+    order: SortOrder,
+    ctx: &mut SortContext,
+) {
+    match order {
+        SortOrder::Ascending => sort_u64_with_ctx(algo, data, ctx),
+        SortOrder::Descending => {
+            complement_in_place(data);
+            sort_u64_with_ctx(algo, data, ctx);
+            complement_in_place(data);
+        }
+    }
+}
+
+#[inline]
+fn complement_in_place(data: &mut [u64]) {
+    for x in data.iter_mut() {
+        *x = !*x;
+    }
+}
+
+pub fn sort_u32(algo: SortAlgorithm, data: &mut [u32], ctx: &mut SortContext) {
+    match algo {
+        SortAlgorithm::RadixSortLsdBase256 => {
+            algorithms::radix_sort_lsd_base256::sort_u32(data, ctx)
+        }
+        _ => sort_widened_u32(algo, data, ctx),
+    }
+}
+
+// Every other algorithm sorts a widened `u64` copy; widening preserves order,
+// so narrowing the sorted copy back is exact. The copy lives in
+// `ctx.widened`, taken out while the algorithm borrows the rest of `ctx`.
+fn sort_widened_u32(algo: SortAlgorithm, data: &mut [u32], ctx: &mut SortContext) {
+    let mut wide = std::mem::take(&mut ctx.widened);
+    wide.clear();
+    wide.extend(data.iter().map(|&x| u64::from(x)));
+    sort_u64_with_ctx(algo, &mut wide, ctx);
+    for (dst, &x) in data.iter_mut().zip(&wide) {
+        *dst = x as u32;
+    }
+    ctx.widened = wide;
+}
+
+const SIGN_BIT: u64 = 1 << 63;
+
+pub fn sort_i64(algo: SortAlgorithm, data: &mut [i64]) {
+    let mut ctx = SortContext::default();
+    sort_i64_with_ctx(algo, data, &mut ctx);
+}
+
+/// Sorts signed keys by flipping the sign bit, which maps `i64` onto `u64`
+/// order-preservingly (`i64::MIN -> 0`, `-1 -> 2^63 - 1`, `0 -> 2^63`).
+///
+/// The mapping is applied in place and undone afterwards. A small signed
+/// range stays a small `u64` range, so counting/pigeonhole sort handle
+/// negative inputs within the same bounds as `DataTrack::BoundedU20`.
+pub fn sort_i64_with_ctx(algo: SortAlgorithm, data: &mut [i64], ctx: &mut SortContext) {
+    // SAFETY: `i64` and `u64` share size, alignment, and validity, and the
+    // reborrow covers exactly the same elements.
+    let keys =
+        unsafe { std::slice::from_raw_parts_mut(data.as_mut_ptr().cast::<u64>(), data.len()) };
+    flip_sign_in_place(keys);
+    sort_u64_with_ctx(algo, keys, ctx);
+    flip_sign_in_place(keys);
+}
+
+#[inline]
+fn flip_sign_in_place(data: &mut [u64]) {
+    for x in data.iter_mut() {
+        *x ^= SIGN_BIT;
+    }
+}
+
+pub fn sort_by_cached_key<T, F: FnMut(&T) -> u64>(algo: SortAlgorithm, data: &mut [T], key: F) {
+    let mut ctx = SortContext::default();
+    sort_by_cached_key_with_ctx(algo, data, key, &mut ctx);
+}
+
+/// Stable sort of arbitrary records by a `u64` key evaluated once per element.
+///
+/// The keys are sorted with `algo`; each record's destination is the first
+/// slot of its key in the sorted keys plus the number of equal keys already
+/// placed, and the permutation is applied by cycle-following swaps, so every
+/// record moves at most once into its final slot.
+pub fn sort_by_cached_key_with_ctx<T, F: FnMut(&T) -> u64>(
+    algo: SortAlgorithm,
+    data: &mut [T],
+    mut key: F,
+    ctx: &mut SortContext,
+) {
+    let len = data.len();
+    if len < 2 {
+        return;
+    }
+
+    let keys: Vec<u64> = data.iter().map(&mut key).collect();
+    let mut dest = stable_destinations(algo, &keys, ctx);
+
+    for i in 0..len {
+        while dest[i] != i {
+            let j = dest[i];
+            data.swap(i, j);
+            dest.swap(i, j);
+        }
+    }
+}
+
+/// Final slot of every key in a stable sort: sorts a copy with `algo`, then
+/// sends each key to the first slot of its value plus the number of equal
+/// keys already placed.
+fn stable_destinations(algo: SortAlgorithm, keys: &[u64], ctx: &mut SortContext) -> Vec<usize> {
+    let mut sorted = keys.to_vec();
+    sort_u64_with_ctx(algo, &mut sorted, ctx);
+
+    let mut placed = vec![0usize; keys.len()];
+    keys.iter()
+        .map(|&k| {
+            let first = sorted.partition_point(|&x| x < k);
+            let slot = first + placed[first];
+            placed[first] += 1;
+            slot
+        })
+        .collect()
+}
+
+pub fn argsort_u64(algo: SortAlgorithm, data: &[u64]) -> Vec<usize> {
+    let mut ctx = SortContext::default();
+    argsort_u64_with_ctx(algo, data, &mut ctx)
+}
+
+/// Indices that sort `data`: `data[perm[0]] <= data[perm[1]] <= ...`.
+///
+/// Equivalent to sorting `(value, index)` pairs, so equal values keep their
+/// original order for every `algo`; the values are sorted with `algo` and
+/// the indices placed as in `sort_by_cached_key_with_ctx`.
+pub fn argsort_u64_with_ctx(
+    algo: SortAlgorithm,
+    data: &[u64],
+    ctx: &mut SortContext,
+) -> Vec<usize> {
+    let mut perm = vec![0usize; data.len()];
+    for (i, slot) in stable_destinations(algo, data, ctx).into_iter().enumerate() {
+        perm[slot] = i;
+    }
+    perm
+}
+
+pub fn counting_sort_with_histogram(data: &mut [u64], max_value: u64) -> Vec<usize> {
+    let mut ctx = SortContext::default();
+    counting_sort_with_histogram_with_ctx(data, max_value, &mut ctx)
+}
+
+/// Sorts `data` by counting and returns the counts it sorted with:
+/// `histogram[v]` is the number of occurrences of `v`.
+///
+/// The histogram always has `max_value + 1` entries, all zero for empty
+/// input. Panics if some element exceeds `max_value`; the counts live in
+/// `ctx.var_counts`, so this needs O(max_value) memory regardless of `data`.
+pub fn counting_sort_with_histogram_with_ctx(
+    data: &mut [u64],
+    max_value: u64,
+    ctx: &mut SortContext,
+) -> Vec<usize> {
+    algorithms::counting_sort::sort_with_histogram(data, max_value, ctx).to_vec()
+}
+
+/// Whether `data` is non-decreasing; `true` for empty and single-element input.
+pub fn is_sorted_u64(data: &[u64]) -> bool {
+    data.windows(2).all(|w| w[0] <= w[1])
+}
+
+/// Number of maximal non-decreasing runs; `0` for empty input and `1` when
+/// `data` is already sorted.
+pub fn count_runs(data: &[u64]) -> usize {
+    algorithms::presortedness::count_runs(data)
+}
+
+pub fn presortedness(data: &[u64]) -> f64 {
+    let mut ctx = SortContext::default();
+    presortedness_with_ctx(data, &mut ctx)
+}
+
+/// Fraction of pairs that are out of order: `inversions / (n * (n - 1) / 2)`.
+///
+/// `0.0` means already sorted and `1.0` strictly decreasing. Inversions are
+/// counted by a bottom-up merge pass over `ctx.scratch`/`ctx.aux`, so `data`
+/// itself is left untouched.
+pub fn presortedness_with_ctx(data: &[u64], ctx: &mut SortContext) -> f64 {
+    let len = data.len() as u64;
+    if len < 2 {
+        return 0.0;
+    }
+    let inversions = algorithms::presortedness::count_inversions(data, ctx);
+    let pairs = len * (len - 1) / 2;
+    inversions as f64 / pairs as f64
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SortMetrics {
+    pub comparisons: u64,
+    pub moves: u64,
+}
+
+/// Sorts `data` like `sort_u64_with_ctx` while counting key comparisons and
+/// element writes (a swap counts as two writes).
+///
+/// Counts are deterministic for a given input. Writes made inside
+/// `slice::sort_unstable`, which `bucket_sort` uses for large buckets, are not
+/// observable and therefore not included.
+pub fn sort_u64_instrumented(
+    algo: SortAlgorithm,
+    data: &mut [u64],
+    ctx: &mut SortContext,
+) -> SortMetrics {
+    let counter = Counter::default();
+    dispatch(algo, data, ctx, &counter);
+    counter.metrics()
+}
+
+fn dispatch<P: Probe>(algo: SortAlgorithm, data: &mut [u64], ctx: &mut SortContext, probe: P) {
+    match algo {
+        SortAlgorithm::InsertionSort => algorithms::insertion_sort::sort(data, ctx, probe),
+        SortAlgorithm::BinaryInsertionSort => {
+            algorithms::binary_insertion_sort::sort(data, ctx, probe)
+        }
+        SortAlgorithm::ShellSortCiura => algorithms::shell_sort_ciura::sort(data, ctx, probe),
+        SortAlgorithm::HeapSort => algorithms::heap_sort::sort(data, ctx, probe),
+        SortAlgorithm::MergeSortTopDown => algorithms::merge_sort_top_down::sort(data, ctx, probe),
+        SortAlgorithm::MergeSortBottomUp => {
+            algorithms::merge_sort_bottom_up::sort(data, ctx, probe)
+        }
+        SortAlgorithm::NaturalMergeSort => algorithms::natural_merge_sort::sort(data, ctx, probe),
+        SortAlgorithm::Timsort => algorithms::timsort::sort(data, ctx, probe),
+        SortAlgorithm::QuickSortMedian3 => algorithms::quick_sort_median3::sort(data, ctx, probe),
+        SortAlgorithm::QuickSort3Way => algorithms::quick_sort_3way::sort(data, ctx, probe),
+        SortAlgorithm::DualPivotQuickSort => {
+            algorithms::dual_pivot_quick_sort::sort(data, ctx, probe)
+        }
+        SortAlgorithm::Introsort => algorithms::introsort::sort(data, ctx, probe),
+        SortAlgorithm::PdqsortLike => algorithms::pdqsort_like::sort(data, ctx, probe),
+        SortAlgorithm::BlockQuickSort => algorithms::block_quick_sort::sort(data, ctx, probe),
+        SortAlgorithm::QuickMergeSort => algorithms::quick_merge_sort::sort(data, ctx, probe),
+        SortAlgorithm::CountingSort => algorithms::counting_sort::sort(data, ctx, probe),
+        SortAlgorithm::PigeonholeSort => algorithms::pigeonhole_sort::sort(data, ctx, probe),
+        SortAlgorithm::BucketSort => algorithms::bucket_sort::sort(data, ctx, probe),
+        SortAlgorithm::RadixSortLsdBase256 => {
+            algorithms::radix_sort_lsd_base256::sort(data, ctx, probe)
+        }
+        SortAlgorithm::AmericanFlagSortMsd => {
+            algorithms::american_flag_sort_msd::sort(data, ctx, probe)
+        }
+        SortAlgorithm::MergeSortInPlace => algorithms::merge_sort_in_place::sort(data, ctx, probe),
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::cell::{Cell, RefCell};
     use std::collections::HashSet;
 
     use rand::rngs::StdRng;
@@ -256,6 +559,385 @@ mod tests {
         }
     }
 
+    #[test]
+    fn instrumented_insertion_sort_on_reversed_input() {
+        let n = 256_u64;
+        let mut data: Vec<u64> = (0..n).rev().collect();
+        let mut ctx = SortContext::default();
+        let metrics = sort_u64_instrumented(SortAlgorithm::InsertionSort, &mut data, &mut ctx);
+
+        assert_eq!(data, (0..n).collect::<Vec<_>>());
+        assert_eq!(metrics.comparisons, n * (n - 1) / 2);
+        assert_eq!(metrics.moves, n * (n - 1) / 2 + (n - 1));
+    }
+
+    #[test]
+    fn instrumented_counts_are_deterministic() {
+        let mut rng = StdRng::seed_from_u64(0x1257_2026);
+        let base: Vec<u64> = (0..1024).map(|_| rng.random_range(0..4096)).collect();
+        let mut ctx = SortContext::default();
+        for &algo in all_algorithms() {
+            let mut first = base.clone();
+            let a = sort_u64_instrumented(algo, &mut first, &mut ctx);
+            let mut second = base.clone();
+            let b = sort_u64_instrumented(algo, &mut second, &mut ctx);
+
+            let mut expected = base.clone();
+            expected.sort_unstable();
+            assert_eq!(first, expected, "algorithm={}", algorithm_name(algo));
+            assert_eq!(a, b, "algorithm={}", algorithm_name(algo));
+            assert!(a.moves > 0, "algorithm={}", algorithm_name(algo));
+        }
+    }
+
+    #[test]
+    fn descending_order_matches_reversed_ascending() {
+        let mut rng = StdRng::seed_from_u64(0x1258_2026);
+        let mut cases = vec![
+            vec![],
+            vec![3],
+            vec![u64::MIN, 1, u64::MAX, 0, u64::MAX - 1, 2],
+        ];
+        cases.push((0..777).map(|_| rng.random_range(0..(1 << 20))).collect());
+        cases.push((0..777).map(|_| rng.random_range(0..8)).collect());
+
+        for case in &cases {
+            for &algo in all_algorithms() {
+                let mut ascending = case.clone();
+                sort_u64_ordered(algo, &mut ascending, SortOrder::Ascending);
+                let mut descending = case.clone();
+                sort_u64_ordered(algo, &mut descending, SortOrder::Descending);
+
+                assert!(
+                    descending.windows(2).all(|w| w[0] >= w[1]),
+                    "algorithm={}",
+                    algorithm_name(algo)
+                );
+                ascending.reverse();
+                assert_eq!(descending, ascending, "algorithm={}", algorithm_name(algo));
+            }
+        }
+    }
+
+    #[test]
+    fn signed_keys_sort_like_std() {
+        let mut rng = StdRng::seed_from_u64(0x1283_2026);
+        let mut cases = vec![
+            vec![],
+            vec![-7],
+            vec![i64::MAX, -1, 0, i64::MIN, 1, i64::MIN + 1, i64::MAX - 1],
+            vec![-3, -3, 2, -1, 0, 2, -3],
+        ];
+        cases.push(
+            (0..1500)
+                .map(|_| rng.random_range(-500_000..500_000))
+                .collect(),
+        );
+        cases.push((0..1500).map(|_| rng.random::<u64>() as i64).collect());
+
+        for case in &cases {
+            let mut expected = case.clone();
+            expected.sort_unstable();
+            for &algo in all_algorithms() {
+                let mut actual = case.clone();
+                sort_i64(algo, &mut actual);
+                assert_eq!(
+                    actual,
+                    expected,
+                    "algorithm={} input_len={}",
+                    algorithm_name(algo),
+                    case.len(),
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_merge_sort_matches_std() {
+        let mut rng = StdRng::seed_from_u64(0x1284_2026);
+        let mut ctx = SortContext::default();
+        for &size in &[0_usize, 1, 2, 24, 25, 1000, 8191, 8192, 100_003] {
+            let mut data: Vec<u64> = (0..size).map(|_| rng.random::<u64>()).collect();
+            let mut expected = data.clone();
+            expected.sort_unstable();
+            par_merge_sort(&mut data, &mut ctx);
+            assert_eq!(data, expected, "input_len={size}");
+        }
+
+        let mut data: Vec<u64> = (0..50_000).map(|_| rng.random_range(0..32)).collect();
+        let mut expected = data.clone();
+        expected.sort_unstable();
+        par_merge_sort(&mut data, &mut ctx);
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn cached_key_sort_matches_stable_sort_by_key() {
+        let mut rng = StdRng::seed_from_u64(0x1285_2026);
+        let words: Vec<String> = (0..600)
+            .map(|i| {
+                let len = rng.random_range(0..12);
+                format!("{i}{}", "x".repeat(len))
+            })
+            .collect();
+
+        let mut expected = words.clone();
+        expected.sort_by_key(|w| w.len());
+        for &algo in all_algorithms() {
+            let mut actual = words.clone();
+            sort_by_cached_key(algo, &mut actual, |w| w.len() as u64);
+            assert_eq!(actual, expected, "algorithm={}", algorithm_name(algo));
+        }
+    }
+
+    #[test]
+    fn argsort_is_a_stable_sorting_permutation() {
+        let mut rng = StdRng::seed_from_u64(0x1377_2026);
+        let data: Vec<u64> = (0..700).map(|_| rng.random_range(0..40)).collect();
+        let mut sorted = data.clone();
+        sorted.sort_unstable();
+
+        let mut expected: Vec<usize> = (0..data.len()).collect();
+        expected.sort_by_key(|&i| data[i]);
+        for &algo in all_algorithms() {
+            let perm = argsort_u64(algo, &data);
+            let name = algorithm_name(algo);
+            assert!(perm.windows(2).all(|w| data[w[0]] <= data[w[1]]), "{name}");
+            let applied: Vec<u64> = perm.iter().map(|&i| data[i]).collect();
+            assert_eq!(applied, sorted, "{name}");
+            assert_eq!(perm, expected, "{name}");
+        }
+
+        assert!(argsort_u64(SortAlgorithm::Timsort, &[]).is_empty());
+        assert_eq!(argsort_u64(SortAlgorithm::HeapSort, &[5]), [0]);
+        assert_eq!(argsort_u64(SortAlgorithm::HeapSort, &[3, 1, 2]), [1, 2, 0]);
+    }
+
+    #[test]
+    fn merge_sort_in_place_leaves_buffers_untouched() {
+        let mut rng = StdRng::seed_from_u64(0x1286_2026);
+        let mut data: Vec<u64> = (0..5000).map(|_| rng.random_range(0..64)).collect();
+        let mut expected = data.clone();
+        expected.sort_unstable();
+
+        let mut ctx = SortContext::default();
+        sort_u64_with_ctx(SortAlgorithm::MergeSortInPlace, &mut data, &mut ctx);
+        assert_eq!(data, expected);
+        assert!(ctx.scratch.is_empty() && ctx.aux.is_empty() && ctx.var_counts.is_empty());
+    }
+
+    #[test]
+    fn u32_keys_sort_like_std() {
+        let mut rng = StdRng::seed_from_u64(0x1288_2026);
+        let mut ctx = SortContext::default();
+        for &size in &[0_usize, 1, 2, 31, 64, 1000, 4099] {
+            let full: Vec<u32> = (0..size).map(|_| rng.random::<u32>()).collect();
+            let narrow: Vec<u32> = (0..size).map(|_| rng.random_range(0..300)).collect();
+            for base in [&full, &narrow] {
+                let mut expected = base.clone();
+                expected.sort_unstable();
+                for &algo in all_algorithms() {
+                    let mut actual = base.clone();
+                    sort_u32(algo, &mut actual, &mut ctx);
+                    assert_eq!(
+                        actual,
+                        expected,
+                        "algorithm={} input_len={}",
+                        algorithm_name(algo),
+                        size
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn u32_widening_reuses_context_buffer() {
+        let mut rng = StdRng::seed_from_u64(0x1288_2027);
+        let mut ctx = SortContext::default();
+        let mut data: Vec<u32> = (0..4096).map(|_| rng.random::<u32>()).collect();
+        sort_u32(SortAlgorithm::Timsort, &mut data, &mut ctx);
+        let buffer = ctx.widened.as_ptr();
+        assert!(ctx.widened.capacity() >= data.len());
+
+        for &algo in all_algorithms() {
+            let mut data: Vec<u32> = (0..4096).map(|_| rng.random::<u32>()).collect();
+            sort_u32(algo, &mut data, &mut ctx);
+            assert!(data.is_sorted(), "algorithm={}", algorithm_name(algo));
+            assert_eq!(
+                ctx.widened.as_ptr(),
+                buffer,
+                "algorithm={}",
+                algorithm_name(algo)
+            );
+        }
+    }
+
+    #[test]
+    fn counting_sort_histogram_matches_tally() {
+        let mut rng = StdRng::seed_from_u64(0x4157_2026);
+        let mut ctx = SortContext::default();
+        for &(size, max_value) in &[(0_usize, 0_u64), (0, 9), (1, 0), (100, 9), (5000, 1000)] {
+            let mut data: Vec<u64> = (0..size).map(|_| rng.random_range(0..=max_value)).collect();
+            let mut tally = vec![0usize; max_value as usize + 1];
+            for &x in &data {
+                tally[x as usize] += 1;
+            }
+            let mut expected = data.clone();
+            expected.sort_unstable();
+
+            let histogram = counting_sort_with_histogram_with_ctx(&mut data, max_value, &mut ctx);
+            assert_eq!(data, expected);
+            assert_eq!(histogram.len(), max_value as usize + 1);
+            assert_eq!(histogram.iter().sum::<usize>(), data.len());
+            assert_eq!(histogram, tally);
+        }
+        assert_eq!(counting_sort_with_histogram(&mut [], 3), vec![0; 4]);
+    }
+
+    /// McIlroy's "killer adversary" run against introsort's own pivot rule:
+    /// values stay unassigned ("gas") until a comparison forces them, and the
+    /// pivot candidate is always frozen to the smallest value left.
+    struct Adversary {
+        values: RefCell<Vec<u64>>,
+        solid: Cell<u64>,
+        candidate: Cell<u64>,
+    }
+
+    const GAS: u64 = u64::MAX;
+
+    impl Adversary {
+        fn cmp(&self, x: u64, y: u64) -> std::cmp::Ordering {
+            let mut values = self.values.borrow_mut();
+            let (xi, yi) = (x as usize, y as usize);
+            if values[xi] == GAS && values[yi] == GAS {
+                let frozen = if x == self.candidate.get() { xi } else { yi };
+                values[frozen] = self.solid.get();
+                self.solid.set(self.solid.get() + 1);
+            }
+            if values[xi] == GAS {
+                self.candidate.set(x);
+            } else if values[yi] == GAS {
+                self.candidate.set(y);
+            }
+            values[xi].cmp(&values[yi])
+        }
+    }
+
+    impl Probe for &Adversary {
+        fn compare(self, _n: u64) {}
+
+        fn moved(self, _n: u64) {}
+
+        fn lt(self, a: u64, b: u64) -> bool {
+            self.cmp(a, b).is_lt()
+        }
+
+        fn le(self, a: u64, b: u64) -> bool {
+            self.cmp(a, b).is_le()
+        }
+
+        fn gt(self, a: u64, b: u64) -> bool {
+            self.cmp(a, b).is_gt()
+        }
+
+        fn ge(self, a: u64, b: u64) -> bool {
+            self.cmp(a, b).is_ge()
+        }
+    }
+
+    /// Input on which every median-of-3 (and ninther) pivot is near the
+    /// minimum of its subarray.
+    fn median_of_three_killer(n: usize) -> Vec<u64> {
+        let adversary = Adversary {
+            values: RefCell::new(vec![GAS; n]),
+            solid: Cell::new(0),
+            candidate: Cell::new(0),
+        };
+        let mut ids: Vec<u64> = (0..n as u64).collect();
+        algorithms::introsort::sort_reporting(&mut ids, &mut SortContext::default(), &adversary);
+
+        let solid = adversary.solid.get();
+        let mut values = adversary.values.into_inner();
+        let gas = values.iter_mut().filter(|x| **x == GAS);
+        for (value, x) in (solid..).zip(gas) {
+            *x = value;
+        }
+        values
+    }
+
+    #[test]
+    fn introsort_reports_heapsort_fallback_on_killer_input() {
+        let mut ctx = SortContext::default();
+        let killer = median_of_three_killer(4096);
+        let mut expected = killer.clone();
+        expected.sort_unstable();
+
+        let mut data = killer.clone();
+        assert!(sort_u64_with_ctx_reporting(
+            SortAlgorithm::Introsort,
+            &mut data,
+            &mut ctx
+        ));
+        assert_eq!(data, expected);
+
+        let mut data = killer;
+        assert!(!sort_u64_with_ctx_reporting(
+            SortAlgorithm::MergeSortBottomUp,
+            &mut data,
+            &mut ctx
+        ));
+        assert_eq!(data, expected);
+
+        let mut rng = StdRng::seed_from_u64(0x1417_2026);
+        let mut data: Vec<u64> = (0..4096).map(|_| rng.random()).collect();
+        assert!(!sort_u64_with_ctx_reporting(
+            SortAlgorithm::Introsort,
+            &mut data,
+            &mut ctx
+        ));
+        assert!(data.is_sorted());
+    }
+
+    #[test]
+    fn is_sorted_u64_cases() {
+        assert!(is_sorted_u64(&[]));
+        assert!(is_sorted_u64(&[42]));
+        assert!(is_sorted_u64(&[1, 2, 2, 3, u64::MAX]));
+        assert!(is_sorted_u64(&[7, 7, 7]));
+        assert!(!is_sorted_u64(&[2, 1]));
+        assert!(!is_sorted_u64(&[1, 2, 3, 5, 4]));
+        assert!(!is_sorted_u64(&[u64::MAX, 0]));
+    }
+
+    #[test]
+    fn runs_and_presortedness() {
+        let sorted: Vec<u64> = (0..1000).collect();
+        assert_eq!(count_runs(&sorted), 1);
+        assert_eq!(presortedness(&sorted), 0.0);
+
+        let reversed: Vec<u64> = (0..1000).rev().collect();
+        assert_eq!(count_runs(&reversed), 1000);
+        assert_eq!(presortedness(&reversed), 1.0);
+
+        assert_eq!(count_runs(&[]), 0);
+        assert_eq!(count_runs(&[7, 7, 7]), 1);
+        assert_eq!(presortedness(&[7, 7, 7]), 0.0);
+        assert_eq!(count_runs(&[1, 3, 2, 4, 0]), 3);
+
+        let mut rng = StdRng::seed_from_u64(0x1289_2026);
+        let data: Vec<u64> = (0..300).map(|_| rng.random_range(0..50)).collect();
+        let mut naive = 0u64;
+        for i in 0..data.len() {
+            for j in (i + 1)..data.len() {
+                naive += u64::from(data[i] > data[j]);
+            }
+        }
+        let pairs = (data.len() * (data.len() - 1) / 2) as f64;
+        assert_eq!(presortedness(&data), naive as f64 / pairs);
+    }
+
     #[test]
     fn fixed_seed_random_cases() {
         let mut rng = StdRng::seed_from_u64(0x5EED_2026);
@@ -1,6 +1,7 @@
 use std::hint::black_box;
 use std::time::Duration;
 
+use bench::bench_seed;
 use criterion::BenchmarkId;
 use criterion::Criterion;
 use criterion::SamplingMode;
@@ -8,7 +9,9 @@ use criterion::criterion_group;
 use criterion::criterion_main;
 use sssp::DirectedGraph;
 use sssp::bmssp_paper;
+use sssp::delta_stepping;
 use sssp::dijkstra_binary_heap;
+use sssp::dijkstra_dial;
 use sssp::dijkstra_radix_heap;
 use sssp::generator::GraphCase;
 use sssp::generator::generate_case;
@@ -57,7 +60,7 @@ fn bench_sssp(c: &mut Criterion) {
 
         for &size in &SIZES {
             apply_runtime(&mut group, size);
-            let seed = 0x5EED_2026 ^ ((size as u64) << 7) ^ (case as u64);
+            let seed = bench_seed() ^ 0x5EED_2026 ^ ((size as u64) << 7) ^ (case as u64);
             let input = generate_case(case, size, seed);
 
             for (algo_name, solver) in ALGORITHMS {
@@ -74,5 +77,84 @@ fn bench_sssp(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, bench_sssp);
+const BOUNDED_CASES: [GraphCase; 3] = [
+    GraphCase::SparseRandom,
+    GraphCase::MaxDenseRandom,
+    GraphCase::GridRandom,
+];
+
+const BOUNDED_MAX_WEIGHT: u64 = 100;
+
+fn bench_bounded(c: &mut Criterion) {
+    for case in BOUNDED_CASES {
+        let mut group = c.benchmark_group(format!("sssp_bounded/{}", case.label()));
+
+        for &size in &SIZES {
+            apply_runtime(&mut group, size);
+            let seed = bench_seed() ^ 0xB0DD_2026 ^ ((size as u64) << 7) ^ (case as u64);
+            let input = generate_case(case, size, seed);
+            let edges: Vec<_> = input
+                .graph
+                .edges_vec()
+                .into_iter()
+                .map(|(u, v, w)| (u, v, w % (BOUNDED_MAX_WEIGHT + 1)))
+                .collect();
+            let graph = DirectedGraph::from_edges(input.graph.vertex_count(), &edges);
+
+            group.bench_function(BenchmarkId::new("dijkstra_dial", size), |bencher| {
+                bencher.iter(|| {
+                    let dist = dijkstra_dial(&graph, input.source, BOUNDED_MAX_WEIGHT);
+                    black_box(dist);
+                });
+            });
+            group.bench_function(BenchmarkId::new("dijkstra_radix", size), |bencher| {
+                bencher.iter(|| {
+                    let dist = dijkstra_radix_heap(&graph, input.source);
+                    black_box(dist);
+                });
+            });
+        }
+
+        group.finish();
+    }
+}
+
+const DENSE_CASES: [GraphCase; 3] = [
+    GraphCase::MaxDenseRandom,
+    GraphCase::MaxDenseLong,
+    GraphCase::MaxDenseZero,
+];
+
+fn bench_dense(c: &mut Criterion) {
+    for case in DENSE_CASES {
+        let mut group = c.benchmark_group(format!("sssp_dense/{}", case.label()));
+
+        for &size in &SIZES {
+            apply_runtime(&mut group, size);
+            let seed = bench_seed() ^ 0xDE17_2026 ^ ((size as u64) << 7) ^ (case as u64);
+            let input = generate_case(case, size, seed);
+            // Meyer-Sanders: bucket width about max weight over average degree.
+            let graph = &input.graph;
+            let max_weight = graph.edges_vec().iter().map(|e| e.2).max().unwrap_or(0);
+            let avg_degree = (graph.edge_count() / graph.vertex_count()).max(1) as u64;
+            let delta = (max_weight / avg_degree).max(1);
+
+            group.bench_function(BenchmarkId::new("delta_stepping", size), |bencher| {
+                bencher.iter(|| black_box(delta_stepping(graph, input.source, delta)));
+            });
+            for (algo_name, solver) in [
+                ("bmssp_paper", bmssp_paper as Solver),
+                ("dijkstra_radix", dijkstra_radix_heap),
+            ] {
+                group.bench_function(BenchmarkId::new(algo_name, size), |bencher| {
+                    bencher.iter(|| black_box(solver(graph, input.source)));
+                });
+            }
+        }
+
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_sssp, bench_bounded, bench_dense);
 criterion_main!(benches);
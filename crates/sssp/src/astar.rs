@@ -0,0 +1,48 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::INF;
+use crate::graph::DirectedGraph;
+
+/// Distance from `source` to `target` by A* search, or `None` if unreachable.
+///
+/// The heap is keyed by `dist[u] + h(u)`. `h` must be admissible (never above
+/// the true distance to `target`); a vertex whose distance later improves is
+/// simply queued again, so an inconsistent `h` still gives the exact answer.
+/// With `h = |_| 0` this is [`crate::dijkstra_to`].
+pub fn astar(
+    graph: &DirectedGraph,
+    source: usize,
+    target: usize,
+    h: impl Fn(u32) -> u64,
+) -> Option<u64> {
+    let n = graph.vertex_count();
+    if source >= n || target >= n {
+        return None;
+    }
+
+    let mut dist = vec![INF; n];
+    let mut heap = BinaryHeap::new();
+    dist[source] = 0;
+    heap.push(Reverse((h(source as u32), 0_u64, source)));
+
+    while let Some(Reverse((_, d, u))) = heap.pop() {
+        if d != dist[u] {
+            continue;
+        }
+        if u == target {
+            return Some(d);
+        }
+
+        for edge in graph.out_edges(u) {
+            let v = edge.to as usize;
+            let cand = d.saturating_add(edge.weight).min(INF);
+            if cand < dist[v] {
+                dist[v] = cand;
+                heap.push(Reverse((cand.saturating_add(h(edge.to)), cand, v)));
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,76 @@
+use crate::bmssp::partial_ds::PartialOrderQueue;
+
+/// Bounded batch priority queue from Lemma 3.3 of the BMSSP paper.
+///
+/// Keys are `usize` indices, each holding at most one value; re-inserting a
+/// key keeps the smaller value. Every value must be at most the upper bound
+/// `B` given at construction. [`Self::pull`] hands back up to `M` keys with
+/// the smallest values (`M` is the pull limit) in no particular order,
+/// together with a separator that is at most every value left behind.
+///
+/// With `N` keys stored, [`Self::insert`] takes amortized O(max(1, log(N / M)))
+/// time, [`Self::batch_prepend`] of `L` values takes amortized
+/// O(L * max(1, log(L / M))), and [`Self::pull`] takes amortized O(|S'|) for
+/// the `S'` keys returned. Clearing is O(1) in the size of the key space.
+#[derive(Debug)]
+pub struct BatchPriorityQueue<V> {
+    inner: PartialOrderQueue<V>,
+    scratch: Vec<(usize, V)>,
+}
+
+impl<V: Ord + Copy> BatchPriorityQueue<V> {
+    /// Empty queue pulling at most `pull_limit` keys at a time (at least one),
+    /// with every value bounded by `upper_bound`.
+    pub fn new(pull_limit: usize, upper_bound: V) -> Self {
+        Self::with_key_space(pull_limit, upper_bound, 0)
+    }
+
+    /// Like [`Self::new`], but preallocates bookkeeping for keys below
+    /// `key_space`. Larger keys are still accepted and grow it on demand.
+    pub fn with_key_space(pull_limit: usize, upper_bound: V, key_space: usize) -> Self {
+        Self {
+            inner: PartialOrderQueue::with_capacity(pull_limit, upper_bound, key_space),
+            scratch: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Drops every key and replaces the upper bound.
+    pub fn clear(&mut self, upper_bound: V) {
+        self.inner.reset(upper_bound);
+    }
+
+    /// Stores `value` for `key`, or lowers the value already stored for it.
+    pub fn insert(&mut self, key: usize, value: V) {
+        self.inner.insert(key, value);
+    }
+
+    /// Inserts a batch whose values are all smaller than every value stored.
+    ///
+    /// Keys may repeat; the smallest value for each wins. The precondition is
+    /// only checked in debug builds.
+    pub fn batch_prepend(&mut self, values: &[(usize, V)]) {
+        self.scratch.clear();
+        self.scratch.extend_from_slice(values);
+        self.scratch.sort_unstable();
+        self.scratch.dedup_by_key(|e| e.0);
+        self.inner.batch_prepend_unique(&self.scratch);
+    }
+
+    /// Removes up to `M` keys with the smallest values.
+    ///
+    /// Returns the keys and a separator: the smallest value still stored, or
+    /// the upper bound once the queue is empty. `None` if nothing was stored.
+    pub fn pull(&mut self) -> Option<(Vec<usize>, V)> {
+        let mut keys = Vec::new();
+        self.pull_into(&mut keys).map(|x| (keys, x))
+    }
+
+    /// Like [`Self::pull`], but reuses `keys` (cleared first) for the output.
+    pub fn pull_into(&mut self, keys: &mut Vec<usize>) -> Option<V> {
+        self.inner.pull_into(keys)
+    }
+}
@@ -0,0 +1,59 @@
+use std::fmt;
+
+use crate::graph::DirectedGraphSigned;
+
+/// Distance used for unreachable vertices in signed results.
+pub const INF_SIGNED: i64 = i64::MAX / 4;
+
+/// A negative cycle is reachable from the source, so some distances are unbounded.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NegativeCycle;
+
+impl fmt::Display for NegativeCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("negative cycle reachable from the source")
+    }
+}
+
+impl std::error::Error for NegativeCycle {}
+
+/// Single-source shortest paths with negative edges in O(nm).
+///
+/// Runs up to `n - 1` rounds of relaxing every edge out of a reached vertex,
+/// stopping early once a round changes nothing. If round `n` still improves a
+/// distance, a negative cycle is reachable and `Err(NegativeCycle)` is returned.
+/// Unreachable vertices get [`INF_SIGNED`]; cycles that the source cannot reach
+/// do not matter.
+pub fn bellman_ford(graph: &DirectedGraphSigned, source: usize) -> Result<Vec<i64>, NegativeCycle> {
+    let n = graph.vertex_count();
+    let mut dist = vec![INF_SIGNED; n];
+    if source >= n {
+        return Ok(dist);
+    }
+
+    dist[source] = 0;
+    for _ in 0..n {
+        let mut changed = false;
+        for u in 0..n {
+            let d = dist[u];
+            if d == INF_SIGNED {
+                continue;
+            }
+            let (to, weight) = graph.out_edge_slices(u);
+            for (&v, &w) in to.iter().zip(weight) {
+                let cand = d.saturating_add(w);
+                if cand < dist[v as usize] {
+                    dist[v as usize] = cand;
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return Ok(dist);
+        }
+    }
+
+    // Shortest simple paths use at most n - 1 edges, so an n-th improving
+    // round can only come from a negative cycle.
+    Err(NegativeCycle)
+}
@@ -0,0 +1,92 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use crate::INF;
+use crate::graph::DirectedGraph;
+
+struct Search<'a> {
+    graph: &'a DirectedGraph,
+    dist: Vec<u64>,
+    settled: Vec<bool>,
+    heap: BinaryHeap<Reverse<(u64, usize)>>,
+}
+
+impl<'a> Search<'a> {
+    fn new(graph: &'a DirectedGraph, start: usize) -> Self {
+        let n = graph.vertex_count();
+        let mut dist = vec![INF; n];
+        dist[start] = 0;
+        Self {
+            graph,
+            dist,
+            settled: vec![false; n],
+            heap: BinaryHeap::from([Reverse((0, start))]),
+        }
+    }
+
+    /// Smallest tentative distance still queued, skipping stale entries.
+    fn top(&mut self) -> Option<u64> {
+        while let Some(&Reverse((d, u))) = self.heap.peek() {
+            if d == self.dist[u] && !self.settled[u] {
+                return Some(d);
+            }
+            self.heap.pop();
+        }
+        None
+    }
+
+    /// Settles the closest queued vertex and relaxes its edges, improving
+    /// `best` with every path that reaches a vertex the other side has seen.
+    fn step(&mut self, other: &Search<'_>, best: &mut u64) {
+        let Some(Reverse((d, u))) = self.heap.pop() else {
+            return;
+        };
+        self.settled[u] = true;
+        for edge in self.graph.out_edges(u) {
+            let v = edge.to as usize;
+            let cand = d.saturating_add(edge.weight).min(INF);
+            if cand < self.dist[v] {
+                self.dist[v] = cand;
+                self.heap.push(Reverse((cand, v)));
+            }
+            if other.dist[v] != INF {
+                *best = (*best).min(cand.saturating_add(other.dist[v]).min(INF));
+            }
+        }
+    }
+}
+
+/// Distance from `source` to `target`, or `None` if `target` is unreachable.
+///
+/// Runs Dijkstra forward from `source` and backward from `target` on the
+/// reversed graph, always advancing the side with the smaller queue head. Every
+/// relaxed edge that lands on a vertex the other side has reached gives a
+/// candidate path; once the two queue heads sum to at least the best candidate,
+/// no shorter path can remain.
+pub fn bidirectional_dijkstra(graph: &DirectedGraph, source: usize, target: usize) -> Option<u64> {
+    let n = graph.vertex_count();
+    if source >= n || target >= n {
+        return None;
+    }
+    if source == target {
+        return Some(0);
+    }
+
+    let reversed = graph.reverse();
+    let mut forward = Search::new(graph, source);
+    let mut backward = Search::new(&reversed, target);
+    let mut best = INF;
+
+    while let (Some(f), Some(b)) = (forward.top(), backward.top()) {
+        if f.saturating_add(b) >= best {
+            break;
+        }
+        if f <= b {
+            forward.step(&backward, &mut best);
+        } else {
+            backward.step(&forward, &mut best);
+        }
+    }
+
+    (best != INF).then_some(best)
+}
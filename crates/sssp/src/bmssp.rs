@@ -1,5 +1,5 @@
 mod find_pivots;
-mod partial_ds;
+pub(crate) mod partial_ds;
 
 use std::cmp::Ordering;
 
@@ -72,7 +72,7 @@ pub(crate) struct BmsspEngine<'a> {
     pub(crate) scratch_pending: Vec<usize>,
     pub(crate) scratch_sizes: Vec<usize>,
     pub(crate) scratch_stack: Vec<usize>,
-    partial_queues: Vec<Option<PartialOrderQueue>>,
+    partial_queues: Vec<Option<PartialOrderQueue<ValueKey>>>,
     base_queue: Vec<(ValueKey, usize)>,
     base_u0: Vec<usize>,
     insert_best: Vec<ValueKey>,
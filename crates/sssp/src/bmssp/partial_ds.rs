@@ -1,12 +1,10 @@
 use std::collections::BTreeSet;
 use std::collections::VecDeque;
 
-use super::ValueKey;
-
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-struct Entry {
+struct Entry<V> {
     key: usize,
-    value: ValueKey,
+    value: V,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -16,15 +14,15 @@ enum BlockKind {
 }
 
 #[derive(Debug)]
-struct Block {
+struct Block<V> {
     kind: BlockKind,
     // Lemma 3.3: D1 blocks have a fixed "upper bound" used for search. D0 blocks do not use it.
-    upper_bound: ValueKey,
-    entries: Vec<Entry>,
+    upper_bound: V,
+    entries: Vec<Entry<V>>,
 }
 
-impl Block {
-    fn new(kind: BlockKind, upper_bound: ValueKey, entries: Vec<Entry>) -> Self {
+impl<V: Ord + Copy> Block<V> {
+    fn new(kind: BlockKind, upper_bound: V, entries: Vec<Entry<V>>) -> Self {
         // D1 is initialized with a single empty block (sentinel). Other blocks should be non-empty.
         debug_assert!(
             kind == BlockKind::D1 || !entries.is_empty(),
@@ -47,26 +45,26 @@ impl Block {
         self.entries.is_empty()
     }
 
-    fn min_value(&self) -> Option<ValueKey> {
+    fn min_value(&self) -> Option<V> {
         self.entries.iter().map(|e| e.value).min()
     }
 }
 
 #[derive(Clone, Copy, Debug)]
-struct KeyLoc {
+struct KeyLoc<V> {
     block_id: usize,
     idx: usize,
-    value: ValueKey,
+    value: V,
     kind: BlockKind,
 }
 
-impl KeyLoc {
+impl<V> KeyLoc<V> {
     #[inline]
-    fn invalid() -> Self {
+    fn invalid(placeholder: V) -> Self {
         Self {
             block_id: usize::MAX,
             idx: 0,
-            value: ValueKey::infinity(),
+            value: placeholder,
             kind: BlockKind::D0,
         }
     }
@@ -79,23 +77,23 @@ impl KeyLoc {
 /// - `reset()` must be O(1) w.r.t. the key universe size; we use an epoch array for key presence.
 /// - `pull_into()` must be O(|S'|) (amortized); do not scan the entire key space.
 #[derive(Debug)]
-pub(super) struct PartialOrderQueue {
-    pull_limit: usize,     // M
-    upper_bound: ValueKey, // B
+pub(crate) struct PartialOrderQueue<V> {
+    pull_limit: usize, // M
+    upper_bound: V,    // B
     total_len: usize,
     d0: VecDeque<usize>,
     // (upper_bound, block_id) in increasing upper_bound order.
-    d1: BTreeSet<(ValueKey, usize)>,
-    blocks: Vec<Block>,
+    d1: BTreeSet<(V, usize)>,
+    blocks: Vec<Block<V>>,
 
     // Key universe bookkeeping: key is present iff key_epoch[key] == epoch.
     key_epoch: Vec<u32>,
-    key_locs: Vec<KeyLoc>,
+    key_locs: Vec<KeyLoc<V>>,
     epoch: u32,
 }
 
-impl PartialOrderQueue {
-    pub fn with_capacity(pull_limit: usize, upper_bound: ValueKey, key_space: usize) -> Self {
+impl<V: Ord + Copy> PartialOrderQueue<V> {
+    pub fn with_capacity(pull_limit: usize, upper_bound: V, key_space: usize) -> Self {
         let pull_limit = pull_limit.max(1);
         let key_cap = key_space.max(1);
         let mut this = Self {
@@ -106,7 +104,7 @@ impl PartialOrderQueue {
             d1: BTreeSet::new(),
             blocks: Vec::new(),
             key_epoch: vec![0; key_cap],
-            key_locs: vec![KeyLoc::invalid(); key_cap],
+            key_locs: vec![KeyLoc::invalid(upper_bound); key_cap],
             epoch: 1,
         };
         this.reset(upper_bound);
@@ -127,7 +125,7 @@ impl PartialOrderQueue {
         }
     }
 
-    pub fn reset(&mut self, upper_bound: ValueKey) {
+    pub fn reset(&mut self, upper_bound: V) {
         self.upper_bound = upper_bound;
         self.total_len = 0;
         self.d0.clear();
@@ -154,7 +152,8 @@ impl PartialOrderQueue {
             new_len = new_len.saturating_mul(2);
         }
         self.key_epoch.resize(new_len, 0);
-        self.key_locs.resize(new_len, KeyLoc::invalid());
+        self.key_locs
+            .resize(new_len, KeyLoc::invalid(self.upper_bound));
     }
 
     #[inline]
@@ -162,7 +161,7 @@ impl PartialOrderQueue {
         key < self.key_epoch.len() && self.key_epoch[key] == self.epoch
     }
 
-    pub fn insert(&mut self, key: usize, value: ValueKey) {
+    pub fn insert(&mut self, key: usize, value: V) {
         self.ensure_key_space(key);
         debug_assert!(
             value <= self.upper_bound,
@@ -206,7 +205,7 @@ impl PartialOrderQueue {
         }
     }
 
-    pub fn batch_prepend_unique(&mut self, values: &[(usize, ValueKey)]) {
+    pub fn batch_prepend_unique(&mut self, values: &[(usize, V)]) {
         if values.is_empty() {
             return;
         }
@@ -223,7 +222,7 @@ impl PartialOrderQueue {
             self.ensure_key_space(key);
         }
 
-        let mut accepted: Vec<Entry> = Vec::with_capacity(values.len());
+        let mut accepted: Vec<Entry<V>> = Vec::with_capacity(values.len());
         for &(key, value) in values {
             debug_assert!(
                 value <= self.upper_bound,
@@ -247,7 +246,7 @@ impl PartialOrderQueue {
 
         let l = accepted.len();
         if l <= self.pull_limit {
-            let block_id = self.allocate_block(BlockKind::D0, ValueKey::infinity(), accepted);
+            let block_id = self.allocate_block(BlockKind::D0, self.upper_bound, accepted);
             self.rebuild_key_locs(block_id);
             self.d0.push_front(block_id);
             self.total_len += l;
@@ -262,14 +261,14 @@ impl PartialOrderQueue {
 
         for (l, r) in segments.into_iter().rev() {
             let chunk = accepted[l..r].to_vec();
-            let block_id = self.allocate_block(BlockKind::D0, ValueKey::infinity(), chunk);
+            let block_id = self.allocate_block(BlockKind::D0, self.upper_bound, chunk);
             self.rebuild_key_locs(block_id);
             self.d0.push_front(block_id);
         }
         self.total_len += l;
     }
 
-    pub fn pull_into(&mut self, keys: &mut Vec<usize>) -> Option<ValueKey> {
+    pub fn pull_into(&mut self, keys: &mut Vec<usize>) -> Option<V> {
         keys.clear();
         if self.total_len == 0 {
             return None;
@@ -300,33 +299,25 @@ impl PartialOrderQueue {
             // Avoid `select_nth_unstable_by` overhead by extracting the minimum directly.
             self.cleanup_d0_front();
 
-            let key = if let Some(&block_id) = self.d0.front() {
-                let block = &self.blocks[block_id];
-                block
-                    .entries
-                    .iter()
-                    .min_by(|a, b| a.value.cmp(&b.value).then_with(|| a.key.cmp(&b.key)))
-                    .expect("non-empty D0 front block")
-                    .key
-            } else {
-                let mut found = None;
-                for &(_, block_id) in &self.d1 {
-                    let block = &self.blocks[block_id];
-                    if block.entries.is_empty() {
-                        continue;
-                    }
-                    found = Some(
-                        block
-                            .entries
-                            .iter()
-                            .min_by(|a, b| a.value.cmp(&b.value).then_with(|| a.key.cmp(&b.key)))
-                            .expect("non-empty D1 block")
-                            .key,
-                    );
-                    break;
-                }
-                found.expect("non-empty D1 when total_len > 0")
+            // The D0 front holds the smallest prepended values, but a later Insert may have put an
+            // even smaller one into D1, so compare the two minima.
+            let by_value = |a: &&Entry<V>, b: &&Entry<V>| {
+                a.value.cmp(&b.value).then_with(|| a.key.cmp(&b.key))
             };
+            let d0_min = self
+                .d0
+                .front()
+                .and_then(|&block_id| self.blocks[block_id].entries.iter().min_by(by_value));
+            let d1_min = self
+                .d1
+                .iter()
+                .find_map(|&(_, block_id)| self.blocks[block_id].entries.iter().min_by(by_value));
+            let key = d0_min
+                .into_iter()
+                .chain(d1_min)
+                .min_by(by_value)
+                .expect("non-empty D0 or D1 when total_len > 0")
+                .key;
 
             keys.push(key);
             debug_assert!(self.is_key_present(key));
@@ -339,7 +330,7 @@ impl PartialOrderQueue {
             return Some(boundary);
         }
 
-        let mut candidates: Vec<Entry> = Vec::with_capacity(take.saturating_mul(4).max(8));
+        let mut candidates: Vec<Entry<V>> = Vec::with_capacity(take.saturating_mul(4).max(8));
 
         self.cleanup_d0_front();
         let mut cnt0 = 0usize;
@@ -407,7 +398,7 @@ impl PartialOrderQueue {
         }
     }
 
-    fn current_min_value(&mut self) -> Option<ValueKey> {
+    fn current_min_value(&mut self) -> Option<V> {
         if self.total_len == 0 {
             return None;
         }
@@ -464,9 +455,9 @@ impl PartialOrderQueue {
         debug_assert!(inserted_right);
     }
 
-    fn delete_loc(&mut self, key: usize, loc: KeyLoc) {
+    fn delete_loc(&mut self, key: usize, loc: KeyLoc<V>) {
         self.key_epoch[key] = 0;
-        self.key_locs[key] = KeyLoc::invalid();
+        self.key_locs[key] = KeyLoc::invalid(self.upper_bound);
         self.total_len = self.total_len.saturating_sub(1);
 
         let block_id = loc.block_id;
@@ -509,19 +500,18 @@ impl PartialOrderQueue {
         }
     }
 
-    fn allocate_block(
-        &mut self,
-        kind: BlockKind,
-        upper_bound: ValueKey,
-        entries: Vec<Entry>,
-    ) -> usize {
+    fn allocate_block(&mut self, kind: BlockKind, upper_bound: V, entries: Vec<Entry<V>>) -> usize {
         let id = self.blocks.len();
         self.blocks.push(Block::new(kind, upper_bound, entries));
         id
     }
 }
 
-fn partition_by_medians(values: &mut [Entry], block_cap: usize, out: &mut Vec<(usize, usize)>) {
+fn partition_by_medians<V: Ord>(
+    values: &mut [Entry<V>],
+    block_cap: usize,
+    out: &mut Vec<(usize, usize)>,
+) {
     out.clear();
     let mut stack: Vec<(usize, usize)> = vec![(0, values.len())];
     while let Some((l, r)) = stack.pop() {
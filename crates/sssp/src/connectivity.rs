@@ -0,0 +1,90 @@
+use crate::graph::DirectedGraph;
+
+/// Marks every vertex reachable from `source`, ignoring weights.
+///
+/// Plain DFS over the CSR arrays, O(n + m). An out-of-range `source` reaches
+/// nothing.
+pub fn reachable_from(graph: &DirectedGraph, source: usize) -> Vec<bool> {
+    let n = graph.vertex_count();
+    let mut seen = vec![false; n];
+    if source >= n {
+        return seen;
+    }
+
+    let mut stack = vec![source as u32];
+    seen[source] = true;
+    while let Some(u) = stack.pop() {
+        let (to, _) = graph.out_edge_slices(u as usize);
+        for &v in to {
+            if !seen[v as usize] {
+                seen[v as usize] = true;
+                stack.push(v);
+            }
+        }
+    }
+    seen
+}
+
+/// Strongly connected component id of every vertex, via Tarjan's algorithm.
+///
+/// Ids are dense in `0..count` and come out in reverse topological order of
+/// the condensation: every edge `u -> v` has `id[u] >= id[v]`. The DFS keeps
+/// its own stack of `(vertex, next edge)` frames, so deep graphs do not
+/// overflow the call stack. Runs in O(n + m).
+pub fn strongly_connected_components(graph: &DirectedGraph) -> Vec<u32> {
+    const UNVISITED: u32 = u32::MAX;
+
+    let n = graph.vertex_count();
+    let mut index = vec![UNVISITED; n];
+    let mut low = vec![0_u32; n];
+    let mut comp = vec![UNVISITED; n];
+    let mut stack: Vec<u32> = Vec::new();
+    let mut frames: Vec<(u32, usize)> = Vec::new();
+    let mut next_index = 0_u32;
+    let mut next_comp = 0_u32;
+
+    for root in 0..n {
+        if index[root] != UNVISITED {
+            continue;
+        }
+        index[root] = next_index;
+        low[root] = next_index;
+        next_index += 1;
+        stack.push(root as u32);
+        frames.push((root as u32, 0));
+
+        while let Some(&mut (u, ref mut pos)) = frames.last_mut() {
+            let u = u as usize;
+            let (to, _) = graph.out_edge_slices(u);
+            if let Some(&v) = to.get(*pos) {
+                *pos += 1;
+                let v = v as usize;
+                if index[v] == UNVISITED {
+                    index[v] = next_index;
+                    low[v] = next_index;
+                    next_index += 1;
+                    stack.push(v as u32);
+                    frames.push((v as u32, 0));
+                } else if comp[v] == UNVISITED {
+                    low[u] = low[u].min(index[v]);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                low[parent as usize] = low[parent as usize].min(low[u]);
+            }
+            if low[u] == index[u] {
+                while let Some(w) = stack.pop() {
+                    comp[w as usize] = next_comp;
+                    if w as usize == u {
+                        break;
+                    }
+                }
+                next_comp += 1;
+            }
+        }
+    }
+    comp
+}
@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+
+use crate::INF;
+use crate::graph::DirectedGraph;
+
+struct Buckets {
+    delta: u64,
+    live: BTreeMap<u64, Vec<(u32, u64)>>,
+    spare: Vec<Vec<(u32, u64)>>,
+}
+
+impl Buckets {
+    fn push(&mut self, v: u32, d: u64) {
+        let spare = &mut self.spare;
+        self.live
+            .entry(d / self.delta)
+            .or_insert_with(|| spare.pop().unwrap_or_default())
+            .push((v, d));
+    }
+
+    fn recycle(&mut self, mut bucket: Vec<(u32, u64)>) {
+        bucket.clear();
+        self.spare.push(bucket);
+    }
+}
+
+fn relax(dist: &mut [u64], buckets: &mut Buckets, v: u32, cand: u64) {
+    let cand = cand.min(INF);
+    if cand < dist[v as usize] {
+        dist[v as usize] = cand;
+        buckets.push(v, cand);
+    }
+}
+
+/// Delta-stepping SSSP (Meyer and Sanders), run sequentially.
+///
+/// Vertices wait in buckets of width `delta` by tentative distance. The lowest
+/// bucket is settled by repeatedly relaxing light edges (`weight <= delta`),
+/// which can only refill the same or later buckets; heavy edges of everything
+/// removed from it are relaxed once at the end of the phase. Stale entries are
+/// skipped lazily, and emptied bucket vectors are recycled rather than freed.
+/// Panics if `delta == 0`.
+pub fn delta_stepping(graph: &DirectedGraph, source: usize, delta: u64) -> Vec<u64> {
+    assert!(delta > 0, "delta must be positive");
+    let n = graph.vertex_count();
+    let mut dist = vec![INF; n];
+    if source >= n {
+        return dist;
+    }
+
+    let mut buckets = Buckets {
+        delta,
+        live: BTreeMap::new(),
+        spare: Vec::new(),
+    };
+    let mut removed: Vec<u32> = Vec::new();
+    let mut in_removed = vec![false; n];
+    dist[source] = 0;
+    buckets.push(source as u32, 0);
+
+    while let Some((&index, _)) = buckets.live.first_key_value() {
+        while let Some(batch) = buckets.live.remove(&index) {
+            for &(u, d) in &batch {
+                if d != dist[u as usize] {
+                    continue;
+                }
+                if !in_removed[u as usize] {
+                    in_removed[u as usize] = true;
+                    removed.push(u);
+                }
+                let (to, weight) = graph.out_edge_slices(u as usize);
+                for (&v, &w) in to.iter().zip(weight) {
+                    if w <= delta {
+                        relax(&mut dist, &mut buckets, v, d.saturating_add(w));
+                    }
+                }
+            }
+            buckets.recycle(batch);
+        }
+
+        for &u in &removed {
+            in_removed[u as usize] = false;
+            let d = dist[u as usize];
+            let (to, weight) = graph.out_edge_slices(u as usize);
+            for (&v, &w) in to.iter().zip(weight) {
+                if w > delta {
+                    relax(&mut dist, &mut buckets, v, d.saturating_add(w));
+                }
+            }
+        }
+        removed.clear();
+    }
+
+    dist
+}
@@ -3,17 +3,20 @@ use std::collections::BinaryHeap;
 
 use crate::INF;
 use crate::graph::DirectedGraph;
+use crate::weight::Weight;
 
-pub fn dijkstra_binary_heap(graph: &DirectedGraph, source: usize) -> Vec<u64> {
+/// Distances from `source`, `W::INFINITY` (`INF` for `u64`) where
+/// unreachable. Generic over the [`Weight`] type, e.g. `u64` or `F64`.
+pub fn dijkstra_binary_heap<W: Weight>(graph: &DirectedGraph<W>, source: usize) -> Vec<W> {
     let n = graph.vertex_count();
-    let mut dist = vec![INF; n];
+    let mut dist = vec![W::INFINITY; n];
     if source >= n {
         return dist;
     }
 
     let mut heap = BinaryHeap::new();
-    dist[source] = 0;
-    heap.push(Reverse((0_u64, source)));
+    dist[source] = W::ZERO;
+    heap.push(Reverse((W::ZERO, source)));
 
     while let Some(Reverse((d, u))) = heap.pop() {
         if d != dist[u] {
@@ -22,7 +25,7 @@ pub fn dijkstra_binary_heap(graph: &DirectedGraph, source: usize) -> Vec<u64> {
 
         for edge in graph.out_edges(u) {
             let v = edge.to as usize;
-            let cand = d.saturating_add(edge.weight).min(INF);
+            let cand = d.add(edge.weight);
             if cand < dist[v] {
                 dist[v] = cand;
                 heap.push(Reverse((cand, v)));
@@ -32,3 +35,105 @@ pub fn dijkstra_binary_heap(graph: &DirectedGraph, source: usize) -> Vec<u64> {
 
     dist
 }
+
+/// A shortest-path distance with unreachability spelled out instead of
+/// encoded as [`INF`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Dist(pub Option<u64>);
+
+impl Dist {
+    /// Maps the raw-API sentinel [`INF`] to unreachable.
+    #[inline]
+    pub fn from_raw(d: u64) -> Self {
+        Self((d < INF).then_some(d))
+    }
+
+    #[inline]
+    pub fn get(self) -> Option<u64> {
+        self.0
+    }
+
+    #[inline]
+    pub fn is_unreachable(self) -> bool {
+        self.0.is_none()
+    }
+}
+
+/// [`dijkstra_binary_heap`] with unreachable vertices as `Dist(None)`.
+pub fn dijkstra_opt(graph: &DirectedGraph, source: usize) -> Vec<Dist> {
+    dijkstra_binary_heap(graph, source)
+        .into_iter()
+        .map(Dist::from_raw)
+        .collect()
+}
+
+/// Distance from the nearest of `sources` to every vertex.
+///
+/// All sources start in the heap at distance 0, which is Dijkstra from a virtual
+/// vertex with zero-weight edges to each of them. Out-of-range sources are
+/// ignored, like an out-of-range `source` in [`dijkstra_binary_heap`].
+pub fn dijkstra_multi<W: Weight>(graph: &DirectedGraph<W>, sources: &[u32]) -> Vec<W> {
+    let n = graph.vertex_count();
+    let mut dist = vec![W::INFINITY; n];
+    let mut heap = BinaryHeap::new();
+    for &s in sources {
+        let s = s as usize;
+        if s < n && dist[s] != W::ZERO {
+            dist[s] = W::ZERO;
+            heap.push(Reverse((W::ZERO, s)));
+        }
+    }
+
+    while let Some(Reverse((d, u))) = heap.pop() {
+        if d != dist[u] {
+            continue;
+        }
+
+        for edge in graph.out_edges(u) {
+            let v = edge.to as usize;
+            let cand = d.add(edge.weight);
+            if cand < dist[v] {
+                dist[v] = cand;
+                heap.push(Reverse((cand, v)));
+            }
+        }
+    }
+
+    dist
+}
+
+/// Distance from `source` to `target`, or `None` if `target` is unreachable.
+///
+/// Same search as [`dijkstra_binary_heap`], but it stops as soon as `target`
+/// is popped: its distance is final from then on.
+pub fn dijkstra_to<W: Weight>(graph: &DirectedGraph<W>, source: usize, target: usize) -> Option<W> {
+    let n = graph.vertex_count();
+    if source >= n || target >= n {
+        return None;
+    }
+
+    let mut dist = vec![W::INFINITY; n];
+    let mut heap = BinaryHeap::new();
+    dist[source] = W::ZERO;
+    heap.push(Reverse((W::ZERO, source)));
+
+    while let Some(Reverse((d, u))) = heap.pop() {
+        if d != dist[u] {
+            continue;
+        }
+        if u == target {
+            return Some(d);
+        }
+
+        for edge in graph.out_edges(u) {
+            let v = edge.to as usize;
+            let cand = d.add(edge.weight);
+            if cand < dist[v] {
+                dist[v] = cand;
+                heap.push(Reverse((cand, v)));
+            }
+        }
+    }
+
+    None
+}
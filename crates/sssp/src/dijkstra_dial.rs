@@ -0,0 +1,50 @@
+use crate::INF;
+use crate::graph::DirectedGraph;
+
+/// Dijkstra with Dial's bucket queue, for integer weights at most `max_weight`.
+///
+/// Distances lie in `[0, max_weight * (n - 1)]`, but the queued keys always sit
+/// within `max_weight` of the one being scanned, so `max_weight + 1` buckets
+/// indexed by `dist % (max_weight + 1)` and a rotating cursor cover them. Runs
+/// in O(m + n * max_weight) time. Panics if an edge is heavier than `max_weight`.
+pub fn dijkstra_dial(graph: &DirectedGraph, source: usize, max_weight: u64) -> Vec<u64> {
+    let n = graph.vertex_count();
+    let mut dist = vec![INF; n];
+    if source >= n {
+        return dist;
+    }
+
+    let ring = max_weight as usize + 1;
+    let mut buckets: Vec<Vec<u32>> = vec![Vec::new(); ring];
+    dist[source] = 0;
+    buckets[0].push(source as u32);
+    let mut queued = 1_usize;
+    let mut current = 0_u64;
+
+    while queued > 0 {
+        let slot = (current % ring as u64) as usize;
+        while let Some(u) = buckets[slot].pop() {
+            queued -= 1;
+            let u = u as usize;
+            if dist[u] != current {
+                continue;
+            }
+            let (to, weight) = graph.out_edge_slices(u);
+            for (&v, &w) in to.iter().zip(weight) {
+                assert!(
+                    w <= max_weight,
+                    "edge weight {w} exceeds max_weight {max_weight}"
+                );
+                let cand = current + w;
+                if cand < dist[v as usize] {
+                    dist[v as usize] = cand;
+                    buckets[(cand % ring as u64) as usize].push(v);
+                    queued += 1;
+                }
+            }
+        }
+        current += 1;
+    }
+
+    dist
+}
@@ -21,6 +21,7 @@ pub enum GraphCase {
     GridSwirl,
     WrongDijkstraKiller,
     SpfaKiller,
+    LayeredDag,
 }
 
 impl GraphCase {
@@ -36,6 +37,7 @@ impl GraphCase {
             Self::GridSwirl => "grid_swirl",
             Self::WrongDijkstraKiller => "wrong_dijkstra_killer",
             Self::SpfaKiller => "spfa_killer",
+            Self::LayeredDag => "layered_dag",
         }
     }
 }
@@ -59,6 +61,10 @@ pub fn generate_case(case: GraphCase, size: usize, seed: u64) -> GeneratedGraph
         GraphCase::GridSwirl => grid_swirl_case(size.max(256), seed),
         GraphCase::WrongDijkstraKiller => wrong_dijkstra_killer_case(size.max(512), seed),
         GraphCase::SpfaKiller => spfa_killer_case(size.max(1_024), seed),
+        GraphCase::LayeredDag => {
+            let size = size.max(64);
+            layered_dag(size, floor_sqrt(size), seed)
+        }
     }
 }
 
@@ -460,6 +466,45 @@ fn spfa_killer_case(size: usize, seed: u64) -> GeneratedGraph {
     }
 }
 
+/// DAG of `layers` equal layers numbered layer by layer, with edges only from
+/// a layer to one of the next two, so vertex order is a topological order.
+///
+/// `GraphCase::LayeredDag` uses about `sqrt(size)` layers. The source is
+/// vertex 0, which feeds all of layer 1, and every later vertex has a parent
+/// in the previous layer, so everything is reachable from the source.
+pub fn layered_dag(size: usize, layers: usize, seed: u64) -> GeneratedGraph {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let size = size.max(4);
+    let layers = layers.clamp(2, size / 2);
+    let width = size / layers;
+    let n = width * layers;
+    let mut edges = Vec::with_capacity(n * 3);
+    let mut used = HashSet::with_capacity(n * 6);
+
+    for v in width..n {
+        let u = if v < 2 * width {
+            0
+        } else {
+            (v / width - 1) * width + rng.random_range(0..width)
+        };
+        push_unique_edge(&mut edges, &mut used, u, v, rng.random_range(0..=C_MAX));
+    }
+    let m_target = n * 3 - width;
+    while edges.len() < m_target {
+        let u = rng.random_range(0..(n - width));
+        let next = (u / width + rng.random_range(1..=2)).min(layers - 1);
+        let v = next * width + rng.random_range(0..width);
+        push_unique_edge(&mut edges, &mut used, u, v, rng.random_range(0..=C_MAX));
+    }
+
+    edges.shuffle(&mut rng);
+    GeneratedGraph {
+        graph: DirectedGraph::from_edges(n, &edges),
+        source: 0,
+        target: n - 1 - rng.random_range(0..width),
+    }
+}
+
 #[inline]
 fn complete_edges(n: usize) -> usize {
     n.saturating_mul(n.saturating_sub(1))
@@ -1,17 +1,25 @@
+use std::sync::OnceLock;
+
+use crate::weight::Weight;
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct Edge {
+pub struct Edge<W = u64> {
     pub to: u32,
-    pub weight: u64,
+    pub weight: W,
 }
 
+/// CSR directed graph with weights of type `W`, `u64` unless stated.
 #[derive(Clone, Debug)]
-pub struct DirectedGraph {
+pub struct DirectedGraph<W = u64> {
     vertex_count: usize,
     offsets: Vec<usize>,
     to: Vec<u32>,
-    weight: Vec<u64>,
+    weight: Vec<W>,
+    in_degrees: OnceLock<Vec<usize>>,
 }
 
+/// Constructors for the default `u64` weights; other weight types build with
+/// [`DirectedGraph::from_weighted_edges`].
 impl DirectedGraph {
     pub fn new(vertex_count: usize) -> Self {
         Self {
@@ -19,10 +27,31 @@ impl DirectedGraph {
             offsets: vec![0; vertex_count + 1],
             to: Vec::new(),
             weight: Vec::new(),
+            in_degrees: OnceLock::new(),
         }
     }
 
     pub fn from_edges(vertex_count: usize, edges: &[(u32, u32, u64)]) -> Self {
+        Self::from_weighted_edges(vertex_count, edges)
+    }
+
+    /// Stores every undirected edge `{u, v}` as both `u -> v` and `v -> u`
+    /// with the same weight, so `edge_count() == 2 * edges.len()`; a loop
+    /// `(u, u, w)` becomes two parallel loops.
+    pub fn from_undirected_edges(vertex_count: usize, edges: &[(u32, u32, u64)]) -> Self {
+        let both: Vec<_> = edges
+            .iter()
+            .flat_map(|&(u, v, w)| [(u, v, w), (v, u, w)])
+            .collect();
+        Self::from_edges(vertex_count, &both)
+    }
+}
+
+impl<W: Weight> DirectedGraph<W> {
+    /// [`DirectedGraph::from_edges`] for any [`Weight`], e.g.
+    /// `DirectedGraph::<F64>::from_weighted_edges`. Edges keep their input
+    /// order within each vertex.
+    pub fn from_weighted_edges(vertex_count: usize, edges: &[(u32, u32, W)]) -> Self {
         let mut out_deg = vec![0_usize; vertex_count];
         for &(from, to, _) in edges {
             assert!((from as usize) < vertex_count, "from vertex out of range");
@@ -36,7 +65,7 @@ impl DirectedGraph {
         }
 
         let mut to = vec![0_u32; edges.len()];
-        let mut weight = vec![0_u64; edges.len()];
+        let mut weight = vec![W::ZERO; edges.len()];
         let mut cursor = offsets[..vertex_count].to_vec();
 
         for &(from, dst, w) in edges {
@@ -51,6 +80,7 @@ impl DirectedGraph {
             offsets,
             to,
             weight,
+            in_degrees: OnceLock::new(),
         }
     }
 
@@ -71,8 +101,36 @@ impl DirectedGraph {
         end - start
     }
 
+    /// Number of edges into `v`. The first call counts every in-degree in one
+    /// pass over the edges and caches them.
     #[inline]
-    pub fn out_edges(&self, v: usize) -> OutEdges<'_> {
+    pub fn in_degree(&self, v: usize) -> usize {
+        self.in_degrees()[v]
+    }
+
+    fn in_degrees(&self) -> &[usize] {
+        self.in_degrees.get_or_init(|| {
+            let mut deg = vec![0_usize; self.vertex_count];
+            for &v in &self.to {
+                deg[v as usize] += 1;
+            }
+            deg
+        })
+    }
+
+    /// `edge_count / (n * (n - 1))`, the fraction of possible non-loop edges
+    /// present; `0.0` for fewer than two vertices. Parallel edges and loops
+    /// can push it above `1.0`.
+    pub fn density(&self) -> f64 {
+        let n = self.vertex_count as f64;
+        if self.vertex_count < 2 {
+            return 0.0;
+        }
+        self.edge_count() as f64 / (n * (n - 1.0))
+    }
+
+    #[inline]
+    pub fn out_edges(&self, v: usize) -> OutEdges<'_, W> {
         let start = self.offsets[v];
         let end = self.offsets[v + 1];
         OutEdges {
@@ -82,14 +140,57 @@ impl DirectedGraph {
         }
     }
 
+    /// Out-edges of `u`; same as [`Self::out_edges`].
     #[inline]
-    pub fn out_edge_slices(&self, v: usize) -> (&[u32], &[u64]) {
+    pub fn neighbors(&self, u: usize) -> impl Iterator<Item = Edge<W>> + '_ {
+        self.out_edges(u)
+    }
+
+    #[inline]
+    pub fn out_edge_slices(&self, v: usize) -> (&[u32], &[W]) {
         let start = self.offsets[v];
         let end = self.offsets[v + 1];
         (&self.to[start..end], &self.weight[start..end])
     }
 
-    pub fn edges_vec(&self) -> Vec<(u32, u32, u64)> {
+    /// The same vertices with every edge `u -> v` turned into `v -> u`.
+    pub fn reverse(&self) -> Self {
+        let mut offsets = vec![0_usize; self.vertex_count + 1];
+        for &v in &self.to {
+            offsets[v as usize + 1] += 1;
+        }
+        for v in 0..self.vertex_count {
+            offsets[v + 1] += offsets[v];
+        }
+
+        let mut to = vec![0_u32; self.to.len()];
+        let mut weight = vec![W::ZERO; self.to.len()];
+        let mut cursor = offsets[..self.vertex_count].to_vec();
+        for u in 0..self.vertex_count {
+            for edge in self.out_edges(u) {
+                let idx = cursor[edge.to as usize];
+                cursor[edge.to as usize] += 1;
+                to[idx] = u as u32;
+                weight[idx] = edge.weight;
+            }
+        }
+
+        Self {
+            vertex_count: self.vertex_count,
+            offsets,
+            to,
+            weight,
+            in_degrees: OnceLock::new(),
+        }
+    }
+
+    /// Same as [`Self::reverse`]: every edge `u -> v` becomes `v -> u` with
+    /// its weight, for searching backward from a target.
+    pub fn transpose(&self) -> Self {
+        self.reverse()
+    }
+
+    pub fn edges_vec(&self) -> Vec<(u32, u32, W)> {
         let mut edges = Vec::with_capacity(self.edge_count());
         for u in 0..self.vertex_count {
             for edge in self.out_edges(u) {
@@ -100,14 +201,113 @@ impl DirectedGraph {
     }
 }
 
-pub struct OutEdges<'a> {
+/// Collects edges one at a time and lays them out as CSR on [`Self::build`].
+///
+/// The vertex count grows to cover every endpoint seen, and `build` does the
+/// same two counting passes as [`DirectedGraph::from_edges`], so edges keep
+/// their insertion order within each vertex.
+#[derive(Clone, Debug, Default)]
+pub struct GraphBuilder {
+    vertex_count: usize,
+    edges: Vec<(u32, u32, u64)>,
+}
+
+impl GraphBuilder {
+    /// Starts with `vertex_count` isolated vertices.
+    pub fn new(vertex_count: usize) -> Self {
+        Self {
+            vertex_count,
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn add_edge(&mut self, from: u32, to: u32, weight: u64) -> &mut Self {
+        self.vertex_count = self.vertex_count.max(from.max(to) as usize + 1);
+        self.edges.push((from, to, weight));
+        self
+    }
+
+    #[inline]
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    #[inline]
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn build(&self) -> DirectedGraph {
+        DirectedGraph::from_edges(self.vertex_count, &self.edges)
+    }
+}
+
+/// CSR graph like [`DirectedGraph`], but with signed weights for algorithms
+/// that allow negative edges.
+#[derive(Clone, Debug)]
+pub struct DirectedGraphSigned {
+    vertex_count: usize,
+    offsets: Vec<usize>,
+    to: Vec<u32>,
+    weight: Vec<i64>,
+}
+
+impl DirectedGraphSigned {
+    pub fn from_edges(vertex_count: usize, edges: &[(u32, u32, i64)]) -> Self {
+        let mut offsets = vec![0_usize; vertex_count + 1];
+        for &(from, to, _) in edges {
+            assert!((from as usize) < vertex_count, "from vertex out of range");
+            assert!((to as usize) < vertex_count, "to vertex out of range");
+            offsets[from as usize + 1] += 1;
+        }
+        for v in 0..vertex_count {
+            offsets[v + 1] += offsets[v];
+        }
+
+        let mut to = vec![0_u32; edges.len()];
+        let mut weight = vec![0_i64; edges.len()];
+        let mut cursor = offsets[..vertex_count].to_vec();
+        for &(from, dst, w) in edges {
+            let idx = cursor[from as usize];
+            cursor[from as usize] += 1;
+            to[idx] = dst;
+            weight[idx] = w;
+        }
+
+        Self {
+            vertex_count,
+            offsets,
+            to,
+            weight,
+        }
+    }
+
+    #[inline]
+    pub fn vertex_count(&self) -> usize {
+        self.vertex_count
+    }
+
+    #[inline]
+    pub fn edge_count(&self) -> usize {
+        self.to.len()
+    }
+
+    #[inline]
+    pub fn out_edge_slices(&self, v: usize) -> (&[u32], &[i64]) {
+        let start = self.offsets[v];
+        let end = self.offsets[v + 1];
+        (&self.to[start..end], &self.weight[start..end])
+    }
+}
+
+pub struct OutEdges<'a, W = u64> {
     to: &'a [u32],
-    weight: &'a [u64],
+    weight: &'a [W],
     idx: usize,
 }
 
-impl<'a> Iterator for OutEdges<'a> {
-    type Item = Edge;
+impl<W: Copy> Iterator for OutEdges<'_, W> {
+    type Item = Edge<W>;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.idx >= self.to.len() {
@@ -127,4 +327,4 @@ impl<'a> Iterator for OutEdges<'a> {
     }
 }
 
-impl ExactSizeIterator for OutEdges<'_> {}
+impl<W: Copy> ExactSizeIterator for OutEdges<'_, W> {}
@@ -0,0 +1,128 @@
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::collections::BinaryHeap;
+use std::collections::HashSet;
+
+use crate::INF;
+use crate::graph::DirectedGraph;
+
+/// Dijkstra from `source` to `target` avoiding `banned_vertices` and the
+/// `(from, to)` pairs in `banned_edges`; returns the cost and vertex sequence.
+fn restricted_path(
+    graph: &DirectedGraph,
+    source: u32,
+    target: u32,
+    banned_vertices: &[bool],
+    banned_edges: &HashSet<(u32, u32)>,
+) -> Option<(u64, Vec<u32>)> {
+    let n = graph.vertex_count();
+    let mut dist = vec![INF; n];
+    let mut parent = vec![u32::MAX; n];
+    let mut heap = BinaryHeap::new();
+    dist[source as usize] = 0;
+    heap.push(Reverse((0_u64, source)));
+
+    while let Some(Reverse((d, u))) = heap.pop() {
+        if d != dist[u as usize] {
+            continue;
+        }
+        if u == target {
+            let mut path = vec![target];
+            let mut cur = target;
+            while cur != source {
+                cur = parent[cur as usize];
+                path.push(cur);
+            }
+            path.reverse();
+            return Some((d, path));
+        }
+        for edge in graph.out_edges(u as usize) {
+            let v = edge.to;
+            if banned_vertices[v as usize] || banned_edges.contains(&(u, v)) {
+                continue;
+            }
+            let cand = d.saturating_add(edge.weight).min(INF);
+            if cand < dist[v as usize] {
+                dist[v as usize] = cand;
+                parent[v as usize] = u;
+                heap.push(Reverse((cand, v)));
+            }
+        }
+    }
+
+    None
+}
+
+/// Weight of the cheapest edge `u -> v`, which is the one shortest paths use.
+fn edge_weight(graph: &DirectedGraph, u: u32, v: u32) -> u64 {
+    graph
+        .out_edges(u as usize)
+        .filter(|edge| edge.to == v)
+        .map(|edge| edge.weight)
+        .min()
+        .expect("path edge exists")
+}
+
+/// Costs of the `k` shortest simple paths from `source` to `target`, ascending.
+///
+/// Yen's algorithm over [`crate::dijkstra_to`]-style searches: each accepted
+/// path spawns, for every prefix, a spur search that bans the next edge of every
+/// accepted path sharing that prefix and the prefix's own vertices. Paths are
+/// vertex sequences, so parallel edges count once, at their cheapest weight.
+/// Returns fewer than `k` costs when fewer simple paths exist.
+pub fn k_shortest(graph: &DirectedGraph, source: usize, target: usize, k: usize) -> Vec<u64> {
+    let n = graph.vertex_count();
+    if k == 0 || source >= n || target >= n {
+        return Vec::new();
+    }
+
+    let (source, target) = (source as u32, target as u32);
+    let no_vertices = vec![false; n];
+    let Some(first) = restricted_path(graph, source, target, &no_vertices, &HashSet::new()) else {
+        return Vec::new();
+    };
+
+    let mut accepted: Vec<(u64, Vec<u32>)> = vec![first];
+    let mut candidates: BTreeSet<(u64, Vec<u32>)> = BTreeSet::new();
+    let mut banned_vertices = vec![false; n];
+    let mut banned_edges = HashSet::new();
+
+    while accepted.len() < k {
+        let (_, last) = accepted.last().expect("at least one path");
+        let mut root_cost = 0_u64;
+        for i in 0..last.len() - 1 {
+            let spur = last[i];
+            let root = &last[..=i];
+
+            banned_edges.clear();
+            for (_, path) in &accepted {
+                if path.len() > i + 1 && path[..=i] == *root {
+                    banned_edges.insert((path[i], path[i + 1]));
+                }
+            }
+            for &v in &root[..i] {
+                banned_vertices[v as usize] = true;
+            }
+
+            if let Some((spur_cost, spur_path)) =
+                restricted_path(graph, spur, target, &banned_vertices, &banned_edges)
+            {
+                let mut path = root[..i].to_vec();
+                path.extend(spur_path);
+                candidates.insert((root_cost + spur_cost, path));
+            }
+
+            for &v in &root[..i] {
+                banned_vertices[v as usize] = false;
+            }
+            root_cost += edge_weight(graph, last[i], last[i + 1]);
+        }
+
+        match candidates.pop_first() {
+            Some(next) => accepted.push(next),
+            None => break,
+        }
+    }
+
+    accepted.into_iter().map(|(cost, _)| cost).collect()
+}
@@ -1,15 +1,45 @@
+mod astar;
+mod batch_queue;
+mod bellman_ford;
+mod bidirectional;
 mod bmssp;
+mod connectivity;
 mod constant_degree;
+mod delta_stepping;
 mod dijkstra_binary;
+mod dijkstra_dial;
 mod dijkstra_radix;
 pub mod generator;
 pub mod graph;
+mod k_shortest;
+mod radix_heap;
+mod weight;
 
+pub use astar::astar;
+pub use batch_queue::BatchPriorityQueue;
+pub use bellman_ford::INF_SIGNED;
+pub use bellman_ford::NegativeCycle;
+pub use bellman_ford::bellman_ford;
+pub use bidirectional::bidirectional_dijkstra;
 pub use bmssp::bmssp_paper;
+pub use connectivity::reachable_from;
+pub use connectivity::strongly_connected_components;
+pub use delta_stepping::delta_stepping;
+pub use dijkstra_binary::Dist;
 pub use dijkstra_binary::dijkstra_binary_heap;
+pub use dijkstra_binary::dijkstra_multi;
+pub use dijkstra_binary::dijkstra_opt;
+pub use dijkstra_binary::dijkstra_to;
+pub use dijkstra_dial::dijkstra_dial;
 pub use dijkstra_radix::dijkstra_radix_heap;
 pub use graph::DirectedGraph;
+pub use graph::DirectedGraphSigned;
 pub use graph::Edge;
+pub use graph::GraphBuilder;
+pub use k_shortest::k_shortest;
+pub use radix_heap::RadixHeap;
+pub use weight::F64;
+pub use weight::Weight;
 
 pub const INF: u64 = u64::MAX / 4;
 
@@ -21,13 +51,35 @@ mod tests {
     use rand::SeedableRng;
     use rand::rngs::StdRng;
 
+    use crate::BatchPriorityQueue;
+    use crate::Dist;
+    use crate::F64;
+    use crate::INF;
+    use crate::INF_SIGNED;
+    use crate::NegativeCycle;
+    use crate::RadixHeap;
+    use crate::Weight;
+    use crate::astar;
+    use crate::bellman_ford;
+    use crate::bidirectional_dijkstra;
     use crate::bmssp_paper;
     use crate::constant_degree::transform_to_constant_degree;
+    use crate::delta_stepping;
     use crate::dijkstra_binary_heap;
+    use crate::dijkstra_dial;
+    use crate::dijkstra_multi;
+    use crate::dijkstra_opt;
     use crate::dijkstra_radix_heap;
+    use crate::dijkstra_to;
     use crate::generator::GraphCase;
     use crate::generator::generate_case;
+    use crate::generator::layered_dag;
     use crate::graph::DirectedGraph;
+    use crate::graph::DirectedGraphSigned;
+    use crate::graph::GraphBuilder;
+    use crate::k_shortest;
+    use crate::reachable_from;
+    use crate::strongly_connected_components;
 
     fn random_graph(n: usize, m: usize, seed: u64) -> DirectedGraph {
         let mut rng = StdRng::seed_from_u64(seed);
@@ -75,6 +127,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn dijkstra_to_matches_full_run() {
+        for seed in 0..20_u64 {
+            let n = 64;
+            let g = random_graph(n, 160, 0x7A26_0000 + seed);
+            let src = (seed as usize) % n;
+            let full = dijkstra_binary_heap(&g, src);
+            for target in [src, (src + 1) % n, (src + 17) % n, n - 1] {
+                let expected = (full[target] != INF).then_some(full[target]);
+                assert_eq!(
+                    dijkstra_to(&g, src, target),
+                    expected,
+                    "seed={seed} t={target}"
+                );
+            }
+        }
+        let g = DirectedGraph::from_edges(3, &[(0, 1, 4)]);
+        assert_eq!(dijkstra_to(&g, 0, 2), None);
+        assert_eq!(dijkstra_to(&g, 0, 3), None);
+    }
+
+    #[test]
+    fn bellman_ford_matches_dijkstra_on_non_negative() {
+        for seed in 0..10_u64 {
+            let g = random_graph(40, 200, 0xBE11_0000 + seed);
+            let signed: Vec<(u32, u32, i64)> = g
+                .edges_vec()
+                .into_iter()
+                .map(|(u, v, w)| (u, v, w as i64))
+                .collect();
+            let sg = DirectedGraphSigned::from_edges(40, &signed);
+            let src = (seed as usize) % 40;
+            let expected: Vec<i64> = dijkstra_binary_heap(&g, src)
+                .into_iter()
+                .map(|d| if d == INF { INF_SIGNED } else { d as i64 })
+                .collect();
+            assert_eq!(bellman_ford(&sg, src), Ok(expected), "seed={seed}");
+        }
+    }
+
+    #[test]
+    fn bellman_ford_negative_acyclic() {
+        // 0 -> 1 -> 3 is cheaper than 0 -> 2 -> 3 only through the -6 edge.
+        let g = DirectedGraphSigned::from_edges(
+            5,
+            &[(0, 1, 4), (0, 2, 1), (1, 3, -6), (2, 3, 2), (3, 4, -1)],
+        );
+        assert_eq!(bellman_ford(&g, 0), Ok(vec![0, 4, 1, -2, -3]));
+        assert_eq!(
+            bellman_ford(&g, 2),
+            Ok(vec![INF_SIGNED, INF_SIGNED, 0, 2, 1])
+        );
+    }
+
+    #[test]
+    fn bellman_ford_detects_reachable_negative_cycle() {
+        // 1 -> 2 -> 3 -> 1 sums to -1.
+        let g = DirectedGraphSigned::from_edges(
+            5,
+            &[(0, 1, 5), (1, 2, 2), (2, 3, -4), (3, 1, 1), (3, 4, 3)],
+        );
+        assert_eq!(bellman_ford(&g, 0), Err(NegativeCycle));
+        // The cycle does not matter from a vertex that cannot reach it.
+        assert_eq!(
+            bellman_ford(&g, 4),
+            Ok(vec![INF_SIGNED, INF_SIGNED, INF_SIGNED, INF_SIGNED, 0])
+        );
+    }
+
     #[test]
     fn zero_and_unreachable_cases() {
         let g = DirectedGraph::from_edges(6, &[(0, 1, 0), (1, 2, 0), (2, 3, 0), (4, 5, 7)]);
@@ -83,6 +204,22 @@ mod tests {
         assert_eq!(got, expected);
     }
 
+    #[test]
+    fn dijkstra_opt_maps_inf_to_none() {
+        let g = DirectedGraph::from_edges(6, &[(0, 1, 3), (1, 2, 0), (2, 3, 4), (4, 5, 7)]);
+        let raw = dijkstra_binary_heap(&g, 0);
+        let opt = dijkstra_opt(&g, 0);
+        assert_eq!(
+            opt,
+            [Some(0), Some(3), Some(3), Some(7), None, None].map(Dist)
+        );
+        for (&d, &o) in raw.iter().zip(&opt) {
+            assert_eq!(o.is_unreachable(), d == INF);
+            assert_eq!(o.get().unwrap_or(INF), d);
+        }
+        assert_eq!(dijkstra_opt(&g, 99), vec![Dist(None); 6]);
+    }
+
     #[test]
     fn constant_degree_transform_preserves_distances() {
         for seed in 0..12_u64 {
@@ -121,6 +258,7 @@ mod tests {
             GraphCase::GridSwirl,
             GraphCase::WrongDijkstraKiller,
             GraphCase::SpfaKiller,
+            GraphCase::LayeredDag,
         ];
 
         for (i, case) in cases.iter().enumerate() {
@@ -131,6 +269,592 @@ mod tests {
             let d2 = bmssp_paper(&input.graph, input.source);
             assert_eq!(d0, d1, "case={:?}", case);
             assert_eq!(d0, d2, "case={:?}", case);
+            for delta in [1, 1_000, 1 << 40] {
+                let d4 = delta_stepping(&input.graph, input.source, delta);
+                assert_eq!(d0, d4, "case={:?} delta={delta}", case);
+            }
+
+            // Dial needs a small weight bound; of these only the zero-weight case has one.
+            let max_weight = input.graph.edges_vec().iter().map(|e| e.2).max().unwrap();
+            if max_weight <= 1_000 {
+                let d3 = dijkstra_dial(&input.graph, input.source, max_weight);
+                assert_eq!(d0, d3, "case={:?}", case);
+            }
+        }
+    }
+
+    #[test]
+    fn layered_dag_has_no_backward_reachability() {
+        for (i, layers) in [2, 7, 32].into_iter().enumerate() {
+            let input = layered_dag(1_000, layers, 0xDA6 + i as u64);
+            let g = &input.graph;
+            assert!(
+                g.edges_vec().iter().all(|&(u, v, _)| u < v),
+                "layers={layers}"
+            );
+
+            let comp = strongly_connected_components(g);
+            let distinct: HashSet<u32> = comp.iter().copied().collect();
+            assert_eq!(distinct.len(), g.vertex_count(), "layers={layers}");
+
+            let reach = reachable_from(g, input.source);
+            for (v, &r) in reach.iter().enumerate() {
+                assert_eq!(
+                    r,
+                    v == input.source || g.in_degree(v) > 0,
+                    "layers={layers} v={v}"
+                );
+            }
+            assert!(!reachable_from(g, input.target)[input.source]);
+        }
+    }
+
+    #[test]
+    fn dial_matches_binary_on_bounded_weights() {
+        for (i, case) in [
+            GraphCase::SparseRandom,
+            GraphCase::MaxDenseRandom,
+            GraphCase::AlmostLine,
+            GraphCase::GridSwirl,
+        ]
+        .iter()
+        .enumerate()
+        {
+            for max_weight in [0, 1, 7, 100] {
+                let input = generate_case(*case, 1_024, 0xD1A1_5000 + i as u64);
+                let bounded: Vec<_> = input
+                    .graph
+                    .edges_vec()
+                    .into_iter()
+                    .map(|(u, v, w)| (u, v, w % (max_weight + 1)))
+                    .collect();
+                let g = DirectedGraph::from_edges(input.graph.vertex_count(), &bounded);
+                assert_eq!(
+                    dijkstra_dial(&g, input.source, max_weight),
+                    dijkstra_binary_heap(&g, input.source),
+                    "case={case:?} max_weight={max_weight}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bidirectional_matches_binary_on_generator_cases() {
+        let cases = [
+            GraphCase::SparseRandom,
+            GraphCase::MaxSparseRandom,
+            GraphCase::MaxDenseRandom,
+            GraphCase::MaxDenseLong,
+            GraphCase::MaxDenseZero,
+            GraphCase::AlmostLine,
+            GraphCase::GridRandom,
+            GraphCase::GridSwirl,
+            GraphCase::WrongDijkstraKiller,
+            GraphCase::SpfaKiller,
+        ];
+
+        for (i, case) in cases.iter().enumerate() {
+            let input = generate_case(*case, 1_024, 0xB1D1_0000 + i as u64);
+            let n = input.graph.vertex_count();
+            let full = dijkstra_binary_heap(&input.graph, input.source);
+            for target in [input.target, input.source, (input.source + n / 3) % n] {
+                let expected = (full[target] != INF).then_some(full[target]);
+                assert_eq!(
+                    bidirectional_dijkstra(&input.graph, input.source, target),
+                    expected,
+                    "case={case:?} target={target}"
+                );
+            }
+        }
+
+        let g = DirectedGraph::from_edges(4, &[(0, 1, 3), (2, 3, 1)]);
+        assert_eq!(bidirectional_dijkstra(&g, 0, 3), None);
+        assert_eq!(bidirectional_dijkstra(&g, 0, 1), Some(3));
+    }
+
+    #[test]
+    fn reverse_flips_every_edge() {
+        let g = random_graph(30, 120, 0x4E7E_45E0);
+        let mut forward = g.edges_vec();
+        let mut backward: Vec<_> = g
+            .reverse()
+            .edges_vec()
+            .into_iter()
+            .map(|(u, v, w)| (v, u, w))
+            .collect();
+        forward.sort_unstable();
+        backward.sort_unstable();
+        assert_eq!(forward, backward);
+    }
+
+    #[test]
+    fn transpose_twice_and_degree_sums() {
+        let g = random_graph(40, 200, 0x7EA4_5905);
+        let mut edges = g.edges_vec();
+        let mut twice = g.transpose().transpose().edges_vec();
+        edges.sort_unstable();
+        twice.sort_unstable();
+        assert_eq!(edges, twice);
+
+        let n = g.vertex_count();
+        let t = g.transpose();
+        assert_eq!(
+            (0..n).map(|v| g.out_degree(v)).sum::<usize>(),
+            g.edge_count()
+        );
+        assert_eq!(
+            (0..n).map(|v| g.in_degree(v)).sum::<usize>(),
+            g.edge_count()
+        );
+        for v in 0..n {
+            assert_eq!(g.in_degree(v), t.out_degree(v));
+            assert_eq!(g.out_degree(v), t.in_degree(v));
+        }
+        assert_eq!(g.density(), 200.0 / (40.0 * 39.0));
+        assert_eq!(DirectedGraph::new(1).density(), 0.0);
+    }
+
+    #[test]
+    fn astar_with_zero_heuristic_is_dijkstra() {
+        for seed in 0..20_u64 {
+            let n = 64;
+            let g = random_graph(n, 200, 0xA570_0000 + seed);
+            let src = (seed as usize) % n;
+            for target in 0..n {
+                assert_eq!(
+                    astar(&g, src, target, |_| 0),
+                    dijkstra_to(&g, src, target),
+                    "seed={seed} target={target}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn astar_manhattan_on_grid_is_optimal() {
+        for seed in 0..4_u64 {
+            let input = generate_case(GraphCase::GridRandom, 4_096, 0xA570_6000 + seed);
+            let g = &input.graph;
+            let side = g.vertex_count().isqrt();
+            assert_eq!(side * side, g.vertex_count());
+            // Every grid step costs at least the lightest edge, so Manhattan
+            // distance scaled by it never overestimates.
+            let min_weight = g.edges_vec().iter().map(|e| e.2).min().unwrap();
+            let full = dijkstra_binary_heap(g, input.source);
+            for target in [input.target, 0, side * side - 1, side * (side / 2) + 1] {
+                let (ti, tj) = (target / side, target % side);
+                let manhattan = |u: u32| {
+                    let (i, j) = (u as usize / side, u as usize % side);
+                    (i.abs_diff(ti) + j.abs_diff(tj)) as u64 * min_weight
+                };
+                assert_eq!(
+                    astar(g, input.source, target, manhattan),
+                    Some(full[target]),
+                    "seed={seed} target={target}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn dijkstra_multi_is_elementwise_min_of_single_runs() {
+        for seed in 0..15_u64 {
+            let n = 60;
+            let g = random_graph(n, 150, 0x3017_0000 + seed);
+            let single = (seed as usize) % n;
+            assert_eq!(
+                dijkstra_multi(&g, &[single as u32]),
+                dijkstra_binary_heap(&g, single)
+            );
+
+            let sources: Vec<u32> = (0..=seed % 5)
+                .map(|i| ((seed * 7 + i * 13) % 60) as u32)
+                .collect();
+            let mut expected = vec![INF; n];
+            for &s in &sources {
+                for (e, d) in expected
+                    .iter_mut()
+                    .zip(dijkstra_binary_heap(&g, s as usize))
+                {
+                    *e = (*e).min(d);
+                }
+            }
+            assert_eq!(dijkstra_multi(&g, &sources), expected, "seed={seed}");
+        }
+        let g = random_graph(10, 30, 0x3017_FFFF);
+        assert_eq!(dijkstra_multi(&g, &[]), vec![INF; 10]);
+    }
+
+    #[test]
+    fn builder_matches_from_edges() {
+        for seed in 0..10_u64 {
+            let g = random_graph(50, 250, 0xB11D_0000 + seed);
+            let mut builder = GraphBuilder::new(50);
+            for (u, v, w) in g.edges_vec() {
+                builder.add_edge(u, v, w);
+            }
+            assert_eq!(builder.edge_count(), g.edge_count());
+            let built = builder.build();
+            assert_eq!(built.vertex_count(), g.vertex_count());
+            assert_eq!(built.edge_count(), g.edge_count());
+            for u in 0..50 {
+                assert!(built.neighbors(u).eq(g.neighbors(u)), "seed={seed} u={u}");
+            }
+            for src in [0, 17, 49] {
+                assert_eq!(
+                    dijkstra_binary_heap(&built, src),
+                    dijkstra_binary_heap(&g, src)
+                );
+            }
+        }
+
+        let mut builder = GraphBuilder::default();
+        builder.add_edge(0, 3, 2).add_edge(3, 1, 5);
+        assert_eq!(builder.vertex_count(), 4);
+        assert_eq!(
+            dijkstra_binary_heap(&builder.build(), 0),
+            vec![0, 7, INF, 2]
+        );
+    }
+
+    #[test]
+    fn undirected_construction_matches_symmetrized_edges() {
+        for seed in 0..10_u64 {
+            let mut rng = StdRng::seed_from_u64(0x0DD1_0000 + seed);
+            let n = 40;
+            let edges: Vec<(u32, u32, u64)> = (0..120)
+                .map(|_| {
+                    let u = rng.random_range(0..n as u32);
+                    let v = rng.random_range(0..n as u32);
+                    (u, v, rng.random_range(0..100))
+                })
+                .collect();
+            let mut symmetric = edges.clone();
+            symmetric.extend(edges.iter().map(|&(u, v, w)| (v, u, w)));
+
+            let g = DirectedGraph::from_undirected_edges(n, &edges);
+            let expected = DirectedGraph::from_edges(n, &symmetric);
+            assert_eq!(g.edge_count(), 2 * edges.len());
+            for src in [0, 13, 39] {
+                let dist = dijkstra_binary_heap(&g, src);
+                assert_eq!(dist, dijkstra_binary_heap(&expected, src), "seed={seed}");
+                // Undirected distances are symmetric.
+                for (v, &d) in dist.iter().enumerate() {
+                    assert_eq!(dijkstra_binary_heap(&g, v)[src], d);
+                }
+            }
+        }
+
+        let g = DirectedGraph::from_undirected_edges(3, &[(0, 1, 4), (2, 1, 1)]);
+        assert_eq!(dijkstra_binary_heap(&g, 2), vec![5, 1, 0]);
+        assert_eq!(g.out_degree(1), 2);
+    }
+
+    #[test]
+    fn f64_weights_hand_computed() {
+        let w = |x: f64| F64::new(x).unwrap();
+        let g = DirectedGraph::<F64>::from_weighted_edges(
+            5,
+            &[
+                (0, 1, w(1.5)),
+                (0, 2, w(4.0)),
+                (1, 2, w(2.25)),
+                (2, 3, w(0.5)),
+                (1, 3, w(10.0)),
+                (4, 0, w(0.1)),
+            ],
+        );
+        let dist: Vec<f64> = dijkstra_binary_heap(&g, 0)
+            .into_iter()
+            .map(F64::get)
+            .collect();
+        assert_eq!(dist, [0.0, 1.5, 3.75, 4.25, f64::INFINITY]);
+        assert_eq!(dijkstra_to(&g, 0, 3), Some(w(4.25)));
+        assert_eq!(dijkstra_to(&g, 0, 4), None);
+        assert_eq!(
+            dijkstra_multi(&g, &[2, 4]),
+            [w(0.1), w(1.6), F64::ZERO, w(0.5), F64::ZERO]
+        );
+
+        assert_eq!(F64::new(f64::NAN), None);
+        assert_eq!(F64::new(-1.0), None);
+        assert_eq!(F64::new(-0.0), Some(F64::ZERO));
+        assert_eq!(
+            F64::new(-0.0).unwrap().cmp(&F64::ZERO),
+            std::cmp::Ordering::Equal
+        );
+        assert_eq!(w(f64::MAX).add(w(f64::MAX)), F64::INFINITY);
+        assert_eq!(u64::INFINITY, INF);
+    }
+
+    #[test]
+    fn k_shortest_hand_computed() {
+        // Yen's textbook example: C=0, D=1, E=2, F=3, G=4, H=5.
+        let g = DirectedGraph::from_edges(
+            6,
+            &[
+                (0, 1, 3),
+                (0, 2, 2),
+                (1, 3, 4),
+                (2, 1, 1),
+                (2, 3, 2),
+                (2, 4, 3),
+                (3, 4, 2),
+                (3, 5, 1),
+                (4, 5, 2),
+            ],
+        );
+        assert_eq!(k_shortest(&g, 0, 5, 3), vec![5, 7, 8]);
+        // All seven simple C-H paths.
+        assert_eq!(k_shortest(&g, 0, 5, 10), vec![5, 7, 8, 8, 8, 11, 11]);
+        assert_eq!(k_shortest(&g, 0, 5, 0), Vec::<u64>::new());
+        assert_eq!(k_shortest(&g, 5, 0, 3), Vec::<u64>::new());
+        assert_eq!(k_shortest(&g, 2, 2, 3), vec![0]);
+    }
+
+    fn all_simple_path_costs(
+        g: &DirectedGraph,
+        u: usize,
+        dst: usize,
+        cost: u64,
+        on_path: &mut [bool],
+        out: &mut Vec<u64>,
+    ) {
+        if u == dst {
+            out.push(cost);
+            return;
+        }
+        on_path[u] = true;
+        let mut best_to = std::collections::HashMap::new();
+        for edge in g.out_edges(u) {
+            let w = best_to.entry(edge.to).or_insert(edge.weight);
+            *w = (*w).min(edge.weight);
+        }
+        for (v, w) in best_to {
+            if !on_path[v as usize] {
+                all_simple_path_costs(g, v as usize, dst, cost + w, on_path, out);
+            }
+        }
+        on_path[u] = false;
+    }
+
+    #[test]
+    fn k_shortest_matches_path_enumeration() {
+        for seed in 0..30_u64 {
+            let n = 7;
+            let g = random_graph(n, 18, 0x4E57_0000 + seed);
+            let (src, dst) = ((seed as usize) % n, (seed as usize * 3 + 1) % n);
+            let mut all = Vec::new();
+            all_simple_path_costs(&g, src, dst, 0, &mut vec![false; n], &mut all);
+            all.sort_unstable();
+            for k in [1, 2, 5, 50] {
+                let got = k_shortest(&g, src, dst, k);
+                assert_eq!(got, all[..k.min(all.len())], "seed={seed} k={k}");
+                assert_eq!(got.first().copied(), dijkstra_to(&g, src, dst));
+            }
+        }
+    }
+
+    #[test]
+    fn radix_heap_hand_computed() {
+        let mut heap = RadixHeap::<u32, char>::new();
+        assert_eq!(heap.pop_min(), None);
+        heap.push(5, 'a');
+        heap.push(1, 'b');
+        heap.push(5, 'c');
+        heap.push(9, 'd');
+        assert_eq!(heap.len(), 4);
+        assert_eq!(heap.pop_min(), Some((1, 'b')));
+        heap.push(1, 'e');
+        assert_eq!(heap.pop_min(), Some((1, 'e')));
+
+        let mut ties = [heap.pop_min().unwrap(), heap.pop_min().unwrap()];
+        ties.sort_unstable();
+        assert_eq!(ties, [(5, 'a'), (5, 'c')]);
+        assert_eq!(heap.last_key(), 5);
+        heap.push(u32::MAX, 'f');
+        assert_eq!(heap.pop_min(), Some((9, 'd')));
+        assert_eq!(heap.pop_min(), Some((u32::MAX, 'f')));
+        assert!(heap.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "monotone")]
+    fn radix_heap_rejects_key_below_last_pop() {
+        let mut heap = RadixHeap::<u64, ()>::new();
+        heap.push(10, ());
+        heap.pop_min();
+        heap.push(9, ());
+    }
+
+    #[test]
+    fn radix_heap_matches_sorted_model() {
+        let mut rng = StdRng::seed_from_u64(0x4AD1_8EA9);
+        let mut heap = RadixHeap::<u64, usize>::new();
+        let mut model: Vec<u64> = Vec::new();
+        let mut last = 0_u64;
+        for id in 0..20_000 {
+            if model.is_empty() || rng.random_bool(0.55) {
+                let bits = rng.random_range(0..40);
+                let key = last + rng.random_range(0..=1_u64 << bits);
+                heap.push(key, id);
+                model.push(key);
+            } else {
+                model.sort_unstable_by(|a, b| b.cmp(a));
+                let expected = model.pop().unwrap();
+                let (key, _) = heap.pop_min().unwrap();
+                assert_eq!(key, expected);
+                assert!(key >= last);
+                last = key;
+            }
+            assert_eq!(heap.len(), model.len());
+        }
+    }
+
+    #[test]
+    fn batch_queue_hand_computed() {
+        let mut q = BatchPriorityQueue::new(2, 100_u64);
+        assert!(q.is_empty());
+        assert_eq!(q.pull(), None);
+
+        q.insert(7, 50);
+        q.insert(3, 40);
+        q.insert(7, 45);
+        q.insert(3, 60); // Larger than the stored value, ignored.
+        q.insert(9, 70);
+        q.batch_prepend(&[(1, 20), (2, 10), (1, 5)]);
+
+        let (mut keys, x) = q.pull().unwrap();
+        keys.sort_unstable();
+        assert_eq!((keys, x), (vec![1, 2], 40));
+        let (mut keys, x) = q.pull().unwrap();
+        keys.sort_unstable();
+        assert_eq!((keys, x), (vec![3, 7], 70));
+        assert_eq!(q.pull(), Some((vec![9], 100)));
+        assert!(q.is_empty());
+
+        q.clear(10);
+        q.insert(1_000, 3);
+        assert_eq!(q.pull(), Some((vec![1_000], 10)));
+    }
+
+    #[test]
+    fn batch_queue_matches_sorted_model() {
+        for (seed, pull_limit) in (0..12_u64).zip([1, 2, 3, 5, 8, 13].into_iter().cycle()) {
+            let mut rng = StdRng::seed_from_u64(0xBA7C_0000 + seed);
+            let bound = (1_000_u64, u32::MAX);
+            let mut q = BatchPriorityQueue::new(pull_limit, bound);
+            let mut model = std::collections::BTreeMap::new();
+            let mut keys = Vec::new();
+
+            for _ in 0..2_000 {
+                match rng.random_range(0..4) {
+                    0 | 1 => {
+                        let k = rng.random_range(0..128_usize);
+                        let v = (rng.random_range(0..1_000_u64), k as u32);
+                        q.insert(k, v);
+                        let cur = model.entry(k).or_insert(v);
+                        *cur = (*cur).min(v);
+                    }
+                    2 => {
+                        let low = model.values().min().map_or(1_000, |v: &(u64, u32)| v.0);
+                        if low == 0 {
+                            continue;
+                        }
+                        let batch: Vec<_> = (0..rng.random_range(0..3 * pull_limit))
+                            .map(|_| {
+                                let k = rng.random_range(0..128_usize);
+                                (k, (rng.random_range(0..low), k as u32))
+                            })
+                            .collect();
+                        q.batch_prepend(&batch);
+                        for (k, v) in batch {
+                            let cur = model.entry(k).or_insert(v);
+                            *cur = (*cur).min(v);
+                        }
+                    }
+                    _ => {
+                        let got = q.pull_into(&mut keys);
+                        let mut sorted: Vec<_> = model.iter().map(|(&k, &v)| (v, k)).collect();
+                        sorted.sort_unstable();
+                        if sorted.is_empty() {
+                            assert_eq!(got, None, "seed={seed}");
+                            continue;
+                        }
+                        let take = pull_limit.min(sorted.len());
+                        let mut expected: Vec<_> = sorted[..take].iter().map(|e| e.1).collect();
+                        for k in &expected {
+                            model.remove(k);
+                        }
+                        expected.sort_unstable();
+                        keys.sort_unstable();
+                        assert_eq!(keys, expected, "seed={seed}");
+                        let separator = sorted.get(take).map_or(bound, |e| e.0);
+                        assert_eq!(got, Some(separator), "seed={seed}");
+                    }
+                }
+                assert_eq!(q.is_empty(), model.is_empty(), "seed={seed}");
+            }
         }
     }
+
+    #[test]
+    fn reachable_matches_finite_dijkstra_on_generator_cases() {
+        let cases = [
+            GraphCase::SparseRandom,
+            GraphCase::MaxSparseRandom,
+            GraphCase::MaxDenseRandom,
+            GraphCase::MaxDenseZero,
+            GraphCase::AlmostLine,
+            GraphCase::GridSwirl,
+            GraphCase::WrongDijkstraKiller,
+            GraphCase::SpfaKiller,
+        ];
+
+        for (i, case) in cases.iter().enumerate() {
+            let input = generate_case(*case, 1_024, 0x4EAC_0000 + i as u64);
+            let n = input.graph.vertex_count();
+            for src in [input.source, input.target, n / 2] {
+                let expected: Vec<bool> = dijkstra_binary_heap(&input.graph, src)
+                    .into_iter()
+                    .map(|d| d != INF)
+                    .collect();
+                assert_eq!(
+                    reachable_from(&input.graph, src),
+                    expected,
+                    "case={case:?} src={src}"
+                );
+            }
+            assert_eq!(reachable_from(&input.graph, n), vec![false; n]);
+        }
+    }
+
+    #[test]
+    fn scc_matches_mutual_reachability() {
+        for seed in 0..20_u64 {
+            let n = 30;
+            let g = random_graph(n, 20 + 3 * seed as usize, 0x5CC0_0000 + seed);
+            let comp = strongly_connected_components(&g);
+            let reach: Vec<Vec<bool>> = (0..n).map(|u| reachable_from(&g, u)).collect();
+            for u in 0..n {
+                for v in 0..n {
+                    let mutual = reach[u][v] && reach[v][u];
+                    assert_eq!(comp[u] == comp[v], mutual, "seed={seed} u={u} v={v}");
+                }
+            }
+            let count = comp.iter().max().map_or(0, |&c| c + 1);
+            assert!((0..count).all(|c| comp.contains(&c)), "seed={seed}");
+            for (u, v, _) in g.edges_vec() {
+                assert!(comp[u as usize] >= comp[v as usize], "seed={seed}");
+            }
+        }
+
+        // A 100k-vertex cycle would overflow a recursive DFS.
+        let n = 100_000_u32;
+        let cycle: Vec<_> = (0..n).map(|u| (u, (u + 1) % n, 1)).collect();
+        let comp = strongly_connected_components(&DirectedGraph::from_edges(n as usize, &cycle));
+        assert!(comp.iter().all(|&c| c == 0));
+        let line = DirectedGraph::from_edges(3, &[(0, 1, 1), (1, 2, 1)]);
+        assert_eq!(strongly_connected_components(&line), vec![2, 1, 0]);
+    }
 }
@@ -0,0 +1,92 @@
+/// Monotone radix heap: a min-queue whose pushed keys never go below the
+/// last popped key, as in label-setting shortest-path searches.
+///
+/// Entries sit in 65 buckets by the highest bit in which their key differs
+/// from the last popped one. [`Self::pop_min`] redistributes the first
+/// non-empty bucket around its minimum, and every entry moves to a strictly
+/// lower bucket each time it is redistributed, so a push/pop pair is amortized
+/// O(log C) for keys within `C` of each other. Equal keys pop in no particular
+/// order.
+#[derive(Debug)]
+pub struct RadixHeap<K, V> {
+    buckets: [Vec<(K, V)>; 65],
+    last: u64,
+    len: usize,
+}
+
+impl<K: Copy + Into<u64>, V> RadixHeap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| Vec::new()),
+            last: 0,
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Key of the last popped entry (`0` before the first pop), the lower
+    /// bound for every push.
+    #[inline]
+    pub fn last_key(&self) -> u64 {
+        self.last
+    }
+
+    /// Panics if `key` is below [`Self::last_key`].
+    pub fn push(&mut self, key: K, value: V) {
+        let k = key.into();
+        assert!(k >= self.last, "radix-heap key must be monotone");
+        self.buckets[bucket_index(self.last, k)].push((key, value));
+        self.len += 1;
+    }
+
+    pub fn pop_min(&mut self) -> Option<(K, V)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        if self.buckets[0].is_empty() {
+            let idx = self.buckets.iter().position(|b| !b.is_empty())?;
+            self.last = self.buckets[idx]
+                .iter()
+                .map(|&(key, _)| key.into())
+                .min()
+                .unwrap();
+
+            let entries = std::mem::take(&mut self.buckets[idx]);
+            for (key, value) in entries {
+                let b = bucket_index(self.last, key.into());
+                self.buckets[b].push((key, value));
+            }
+        }
+
+        let pair = self.buckets[0].pop();
+        if pair.is_some() {
+            self.len -= 1;
+        }
+        pair
+    }
+}
+
+impl<K: Copy + Into<u64>, V> Default for RadixHeap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[inline]
+fn bucket_index(last: u64, key: u64) -> usize {
+    if key == last {
+        0
+    } else {
+        (64 - (key ^ last).leading_zeros()) as usize
+    }
+}
@@ -0,0 +1,74 @@
+use std::cmp::Ordering;
+use std::fmt::Debug;
+
+use crate::INF;
+
+/// Edge weight / distance type for the Dijkstra family.
+///
+/// Distances are totally ordered, start at `ZERO`, and `add` saturates at
+/// `INFINITY`, which also marks unreachable vertices. Weights must be
+/// non-negative.
+pub trait Weight: Copy + Ord + Debug {
+    const ZERO: Self;
+    const INFINITY: Self;
+
+    fn add(self, other: Self) -> Self;
+}
+
+/// `INFINITY` is [`INF`], so the generic functions return exactly what the
+/// `u64` API always has.
+impl Weight for u64 {
+    const ZERO: Self = 0;
+    const INFINITY: Self = INF;
+
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        self.saturating_add(other).min(INF)
+    }
+}
+
+/// Non-negative, non-NaN `f64`, totally ordered so it can key a `BinaryHeap`.
+///
+/// `-0.0` is stored as `0.0`, keeping `Eq` and `Ord` consistent.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct F64(f64);
+
+impl F64 {
+    /// `None` for NaN or negative values; `f64::INFINITY` is allowed.
+    pub fn new(value: f64) -> Option<Self> {
+        (value >= 0.0).then_some(Self(value + 0.0))
+    }
+
+    #[inline]
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl Eq for F64 {}
+
+impl PartialOrd for F64 {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for F64 {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Weight for F64 {
+    const ZERO: Self = Self(0.0);
+    const INFINITY: Self = Self(f64::INFINITY);
+
+    /// Sums of non-negative values never produce NaN; overflow rounds to
+    /// infinity.
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
@@ -2,7 +2,7 @@ use std::hint::black_box;
 
 use bench::{
     apply_large_runtime_config, apply_medium_runtime_config, apply_small_runtime_config,
-    default_rng,
+    bench_seed, rng_with_seed,
 };
 use criterion::measurement::Measurement;
 use criterion::{BenchmarkGroup, BenchmarkId, Criterion, criterion_group, criterion_main};
@@ -37,7 +37,7 @@ fn generate_tree_edges(rng: &mut impl Rng, n: usize) -> Vec<(usize, usize, u64)>
 
 fn bench_xor_linked_tree(c: &mut Criterion) {
     let mut group = c.benchmark_group("xor_linked_tree");
-    let mut rng = default_rng();
+    let mut rng = rng_with_seed(bench_seed());
 
     for &size in &SIZES {
         apply_runtime_config_for_size(size, &mut group);
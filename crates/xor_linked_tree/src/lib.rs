@@ -3,9 +3,18 @@ use std::collections::VecDeque;
 const UNVISITED: u64 = u64::MAX;
 const NONE: usize = usize::MAX;
 
+/// Length of a longest path. This and the other `diameter_*` functions
+/// assume the edges form one tree over all `n` vertices; see
+/// [`forest_diameters`] for disconnected inputs.
 pub fn diameter_vec(n: usize, edges: &[(usize, usize, u64)]) -> u64 {
+    diameter_endpoints_vec(n, edges).2
+}
+
+/// Both ends of a longest path and its length, from the same double search
+/// as [`diameter_vec`]. A tree with at most one vertex gives `(0, 0, 0)`.
+pub fn diameter_endpoints_vec(n: usize, edges: &[(usize, usize, u64)]) -> (usize, usize, u64) {
     if n <= 1 {
-        return 0;
+        return (0, 0, 0);
     }
     let mut adj = vec![Vec::new(); n];
     for &(u, v, w) in edges {
@@ -13,8 +22,59 @@ pub fn diameter_vec(n: usize, edges: &[(usize, usize, u64)]) -> u64 {
         adj[v].push((u, w));
     }
     let (start, _) = farthest_vec(0, &adj);
-    let (_, dist) = farthest_vec(start, &adj);
-    dist
+    let (end, dist) = farthest_vec(start, &adj);
+    (start, end, dist)
+}
+
+/// Like [`diameter_vec`], but adds weights exactly and returns `None` when the
+/// diameter does not fit in a `u64` instead of saturating.
+pub fn diameter_vec_checked(n: usize, edges: &[(usize, usize, u64)]) -> Option<u64> {
+    if n <= 1 {
+        return Some(0);
+    }
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v, w) in edges {
+        adj[u].push((v, w));
+        adj[v].push((u, w));
+    }
+    // Every distance from any vertex is at most the diameter, so an overflow
+    // in either search means the diameter overflows too.
+    let (start, _) = farthest_vec_checked(0, &adj)?;
+    let (_, dist) = farthest_vec_checked(start, &adj)?;
+    Some(dist)
+}
+
+/// Diameter of every tree in a forest, one per connected component in order of
+/// the smallest vertex in each. Isolated vertices count as components of
+/// diameter `0`.
+pub fn forest_diameters(n: usize, edges: &[(usize, usize, u64)]) -> Vec<u64> {
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v, w) in edges {
+        adj[u].push((v, w));
+        adj[v].push((u, w));
+    }
+
+    let mut dist = vec![UNVISITED; n];
+    let mut component = Vec::new();
+    let mut diameters = Vec::new();
+    for root in 0..n {
+        if dist[root] != UNVISITED {
+            continue;
+        }
+        let start = farthest_in_component(root, &adj, &mut dist, &mut component);
+        // Forget this component's distances so the second search can revisit it.
+        for &v in &component {
+            dist[v] = UNVISITED;
+        }
+        let end = farthest_in_component(start, &adj, &mut dist, &mut component);
+        diameters.push(dist[end]);
+    }
+    diameters
+}
+
+/// Largest diameter over the trees of a forest; `0` without vertices.
+pub fn max_forest_diameter(n: usize, edges: &[(usize, usize, u64)]) -> u64 {
+    forest_diameters(n, edges).into_iter().max().unwrap_or(0)
 }
 
 pub fn diameter_chinese(n: usize, edges: &[(usize, usize, u64)]) -> u64 {
@@ -88,6 +148,80 @@ pub fn diameter_xor(n: usize, edges: &[(usize, usize, u64)]) -> u64 {
     ans
 }
 
+/// Largest distance from every vertex to any other, by rerooting: one pass
+/// collects the two deepest branches below each vertex, a second pushes the
+/// best path through the parent down. O(n) overall; the tree center is any
+/// vertex with the smallest value.
+pub fn eccentricities(n: usize, edges: &[(usize, usize, u64)]) -> Vec<u64> {
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v, w) in edges {
+        adj[u].push((v, w));
+        adj[v].push((u, w));
+    }
+
+    // Preorder from 0 with each vertex's parent and the weight of the edge to it.
+    let mut order = Vec::with_capacity(n);
+    let mut parent = vec![(NONE, 0_u64); n];
+    let mut stack = vec![0];
+    parent[0] = (0, 0);
+    while let Some(v) = stack.pop() {
+        order.push(v);
+        for &(to, w) in &adj[v] {
+            if parent[to].0 == NONE {
+                parent[to] = (v, w);
+                stack.push(to);
+            }
+        }
+    }
+
+    // down1/down2: two deepest branches into different children; best_child owns down1.
+    let mut down1 = vec![0_u64; n];
+    let mut down2 = vec![0_u64; n];
+    let mut best_child = vec![NONE; n];
+    for &v in order.iter().skip(1).rev() {
+        let (p, w) = parent[v];
+        let candidate = down1[v].saturating_add(w);
+        if candidate > down1[p] {
+            down2[p] = down1[p];
+            down1[p] = candidate;
+            best_child[p] = v;
+        } else if candidate > down2[p] {
+            down2[p] = candidate;
+        }
+    }
+
+    let mut up = vec![0_u64; n];
+    for &v in order.iter().skip(1) {
+        let (p, w) = parent[v];
+        let sideways = if best_child[p] == v {
+            down2[p]
+        } else {
+            down1[p]
+        };
+        up[v] = up[p].max(sideways).saturating_add(w);
+    }
+
+    (0..n).map(|v| down1[v].max(up[v])).collect()
+}
+
+/// Vertices of smallest eccentricity, in increasing order. With positive
+/// weights there are one or two of them, adjacent in the latter case.
+pub fn center(n: usize, edges: &[(usize, usize, u64)]) -> Vec<usize> {
+    let ecc = eccentricities(n, edges);
+    let Some(&radius) = ecc.iter().min() else {
+        return Vec::new();
+    };
+    (0..n).filter(|&v| ecc[v] == radius).collect()
+}
+
+/// Smallest eccentricity over all vertices; `0` for an empty tree.
+pub fn radius(n: usize, edges: &[(usize, usize, u64)]) -> u64 {
+    eccentricities(n, edges).into_iter().min().unwrap_or(0)
+}
+
 fn farthest_vec(start: usize, adj: &[Vec<(usize, u64)>]) -> (usize, u64) {
     let n = adj.len();
     let mut dist = vec![UNVISITED; n];
@@ -106,6 +240,57 @@ fn farthest_vec(start: usize, adj: &[Vec<(usize, u64)>]) -> (usize, u64) {
     max_dist(&dist, start)
 }
 
+/// Searches the component of `start`, leaving its distances in `dist` and its
+/// vertices in `component`, and returns the farthest vertex.
+fn farthest_in_component(
+    start: usize,
+    adj: &[Vec<(usize, u64)>],
+    dist: &mut [u64],
+    component: &mut Vec<usize>,
+) -> usize {
+    component.clear();
+    let mut far = start;
+    dist[start] = 0;
+    component.push(start);
+    let mut stack = vec![start];
+    while let Some(v) = stack.pop() {
+        let base = dist[v];
+        if base > dist[far] {
+            far = v;
+        }
+        for &(to, w) in &adj[v] {
+            if dist[to] == UNVISITED {
+                dist[to] = base.saturating_add(w);
+                component.push(to);
+                stack.push(to);
+            }
+        }
+    }
+    far
+}
+
+fn farthest_vec_checked(start: usize, adj: &[Vec<(usize, u64)>]) -> Option<(usize, u64)> {
+    // `Option` rather than the `UNVISITED` sentinel: `u64::MAX` is a valid distance here.
+    let mut dist = vec![None; adj.len()];
+    let mut stack = Vec::with_capacity(adj.len());
+    let mut far = (start, 0_u64);
+    dist[start] = Some(0_u64);
+    stack.push((start, 0_u64));
+    while let Some((v, base)) = stack.pop() {
+        if base > far.1 {
+            far = (v, base);
+        }
+        for &(to, w) in &adj[v] {
+            if dist[to].is_none() {
+                let d = base.checked_add(w)?;
+                dist[to] = Some(d);
+                stack.push((to, d));
+            }
+        }
+    }
+    Some(far)
+}
+
 fn farthest_chinese(start: usize, adj: &ChineseAdj) -> (usize, u64) {
     let n = adj.head.len();
     let mut dist = vec![UNVISITED; n];
@@ -256,7 +441,11 @@ impl Csr {
 
 #[cfg(test)]
 mod tests {
-    use super::{diameter_chinese, diameter_csr, diameter_vec, diameter_xor};
+    use super::{
+        center, diameter_chinese, diameter_csr, diameter_endpoints_vec, diameter_vec,
+        diameter_vec_checked, diameter_xor, eccentricities, forest_diameters, max_forest_diameter,
+        radius,
+    };
     use rand::rngs::StdRng;
     use rand::{Rng, SeedableRng};
 
@@ -300,4 +489,158 @@ mod tests {
         assert_eq!(diameter_csr(n, &edges), expected);
         assert_eq!(diameter_xor(n, &edges), expected);
     }
+
+    fn tree_distances(n: usize, edges: &[(usize, usize, u64)], src: usize) -> Vec<u64> {
+        let mut adj = vec![Vec::new(); n];
+        for &(u, v, w) in edges {
+            adj[u].push((v, w));
+            adj[v].push((u, w));
+        }
+        let mut dist = vec![u64::MAX; n];
+        let mut queue = std::collections::VecDeque::from([src]);
+        dist[src] = 0;
+        while let Some(v) = queue.pop_front() {
+            for &(to, w) in &adj[v] {
+                if dist[to] == u64::MAX {
+                    dist[to] = dist[v] + w;
+                    queue.push_back(to);
+                }
+            }
+        }
+        dist
+    }
+
+    #[test]
+    fn diameter_endpoints_span_the_diameter() {
+        assert_eq!(diameter_endpoints_vec(0, &[]), (0, 0, 0));
+        assert_eq!(diameter_endpoints_vec(1, &[]), (0, 0, 0));
+        let (a, b, len) = diameter_endpoints_vec(4, &[(0, 1, 2), (0, 2, 4), (0, 3, 6)]);
+        assert_eq!(len, 10);
+        assert_eq!((a.min(b), a.max(b)), (2, 3));
+
+        let mut rng = StdRng::seed_from_u64(0xE4D5);
+        for n in [2_usize, 3, 10, 64, 300] {
+            for _ in 0..5 {
+                let edges: Vec<_> = (1..n)
+                    .map(|i| (i, rng.random_range(0..i), rng.random_range(0..=1_000_u64)))
+                    .collect();
+                let (a, b, len) = diameter_endpoints_vec(n, &edges);
+                assert_eq!(tree_distances(n, &edges, a)[b], len, "n={n}");
+                assert_eq!(len, diameter_xor(n, &edges), "n={n}");
+            }
+        }
+    }
+
+    #[test]
+    fn eccentricities_match_search_from_every_vertex() {
+        assert_eq!(eccentricities(0, &[]), Vec::<u64>::new());
+        assert_eq!(eccentricities(1, &[]), vec![0]);
+        assert_eq!(
+            eccentricities(4, &[(0, 1, 3), (1, 2, 5), (2, 3, 7)]),
+            vec![15, 12, 8, 15]
+        );
+
+        let mut rng = StdRng::seed_from_u64(0xECC0);
+        for n in [2_usize, 3, 7, 20, 60] {
+            for _ in 0..8 {
+                let edges: Vec<_> = (1..n)
+                    .map(|i| (rng.random_range(0..i), i, rng.random_range(0..=50_u64)))
+                    .collect();
+                let got = eccentricities(n, &edges);
+                for (v, &ecc) in got.iter().enumerate() {
+                    let expected = tree_distances(n, &edges, v).into_iter().max().unwrap();
+                    assert_eq!(ecc, expected, "n={n} v={v}");
+                }
+                assert_eq!(got.iter().max().copied(), Some(diameter_vec(n, &edges)));
+            }
+        }
+    }
+
+    #[test]
+    fn center_and_radius_of_unweighted_trees() {
+        assert_eq!((center(0, &[]), radius(0, &[])), (Vec::new(), 0));
+        assert_eq!((center(1, &[]), radius(1, &[])), (vec![0], 0));
+        let path = [(0, 1, 1), (1, 2, 1), (2, 3, 1)];
+        assert_eq!((center(4, &path), radius(4, &path)), (vec![1, 2], 2));
+        assert_eq!((center(3, &path[..2]), radius(3, &path[..2])), (vec![1], 1));
+
+        let mut rng = StdRng::seed_from_u64(0xCE47);
+        for n in [2_usize, 3, 9, 40, 200] {
+            for _ in 0..8 {
+                let edges: Vec<_> = (1..n).map(|i| (i, rng.random_range(0..i), 1)).collect();
+                let diameter = diameter_vec(n, &edges);
+                let centers = center(n, &edges);
+                assert_eq!(radius(n, &edges), diameter.div_ceil(2), "n={n}");
+                assert_eq!(centers.len(), 1 + diameter as usize % 2, "n={n}");
+                if let [a, b] = centers[..] {
+                    assert!(
+                        edges
+                            .iter()
+                            .any(|&(u, v, _)| (u, v) == (a, b) || (u, v) == (b, a))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn checked_diameter_detects_overflow() {
+        assert_eq!(diameter_vec_checked(0, &[]), Some(0));
+        assert_eq!(diameter_vec_checked(1, &[]), Some(0));
+
+        let half = u64::MAX / 2;
+        // Two heavy edges meet at the middle vertex: 2 * half + 1 == u64::MAX fits exactly.
+        let fits = [(0, 1, half), (1, 2, half + 1)];
+        assert_eq!(diameter_vec_checked(3, &fits), Some(u64::MAX));
+        let overflows = [(0, 1, half + 1), (1, 2, half + 1)];
+        assert_eq!(diameter_vec_checked(3, &overflows), None);
+        // Distances from vertex 0 fit; only the second search, from a heavy leaf, overflows.
+        let late = [(0, 1, 1), (0, 2, half + 1), (0, 3, half + 1)];
+        assert_eq!(diameter_vec_checked(4, &late), None);
+
+        let mut rng = StdRng::seed_from_u64(0xC4EC);
+        for n in [2_usize, 10, 100] {
+            let edges: Vec<_> = (1..n)
+                .map(|i| {
+                    (
+                        i,
+                        rng.random_range(0..i),
+                        rng.random_range(0..=1_000_000_u64),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                diameter_vec_checked(n, &edges),
+                Some(diameter_vec(n, &edges))
+            );
+        }
+    }
+
+    #[test]
+    fn forest_diameters_per_component() {
+        assert_eq!(forest_diameters(0, &[]), Vec::<u64>::new());
+        assert_eq!(max_forest_diameter(0, &[]), 0);
+
+        // {0, 3, 5}: path 5-0-3 of length 9; {1, 4}: one edge of 20; {2}: isolated.
+        let edges = [(0, 3, 4), (5, 0, 5), (4, 1, 20)];
+        assert_eq!(forest_diameters(6, &edges), vec![9, 20, 0]);
+        assert_eq!(max_forest_diameter(6, &edges), 20);
+
+        let mut rng = StdRng::seed_from_u64(0xF0E5);
+        let (a, b) = (40_usize, 25_usize);
+        let first: Vec<_> = (1..a)
+            .map(|i| (i, rng.random_range(0..i), rng.random_range(1..=100_u64)))
+            .collect();
+        let second: Vec<_> = (1..b)
+            .map(|i| (i, rng.random_range(0..i), rng.random_range(1..=100_u64)))
+            .collect();
+        let shifted = second.iter().map(|&(u, v, w)| (u + a, v + a, w));
+        let forest: Vec<_> = first.iter().copied().chain(shifted).collect();
+        let expected = vec![diameter_vec(a, &first), diameter_vec(b, &second)];
+        assert_eq!(forest_diameters(a + b, &forest), expected);
+        assert_eq!(
+            max_forest_diameter(a + b, &forest),
+            expected[0].max(expected[1])
+        );
+    }
 }